@@ -0,0 +1,181 @@
+use std::{fmt, fs, io, path::Path, path::PathBuf, str::FromStr};
+
+use crate::{
+    config::config_dir,
+    schema::{unversioned_to_v1, Schema, SchemaError},
+};
+
+// personal chart shorthand, kept in the user's config so "C^" can mean Cmaj7 or "Ch7" Cm7b5
+// the parser swaps these in for the text after the root before reading the symbol
+
+const ALIASES_SCHEMA: Schema = Schema {
+    kind: "aliases",
+    migrations: &[unversioned_to_v1],
+};
+const ALIASES_FILE: &str = "aliases";
+
+#[derive(Debug)]
+pub enum AliasError {
+    Io(String),
+    InvalidLine(String),
+    Schema(SchemaError),
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AliasError::Io(e) => write!(f, "{}", e),
+            AliasError::InvalidLine(line) => write!(f, "couldn't read the line {}", line),
+            AliasError::Schema(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<SchemaError> for AliasError {
+    fn from(e: SchemaError) -> Self {
+        AliasError::Schema(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alias {
+    pub symbol: String,
+    pub expansion: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Aliases {
+    pub entries: Vec<Alias>,
+}
+
+impl Aliases {
+    pub fn default_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(ALIASES_FILE))
+    }
+
+    // no aliases file just means no aliases
+    pub fn load(path: &Path) -> Result<Aliases, AliasError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Aliases::from_str(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Aliases::default()),
+            Err(e) => Err(AliasError::Io(e.to_string())),
+        }
+    }
+
+    // swap every alias for what it stands for in one pass, so an expansion never gets expanded again
+    // longer aliases go first, with "h7" and "h" both set "h7" wins
+    pub fn expand(&self, s: &str) -> String {
+        let mut entries: Vec<&Alias> = self.entries.iter().collect();
+        entries.sort_by_key(|a| std::cmp::Reverse(a.symbol.len()));
+
+        let mut expanded = String::new();
+        let mut rest = s;
+
+        while let Some(c) = rest.chars().next() {
+            match entries.iter().find(|a| rest.starts_with(a.symbol.as_str())) {
+                Some(alias) => {
+                    expanded.push_str(&alias.expansion);
+                    rest = &rest[alias.symbol.len()..];
+                }
+                None => {
+                    expanded.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+
+        expanded
+    }
+}
+
+// one alias per line, e.g. "h7 = m7b5"
+impl fmt::Display for Aliases {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", ALIASES_SCHEMA.header())?;
+
+        for alias in &self.entries {
+            writeln!(f, "{} = {}", alias.symbol, alias.expansion)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Aliases {
+    type Err = AliasError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = &ALIASES_SCHEMA.migrate(s)?;
+        let mut entries: Vec<Alias> = vec![];
+
+        for line in s.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (symbol, expansion) = match line.split_once('=') {
+                Some((symbol, expansion)) => (symbol.trim(), expansion.trim()),
+                None => return Err(AliasError::InvalidLine(line.to_string())),
+            };
+
+            if symbol.is_empty() {
+                return Err(AliasError::InvalidLine(line.to_string()));
+            }
+
+            entries.push(Alias {
+                symbol: symbol.to_string(),
+                expansion: expansion.to_string(),
+            });
+        }
+
+        Ok(Aliases { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases() -> Aliases {
+        Aliases::from_str("# struck aliases\n^ = maj7\nh7 = m7b5\nh = dim\n").expect("hmm")
+    }
+
+    //
+    // expand
+    //
+
+    #[test]
+    fn test_expand_replaces_aliases() {
+        assert_eq!(aliases().expand("^"), "maj7");
+        assert_eq!(aliases().expand("h7"), "m7b5");
+    }
+
+    #[test]
+    fn test_expand_longest_first() {
+        assert_eq!(aliases().expand("h"), "dim");
+        assert_eq!(aliases().expand("h7"), "m7b5");
+    }
+
+    #[test]
+    fn test_expand_leaves_everything_else() {
+        assert_eq!(aliases().expand("m7(b9)"), "m7(b9)");
+        assert_eq!(Aliases::default().expand("^"), "^");
+    }
+
+    //
+    // from_str
+    //
+
+    #[test]
+    fn test_aliases_round_trip() {
+        let ret = Aliases::from_str(&aliases().to_string()).expect("hmm");
+
+        assert_eq!(ret, aliases());
+    }
+
+    #[test]
+    fn test_aliases_from_str_invalid_line() {
+        assert!(Aliases::from_str("^ maj7").is_err());
+        assert!(Aliases::from_str(" = maj7").is_err());
+    }
+}
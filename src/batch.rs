@@ -0,0 +1,232 @@
+use serde::Serialize;
+use std::fmt;
+
+use crate::{
+    aliases::Aliases,
+    catalog,
+    parser::{
+        chord_parser::{
+            identify_from_name_as, identify_from_notes, identify_from_pitches, ParseOptions,
+        },
+        note_parser::{identify_midi_pitches, identify_notes},
+    },
+    report::ChordReport,
+    theory::chord::Chord,
+};
+
+// struck batch chords.txt, a file of chord names or note lists identified a line at a time
+// a line with more than one note on it (C E G, or midi numbers 60 64 67) is a note list, anything else a chord name
+// blank lines and # comments are skipped, and a line that doesn't read is in the report with why
+// rather than stopping the rest
+
+#[derive(Debug)]
+pub enum BatchError {
+    Serialize(String),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Serialize(e) => write!(f, "couldn't write the output: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchEntry {
+    // counted from 1 like an editor does
+    pub line: usize,
+    pub input: String,
+    // the chord named or the most likely reading of the notes, null when the line didn't read
+    pub chord: Option<ChordReport>,
+    pub error: Option<String>,
+}
+
+pub fn identify_batch(contents: &str, aliases: &Aliases, options: ParseOptions) -> Vec<BatchEntry> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, input)| {
+            let (chord, error) = match identify_line(input, aliases, options) {
+                Ok(chord) => (Some(ChordReport::from(&chord)), None),
+                Err(e) => (None, Some(e)),
+            };
+            BatchEntry {
+                line,
+                input: input.to_string(),
+                chord,
+                error,
+            }
+        })
+        .collect()
+}
+
+fn identify_line(input: &str, aliases: &Aliases, options: ParseOptions) -> Result<Chord, String> {
+    let readings = match is_note_list(input) {
+        false => {
+            return identify_from_name_as(input.to_string(), aliases, options)
+                .map_err(|e| e.message())
+        }
+        true if input.starts_with(|c: char| c.is_ascii_digit()) => {
+            identify_from_pitches(&identify_midi_pitches(input).map_err(|e| e.message())?)
+        }
+        true => identify_from_notes(&identify_notes(input).map_err(|e| e.message())?),
+    };
+
+    readings
+        .into_iter()
+        .next()
+        .ok_or_else(|| "no chord found in those notes".to_string())
+}
+
+// more than one thing on the line and every one of them a note, or every one a number
+pub fn is_note_list(input: &str) -> bool {
+    let parts: Vec<&str> = input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    parts.len() > 1
+        && (identify_notes(input).is_ok()
+            || parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit())))
+}
+
+pub fn to_json(entries: &[BatchEntry]) -> Result<String, BatchError> {
+    serde_json::to_string_pretty(entries).map_err(|e| BatchError::Serialize(e.to_string()))
+}
+
+pub fn to_yaml(entries: &[BatchEntry]) -> Result<String, BatchError> {
+    serde_yaml::to_string(entries).map_err(|e| BatchError::Serialize(e.to_string()))
+}
+
+// the chord's columns are left empty on a line that didn't read, the error empty on one that did
+pub fn to_csv(entries: &[BatchEntry]) -> String {
+    let rows = entries.iter().map(|entry| {
+        let chord = entry.chord.as_ref();
+        let field = |f: fn(&ChordReport) -> String| chord.map(f).unwrap_or_default();
+        [
+            entry.line.to_string(),
+            entry.input.clone(),
+            field(|c| c.symbol.clone()),
+            field(|c| c.root.clone()),
+            field(|c| c.bass.clone().unwrap_or_default()),
+            field(|c| c.quality.clone()),
+            field(|c| c.notes.join(" ")),
+            field(|c| c.intervals.join(" ")),
+            field(|c| c.extensions.join(" ")),
+            field(|c| c.alternatives.join(" ")),
+            entry.error.clone().unwrap_or_default(),
+        ]
+    });
+
+    catalog::to_csv(
+        &[
+            "line",
+            "input",
+            "symbol",
+            "root",
+            "bass",
+            "quality",
+            "notes",
+            "intervals",
+            "extensions",
+            "alternatives",
+            "error",
+        ],
+        rows,
+    )
+}
+
+// the lines that read with their chords and the ones that didn't with why, in columns
+pub fn to_table(entries: &[BatchEntry]) -> String {
+    let rows = entries.iter().map(|entry| {
+        let chord = entry.chord.as_ref();
+        let field = |f: fn(&ChordReport) -> String| chord.map(f).unwrap_or_default();
+        [
+            entry.line.to_string(),
+            entry.input.clone(),
+            field(|c| c.symbol.clone()),
+            field(|c| c.quality.clone()),
+            field(|c| c.intervals.join(", ")),
+            field(|c| c.notes.join(" ")),
+            entry.error.clone().unwrap_or_default(),
+        ]
+    });
+
+    catalog::to_table(
+        &[
+            "Line",
+            "Input",
+            "Name",
+            "Quality",
+            "Intervals",
+            "Notes",
+            "Error",
+        ],
+        rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "# a few chords
+Gm7
+C E G A
+
+Gmn7
+60 64 67 74
+60 64 200";
+
+    fn batch() -> Vec<BatchEntry> {
+        identify_batch(FILE, &Aliases::default(), ParseOptions::default())
+    }
+
+    //
+    // identify_batch
+    //
+
+    #[test]
+    fn test_identify_batch_lines() {
+        let ret = batch();
+
+        assert_eq!(
+            ret.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3, 5, 6, 7]
+        );
+        assert_eq!(
+            ret.iter()
+                .map(|e| e.chord.as_ref().map(|c| c.symbol.as_str()))
+                .collect::<Vec<_>>(),
+            vec![Some("Gm7"), Some("C6"), None, Some("Cadd9"), None]
+        );
+    }
+
+    #[test]
+    fn test_identify_batch_errors_carry_on() {
+        let ret = batch();
+
+        assert_eq!(
+            ret[2].error.as_deref(),
+            Some("couldn't make sense of n, did you mean Gm7?")
+        );
+        assert_eq!(ret[4].error.as_deref(), Some("200 isn't a note"));
+    }
+
+    //
+    // to_csv
+    //
+
+    #[test]
+    fn test_to_csv() {
+        let ret = to_csv(&batch());
+        let lines: Vec<&str> = ret.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].starts_with("2,Gm7,Gm7,G,,Minor 7th,"));
+        assert_eq!(lines[5], "7,60 64 200,,,,,,,,,200 isn't a note");
+    }
+}
@@ -0,0 +1,492 @@
+use serde::Serialize;
+use std::fmt;
+
+use crate::{
+    parser::chord_parser::identify_from_name,
+    theory::{
+        chord::Chord,
+        chord_scale::ChordScale,
+        error::ChordParseError,
+        interval::OCTAVE,
+        scale::SCALES,
+        spelling::{spell_chord, spell_name},
+    },
+};
+
+// every chord and scale struck knows how to build, on every root, for anything that wants struck's chords
+// without linking the crate (struck dict --format json, struck scales --format csv)
+// not to be mixed up with the dictionary of voicings the user has saved
+
+// what goes after the root, the plain major triad first
+pub const CATALOG_QUALITIES: [&str; 32] = [
+    "", "m", "5", "dim", "aug", "sus2", "sus4", "6", "m6", "6add9", "add9", "madd9", "7", "maj7",
+    "m7", "mmaj7", "dim7", "m7b5", "aug7", "7sus4", "7b9", "7#9", "7#11", "9", "maj9", "m9", "11",
+    "m11", "13", "maj13", "m13", "7b5",
+];
+
+// the other ways the same symbol is written, each swapped in for the first of what it stands for
+// min or − for m, M or Δ for maj, ° for dim, + for aug and ø for m7b5
+const SYMBOL_SPELLINGS: [(&str, &[&str]); 5] = [
+    ("m7b5", &["ø7", "ø"]),
+    ("maj", &["M", "Δ"]),
+    ("m", &["min", "−"]),
+    ("dim", &["°"]),
+    ("aug", &["+"]),
+];
+
+// bumped whenever an entry changes shape so anything reading the file can tell
+const CATALOG_VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum CatalogError {
+    Chord(ChordParseError),
+    Serialize(String),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogError::Chord(e) => write!(f, "{}", e),
+            CatalogError::Serialize(e) => write!(f, "couldn't write the output: {}", e),
+        }
+    }
+}
+
+impl From<ChordParseError> for CatalogError {
+    fn from(e: ChordParseError) -> Self {
+        CatalogError::Chord(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogEntry {
+    pub symbol: String,
+    pub root: String,
+    pub quality: String,
+    pub notes: Vec<String>,
+    pub intervals: Vec<String>,
+    // what's past the triad, 7 b9 or add9
+    pub extensions: Vec<String>,
+    // the same chord written other ways, Cm's Cmin and C−
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Catalog {
+    pub version: u32,
+    pub chords: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    // roots up from C, each with every quality in turn
+    pub fn build() -> Result<Catalog, CatalogError> {
+        let mut chords: Vec<CatalogEntry> = vec![];
+
+        for root in OCTAVE {
+            for quality in CATALOG_QUALITIES {
+                // the root's a sharp as it's put together, so it's spelled as if worked out, Bb7 rather than A#7
+                let chord = Chord {
+                    written: false,
+                    ..identify_from_name(format!("{}{}", root, quality))?
+                };
+                let spelled = spell_chord(&chord, None);
+                let symbol = spell_name(&chord, None);
+                let root = spelled.first().map_or(root.to_string(), |r| r.to_string());
+
+                chords.push(CatalogEntry {
+                    aliases: aliases(&root, quality, &chord),
+                    symbol,
+                    root,
+                    quality: chord.chord_quality().to_string(),
+                    notes: spelled.iter().map(|n| n.to_string()).collect(),
+                    intervals: chord.intervals.iter().map(|i| i.to_string()).collect(),
+                    extensions: chord.extensions.parts(),
+                });
+            }
+        }
+
+        Ok(Catalog {
+            version: CATALOG_VERSION,
+            chords,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, CatalogError> {
+        serde_json::to_string_pretty(self).map_err(|e| CatalogError::Serialize(e.to_string()))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, CatalogError> {
+        serde_yaml::to_string(self).map_err(|e| CatalogError::Serialize(e.to_string()))
+    }
+
+    pub fn to_csv(&self) -> String {
+        let rows = self.chords.iter().map(|entry| {
+            [
+                entry.symbol.clone(),
+                entry.root.clone(),
+                entry.quality.clone(),
+                entry.notes.join(" "),
+                entry.intervals.join(" "),
+                entry.extensions.join(" "),
+                entry.aliases.join(" "),
+            ]
+        });
+
+        to_csv(
+            &[
+                "symbol",
+                "root",
+                "quality",
+                "notes",
+                "intervals",
+                "extensions",
+                "aliases",
+            ],
+            rows,
+        )
+    }
+
+    pub fn to_table(&self) -> String {
+        let rows = self.chords.iter().map(|entry| {
+            [
+                entry.symbol.clone(),
+                entry.quality.clone(),
+                entry.intervals.join(", "),
+                entry.notes.join(" "),
+            ]
+        });
+
+        to_table(&["Name", "Quality", "Intervals", "Notes"], rows)
+    }
+}
+
+// every other way of writing the quality that reads back as the same chord
+fn aliases(root: &str, quality: &str, chord: &Chord) -> Vec<String> {
+    SYMBOL_SPELLINGS
+        .iter()
+        .filter(|(written, _)| quality.contains(written))
+        .flat_map(|(written, others)| {
+            others
+                .iter()
+                .map(move |other| quality.replacen(written, other, 1))
+        })
+        .map(|other| format!("{}{}", root, other))
+        .filter(|alias| {
+            identify_from_name(alias.clone())
+                .is_ok_and(|c| c.notes == chord.notes && c.bass == chord.bass)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleCatalogEntry {
+    pub tonic: String,
+    pub scale: String,
+    pub notes: Vec<String>,
+    pub formula: Vec<String>,
+    // a triad and a 7th chord on each degree in turn
+    pub triads: Vec<String>,
+    pub sevenths: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleCatalog {
+    pub version: u32,
+    pub scales: Vec<ScaleCatalogEntry>,
+}
+
+impl ScaleCatalog {
+    // tonics up from C, each with every scale in turn
+    pub fn build() -> ScaleCatalog {
+        let mut scales: Vec<ScaleCatalogEntry> = vec![];
+
+        for tonic in OCTAVE {
+            for scale in SCALES {
+                // spelled the way the scale is written, F major's Bb and C minor's Eb Ab and Bb
+                let chord_scale = ChordScale { root: tonic, scale };
+                let key = chord_scale.key();
+                let notes: Vec<String> = chord_scale
+                    .spelled_notes()
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect();
+                let names = |size: usize| -> Vec<String> {
+                    scale
+                        .diatonic_chords(&tonic, size)
+                        .iter()
+                        .map(|c| spell_name(c, key.as_ref()))
+                        .collect()
+                };
+
+                scales.push(ScaleCatalogEntry {
+                    tonic: notes.first().cloned().unwrap_or(tonic.to_string()),
+                    scale: scale.to_string(),
+                    notes,
+                    formula: scale.formula(),
+                    triads: names(3),
+                    sevenths: names(4),
+                });
+            }
+        }
+
+        ScaleCatalog {
+            version: CATALOG_VERSION,
+            scales,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, CatalogError> {
+        serde_json::to_string_pretty(self).map_err(|e| CatalogError::Serialize(e.to_string()))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, CatalogError> {
+        serde_yaml::to_string(self).map_err(|e| CatalogError::Serialize(e.to_string()))
+    }
+
+    pub fn to_csv(&self) -> String {
+        let rows = self.scales.iter().map(|entry| {
+            [
+                entry.tonic.clone(),
+                entry.scale.clone(),
+                entry.notes.join(" "),
+                entry.formula.join(" "),
+                entry.triads.join(" "),
+                entry.sevenths.join(" "),
+            ]
+        });
+
+        to_csv(
+            &["tonic", "scale", "notes", "formula", "triads", "sevenths"],
+            rows,
+        )
+    }
+
+    pub fn to_table(&self) -> String {
+        let rows = self.scales.iter().map(|entry| {
+            [
+                format!("{} {}", entry.tonic, entry.scale),
+                entry.formula.join(" "),
+                entry.notes.join(" "),
+            ]
+        });
+
+        to_table(&["Scale", "Formula", "Notes"], rows)
+    }
+}
+
+// a header then one entry a row, lists inside a field are space separated so every row has the same columns
+pub(crate) fn to_csv<const N: usize>(
+    header: &[&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) -> String {
+    let mut csv = header.join(",");
+    csv.push('\n');
+
+    for row in rows {
+        csv.push_str(
+            &row.iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+
+    csv
+}
+
+// the same again lined up in columns for reading in a terminal, a rule under the header
+// and each column as wide as its widest field
+pub(crate) fn to_table<const N: usize>(
+    header: &[&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) -> String {
+    let rows: Vec<[String; N]> = std::iter::once(header.map(String::from))
+        .chain(rows)
+        .collect();
+
+    let mut widths = [0; N];
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+
+    let line = |fields: Vec<String>| {
+        fields
+            .iter()
+            .zip(widths)
+            .map(|(field, width)| format!("{:<width$}", field, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut table = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        table.push_str(&line(row.to_vec()));
+        table.push('\n');
+        if index == 0 {
+            table.push_str(&line(widths.iter().map(|w| "-".repeat(*w)).collect()));
+            table.push('\n');
+        }
+    }
+
+    table
+}
+
+// quoted only when it has to be, with any quotes inside doubled up
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // build
+    //
+
+    #[test]
+    fn test_build_every_root_and_quality() {
+        let ret = Catalog::build().expect("hmm");
+
+        assert_eq!(ret.chords.len(), 12 * CATALOG_QUALITIES.len());
+        assert_eq!(ret.chords[0].symbol, "C");
+        assert_eq!(ret.chords[0].notes, vec!["C", "E", "G"]);
+
+        let c7b9 = ret.chords.iter().find(|c| c.symbol == "C7b9").expect("hmm");
+        assert_eq!(c7b9.extensions, vec!["7", "b9"]);
+
+        let bb7 = ret.chords.iter().find(|c| c.symbol == "Bb7").expect("hmm");
+        assert_eq!(bb7.root, "Bb");
+        assert_eq!(bb7.notes, vec!["Bb", "D", "F", "Ab"]);
+
+        let cm = ret.chords.iter().find(|c| c.symbol == "Cm").expect("hmm");
+        assert_eq!(cm.notes, vec!["C", "Eb", "G"]);
+
+        let mut symbols: Vec<&String> = ret.chords.iter().map(|c| &c.symbol).collect();
+        symbols.sort();
+        symbols.dedup();
+        assert_eq!(symbols.len(), ret.chords.len());
+    }
+
+    #[test]
+    fn test_build_aliases() {
+        let ret = Catalog::build().expect("hmm");
+        let aliases = |symbol: &str| {
+            ret.chords
+                .iter()
+                .find(|c| c.symbol == symbol)
+                .expect("hmm")
+                .aliases
+                .clone()
+        };
+
+        assert_eq!(aliases("Cm"), vec!["Cmin", "C−"]);
+        assert_eq!(aliases("Cmaj7"), vec!["CM7", "CΔ7"]);
+        assert_eq!(aliases("Cm7b5"), vec!["Cø7", "Cø", "Cmin7b5", "C−7b5"]);
+        assert!(aliases("C7").is_empty());
+    }
+
+    //
+    // to_json
+    //
+
+    #[test]
+    fn test_to_json_includes_aliases() {
+        let catalog = Catalog {
+            version: CATALOG_VERSION,
+            chords: vec![CatalogEntry {
+                symbol: "Am7".to_string(),
+                root: "A".to_string(),
+                quality: "Minor".to_string(),
+                notes: vec![],
+                intervals: vec![],
+                extensions: vec!["7".to_string()],
+                aliases: vec!["Amin7".to_string()],
+            }],
+        };
+
+        let ret = catalog.to_json().expect("hmm");
+
+        assert!(ret.starts_with("{\n  \"version\": 2,"));
+        assert!(ret.contains("\"symbol\": \"Am7\""));
+        assert!(ret.contains("\"Amin7\""));
+    }
+
+    //
+    // ScaleCatalog::build
+    //
+
+    #[test]
+    fn test_scale_catalog_every_tonic_and_scale() {
+        let ret = ScaleCatalog::build();
+
+        assert_eq!(ret.scales.len(), 12 * SCALES.len());
+
+        let a_minor = ret
+            .scales
+            .iter()
+            .find(|s| s.tonic == "A" && s.scale == "Natural Minor")
+            .expect("hmm");
+        assert_eq!(a_minor.notes.join(" "), "A B C D E F G");
+        assert_eq!(a_minor.triads.join(" "), "Am Bdim C Dm Em F G");
+    }
+
+    #[test]
+    fn test_scale_catalog_spelled() {
+        let ret = ScaleCatalog::build();
+        let find = |tonic: &str, scale: &str| {
+            ret.scales
+                .iter()
+                .find(|s| s.tonic == tonic && s.scale == scale)
+                .expect("hmm")
+        };
+
+        assert_eq!(find("F", "Major").notes.join(" "), "F G A Bb C D E");
+
+        let c_minor = find("C", "Natural Minor");
+        assert_eq!(c_minor.notes.join(" "), "C D Eb F G Ab Bb");
+        assert_eq!(c_minor.triads.join(" "), "Cm Ddim Eb Fm Gm Ab Bb");
+
+        assert_eq!(find("Bb", "Major").sevenths[0], "Bbmaj7");
+    }
+
+    //
+    // ScaleCatalog::to_csv
+    //
+
+    #[test]
+    fn test_scale_catalog_to_csv() {
+        let ret = ScaleCatalog::build().to_csv();
+        let mut lines = ret.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("tonic,scale,notes,formula,triads,sevenths")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("C,Major,C D E F G A B,1 2 3 4 5 6 7,C Dm Em F G Am Bdim,Cmaj7 Dm7 Em7 Fmaj7 G7 Am7 Bm7b5")
+        );
+        assert_eq!(lines.count(), 12 * SCALES.len() - 1);
+    }
+
+    //
+    // csv_field
+    //
+
+    #[test]
+    fn test_csv_field_quotes_when_needed() {
+        assert_eq!(csv_field("C E G"), "C E G");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,19 +1,126 @@
-use dialoguer::{theme::ColorfulTheme, Input, Select};
+use dialoguer::{theme::ColorfulTheme, BasicHistory, Input, InputValidator, Select};
+use itertools::Itertools;
+use std::{
+    fmt,
+    io::{self, BufRead, IsTerminal, Write},
+    num::ParseIntError,
+    path::Path,
+    str::FromStr,
+    sync::OnceLock,
+};
 
 use crate::{
-    parser::{self, chord_parser::identify_from_root_and_notes},
+    output::{format, shows, Format, Options, OutputLevel},
+    timing::{timed, Phase},
+};
+
+use struck::{
+    aliases::{AliasError, Aliases},
+    batch::{self, identify_batch, is_note_list},
+    catalog::{Catalog, ScaleCatalog},
+    dictionary::{ChordDictionary, DictionaryError},
+    drill::{DrillQuestion, DrillTier, DRILL_TIERS},
+    instrument::{
+        fingering::{find_fingerings, identify_fingering, FingeringStrategy},
+        fretboard::{Capo, Fingering, FretboardError, Instrument, Tuning, GUITAR_TUNINGS},
+    },
+    midi_export::{
+        AbComparison, MidiExportError, PracticeTrack, ACCENTS, DRUM_STYLES, ENVELOPES, PRESETS,
+    },
+    parser::{
+        chord_parser::{
+            enumerate_chord_names, identify_all_from_name, identify_from_name_as,
+            identify_from_notes, identify_from_pitches, identify_polychord, score_chord,
+            split_polychord, ParseOptions,
+        },
+        chordpro_parser::{transpose_chordpro, ChordProError, Song},
+        note_parser::{identify_midi_pitches, identify_notes},
+        progression_parser::identify_progression,
+        roman_parser::{identify_from_nashville, identify_from_roman, is_nashville},
+    },
+    render::{
+        choir::render_choir_voices,
+        chord_info::render_chord_info,
+        circle::render_circle_of_fifths,
+        fretboard::{render_chord_diagram, render_neck},
+        keyboard::render_keyboard,
+        timeline::render_timeline,
+    },
+    report::{self, ChordReport, ReportError},
+    session::{Session, SessionError},
     theory::{
-        self,
-        chord::ChordQuality,
-        error::{ChordParseError, NoteParseError},
+        cadence::{find_cadences, CadenceMatch},
+        chord::{Chord, ChordStructure, Inversion},
+        chord_scale::{chord_scales, scales_containing, ChordScale},
+        degree_name::describe_degree,
+        error::{ChordParseError, KeyParseError, NoteParseError, ScaleParseError, Span},
+        harmonic_function::harmonic_function,
+        inversion_plan::plan_inversions,
+        key::Key,
+        key_finder::{find_keys, find_modulations, key_confidence, KeyMatch},
+        melody_voicing::voice_under_melody,
+        morph::morph,
+        naming::{name_chord_as, pretty_symbol, TensionReading},
+        nashville::nashville_number,
+        negative_harmony::negative_progression,
         note::Note,
+        pitch_class::PitchClass,
+        polychord::Polychord,
+        progression::Progression,
+        progression_fill::{fill_progression, FillConstraints, FillError},
+        roman_numeral::{roman_numeral, Origin},
+        scale::Scale,
+        spelling::{spell_chord, spell_key, spell_name, spell_reading, spell_voicing, SpelledNote},
+        template::{Template, TEMPLATES},
+        transpose::transpose_chord_diatonic,
+        voice_leading::{lead_voices, smooth_voicings},
+        voicing::Voicing,
     },
 };
 
+const TIMELINE_WIDTH: usize = 60;
+const FINGERINGS_SHOWN: usize = 8;
+const SHAPES_SHOWN: usize = 3;
+const NECK_FRETS: u8 = 12;
+const HISTORY_KEPT: usize = 200;
+
+const REPL_HELP: &str =
+    "a chord name (Gm7, ii in C, C/E|G), some notes (C E G, or midi numbers 60 64 67) or
+  neck <chord or scale>  where it all is on the fretboard
+  scale <root and name>  the notes and steps of a scale, e.g. scale D dorian
+  help                   this again
+  quit                   done (or ctrl-d)
+up and down go back through what's been typed";
+const ALTERNATIVES_SHOWN: usize = 3;
+const KEYS_SHOWN: usize = 3;
+
 pub fn handle_menu() {
     let items = vec![
         "Information on a known chord",
         "Create chord from notes",
+        "Replay a recorded session",
+        "Choir view of a chord in a key",
+        "Transpose a chord within a key",
+        "Piano inversion plan for a progression",
+        "Voice a chord under a melody note",
+        "Guitar fingerings for a chord",
+        "Identify a guitar fingering",
+        "Save a played voicing to your dictionary",
+        "Compare two chords",
+        "Information on a scale",
+        "Circle of fifths around a key",
+        "Export a progression as practice MIDI",
+        "Morph one chord into another",
+        "What key is this progression in?",
+        "Scales over a chord",
+        "Scales with these notes",
+        "Analyze a progression",
+        "Transpose a progression",
+        "Smooth voicings for a progression",
+        "Progression from a template",
+        "Negative harmony of a progression",
+        "Import a ChordPro file",
+        "Interval drill",
         "Quit",
     ];
 
@@ -32,26 +139,598 @@ pub fn handle_menu() {
                     0 => {
                         let chord_name: String = Input::new()
                             .with_prompt("Enter chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let key: String = Input::new()
+                            .with_prompt("Key to analyse it in e.g. C or F#m (blank for none) ")
+                            .allow_empty(true)
                             .interact_text()
                             .expect(""); // TODO: probably won't panic
 
-                        match identify_notes_from_chord_name(chord_name) {
+                        let key = match key.trim() {
+                            "" => None,
+                            key => match Key::from_str(key) {
+                                Ok(key) => Some(key),
+                                Err(e) => {
+                                    print_caught(&e);
+                                    continue;
+                                }
+                            },
+                        };
+
+                        match identify_notes_from_chord_name(chord_name, key) {
                             Ok(()) => (),
-                            Err(e) => println!("caught error: {:?}", e),
+                            Err(e) => print_caught(&e),
                         }
                     }
                     1 => {
                         let notes_raw: String = Input::new()
-                            .with_prompt("Enter notes seperated by space e.g. A# B C ")
+                            .with_prompt("Enter notes seperated by spaces or commas e.g. A# B C, or midi numbers e.g. 60 64 67 70 ")
+                            .validate_with(NotesInput { midi: true })
                             .interact_text()
                             .expect(""); // TODO: probably won't panic
 
                         match identify_chord_from_notes(notes_raw) {
                             Ok(()) => (),
-                            Err(e) => println!("caught error: {:?}", e),
+                            Err(e) => print_caught(&e),
                         }
                     }
                     2 => {
+                        let path: String = Input::new()
+                            .with_prompt("Enter session file path ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let window_ms: u64 = Input::new()
+                            .with_prompt("Window size in milliseconds ")
+                            .default(250)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match replay_session(path, window_ms) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    3 => {
+                        let key: String = Input::new()
+                            .with_prompt("Enter the key's tonic e.g. C ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let chord_name: String = Input::new()
+                            .with_prompt("Enter chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_choir_voices(key, chord_name) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    4 => {
+                        let key: String = Input::new()
+                            .with_prompt("Enter the key's tonic e.g. C ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let chord_name: String = Input::new()
+                            .with_prompt("Enter chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let degrees: i32 = Input::new()
+                            .with_prompt("Scale degrees to move by (negative for down) ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match transpose_chord_in_key(key, chord_name, degrees) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    5 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names seperated by space e.g. C Am F G ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let melody: String = Input::new()
+                            .with_prompt(
+                                "Melody note to keep on top of each chord (blank for smoothest) ",
+                            )
+                            .allow_empty(true)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_inversion_plan(progression, melody) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    6 => {
+                        let chord_name: String = Input::new()
+                            .with_prompt("Enter chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let melody: String = Input::new()
+                            .with_prompt("Enter the melody note to put on top ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_melody_voicings(chord_name, melody) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    7 => {
+                        let chord_name: String = Input::new()
+                            .with_prompt("Enter chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let strategies = vec!["Open strings", "Compact"];
+                        let strategy = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Prefer ")
+                            .default(0)
+                            .items(&strategies)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(1) => FingeringStrategy::Compact,
+                            _ => FingeringStrategy::OpenStrings,
+                        };
+
+                        let capo: String = Input::new()
+                            .with_prompt(
+                                "Capo fret, optionally on some strings e.g. 2 or 2:3-5 (blank for none) ",
+                            )
+                            .allow_empty(true)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_guitar_fingerings(chord_name, strategy, capo) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    8 => {
+                        let fingering: String = Input::new()
+                            .with_prompt("Enter a fingering from the lowest string e.g. x32010 or 8-10-10-9-8-8 or 8 10 10 9 8 8 ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let tunings: Vec<&str> = GUITAR_TUNINGS.iter().map(|(n, _)| *n).collect();
+                        let tuning = Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Tuning ")
+                            .default(0)
+                            .items(&tunings)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                            .and_then(|i| Tuning::guitar(tunings[i]))
+                            .unwrap_or_else(Tuning::standard_guitar);
+
+                        match print_fingering_chords(fingering, tuning) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    9 => {
+                        let path: String = Input::new()
+                            .with_prompt("Enter session file path with the voicing played ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let name: String = Input::new()
+                            .with_prompt("Save it as ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match save_played_voicing(path, name) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    10 => {
+                        let first: String = Input::new()
+                            .with_prompt("Enter the first chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let second: String = Input::new()
+                            .with_prompt("Enter the second chord name ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match compare_chords(first.clone(), second.clone()) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+
+                        let path: String = Input::new()
+                            .with_prompt(
+                                "Save an A/B MIDI file to hear them e.g. ab.mid (blank to skip) ",
+                            )
+                            .allow_empty(true)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+                        if path.trim().is_empty() {
+                            continue;
+                        }
+
+                        let first_style = match select_voicing_style(&first) {
+                            Some(index) => index,
+                            None => continue,
+                        };
+                        let second_style = match select_voicing_style(&second) {
+                            Some(index) => index,
+                            None => continue,
+                        };
+
+                        let gap: u32 = Input::new()
+                            .with_prompt("Beats of silence between them ")
+                            .default(1)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match export_ab_comparison(
+                            first,
+                            first_style,
+                            second,
+                            second_style,
+                            gap,
+                            path,
+                        ) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    11 => {
+                        let scale: String = Input::new()
+                            .with_prompt(
+                                "Enter the scale e.g. C harmonic minor, F# dorian or Bb blues ",
+                            )
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_scale_info(scale) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    12 => {
+                        let key: String = Input::new()
+                            .with_prompt("Enter the key e.g. C, F#m or Bb major ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_circle_of_fifths(key) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    13 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names seperated by space e.g. C Am F G ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let tempo: u32 = Input::new()
+                            .with_prompt("Tempo in beats per minute ")
+                            .default(100)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let mut styles = vec!["none".to_string()];
+                        styles.extend(DRUM_STYLES.iter().map(|s| s.to_string()));
+                        let drums = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Drums ")
+                            .default(0)
+                            .items(&styles)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(0) => None,
+                            Some(index) => Some(DRUM_STYLES[index - 1]),
+                            None => continue,
+                        };
+
+                        let preset_names: Vec<String> =
+                            PRESETS.iter().map(|p| p.to_string()).collect();
+                        let preset = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Play the chords on ")
+                            .default(0)
+                            .items(&preset_names)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(index) => PRESETS[index],
+                            None => continue,
+                        };
+
+                        let accent_names: Vec<String> =
+                            ACCENTS.iter().map(|a| a.to_string()).collect();
+                        let accent = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Voices to bring out ")
+                            .default(3)
+                            .items(&accent_names)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(index) => ACCENTS[index],
+                            None => continue,
+                        };
+
+                        let envelope_names: Vec<String> =
+                            ENVELOPES.iter().map(|e| e.to_string()).collect();
+                        let envelope = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Chord envelope ")
+                            .default(0)
+                            .items(&envelope_names)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(index) => ENVELOPES[index],
+                            None => continue,
+                        };
+
+                        let path: String = Input::new()
+                            .with_prompt("Save to e.g. practice.mid ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let practice = PracticeTrack::new(vec![], tempo, drums)
+                            .with_dynamics(accent, envelope)
+                            .with_preset(preset);
+
+                        match export_practice_midi(progression, practice, path) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    14 => {
+                        let from: String = Input::new()
+                            .with_prompt("Enter the chord to start from ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let to: String = Input::new()
+                            .with_prompt("Enter the chord to end on ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let path: String = Input::new()
+                            .with_prompt("Save it as practice MIDI e.g. morph.mid (blank to skip) ")
+                            .allow_empty(true)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_morph(from, to, path) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    15 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names seperated by space e.g. C Am F G ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_likely_keys(progression) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    16 => {
+                        let chord_name: String = Input::new()
+                            .with_prompt("Enter a chord name e.g. G7 or G7alt ")
+                            .validate_with(ChordNameInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_chord_scales(chord_name) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    17 => {
+                        let notes: String = Input::new()
+                            .with_prompt(
+                                "Enter notes seperated by spaces or commas e.g. A C D D# E G ",
+                            )
+                            .validate_with(NotesInput { midi: false })
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_scales_containing(notes) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    18 => {
+                        let progression: String = Input::new()
+                            .with_prompt(
+                                "Enter chord names e.g. C Am F G or | Cmaj7 | Am7 | Dm7 G7 | ",
+                            )
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match analyze_progression(progression) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    19 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names e.g. C Am/E F G7 ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let target: String = Input::new()
+                            .with_prompt(
+                                "Semitones to move it by e.g. -2, or a key to move it to e.g. Eb ",
+                            )
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let target = match target.trim().parse::<i32>() {
+                            Ok(semitones) => Transposition::By(semitones),
+                            Err(_) => match Key::from_str(target.trim()) {
+                                Ok(key) => Transposition::To(key),
+                                Err(e) => {
+                                    print_caught(&e);
+                                    continue;
+                                }
+                            },
+                        };
+
+                        match transpose_progression(progression, target) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    20 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names e.g. C Am F G7 ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        match print_smooth_voicings(progression) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    21 => {
+                        let template = Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Which progression ")
+                            .default(0)
+                            .items(TEMPLATES.map(|t| t.name))
+                            .interact_opt()
+                            .expect(""); // TODO: probably won't panic
+                        let Some(template) = template else {
+                            continue;
+                        };
+
+                        let key: String = Input::new()
+                            .with_prompt("Key to play it in e.g. Bb or F#m ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let key = match Key::from_str(key.trim()) {
+                            Ok(key) => key,
+                            Err(e) => {
+                                print_caught(&e);
+                                continue;
+                            }
+                        };
+
+                        match print_template(TEMPLATES[template], key) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    22 => {
+                        let progression: String = Input::new()
+                            .with_prompt("Enter chord names e.g. C Am F G7 ")
+                            .validate_with(ProgressionInput)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let key: String = Input::new()
+                            .with_prompt(
+                                "Key to mirror it in e.g. C or F#m (blank to work it out) ",
+                            )
+                            .allow_empty(true)
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let key = match key.trim() {
+                            "" => None,
+                            key => match Key::from_str(key) {
+                                Ok(key) => Some(key),
+                                Err(e) => {
+                                    print_caught(&e);
+                                    continue;
+                                }
+                            },
+                        };
+
+                        match print_negative_harmony(progression, key) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    23 => {
+                        let path: String = Input::new()
+                            .with_prompt("Path to a ChordPro file e.g. song.cho ")
+                            .interact_text()
+                            .expect(""); // TODO: probably won't panic
+
+                        let reports = vec!["Key", "Chord diagrams", "Transposed"];
+                        let report = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Report ")
+                            .default(0)
+                            .items(&reports)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(1) => SongReport::Diagrams,
+                            Some(2) => {
+                                let semitones: String = Input::new()
+                                    .with_prompt("Semitones to move it by e.g. -2 ")
+                                    .interact_text()
+                                    .expect(""); // TODO: probably won't panic
+
+                                match semitones.trim().parse::<i32>() {
+                                    Ok(semitones) => SongReport::Transposed(semitones),
+                                    Err(e) => {
+                                        print_caught(&e);
+                                        continue;
+                                    }
+                                }
+                            }
+                            _ => SongReport::Key,
+                        };
+
+                        match print_chordpro(path, report) {
+                            Ok(()) => (),
+                            Err(e) => print_caught(&e),
+                        }
+                    }
+                    24 => {
+                        let tiers: Vec<String> =
+                            DRILL_TIERS.iter().map(|t| t.to_string()).collect();
+                        let tier = match Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Intervals to drill ")
+                            .default(0)
+                            .items(&tiers)
+                            .interact_opt()
+                            .expect("") // TODO: probably won't panic
+                        {
+                            Some(index) => DRILL_TIERS[index],
+                            None => continue,
+                        };
+
+                        run_interval_drill(tier);
+                    }
+                    25 => {
                         println!("Goodbye!");
                         break;
                     }
@@ -68,43 +747,2103 @@ pub fn handle_menu() {
     }
 }
 
-fn identify_notes_from_chord_name(chord_name: String) -> Result<(), ChordParseError> {
-    let chord = match parser::chord_parser::identify_from_name(chord_name) {
-        Ok(res) => res,
-        Err(_) => {
-            return Err(ChordParseError::InvalidChordName(
-                "error identifying from name".to_string(),
-            ))
+// struck repl, a prompt that reads each line as a chord name, a list of notes or one of a few commands
+// and prints what the menu would, without going back through the menu every time
+// up and down step through the lines typed so far, and piped in it just reads lines with no prompt
+pub fn handle_repl(instrument: Option<&Instrument>) {
+    let interactive = io::stdin().is_terminal();
+    let mut history = BasicHistory::new()
+        .max_entries(HISTORY_KEPT)
+        .no_duplicates(true);
+    let mut piped = io::stdin().lock().lines().map_while(Result::ok);
+
+    if interactive {
+        println!("{}", REPL_HELP);
+    }
+
+    loop {
+        let line: String = match interactive {
+            true => match Input::new()
+                .with_prompt("struck")
+                .allow_empty(true)
+                .history_with(&mut history)
+                .interact_text()
+            {
+                Ok(line) => line,
+                // ctrl-d or the terminal going away
+                Err(_) => break,
+            },
+            false => match piped.next() {
+                Some(line) => line,
+                None => break,
+            },
+        };
+
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" | "?" => println!("{}", REPL_HELP),
+            "neck" => {
+                if let Err(e) = print_neck(rest, instrument) {
+                    println!("{}", e);
+                }
+            }
+            "scale" => {
+                if let Err(e) = print_scale_info(rest.to_string()) {
+                    print_caught(&e);
+                }
+            }
+            _ if split_polychord(line).is_some() => {
+                if let Err(e) = identify_notes_from_polychord_name(line.to_string()) {
+                    print_caught(&e);
+                }
+            }
+            _ if is_note_list(line) => {
+                if let Err(e) = identify_chord_from_notes(line.to_string()) {
+                    print_caught(&e);
+                }
+            }
+            _ => {
+                if let Err(e) = identify_notes_from_chord_name(line.to_string(), None) {
+                    print_caught(&e);
+                }
+            }
         }
-    };
 
-    println!("{}", chord);
-    Ok(())
+        println!();
+    }
 }
 
-fn identify_chord_from_notes(notes_raw: String) -> Result<(), NoteParseError> {
-    let notes: Vec<theory::note::Note> = notes_raw
-        .split_whitespace()
-        .map(|n| Note::parse(n).unwrap())
-        .collect();
+// questions on intervals one after another until a blank answer, with the score at the end
+fn run_interval_drill(tier: DrillTier) {
+    let mut rng = fastrand::Rng::new();
+    let (mut asked, mut right) = (0, 0);
 
-    let mut possible_chords = vec![];
+    println!("Name the interval (Major 3rd, m3, b3...) or the note. Blank to stop.");
 
-    // for each of the notes treated as the root, get what chords it could be considered
-    notes.iter().for_each(|root: &Note| {
-        let chord = identify_from_root_and_notes(root, &notes);
+    loop {
+        let question = DrillQuestion::random(tier, &mut rng);
+        let answer: String = Input::new()
+            .with_prompt(question.to_string())
+            .allow_empty(true)
+            .interact_text()
+            .expect(""); // TODO: probably won't panic
 
-        if chord.chord_quality != ChordQuality::Ambiguous {
-            possible_chords.push(chord);
+        if answer.trim().is_empty() {
+            break;
         }
-    });
 
-    if possible_chords.len() == 0 {
-        println!("No possible chords found!")
-    } else {
-        println!("Could be: ");
-        possible_chords.iter().for_each(|c| println!("{}", c.name));
+        asked += 1;
+        match question.check(&answer) {
+            true => {
+                right += 1;
+                println!("Right!");
+            }
+            false => println!("No, it's {}", question.answer()),
+        }
+    }
+
+    if asked > 0 {
+        println!("{} of {} right", right, asked);
+    }
+}
+
+// struck dict, every chord on every root in one go for putting in a file
+// written all at once and stopping quietly if what it's piped into stops reading, struck dict | head
+pub fn export_chord_catalog(format: Format) -> Result<(), String> {
+    let catalog = timed(Phase::Derivation, Catalog::build).map_err(|e| e.to_string())?;
+
+    let written = match format {
+        Format::Json => catalog.to_json(),
+        Format::Yaml => catalog.to_yaml(),
+        Format::Csv => Ok(catalog.to_csv()),
+        Format::Table => Ok(catalog.to_table()),
+        Format::Text => Ok(catalog
+            .chords
+            .iter()
+            .map(|chord| format!("{}: {}", chord.symbol, chord.notes.join(" ")))
+            .join("\n")),
+    };
+    let written = written.map_err(|e| e.to_string())?;
+
+    writeln!(io::stdout().lock(), "{}", written.trim_end()).or_else(until_closed)
+}
+
+// struck scales, the same again for every scale on every tonic
+pub fn export_scale_catalog(format: Format) -> Result<(), String> {
+    let catalog = timed(Phase::Derivation, ScaleCatalog::build);
+
+    let written = match format {
+        Format::Json => catalog.to_json(),
+        Format::Yaml => catalog.to_yaml(),
+        Format::Csv => Ok(catalog.to_csv()),
+        Format::Table => Ok(catalog.to_table()),
+        Format::Text => Ok(catalog
+            .scales
+            .iter()
+            .map(|scale| format!("{} {}: {}", scale.tonic, scale.scale, scale.notes.join(" ")))
+            .join("\n")),
+    };
+    let written = written.map_err(|e| e.to_string())?;
+
+    writeln!(io::stdout().lock(), "{}", written.trim_end()).or_else(until_closed)
+}
+
+// struck fill "C ? ? G7 C", every ? filled in from the key
+pub fn fill_gaps(progression: &str, options: &Options) -> Result<(), FillError> {
+    let bars = timed(Phase::Parse, || {
+        progression
+            .split_whitespace()
+            .map(|name| match name {
+                "?" => Ok(None),
+                name => parse_chord_name(name.to_string()).map(Some),
+            })
+            .collect::<Result<Vec<Option<Chord>>, ChordParseError>>()
+    })?;
+
+    let constraints = FillConstraints {
+        key: options.key.as_deref().map(Key::from_str).transpose()?,
+        allowed: options
+            .allow
+            .as_ref()
+            .map(|allow| allow.split(',').map(|q| q.trim().to_string()).collect()),
+        max_borrowed: options.max_borrowed,
+    };
+
+    let (key, chords) = timed(Phase::Analysis, || fill_progression(&bars, &constraints))?;
+
+    if format() != Format::Text {
+        print_formatted(&chords);
+        return Ok(());
+    }
+    println!("{}", chords.iter().map(|c| &c.name).join(" "));
+    if shows(OutputLevel::Normal) {
+        println!(
+            "In {}: {}",
+            key,
+            chords.iter().map(|c| roman_numeral(c, &key)).join(" ")
+        );
+    }
+
+    Ok(())
+}
+
+// struck batch chords.txt, every chord name or note list in the file identified and written out
+// as a report in whichever format, a line that doesn't read is in there with why rather than stopping it
+// exits with an error at the end if any didn't
+pub fn identify_file(path: &str, format: Format) -> Result<(), String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let entries = timed(Phase::Analysis, || {
+        identify_batch(&contents, &load_aliases(), parse_options())
+    });
+
+    let written = match format {
+        Format::Json => batch::to_json(&entries),
+        Format::Yaml => batch::to_yaml(&entries),
+        Format::Csv => Ok(batch::to_csv(&entries)),
+        Format::Table => Ok(batch::to_table(&entries)),
+        Format::Text => Ok(entries
+            .iter()
+            .map(|entry| match (&entry.chord, &entry.error) {
+                (Some(chord), _) if shows(OutputLevel::Normal) => format!(
+                    "{}: {} -> {} ({})",
+                    entry.line,
+                    entry.input,
+                    shown(chord.symbol.clone()),
+                    chord.notes.join(" ")
+                ),
+                (Some(chord), _) => shown(chord.symbol.clone()),
+                (None, error) => format!(
+                    "{}: {} -> error: {}",
+                    entry.line,
+                    entry.input,
+                    error.as_deref().unwrap_or_default()
+                ),
+            })
+            .join("\n")),
+    };
+    let written = written.map_err(|e| e.to_string())?;
+
+    let failed = entries.iter().filter(|e| e.error.is_some()).count();
+    let mut out = io::stdout().lock();
+    let printed = writeln!(out, "{}", written.trim_end()).and_then(|_| {
+        match format == Format::Text && shows(OutputLevel::Normal) {
+            true => writeln!(out, "{} of {} read", entries.len() - failed, entries.len()),
+            false => Ok(()),
+        }
+    });
+    if let Err(e) = printed {
+        return until_closed(e);
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!("{} line(s) of {} couldn't be read", failed, path)),
+    }
+}
+
+// what each line given to struck chord or struck notes is read as
+pub enum LineInput {
+    ChordNames,
+    Notes,
+}
+
+// shown as the menu shows it at the output level, one result a line at brief so it fits in a pipeline, echo Gm7 | struck chord - -o brief
+// a line that doesn't read is reported on stderr and the rest carry on
+// with --instrument each chord is followed by a few shapes for it, drawn as chord boxes past brief
+pub fn identify_lines(
+    input: LineInput,
+    arg: &str,
+    instrument: Option<&Instrument>,
+) -> Result<(), String> {
+    let lines: Box<dyn Iterator<Item = String>> = match arg {
+        "-" => Box::new(io::stdin().lock().lines().map_while(Result::ok)),
+        arg => Box::new(std::iter::once(arg.to_string())),
+    };
+
+    match write_lines(&mut io::stdout().lock(), input, lines, instrument) {
+        Ok(0) => Ok(()),
+        Ok(failed) => Err(format!("{} line(s) couldn't be read", failed)),
+        Err(e) => until_closed(e),
+    }
+}
+
+// a reader that's stopped reading (struck chord - | head -1) has all it wanted, that's not an error
+fn until_closed(e: io::Error) -> Result<(), String> {
+    match e.kind() {
+        io::ErrorKind::BrokenPipe => Ok(()),
+        _ => Err(e.to_string()),
+    }
+}
+
+// each line identified and written out, with how many didn't read
+fn write_lines(
+    out: &mut impl Write,
+    input: LineInput,
+    lines: impl Iterator<Item = String>,
+    instrument: Option<&Instrument>,
+) -> io::Result<usize> {
+    if format() == Format::Csv {
+        writeln!(out, "{}", report::to_csv(&[]).trim_end())?;
+    }
+
+    // a table can only be lined up once every row is in
+    let mut table: Vec<ChordReport> = vec![];
+    let mut failed = 0;
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // the most likely reading of some notes, the others are its alternatives
+        // as text it's written the way the menu shows it, at the same output level
+        // a polychord (D|C) is both its halves, upper first
+        let text = format() == Format::Text;
+        let read = match input {
+            LineInput::ChordNames if split_polychord(line).is_some() => timed(Phase::Parse, || {
+                identify_polychord(line.to_string(), &load_aliases(), parse_options())
+            })
+            .map_err(|e| caught(&e))
+            .map(|polychord| {
+                let lines = match text {
+                    true => describe_polychord(&polychord),
+                    false => vec![],
+                };
+                (vec![polychord.upper, polychord.lower], lines)
+            }),
+            LineInput::ChordNames => timed(Phase::Parse, || parse_chord_name(line.to_string()))
+                .map_err(|e| caught(&e))
+                .map(|chord| {
+                    let lines = match text {
+                        true => describe_chord(&chord, None),
+                        false => vec![],
+                    };
+                    (vec![chord], lines)
+                }),
+            LineInput::Notes => timed(Phase::Parse, || read_notes(line))
+                .map_err(|e| caught(&e))
+                .and_then(|(notes, voicing)| {
+                    let readings = timed(Phase::Analysis, || readings_of(&notes, voicing.as_ref()));
+                    let lines = match text {
+                        true => describe_readings(&notes, voicing.as_ref(), &readings),
+                        false => vec![],
+                    };
+                    readings
+                        .into_iter()
+                        .next()
+                        .map(|chord| (vec![chord], lines))
+                        .ok_or_else(|| format!("{}: no chord found", line))
+                }),
+        };
+
+        match read {
+            Ok((chords, _)) if format() == Format::Table => {
+                table.extend(chords.iter().map(ChordReport::from))
+            }
+            Ok((chords, lines)) => {
+                if text {
+                    lines.iter().try_for_each(|l| writeln!(out, "{}", l))?;
+                }
+                for chord in &chords {
+                    if !text {
+                        write_line(out, chord)?;
+                    }
+                    if let (Some(instrument), true) = (instrument, text) {
+                        write_shapes(out, chord, instrument)?;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    if !table.is_empty() {
+        write!(out, "{}", report::to_table(&table))?;
+    }
+
+    Ok(failed)
+}
+
+// one chord as a line of its own, a row for csv and an item of the one list for yaml
+fn write_line(out: &mut impl Write, chord: &Chord) -> io::Result<()> {
+    let report = ChordReport::from(chord);
+    let written = match format() {
+        Format::Yaml => report::to_yaml(&[report]),
+        Format::Csv => Ok(report::to_csv(&[report])
+            .lines()
+            .skip(1)
+            .collect::<String>()),
+        _ => report::to_json_line(&report),
+    };
+
+    match written {
+        Ok(written) => writeln!(out, "{}", written.trim_end()),
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(())
+        }
+    }
+}
+
+// the best few fingerings of a chord on an instrument in its standard tuning
+fn write_shapes(out: &mut impl Write, chord: &Chord, instrument: &Instrument) -> io::Result<()> {
+    let tuning = instrument.standard_tuning();
+    let fingerings = timed(Phase::Derivation, || {
+        find_fingerings(chord, &tuning, FingeringStrategy::OpenStrings)
+    });
+
+    if fingerings.is_empty() {
+        writeln!(
+            out,
+            "No playable {} fingerings found for {}!",
+            instrument.name, chord.name
+        )?;
+    }
+
+    for fingering in fingerings.iter().take(SHAPES_SHOWN) {
+        match shows(OutputLevel::Normal) {
+            true => writeln!(
+                out,
+                "{}\n{}\n",
+                fingering,
+                render_chord_diagram(fingering, &tuning)
+            )?,
+            false => writeln!(out, "{}", fingering)?,
+        }
+    }
+
+    Ok(())
+}
+
+// struck neck Am7 or struck neck "A blues", where the chord tones or scale notes are
+// on every string up to the 12th fret, on a guitar in standard tuning unless --instrument says otherwise
+// a scale is a root and its name, anything without a space is taken as a chord
+pub fn print_neck(arg: &str, instrument: Option<&Instrument>) -> Result<(), String> {
+    let (title, tones, root) = match arg.trim().contains(char::is_whitespace) {
+        true => {
+            let scale =
+                timed(Phase::Parse, || ChordScale::from_str(arg)).map_err(|e| e.to_string())?;
+            let title = format!("{}: {}", scale, scale.spelled_notes().iter().join(" "));
+            (title, scale.notes(), scale.root)
+        }
+        false => {
+            let chord = timed(Phase::Parse, || parse_chord_name(arg.to_string()))
+                .map_err(|e| e.to_string())?;
+            let title = format!(
+                "{}: {}",
+                shown(chord.name.clone()),
+                spell_chord(&chord, None).iter().join(" ")
+            );
+            (title, chord.notes.clone(), chord.root)
+        }
+    };
+
+    let tuning = instrument.map_or_else(Tuning::standard_guitar, |i| i.standard_tuning());
+
+    if shows(OutputLevel::Normal) {
+        println!("{}", title);
+    }
+    println!("{}", render_neck(&tones, root, &tuning, NECK_FRETS));
+
+    Ok(())
+}
+
+fn identify_notes_from_chord_name(
+    chord_name: String,
+    key: Option<Key>,
+) -> Result<(), ChordParseError> {
+    if chord_name.contains(" in ") {
+        return identify_notes_from_roman(chord_name);
+    }
+    if split_polychord(&chord_name).is_some() {
+        return identify_notes_from_polychord_name(chord_name);
+    }
+
+    // passed on as it is so the caret and suggestions make it to the menu
+    let chord = choose_reading(chord_name)?;
+
+    if format() != Format::Text {
+        print_formatted(std::slice::from_ref(&chord));
+        return Ok(());
+    }
+    for line in describe_chord(&chord, key.as_ref()) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+// what chord info shows for a chord at the output level, in the menu and for struck chord
+fn describe_chord(chord: &Chord, key: Option<&Key>) -> Vec<String> {
+    if !shows(OutputLevel::Normal) {
+        return vec![reading_name(chord)];
+    }
+
+    let mut lines = vec![render_chord_info(
+        chord,
+        &shown(spell_name(chord, key)),
+        key,
+        console::colors_enabled(),
+    )];
+
+    if let Some(key) = key {
+        let numeral = timed(Phase::Analysis, || roman_numeral(chord, key));
+        let number = nashville_number(chord, key);
+        lines.push(match numeral.origin {
+            Origin::Diatonic => format!("In {}: {} ({})", key, numeral, number),
+            Origin::Borrowed(from) => {
+                format!(
+                    "In {}: {} ({}, borrowed from {})",
+                    key, numeral, number, from
+                )
+            }
+            Origin::Chromatic => format!("In {}: {} ({}, chromatic)", key, numeral, number),
+        });
+        if let Some(function) = harmonic_function(chord, key) {
+            lines.push(format!("Function: {}", function));
+        }
+        if shows(OutputLevel::Full) {
+            lines.push(format!(
+                "Degrees: {}",
+                chord
+                    .notes
+                    .iter()
+                    .zip(spell_chord(chord, Some(key)))
+                    .map(|(n, spelled)| format!("{} ({})", spelled, describe_degree(n, key)))
+                    .join(", ")
+            ));
+        }
+    }
+
+    if shows(OutputLevel::Full) {
+        let spelled = spell_chord(chord, key);
+        let voiced = |voicing: &Voicing| spell_voicing(voicing, &spelled);
+        let (close, open) = timed(Phase::Derivation, || {
+            (chord.close_voicing(4), chord.open_voicing())
+        });
+        lines.push(format!("Close voicing: {}", voiced(&close)));
+        lines.push(format!("Open voicing: {}", voiced(&open)));
+        lines.push(match chord.realized_voicing(3) {
+            Ok(voicing) => format!("As written: {}", voiced(&voicing)),
+            Err(e) => caught(&e),
+        });
+        if let Some(shell) = chord.shell_voicing(3) {
+            lines.push(format!("Shell voicing: {}", voiced(&shell)));
+        }
+        if let Some(rootless) = chord.rootless_voicing(3) {
+            lines.push(format!("Rootless voicing: {}", voiced(&rootless)));
+        }
+        if let Some(drop2) = chord.drop2_voicing(4) {
+            lines.push(format!("Drop 2 voicing: {}", voiced(&drop2)));
+        }
+        let tensions = timed(Phase::Analysis, || chord.tensions());
+        if !tensions.available.is_empty() {
+            lines.push(format!(
+                "Tensions: {}",
+                tensions.available.iter().join(", ")
+            ));
+        }
+        if !tensions.avoid.is_empty() {
+            lines.push(format!("Avoid: {}", tensions.avoid.iter().join(", ")));
+        }
+        lines.push(render_keyboard(&chord.notes));
+        if !chord.alternatives.is_empty() {
+            lines.push(format!(
+                "Alternatives: {}",
+                chord
+                    .alternatives
+                    .iter()
+                    .take(ALTERNATIVES_SHOWN)
+                    .map(|a| format!("{} ({}%)", spell_reading(&a.name, key), a.score))
+                    .format(", ")
+            ));
+        }
+    }
+
+    lines
+}
+
+// numerals or Nashville numbers in a key, one chord or a progression like ii-V-I in Bb or 1 4 5 in G
+fn identify_notes_from_roman(input: String) -> Result<(), ChordParseError> {
+    let (key, chords) = timed(Phase::Parse, || {
+        if is_nashville(&input) {
+            identify_from_nashville(&input)
+        } else {
+            identify_from_roman(&input)
+        }
+    })?;
+
+    if format() != Format::Text {
+        print_formatted(&chords);
+        return Ok(());
+    }
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", chords.iter().map(|c| &c.name).join(" "));
+        return Ok(());
+    }
+
+    println!("In {}:", key);
+    for chord in &chords {
+        println!(
+            "{} ({}): {} ({})",
+            roman_numeral(chord, &key),
+            nashville_number(chord, &key),
+            shown(spell_name(chord, Some(&key))),
+            spell_chord(chord, Some(&key)).iter().join(" ")
+        );
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&chord.notes));
+        }
+    }
+
+    Ok(())
+}
+
+// D|C or D over C, two chords at once rather than one with a bass note
+fn identify_notes_from_polychord_name(chord_name: String) -> Result<(), ChordParseError> {
+    let polychord = timed(Phase::Parse, || {
+        identify_polychord(chord_name, &load_aliases(), parse_options())
+    })?;
+
+    // the two halves, upper first as it's written
+    if format() != Format::Text {
+        print_formatted(&[polychord.upper, polychord.lower]);
+        return Ok(());
+    }
+
+    for line in describe_polychord(&polychord) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+// what a polychord shows at the output level, in the menu, the repl and for struck chord
+// each half spelled as a chord of its own, the name and all the notes on one line at brief
+fn describe_polychord(polychord: &Polychord) -> Vec<String> {
+    let upper = spell_chord(&polychord.upper, None);
+    let lower = spell_chord(&polychord.lower, None);
+    let notes = lower.iter().chain(&upper).unique_by(|n| n.note()).join(" ");
+    let name = format!(
+        "{}|{}",
+        spell_name(&polychord.upper, None),
+        spell_name(&polychord.lower, None)
+    );
+
+    if !shows(OutputLevel::Normal) {
+        return vec![format!("{}: {}", name, notes)];
+    }
+
+    let mut lines = vec![
+        format!("Polychord: {}", name),
+        format!(
+            "Upper: {} ({})",
+            spell_name(&polychord.upper, None),
+            upper.iter().join(" ")
+        ),
+        format!(
+            "Lower: {} ({})",
+            spell_name(&polychord.lower, None),
+            lower.iter().join(" ")
+        ),
+        format!("Notes: {}", notes),
+    ];
+
+    if shows(OutputLevel::Full) {
+        let shared: Vec<&SpelledNote> = upper
+            .iter()
+            .filter(|n| polychord.shared_notes().contains(&n.note()))
+            .collect();
+        if !shared.is_empty() {
+            lines.push(format!("Shared: {}", shared.iter().join(" ")));
+        }
+        lines.push(render_keyboard(&polychord.notes));
+    }
+
+    lines
+}
+
+// errors from reading what was typed, which can say where it went wrong and what might have been meant
+trait ParseFailure: fmt::Display {
+    fn span(&self) -> Option<&Span> {
+        None
+    }
+}
+
+impl ParseFailure for ChordParseError {
+    fn span(&self) -> Option<&Span> {
+        ChordParseError::span(self)
+    }
+}
+
+impl ParseFailure for ChordProError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            ChordProError::Chord(e) => e.span(),
+            ChordProError::Io(_) => None,
+        }
+    }
+}
+
+impl ParseFailure for NoteParseError {
+    fn span(&self) -> Option<&Span> {
+        NoteParseError::span(self)
+    }
+}
+
+// the rest can't point at where, they just say what went wrong
+impl ParseFailure for KeyParseError {}
+impl ParseFailure for ScaleParseError {}
+impl ParseFailure for FretboardError {}
+impl ParseFailure for SessionError {}
+impl ParseFailure for DictionaryError {}
+impl ParseFailure for AliasError {}
+impl ParseFailure for MidiExportError {}
+impl ParseFailure for ReportError {}
+impl ParseFailure for ParseIntError {}
+
+// the error, with a caret under the part of the input that went wrong when it's known
+fn print_caught(e: &impl ParseFailure) {
+    println!("{}", caught(e));
+}
+
+fn caught(e: &impl ParseFailure) -> String {
+    match e.span() {
+        Some(span) => format!("{}\n{}", e, span.caret()),
+        None => e.to_string(),
+    }
+}
+
+// checks on what's typed into the menu's prompts, so a chord name or notes that won't read
+// are turned away there and then with what's wrong, rather than back at the menu after
+
+// roman numerals in a key and polychords are read by chord info and checked when they are
+struct ChordNameInput;
+
+impl InputValidator<String> for ChordNameInput {
+    type Err = String;
+
+    fn validate(&mut self, input: &String) -> Result<(), String> {
+        if input.contains(" in ") || split_polychord(input).is_some() {
+            return Ok(());
+        }
+
+        parse_chord_name(input.clone())
+            .map(|_| ())
+            .map_err(|e| e.message())
+    }
+}
+
+struct ProgressionInput;
+
+impl InputValidator<String> for ProgressionInput {
+    type Err = String;
+
+    fn validate(&mut self, input: &String) -> Result<(), String> {
+        parse_progression(input)
+            .map(|_| ())
+            .map_err(|e| e.message())
+    }
+}
+
+// note names, or midi numbers too where the prompt takes them
+struct NotesInput {
+    midi: bool,
+}
+
+impl InputValidator<String> for NotesInput {
+    type Err = String;
+
+    fn validate(&mut self, input: &String) -> Result<(), String> {
+        let read = match self.midi {
+            true => read_notes(input).map(|_| ()),
+            false => identify_notes(input).map(|_| ()),
+        };
+
+        read.map_err(|e| e.message())
+    }
+}
+
+// every chord name typed in goes through the user's aliases
+static PARSING: OnceLock<ParseOptions> = OnceLock::new();
+
+pub fn set_parse_options(options: ParseOptions) {
+    let _ = PARSING.set(options);
+}
+
+fn parse_options() -> ParseOptions {
+    PARSING.get().copied().unwrap_or_default()
+}
+
+fn tension_reading() -> TensionReading {
+    parse_options().tensions
+}
+
+fn parse_chord_name(chord_name: String) -> Result<Chord, ChordParseError> {
+    identify_from_name_as(chord_name, &load_aliases(), parse_options())
+}
+
+// every plausible reading of the name, asking which one was meant when there's more than one
+// C9 could be C9 or Cadd9 depending on whose chart it's from
+fn choose_reading(chord_name: String) -> Result<Chord, ChordParseError> {
+    let mut readings = timed(Phase::Parse, || {
+        identify_all_from_name(chord_name, &load_aliases(), parse_options())
+    })?;
+    // nobody to ask when it's piped in, the first reading is the most likely
+    if readings.len() == 1 || !io::stdin().is_terminal() {
+        return Ok(readings.remove(0));
+    }
+
+    let items: Vec<String> = readings
+        .iter()
+        .map(|c| {
+            format!(
+                "{}: {}",
+                shown(c.name.clone()),
+                spell_chord(c, None).iter().join(" ")
+            )
+        })
+        .collect();
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("That could be read more than one way, which is it? ")
+        .default(0)
+        .items(&items)
+        .interact_opt()
+        .expect("") // TODO: probably won't panic
+        .unwrap_or(0);
+
+    Ok(readings.swap_remove(choice))
+}
+
+// chords for other programs to read, printed instead of the text with --format json, yaml or csv,
+// or lined up in a table with --format table
+fn print_formatted(chords: &[Chord]) {
+    let reports: Vec<ChordReport> = chords.iter().map(ChordReport::from).collect();
+    let written = match format() {
+        Format::Yaml => report::to_yaml(&reports),
+        Format::Csv => Ok(report::to_csv(&reports)),
+        Format::Table => Ok(report::to_table(&reports)),
+        _ => report::to_json(&reports),
+    };
+    match written {
+        Ok(written) => println!("{}", written.trim_end()),
+        Err(e) => print_caught(&e),
+    }
+}
+
+static PRETTY: OnceLock<bool> = OnceLock::new();
+
+pub fn set_pretty(pretty: bool) {
+    let _ = PRETTY.set(pretty);
+}
+
+// a chord name as it's shown, with the music symbols if asked for
+fn shown(name: String) -> String {
+    match PRETTY.get() {
+        Some(true) => pretty_symbol(&name),
+        _ => name,
+    }
+}
+
+// the chord's name written for how tensions are being read, G13add9 rather than G7add13 read literally
+fn reading_name(chord: &Chord) -> String {
+    if tension_reading() == TensionReading::Implied || chord.structure != ChordStructure::Tertian {
+        return shown(spell_name(chord, None));
+    }
+
+    shown(
+        match (
+            name_chord_as(&chord.root, &chord.intervals, tension_reading()),
+            chord.bass,
+        ) {
+            (Some(name), Some(bass)) => format!("{}/{}", name, bass),
+            (Some(name), None) => name,
+            (None, _) => chord.name.clone(),
+        },
+    )
+}
+
+// like the dictionary, a broken aliases file is reported but doesn't stop anything
+fn load_aliases() -> Aliases {
+    let path = match Aliases::default_path() {
+        Some(path) => path,
+        None => return Aliases::default(),
+    };
+
+    match Aliases::load(&path) {
+        Ok(aliases) => aliases,
+        Err(e) => {
+            print_caught(&e);
+            Aliases::default()
+        }
+    }
+}
+
+// notes by name, or as midi numbers (60 64 67 70) when they start with a digit,
+// which keeps their octaves so a note an octave up from the root can be told apart as a 9th, 11th or 13th
+fn read_notes(notes_raw: &str) -> Result<(Vec<Note>, Option<Voicing>), NoteParseError> {
+    if !notes_raw
+        .trim_start()
+        .starts_with(|c: char| c.is_ascii_digit())
+    {
+        return Ok((identify_notes(notes_raw)?, None));
+    }
+
+    let mut pitches = identify_midi_pitches(notes_raw)?;
+    pitches.sort();
+    let voicing = Voicing { pitches };
+    Ok((
+        voicing.notes().into_iter().unique().collect(),
+        Some(voicing),
+    ))
+}
+
+// every chord the notes could be, most likely first
+fn readings_of(notes: &[Note], voicing: Option<&Voicing>) -> Vec<Chord> {
+    match voicing {
+        Some(voicing) => identify_from_pitches(&voicing.pitches),
+        None => identify_from_notes(notes),
+    }
+}
+
+fn identify_chord_from_notes(notes_raw: String) -> Result<(), NoteParseError> {
+    let (notes, voicing) = timed(Phase::Parse, || read_notes(&notes_raw))?;
+
+    // for each of the notes treated as the root, get what chords it could be considered
+    let possible_chords = timed(Phase::Analysis, || readings_of(&notes, voicing.as_ref()));
+
+    if format() != Format::Text {
+        print_formatted(&possible_chords);
+        return Ok(());
+    }
+    for line in describe_readings(&notes, voicing.as_ref(), &possible_chords) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+// what a chord from some notes shows for the readings of them at the output level, in the menu and for struck notes
+fn describe_readings(
+    notes: &[Note],
+    voicing: Option<&Voicing>,
+    possible_chords: &[Chord],
+) -> Vec<String> {
+    let mut lines = vec![];
+
+    if let Some(voicing) = voicing.filter(|_| shows(OutputLevel::Normal)) {
+        lines.push(format!("Voiced: {}", voicing));
+    }
+
+    if possible_chords.is_empty() {
+        lines.push("No possible chords found!".to_string());
+    } else {
+        if !shows(OutputLevel::Normal) {
+            lines.push(possible_chords.iter().map(reading_name).join(" "));
+            return lines;
+        }
+
+        // the same notes can be written more ways than one per root, C6 as well as C6/A
+        let names = timed(Phase::Analysis, || enumerate_chord_names(notes));
+
+        lines.push("Could be (most likely first): ".to_string());
+        possible_chords.iter().for_each(|c| {
+            let name = reading_name(c);
+            let description = match c.inversion() {
+                _ if c.structure != ChordStructure::Tertian => {
+                    format!("{}: {}", name, spell_chord(c, None).iter().join(" "))
+                }
+                Some(Inversion::RootPosition) => name,
+                Some(inversion) => {
+                    format!("{} {}, {} ({})", c.root, c.chord_quality(), inversion, name)
+                }
+                None => format!(
+                    "{} {} over {} ({})",
+                    c.root,
+                    c.chord_quality(),
+                    notes[0],
+                    name
+                ),
+            };
+
+            lines.push(format!("{} - {}%", description, score_chord(c)));
+        });
+
+        lines.push(format!(
+            "Every name: {}",
+            names
+                .iter()
+                .map(|n| format!("{} ({}%)", n.name, n.score))
+                .format(", ")
+        ));
+
+        if shows(OutputLevel::Full) {
+            lines.push(render_keyboard(notes));
+        }
+    }
+
+    for saved in load_dictionary().find_matches(notes) {
+        lines.push(format!("Your saved '{}' ({})", saved.name, saved.voicing));
+    }
+
+    lines
+}
+
+fn print_choir_voices(key: String, chord_name: String) -> Result<(), ChordParseError> {
+    let tonic = match Note::parse(key.trim()) {
+        Ok(n) => n,
+        Err(_) => {
+            return Err(ChordParseError::InvalidChordName(
+                "error identifying key".to_string(),
+            ))
+        }
+    };
+
+    let chord = parse_chord_name(chord_name)?;
+
+    println!("{} in {}", chord.name, tonic);
+
+    if shows(OutputLevel::Normal) {
+        println!("{}", render_choir_voices(&tonic, &chord));
+    }
+
+    if shows(OutputLevel::Full) {
+        println!("{}", render_keyboard(&chord.notes));
+    }
+
+    Ok(())
+}
+
+fn transpose_chord_in_key(
+    key: String,
+    chord_name: String,
+    degrees: i32,
+) -> Result<(), ChordParseError> {
+    let tonic = match Note::parse(key.trim()) {
+        Ok(n) => n,
+        Err(_) => {
+            return Err(ChordParseError::InvalidChordName(
+                "error identifying key".to_string(),
+            ))
+        }
+    };
+
+    let chord = parse_chord_name(chord_name)?;
+    let transposed = transpose_chord_diatonic(&tonic, &chord, degrees);
+    let key = Key::major(tonic);
+
+    println!(
+        "{} => {}",
+        chord.name,
+        shown(spell_name(&transposed, Some(&key)))
+    );
+
+    if shows(OutputLevel::Normal) {
+        println!(
+            "{}",
+            spell_chord(&transposed, Some(&key)).iter().format(", ")
+        );
+    }
+
+    if shows(OutputLevel::Full) {
+        println!("{}", render_keyboard(&transposed.notes));
+    }
+
+    Ok(())
+}
+
+fn print_inversion_plan(progression: String, melody: String) -> Result<(), ChordParseError> {
+    let chords = progression
+        .split_whitespace()
+        .map(|name| parse_chord_name(name.to_string()))
+        .collect::<Result<Vec<Chord>, ChordParseError>>()?;
+
+    let melody = match melody
+        .split_whitespace()
+        .map(Note::parse)
+        .collect::<Result<Vec<Note>, NoteParseError>>()
+    {
+        Ok(m) => m,
+        Err(_) => {
+            return Err(ChordParseError::InvalidChordName(
+                "error identifying melody notes".to_string(),
+            ))
+        }
+    };
+
+    if !melody.is_empty() && melody.len() != chords.len() {
+        return Err(ChordParseError::InvalidChordName(
+            "need one melody note for each chord".to_string(),
+        ));
+    }
+
+    let melody = Some(melody.as_slice()).filter(|m| !m.is_empty());
+
+    let plan = plan_inversions(&chords, melody);
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", plan.iter().map(|c| &c.name).join(" "));
+        return Ok(());
+    }
+
+    for chord in plan {
+        println!(
+            "{}: {}",
+            chord.name,
+            spell_chord(&chord, None).iter().join(" ")
+        );
+
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&chord.notes));
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn print_melody_voicings(chord_name: String, melody: String) -> Result<(), ChordParseError> {
+    let chord = parse_chord_name(chord_name)?;
+
+    let melody = match Note::parse(melody.trim()) {
+        Ok(n) => n,
+        Err(_) => {
+            return Err(ChordParseError::InvalidChordName(
+                "error identifying melody note".to_string(),
+            ))
+        }
+    };
+
+    let voicings = voice_under_melody(&chord, &melody);
+
+    if voicings.is_empty() {
+        println!("No voicings found with {} on top!", melody);
+    }
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", voicings.iter().map(|v| &v.name).join(" "));
+        return Ok(());
+    }
+
+    for voicing in voicings {
+        println!(
+            "{}: {}",
+            voicing.name,
+            spell_chord(&voicing, None).iter().join(" ")
+        );
+
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&voicing.notes));
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn print_guitar_fingerings(
+    chord_name: String,
+    strategy: FingeringStrategy,
+    capo: String,
+) -> Result<(), ChordParseError> {
+    let chord = timed(Phase::Parse, || parse_chord_name(chord_name))?;
+
+    let tuning = match capo.trim() {
+        "" => Tuning::standard_guitar(),
+        capo => match Capo::from_str(capo) {
+            Ok(capo) => Tuning::standard_guitar().with_capo(capo),
+            Err(_) => {
+                return Err(ChordParseError::InvalidChordName(
+                    "error reading capo".to_string(),
+                ))
+            }
+        },
+    };
+
+    let fingerings = timed(Phase::Derivation, || {
+        find_fingerings(&chord, &tuning, strategy)
+    });
+
+    if fingerings.is_empty() {
+        println!("No playable fingerings found for {}!", chord.name);
+    }
+
+    for fingering in fingerings.iter().take(FINGERINGS_SHOWN) {
+        match shows(OutputLevel::Normal) {
+            true => println!(
+                "{:<16}{} open   {}",
+                fingering.to_string(),
+                fingering.open_strings(),
+                fingering.sounding_notes(&tuning).iter().join(" ")
+            ),
+            false => println!("{}", fingering),
+        }
+
+        if shows(OutputLevel::Full) {
+            println!("{}", render_chord_diagram(fingering, &tuning));
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn print_fingering_chords(fingering: String, tuning: Tuning) -> Result<(), FretboardError> {
+    let fingering = timed(Phase::Parse, || Fingering::from_str(&fingering))?;
+
+    if shows(OutputLevel::Normal) {
+        println!(
+            "Notes: {}",
+            fingering.sounding_notes(&tuning).iter().join(" ")
+        );
+    }
+
+    let chords = timed(Phase::Analysis, || identify_fingering(&fingering, &tuning))?;
+
+    if format() != Format::Text {
+        print_formatted(&chords);
+        return Ok(());
+    }
+
+    if chords.is_empty() {
+        println!("No chords found!")
+    }
+
+    for chord in chords.iter() {
+        println!("{}", chord.name);
+    }
+
+    if shows(OutputLevel::Full) {
+        if let Some(chord) = chords.first() {
+            println!("{}", render_keyboard(&chord.notes));
+        }
+    }
+
+    Ok(())
+}
+
+fn replay_session(path: String, window_ms: u64) -> Result<(), SessionError> {
+    let session = timed(Phase::Parse, || Session::load(Path::new(&path)))?;
+    let timeline = timed(Phase::Analysis, || session.identify(window_ms));
+
+    if timeline.is_empty() {
+        println!("No chords found!")
+    } else if !shows(OutputLevel::Normal) {
+        println!("{}", timeline.iter().map(|t| &t.chord.name).join(" "));
+    } else {
+        println!("{}", render_timeline(&timeline, TIMELINE_WIDTH));
+    }
+
+    if shows(OutputLevel::Full) {
+        for timed in timeline.iter() {
+            println!(
+                "{}ms-{}ms {}: {}",
+                timed.start_ms,
+                timed.end_ms,
+                timed.chord.name,
+                spell_chord(&timed.chord, None).iter().join(" ")
+            );
+        }
+    }
+
+    let dictionary = load_dictionary();
+    for timed in timeline.iter() {
+        for saved in dictionary.find_matches(&timed.chord.notes) {
+            println!(
+                "{}ms: {} is your saved '{}'",
+                timed.start_ms, timed.chord.name, saved.name
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// some of a chord's notes the way the chord spells them
+fn spelled_in(chord: &Chord, notes: &[Note]) -> String {
+    let spelled = spell_chord(chord, None);
+
+    notes
+        .iter()
+        .map(|note| {
+            spelled
+                .iter()
+                .find(|n| n.note() == *note)
+                .map_or(note.to_string(), |n| n.to_string())
+        })
+        .join(" ")
+}
+
+fn compare_chords(first: String, second: String) -> Result<(), ChordParseError> {
+    let first = parse_chord_name(first)?;
+    let second = parse_chord_name(second)?;
+
+    let common_tones = spelled_in(&first, &first.common_tones(&second));
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", common_tones);
+        return Ok(());
+    }
+
+    println!("Shared: {}", common_tones);
+    println!(
+        "Only in {}: {}",
+        first.name,
+        spelled_in(&first, &first.differing_tones(&second))
+    );
+    println!(
+        "Only in {}: {}",
+        second.name,
+        spelled_in(&second, &second.differing_tones(&first))
+    );
+    println!("Similarity: {}%", first.similarity(&second));
+
+    // close voicings from middle C, the same as the A/B file defaults to
+    let leading = timed(Phase::Analysis, || {
+        lead_voices(&first.close_voicing(4), &second.close_voicing(4))
+    });
+    println!(
+        "Voice leading ({} semitones in all):",
+        leading.total_movement()
+    );
+    for motion in &leading.motions {
+        println!("  {}", motion);
+    }
+    for parallel in &leading.parallels {
+        let (low, high) = parallel.voices;
+        println!(
+            "  {} between {} and {}",
+            parallel.kind, leading.motions[low], leading.motions[high]
+        );
+    }
+
+    Ok(())
+}
+
+// the voicings a chord can be heard in, in the order select_voicing_style lists them
+const VOICING_STYLES: [&str; 5] = ["close", "open", "drop 2", "shell", "rootless"];
+
+fn select_voicing_style(chord_name: &str) -> Option<usize> {
+    Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Voicing for {} ", chord_name.trim()))
+        .default(0)
+        .items(VOICING_STYLES)
+        .interact_opt()
+        .expect("") // TODO: probably won't panic
+}
+
+// shell, rootless and drop 2 voicings only exist for some chords
+fn voicing_in_style(chord: &Chord, style: usize) -> Result<Voicing, ChordParseError> {
+    let voicing = match style {
+        1 => Some(chord.open_voicing()),
+        2 => chord.drop2_voicing(4),
+        3 => chord.shell_voicing(3),
+        4 => chord.rootless_voicing(3),
+        _ => Some(chord.close_voicing(4)),
+    };
+
+    voicing.ok_or_else(|| {
+        ChordParseError::InvalidChordName(format!(
+            "{} has no {} voicing",
+            chord.name, VOICING_STYLES[style]
+        ))
+    })
+}
+
+// the two chords taking turns in a midi file, to pick between by ear
+fn export_ab_comparison(
+    first: String,
+    first_style: usize,
+    second: String,
+    second_style: usize,
+    gap_beats: u32,
+    path: String,
+) -> Result<(), MidiExportError> {
+    let first = parse_chord_name(first)?;
+    let second = parse_chord_name(second)?;
+
+    let comparison = AbComparison::new(
+        voicing_in_style(&first, first_style)?,
+        voicing_in_style(&second, second_style)?,
+        100,
+        gap_beats,
+    );
+    timed(Phase::Derivation, || comparison.save(Path::new(&path)))?;
+
+    if shows(OutputLevel::Normal) {
+        println!(
+            "Saved {} ({}) and {} ({}) taking turns {} times to {}",
+            first.name, comparison.a, second.name, comparison.b, comparison.rounds, path
+        );
+    }
+
+    Ok(())
+}
+
+fn print_scale_info(scale: String) -> Result<(), ScaleParseError> {
+    let chord_scale = ChordScale::from_str(&scale)?;
+    let ChordScale { root, scale } = chord_scale;
+    let key = chord_scale.key();
+    let notes = scale.notes(&root);
+    let spelled = chord_scale.spelled_notes().iter().join(" ");
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", spelled);
+        return Ok(());
+    }
+
+    println!("{}: {}", chord_scale, spelled);
+    println!("Steps: {}", scale.step_pattern());
+    if let (Some(degree), Some(parent)) = (scale.mode_degree(), scale.parent_major(&root)) {
+        if scale != Scale::Major {
+            println!("Mode {} of {}", degree + 1, Key::major(parent));
+        }
+    }
+    if let Some(key) = &key {
+        println!("Key signature: {} ({})", describe_signature(key), key);
+    }
+
+    // only the seven note scales stack up into chords a 3rd apart
+    let triads = scale.diatonic_chords(&root, 3);
+    if !triads.is_empty() {
+        let name = |chord: &Chord| shown(spell_name(chord, key.as_ref()));
+        println!("Triads: {}", triads.iter().map(name).join(" "));
+        if shows(OutputLevel::Full) {
+            let sevenths = scale.diatonic_chords(&root, 4);
+            println!("7ths: {}", sevenths.iter().map(name).join(" "));
+        }
+    }
+
+    let same_notes = chord_scale.same_notes();
+    if !same_notes.is_empty() {
+        println!("Same notes as: {}", same_notes.iter().join(", "));
+    }
+
+    if shows(OutputLevel::Full) {
+        println!("{}", render_keyboard(&notes));
+    }
+
+    Ok(())
+}
+
+// how many sharps or flats, the way it's said
+fn describe_signature(key: &Key) -> String {
+    match key.signature() {
+        0 => "no sharps or flats".to_string(),
+        1 => "1 sharp".to_string(),
+        -1 => "1 flat".to_string(),
+        sharps if sharps > 0 => format!("{} sharps", sharps),
+        flats => format!("{} flats", -flats),
+    }
+}
+
+fn print_circle_of_fifths(key: String) -> Result<(), KeyParseError> {
+    let key = Key::from_str(&key)?;
+    let related = key.closely_related();
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", related.iter().join(", "));
+        return Ok(());
+    }
+
+    println!("{}", render_circle_of_fifths(&key));
+    println!();
+
+    let [down, up] = key.fifth_neighbours();
+
+    println!("{}: {}", key, describe_signature(&key));
+    println!("Relative: {}", key.relative());
+    println!(
+        "Parallel: {}, borrowed chords: {}",
+        key.parallel(),
+        key.borrowed_chords().iter().map(|c| &c.name).join(" ")
+    );
+    println!("A 5th down: {}, a 5th up: {}", down, up);
+    println!("Closely related: {}", related.iter().join(", "));
+
+    Ok(())
+}
+
+// the settings come in on a practice track with no chords yet, the progression fills it in
+fn export_practice_midi(
+    progression: String,
+    mut practice: PracticeTrack,
+    path: String,
+) -> Result<(), MidiExportError> {
+    practice.chords = timed(Phase::Parse, || {
+        progression
+            .split_whitespace()
+            .map(|name| parse_chord_name(name.to_string()))
+            .collect::<Result<Vec<Chord>, ChordParseError>>()
+    })?;
+
+    timed(Phase::Derivation, || practice.save(Path::new(&path)))?;
+
+    if shows(OutputLevel::Normal) {
+        let drums = match practice.drums {
+            Some(style) => format!("{} drums", style),
+            None => "no drums".to_string(),
+        };
+        println!(
+            "Saved {} bars of {} at {} bpm with {} to {}",
+            practice.chords.len(),
+            practice.preset,
+            practice.tempo_bpm,
+            drums,
+            path
+        );
+    }
+
+    Ok(())
+}
+
+// a chord a step from one to the other, saved as a bar each when there's somewhere to save it
+fn print_morph(from: String, to: String, path: String) -> Result<(), MidiExportError> {
+    let from = parse_chord_name(from)?;
+    let to = parse_chord_name(to)?;
+
+    let chords = match timed(Phase::Analysis, || morph(&from, &to)) {
+        Some(chords) => chords,
+        None => {
+            return Err(ChordParseError::InvalidChordName(format!(
+                "no way from {} to {} a named chord at a time",
+                from.name, to.name
+            ))
+            .into())
+        }
+    };
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", chords.iter().map(|c| &c.name).join(" "));
+    } else {
+        for chord in &chords {
+            println!(
+                "{}: {}",
+                chord.name,
+                spell_chord(chord, None).iter().join(" ")
+            );
+        }
+    }
+
+    if !path.trim().is_empty() {
+        let bars = chords.len();
+        PracticeTrack::new(chords, 100, None).save(Path::new(path.trim()))?;
+        if shows(OutputLevel::Normal) {
+            println!("Saved {} bars to {}", bars, path.trim());
+        }
+    }
+
+    Ok(())
+}
+
+fn print_likely_keys(progression: String) -> Result<(), ChordParseError> {
+    let chords = timed(Phase::Parse, || parse_progression(&progression))?.chords;
+
+    let matches = timed(Phase::Analysis, || find_keys(&chords));
+
+    if !shows(OutputLevel::Normal) {
+        if let Some(best) = matches.first() {
+            println!("{}", best.key);
+        }
+        return Ok(());
+    }
+
+    for key_match in matches.iter().take(KEYS_SHOWN) {
+        println!(
+            "{}: {}",
+            key_match,
+            chords
+                .iter()
+                .map(|c| roman_numeral(c, &key_match.key))
+                .join(" ")
+        );
+    }
+
+    // when it doesn't stay in the one key, where it goes
+    let sections = timed(Phase::Analysis, || find_modulations(&chords));
+    if sections.len() > 1 {
+        println!("Changes key:");
+        for section in &sections {
+            let pivot = match section.pivot {
+                Some(pivot) => format!(", turning on {}", chords[pivot].name),
+                None => String::new(),
+            };
+            println!(
+                "  {} from {}{}",
+                section.key, chords[section.start].name, pivot
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_progression(progression: &str) -> Result<Progression, ChordParseError> {
+    identify_progression(progression, &load_aliases(), parse_options())
+}
+
+// the key it's most likely in, then each chord's numeral and Nashville number in that key with its notes
+fn analyze_progression(progression: String) -> Result<(), ChordParseError> {
+    let progression = timed(Phase::Parse, || parse_progression(&progression))?;
+    let sections = timed(Phase::Analysis, || find_modulations(&progression.chords));
+
+    if !shows(OutputLevel::Normal) {
+        println!(
+            "{}",
+            sections
+                .iter()
+                .flat_map(|s| {
+                    progression.chords[s.start..s.end]
+                        .iter()
+                        .map(|c| roman_numeral(c, &s.key))
+                })
+                .join(" ")
+        );
+        return Ok(());
+    }
+
+    println!("{}", progression);
+    let mut previous: Option<Key> = None;
+    for section in &sections {
+        let key = section.key;
+        let key_match = KeyMatch {
+            key,
+            confidence: key_confidence(&progression.chords[section.start..section.end], &key),
+        };
+
+        match (previous, section.pivot) {
+            (None, _) => println!("In {}:", key_match),
+            (Some(from), Some(pivot)) => {
+                let pivot = &progression.chords[pivot];
+                println!(
+                    "Into {} by {} ({} in {}, {} in {}):",
+                    key_match,
+                    shown(spell_name(pivot, Some(&key))),
+                    roman_numeral(pivot, &from),
+                    from,
+                    roman_numeral(pivot, &key),
+                    key
+                );
+            }
+            (Some(_), None) => println!("Straight into {}:", key_match),
+        }
+        previous = Some(key);
+
+        for chord in &progression.chords[section.start..section.end] {
+            let function = match harmonic_function(chord, &key) {
+                Some(function) => format!(", {}", function),
+                None => String::new(),
+            };
+            println!(
+                "{}: {} ({}) {}{}",
+                shown(spell_name(chord, Some(&key))),
+                roman_numeral(chord, &key),
+                nashville_number(chord, &key),
+                spell_chord(chord, Some(&key)).iter().join(" "),
+                function
+            );
+            if shows(OutputLevel::Full) {
+                println!("{}", render_keyboard(&chord.notes));
+            }
+        }
+    }
+
+    // each cadence in the key of the section it lands in
+    let cadences = timed(Phase::Analysis, || {
+        sections
+            .iter()
+            .flat_map(|s| {
+                find_cadences(&progression, &s.key)
+                    .into_iter()
+                    .filter(|c| (s.start..s.end).contains(&c.at))
+                    .map(|c| (c, s.key))
+            })
+            .collect::<Vec<(CadenceMatch, Key)>>()
+    });
+    for (CadenceMatch { cadence, at }, key) in cadences {
+        let (from, to) = (&progression.chords[at - 1], &progression.chords[at]);
+        println!(
+            "{} cadence in bar {}: {} {} ({} {})",
+            cadence,
+            progression.bars[at] + 1,
+            shown(spell_name(from, Some(&key))),
+            shown(spell_name(to, Some(&key))),
+            roman_numeral(from, &key),
+            roman_numeral(to, &key)
+        );
+    }
+
+    Ok(())
+}
+
+// a voicing for every chord with the voices moving as little as they can, and how far they moved to get there
+fn print_smooth_voicings(progression: String) -> Result<(), ChordParseError> {
+    let progression = timed(Phase::Parse, || parse_progression(&progression))?;
+    let voicings = timed(Phase::Derivation, || {
+        smooth_voicings(&progression.chords, 4)
+    });
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", voicings.iter().join(" | "));
+        return Ok(());
+    }
+
+    let mut previous: Option<&Voicing> = None;
+    for (chord, voicing) in progression.chords.iter().zip(&voicings) {
+        let voiced = spell_voicing(voicing, &spell_chord(chord, None));
+        match previous {
+            Some(previous) => println!(
+                "{}: {} (moves {})",
+                chord.name,
+                voiced,
+                lead_voices(previous, voicing).total_movement()
+            ),
+            None => println!("{}: {}", chord.name, voiced),
+        }
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&voicing.notes()));
+        }
+        previous = Some(voicing);
+    }
+
+    let total: u32 = voicings
+        .windows(2)
+        .map(|w| lead_voices(&w[0], &w[1]).total_movement())
+        .sum();
+    println!("{} semitones of movement in all", total);
+
+    Ok(())
+}
+
+// a template's chords in the key, spelled for it, with their notes
+fn print_template(template: Template, key: Key) -> Result<(), ChordParseError> {
+    let mut progression = timed(Phase::Derivation, || template.in_key(&key))?;
+    let spelled: Vec<String> = progression
+        .chords
+        .iter()
+        .map(|c| spell_chord(c, Some(&key)).iter().join(" "))
+        .collect();
+    for chord in progression.chords.iter_mut() {
+        chord.name = shown(spell_name(chord, Some(&key)));
+    }
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", progression);
+        return Ok(());
+    }
+
+    println!("{} in {}:", template.name, key);
+    println!("{}", progression);
+    for (chord, notes) in progression
+        .chords
+        .iter()
+        .zip(&spelled)
+        .unique_by(|(c, _)| &c.name)
+    {
+        println!("{}: {}", chord.name, notes);
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&chord.notes));
+        }
+    }
+
+    Ok(())
+}
+
+// every chord mirrored around the key's tonic and 5th, spelled for the parallel key
+// since that's where the mirror takes the chords, C major's negative harmony is mostly in C minor
+fn print_negative_harmony(progression: String, key: Option<Key>) -> Result<(), ChordParseError> {
+    let progression = timed(Phase::Parse, || parse_progression(&progression))?;
+    let key = match key {
+        Some(key) => key,
+        None => match timed(Phase::Analysis, || find_keys(&progression.chords)).first() {
+            Some(key_match) => key_match.key,
+            None => return Ok(()),
+        },
+    };
+
+    let mut negative = timed(Phase::Derivation, || {
+        negative_progression(&progression, &key)
+    })?;
+    let spelling = key.parallel();
+    for chord in negative.chords.iter_mut() {
+        chord.name = shown(spell_name(chord, Some(&spelling)));
+    }
+
+    if format() != Format::Text {
+        print_formatted(&negative.chords);
+        return Ok(());
+    }
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", negative);
+        return Ok(());
+    }
+
+    let scale = spell_key(&key);
+    println!("Mirrored between {} and {} in {}:", scale[0], scale[4], key);
+    println!("{}", progression);
+    println!("{}", negative);
+    if shows(OutputLevel::Full) {
+        for chord in &negative.chords {
+            println!(
+                "{}: {}",
+                chord.name,
+                spell_chord(chord, Some(&spelling)).iter().join(" ")
+            );
+            println!("{}", render_keyboard(&chord.notes));
+        }
+    }
+
+    Ok(())
+}
+
+// what to transpose a progression by, a number of semitones or the key to take it to
+enum Transposition {
+    By(i32),
+    To(Key),
+}
+
+// every chord moved the same way, the key it's in worked out when going to a new one,
+// taking whichever way round is nearer so F to Eb is down a tone not up a 7th
+fn transpose_progression(
+    progression: String,
+    target: Transposition,
+) -> Result<(), ChordParseError> {
+    let progression = timed(Phase::Parse, || parse_progression(&progression))?;
+    let from = timed(Phase::Analysis, || find_keys(&progression.chords))
+        .first()
+        .map(|m| m.key);
+
+    let (semitones, to) = match target {
+        Transposition::By(semitones) => (
+            semitones,
+            from.map(|k| Key {
+                tonic: PitchClass::from(k.tonic)
+                    .up(semitones.rem_euclid(12) as usize)
+                    .note(),
+                ..k
+            }),
+        ),
+        Transposition::To(key) => {
+            let up = match from {
+                Some(from) => PitchClass::from(from.tonic).distance(&PitchClass::from(key.tonic)),
+                None => 0,
+            } as i32;
+            (if up > 6 { up - 12 } else { up }, Some(key))
+        }
+    };
+
+    let mut transposed = timed(Phase::Derivation, || progression.transpose(semitones));
+    // flat names read back fine, so the new key's spelling can stand as the name
+    for chord in transposed.chords.iter_mut() {
+        chord.name = shown(spell_name(chord, to.as_ref()));
+    }
+
+    if format() != Format::Text {
+        print_formatted(&transposed.chords);
+        return Ok(());
+    }
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", transposed);
+        return Ok(());
+    }
+
+    if let (Some(from), Some(to)) = (from, to) {
+        println!("{} -> {} ({:+} semitones)", from, to, semitones);
+    }
+    println!("{}", progression);
+    println!("{}", transposed);
+    if shows(OutputLevel::Full) {
+        for chord in &transposed.chords {
+            println!(
+                "{}: {}",
+                chord.name,
+                spell_chord(chord, to.as_ref()).iter().join(" ")
+            );
+            println!("{}", render_keyboard(&chord.notes));
+        }
+    }
+
+    Ok(())
+}
+
+// the scales on the chord's own root one per line with their notes, then every other one it fits in
+fn print_chord_scales(chord_name: String) -> Result<(), ChordParseError> {
+    let chord = timed(Phase::Parse, || parse_chord_name(chord_name))?;
+    let scales = timed(Phase::Analysis, || chord_scales(&chord));
+    let (on_root, elsewhere): (Vec<ChordScale>, Vec<ChordScale>) =
+        scales.into_iter().partition(|s| s.root == chord.root);
+
+    if !shows(OutputLevel::Normal) {
+        println!("{}", on_root.iter().join(", "));
+        return Ok(());
+    }
+
+    if on_root.is_empty() {
+        println!(
+            "No scale on {} has every note of {}",
+            chord.root, chord.name
+        );
+    } else {
+        println!("Over {}:", chord.name);
+    }
+    for chord_scale in &on_root {
+        println!(
+            "{} ({})",
+            chord_scale,
+            chord_scale.spelled_notes().iter().join(" ")
+        );
+        if shows(OutputLevel::Full) {
+            println!("{}", render_keyboard(&chord_scale.notes()));
+        }
+    }
+
+    if !elsewhere.is_empty() {
+        println!("Also in: {}", elsewhere.iter().join(", "));
+    }
+
+    Ok(())
+}
+
+// how many of the best fits to list, there's a lot of scales with a few notes in common
+const SCALES_SHOWN: usize = 10;
+
+// the scales with the most of the notes in, how many of them each has and what it's missing
+fn print_scales_containing(notes: String) -> Result<(), NoteParseError> {
+    let notes = timed(Phase::Parse, || identify_notes(&notes))?;
+    let matches = timed(Phase::Analysis, || scales_containing(&notes));
+    let total = notes.iter().unique().count();
+
+    if !shows(OutputLevel::Normal) {
+        println!(
+            "{}",
+            matches
+                .iter()
+                .filter(|m| m.matched == total)
+                .map(|m| m.scale)
+                .join(", ")
+        );
+        return Ok(());
+    }
+
+    let shown = match shows(OutputLevel::Full) {
+        true => matches.len(),
+        false => SCALES_SHOWN,
+    };
+    for scale_match in matches.iter().take(shown) {
+        match scale_match.missing.is_empty() {
+            true => println!("{} ({}/{})", scale_match.scale, scale_match.matched, total),
+            false => println!(
+                "{} ({}/{}, no {})",
+                scale_match.scale,
+                scale_match.matched,
+                total,
+                scale_match.missing.iter().join(" ")
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+// a missing or broken dictionary shouldn't get in the way of identifying anything
+fn load_dictionary() -> ChordDictionary {
+    let path = match ChordDictionary::default_path() {
+        Some(path) => path,
+        None => return ChordDictionary::default(),
+    };
+
+    match ChordDictionary::load(&path) {
+        Ok(dictionary) => dictionary,
+        Err(e) => {
+            print_caught(&e);
+            ChordDictionary::default()
+        }
+    }
+}
+
+fn save_played_voicing(path: String, name: String) -> Result<(), DictionaryError> {
+    let session =
+        Session::load(Path::new(&path)).map_err(|e| DictionaryError::Io(e.to_string()))?;
+
+    let voicing = match session.fullest_voicing() {
+        Some(voicing) => voicing,
+        None => {
+            println!("No notes found in session!");
+            return Ok(());
+        }
+    };
+
+    let dictionary_path = match ChordDictionary::default_path() {
+        Some(path) => path,
+        None => {
+            return Err(DictionaryError::Io(
+                "couldn't find a config directory".to_string(),
+            ))
+        }
+    };
+
+    let mut dictionary = ChordDictionary::load(&dictionary_path)?;
+    dictionary.add(&name, voicing.clone())?;
+    dictionary.save(&dictionary_path)?;
+
+    println!(
+        "Saved '{}' as {} to {}",
+        name.trim(),
+        voicing,
+        dictionary_path.display()
+    );
+
+    Ok(())
+}
+
+// what to print for a ChordPro song
+enum SongReport {
+    Key,
+    Diagrams,
+    Transposed(i32),
+}
+
+// a song's chords taken out of its ChordPro file, with the key it's in (from its key directive
+// or worked out from the chords), a fingering for each different chord, or the whole file moved
+fn print_chordpro(path: String, report: SongReport) -> Result<(), ChordProError> {
+    let path = Path::new(path.trim());
+    let load = || {
+        timed(Phase::Parse, || {
+            Song::load(path, &load_aliases(), parse_options())
+        })
+    };
+
+    match report {
+        SongReport::Key => {
+            let song = load()?;
+            let key = match song.key {
+                Some(key) => Some(key),
+                None => timed(Phase::Analysis, || find_keys(&song.progression().chords))
+                    .first()
+                    .map(|m| m.key),
+            };
+
+            if !shows(OutputLevel::Normal) {
+                if let Some(key) = key {
+                    println!("{}", key);
+                }
+                return Ok(());
+            }
+
+            if let Some(title) = &song.title {
+                println!("{}", title);
+            }
+            match (key, song.key) {
+                (Some(key), Some(_)) => println!("Key: {}", key),
+                (Some(key), None) => println!("Key: {} (from the chords)", key),
+                (None, _) => println!("Key: unknown"),
+            }
+            for section in &song.sections {
+                println!(
+                    "{}: {}",
+                    section.name.as_deref().unwrap_or("untitled"),
+                    section.progression
+                );
+            }
+        }
+        SongReport::Diagrams => {
+            let song = load()?;
+            let tuning = Tuning::standard_guitar();
+            for chord in song.chord_set() {
+                let fingerings = timed(Phase::Derivation, || {
+                    find_fingerings(chord, &tuning, FingeringStrategy::OpenStrings)
+                });
+                match fingerings.first() {
+                    Some(fingering) => {
+                        println!("{:<10}{}", shown(chord.name.clone()), fingering);
+                        if shows(OutputLevel::Normal) {
+                            println!("{}", render_chord_diagram(fingering, &tuning));
+                            println!();
+                        }
+                    }
+                    None => println!("{:<10}no playable fingering", shown(chord.name.clone())),
+                }
+            }
+        }
+        SongReport::Transposed(semitones) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(|e| ChordProError::Io(e.to_string()))?;
+            println!("{}", transpose_chordpro(&contents, semitones));
+        }
     }
 
-    return Ok(());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // write_lines
+    //
+
+    #[test]
+    fn test_write_lines_polychord() {
+        let mut out: Vec<u8> = vec![];
+        let lines = ["D|C", "C/E over G"].into_iter().map(String::from);
+
+        let failed = write_lines(&mut out, LineInput::ChordNames, lines, None).expect("hmm");
+        let ret = String::from_utf8(out).expect("hmm");
+
+        assert_eq!(failed, 0);
+        assert!(ret.contains("Polychord: D|C\nUpper: D (D F# A)\nLower: C (C E G)\n"));
+        assert!(ret.contains("Polychord: C/E|G\n"));
+    }
 }
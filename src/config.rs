@@ -0,0 +1,12 @@
+use std::{env, path::PathBuf};
+
+// personal files like the chord dictionary live in $XDG_CONFIG_HOME/struck,
+// falling back to ~/.config/struck
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(base.join("struck"))
+}
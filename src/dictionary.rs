@@ -0,0 +1,304 @@
+use std::{fmt, fs, io, path::Path, path::PathBuf, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    config::config_dir,
+    schema::{unversioned_to_v1, Schema, SchemaError},
+    theory::{note::Note, voicing::Voicing},
+};
+
+// voicings the user has played in and named, kept in their config so later identifications
+// can point out "that's your saved 'Hendrix-voicing-1'"
+
+const DICTIONARY_SCHEMA: Schema = Schema {
+    kind: "dictionary",
+    migrations: &[unversioned_to_v1],
+};
+const DICTIONARY_FILE: &str = "dictionary";
+
+#[derive(Debug)]
+pub enum DictionaryError {
+    Io(String),
+    InvalidLine(String),
+    InvalidName(String),
+    Schema(SchemaError),
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DictionaryError::Io(e) => write!(f, "{}", e),
+            DictionaryError::InvalidLine(line) => write!(f, "couldn't read the line {}", line),
+            DictionaryError::InvalidName(name) => {
+                write!(
+                    f,
+                    "{} can't be a name, it needs to be on one line without an =",
+                    name
+                )
+            }
+            DictionaryError::Schema(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<SchemaError> for DictionaryError {
+    fn from(e: SchemaError) -> Self {
+        DictionaryError::Schema(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedVoicing {
+    pub name: String,
+    pub voicing: Voicing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChordDictionary {
+    pub entries: Vec<SavedVoicing>,
+}
+
+impl ChordDictionary {
+    pub fn default_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(DICTIONARY_FILE))
+    }
+
+    // nothing saved yet is just an empty dictionary
+    pub fn load(path: &Path) -> Result<ChordDictionary, DictionaryError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => ChordDictionary::from_str(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ChordDictionary::default()),
+            Err(e) => Err(DictionaryError::Io(e.to_string())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), DictionaryError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| DictionaryError::Io(e.to_string()))?;
+        }
+
+        fs::write(path, self.to_string()).map_err(|e| DictionaryError::Io(e.to_string()))
+    }
+
+    // saving under a name that's already there replaces the old voicing
+    pub fn add(&mut self, name: &str, voicing: Voicing) -> Result<(), DictionaryError> {
+        let name = name.trim();
+
+        if name.is_empty() || name.contains('=') || name.contains('\n') {
+            return Err(DictionaryError::InvalidName(name.to_string()));
+        }
+
+        self.entries.retain(|e| e.name != name);
+        self.entries.push(SavedVoicing {
+            name: name.to_string(),
+            voicing,
+        });
+
+        Ok(())
+    }
+
+    // saved voicings with the same bass and the same notes above it, octaves and doublings aside
+    pub fn find_matches(&self, notes: &[Note]) -> Vec<&SavedVoicing> {
+        let bass = notes.first();
+        let wanted: Vec<&Note> = notes
+            .iter()
+            .unique()
+            .sorted_by_key(|n| n.to_string())
+            .collect();
+
+        self.entries
+            .iter()
+            .filter(|entry| {
+                let saved = entry.voicing.notes();
+                let saved_notes: Vec<&Note> = saved
+                    .iter()
+                    .unique()
+                    .sorted_by_key(|n| n.to_string())
+                    .collect();
+
+                saved.first() == bass && saved_notes == wanted
+            })
+            .collect()
+    }
+}
+
+// one voicing per line as midi numbers from the lowest up, e.g. "Hendrix-voicing-1 = 40 52 56 62 67"
+impl fmt::Display for ChordDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", DICTIONARY_SCHEMA.header())?;
+
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{} = {}",
+                entry.name,
+                entry.voicing.pitches.iter().join(" ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ChordDictionary {
+    type Err = DictionaryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // older files are brought up to date first, so everything below only reads the current layout
+        let s = &DICTIONARY_SCHEMA.migrate(s)?;
+        let mut entries: Vec<SavedVoicing> = vec![];
+
+        for line in s.lines().map(|l| l.trim()) {
+            // the header and any other comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, pitches) = match line.rsplit_once('=') {
+                Some(parts) => parts,
+                None => return Err(DictionaryError::InvalidLine(line.to_string())),
+            };
+
+            let mut pitches = pitches
+                .split_whitespace()
+                .map(|p| p.parse::<u8>())
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| DictionaryError::InvalidLine(line.to_string()))?;
+
+            if name.trim().is_empty() || pitches.is_empty() {
+                return Err(DictionaryError::InvalidLine(line.to_string()));
+            }
+
+            pitches.sort();
+
+            entries.push(SavedVoicing {
+                name: name.trim().to_string(),
+                voicing: Voicing { pitches },
+            });
+        }
+
+        Ok(ChordDictionary { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hendrix() -> Voicing {
+        // E7#9 as E E G# D G up from the low E
+        Voicing {
+            pitches: vec![40, 52, 56, 62, 67],
+        }
+    }
+
+    //
+    // add
+    //
+
+    #[test]
+    fn test_add_replaces_same_name() {
+        let mut dictionary = ChordDictionary::default();
+
+        dictionary.add("mine", hendrix()).expect("hmm");
+        dictionary
+            .add(
+                "mine",
+                Voicing {
+                    pitches: vec![48, 52, 55],
+                },
+            )
+            .expect("hmm");
+
+        assert_eq!(dictionary.entries.len(), 1);
+        assert_eq!(dictionary.entries[0].voicing.pitches, vec![48, 52, 55]);
+    }
+
+    #[test]
+    fn test_add_invalid_name() {
+        let mut dictionary = ChordDictionary::default();
+
+        assert!(dictionary.add("  ", hendrix()).is_err());
+        assert!(dictionary.add("a = b", hendrix()).is_err());
+    }
+
+    //
+    // find_matches
+    //
+
+    #[test]
+    fn test_find_matches_ignores_octaves_and_order() {
+        let mut dictionary = ChordDictionary::default();
+        dictionary.add("Hendrix-voicing-1", hendrix()).expect("hmm");
+
+        let ret = dictionary.find_matches(&[Note::E, Note::D, Note::Gs, Note::G]);
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].name, "Hendrix-voicing-1");
+    }
+
+    #[test]
+    fn test_find_matches_needs_same_bass() {
+        let mut dictionary = ChordDictionary::default();
+        dictionary.add("Hendrix-voicing-1", hendrix()).expect("hmm");
+
+        let ret = dictionary.find_matches(&[Note::Gs, Note::E, Note::D, Note::G]);
+
+        assert!(ret.is_empty());
+    }
+
+    //
+    // round trip
+    //
+
+    #[test]
+    fn test_dictionary_round_trip() {
+        let mut dictionary = ChordDictionary::default();
+        dictionary.add("Hendrix-voicing-1", hendrix()).expect("hmm");
+        dictionary
+            .add(
+                "so what",
+                Voicing {
+                    pitches: vec![52, 57, 62, 67, 71],
+                },
+            )
+            .expect("hmm");
+
+        let ret = ChordDictionary::from_str(&dictionary.to_string()).expect("hmm");
+
+        assert_eq!(ret, dictionary);
+    }
+
+    #[test]
+    fn test_dictionary_from_str_unversioned_file_still_loads() {
+        let ret = ChordDictionary::from_str("# struck dictionary\nmine = 48 52 55\n").expect("hmm");
+
+        assert_eq!(ret.entries[0].voicing.pitches, vec![48, 52, 55]);
+        assert!(ret.to_string().starts_with("# struck dictionary v1\n"));
+    }
+
+    #[test]
+    fn test_dictionary_from_str_newer_version() {
+        let ret = ChordDictionary::from_str("# struck dictionary v99\nmine = 48 52 55\n");
+
+        assert!(matches!(
+            ret,
+            Err(DictionaryError::Schema(SchemaError::TooNew(99)))
+        ));
+    }
+
+    #[test]
+    fn test_dictionary_from_str_invalid_line() {
+        let ret = ChordDictionary::from_str("# struck dictionary\nmine 40 52\n");
+
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let ret = ChordDictionary::load(Path::new("/nonexistent/struck/dictionary")).expect("hmm");
+
+        assert!(ret.entries.is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    env, fmt,
+    io::{self, IsTerminal},
+};
+
+use struck::{aliases::Aliases, config::config_dir, dictionary::ChordDictionary};
+
+// struck doctor, looks over everything struck depends on outside itself and says what to do about anything wrong
+// only a failed check means something won't work, warnings are things that'll look worse than they should
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Ok,
+    Warning,
+    Failed,
+    // nothing there to check in this build
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Warning => write!(f, "warn"),
+            Status::Failed => write!(f, "FAIL"),
+            Status::Skipped => write!(f, "skip"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Check {
+        Check {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+// [ok] config directory: /home/me/.config/struck
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.status, self.name, self.detail)
+    }
+}
+
+// prints every check, false if any failed
+pub fn run() -> bool {
+    let checks = [
+        check_config_dir(),
+        check_aliases(),
+        check_dictionary(),
+        check_color(
+            env::var_os("NO_COLOR").is_some(),
+            env::var("TERM").ok().as_deref(),
+            io::stdout().is_terminal(),
+        ),
+        check_unicode(locale().as_deref()),
+        Check::new(
+            "audio output",
+            Status::Skipped,
+            "no audio playback in this build",
+        ),
+        Check::new("midi ports", Status::Skipped, "no live midi in this build"),
+    ];
+
+    for check in &checks {
+        println!("{}", check);
+    }
+
+    !checks.iter().any(|c| c.status == Status::Failed)
+}
+
+fn check_config_dir() -> Check {
+    match config_dir() {
+        Some(dir) if dir.is_dir() => {
+            Check::new("config directory", Status::Ok, dir.display().to_string())
+        }
+        Some(dir) => Check::new(
+            "config directory",
+            Status::Ok,
+            format!("{} (made the first time something is saved)", dir.display()),
+        ),
+        None => Check::new(
+            "config directory",
+            Status::Failed,
+            "neither XDG_CONFIG_HOME nor HOME is set, set one so the dictionary and aliases have somewhere to live",
+        ),
+    }
+}
+
+fn check_aliases() -> Check {
+    let path = match Aliases::default_path() {
+        Some(path) => path,
+        None => return Check::new("aliases", Status::Skipped, "no config directory"),
+    };
+
+    match Aliases::load(&path) {
+        Ok(aliases) => Check::new(
+            "aliases",
+            Status::Ok,
+            format!("{} loaded from {}", aliases.entries.len(), path.display()),
+        ),
+        Err(e) => Check::new(
+            "aliases",
+            Status::Failed,
+            format!("{}, fix or remove {}", e, path.display()),
+        ),
+    }
+}
+
+fn check_dictionary() -> Check {
+    let path = match ChordDictionary::default_path() {
+        Some(path) => path,
+        None => return Check::new("dictionary", Status::Skipped, "no config directory"),
+    };
+
+    match ChordDictionary::load(&path) {
+        Ok(dictionary) => Check::new(
+            "dictionary",
+            Status::Ok,
+            format!(
+                "{} voicings loaded from {}",
+                dictionary.entries.len(),
+                path.display()
+            ),
+        ),
+        Err(e) => Check::new(
+            "dictionary",
+            Status::Failed,
+            format!("{}, fix or remove {}", e, path.display()),
+        ),
+    }
+}
+
+// https://no-color.org
+fn check_color(no_color: bool, term: Option<&str>, is_terminal: bool) -> Check {
+    if no_color {
+        return Check::new("colour", Status::Ok, "off, NO_COLOR is set");
+    }
+
+    match term {
+        _ if !is_terminal => Check::new(
+            "colour",
+            Status::Warning,
+            "output isn't going to a terminal, it'll be plain",
+        ),
+        None | Some("") | Some("dumb") => Check::new(
+            "colour",
+            Status::Warning,
+            "TERM doesn't say the terminal can do colour, try TERM=xterm-256color",
+        ),
+        Some(term) => Check::new("colour", Status::Ok, format!("TERM={}", term)),
+    }
+}
+
+// the same order the C library looks in
+fn locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+fn check_unicode(locale: Option<&str>) -> Check {
+    match locale {
+        Some(locale) if locale.to_lowercase().replace('-', "").contains("utf8") => {
+            Check::new("unicode", Status::Ok, locale.to_string())
+        }
+        Some(locale) => Check::new(
+            "unicode",
+            Status::Warning,
+            format!(
+                "locale {} isn't UTF-8, keyboard diagrams may not draw properly, try LANG=en_US.UTF-8",
+                locale
+            ),
+        ),
+        None => Check::new(
+            "unicode",
+            Status::Warning,
+            "no locale set, keyboard diagrams may not draw properly, try LANG=en_US.UTF-8",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // check_color
+    //
+
+    #[test]
+    fn test_check_color() {
+        assert_eq!(
+            check_color(false, Some("xterm-256color"), true).status,
+            Status::Ok
+        );
+        assert_eq!(check_color(true, None, false).status, Status::Ok);
+        assert_eq!(
+            check_color(false, Some("dumb"), true).status,
+            Status::Warning
+        );
+        assert_eq!(
+            check_color(false, Some("xterm"), false).status,
+            Status::Warning
+        );
+    }
+
+    //
+    // check_unicode
+    //
+
+    #[test]
+    fn test_check_unicode() {
+        assert_eq!(check_unicode(Some("en_GB.UTF-8")).status, Status::Ok);
+        assert_eq!(check_unicode(Some("C.utf8")).status, Status::Ok);
+        assert_eq!(check_unicode(Some("C")).status, Status::Warning);
+        assert_eq!(check_unicode(None).status, Status::Warning);
+    }
+
+    //
+    // Check display
+    //
+
+    #[test]
+    fn test_check_display() {
+        let check = Check::new("unicode", Status::Ok, "C.UTF-8");
+
+        assert_eq!(check.to_string(), "[ok] unicode: C.UTF-8");
+    }
+}
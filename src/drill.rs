@@ -0,0 +1,273 @@
+use std::{fmt, str::FromStr};
+
+use crate::theory::{interval::Interval, note::Note, pitch::Pitch, pitch_class::PitchClass};
+
+// interval drills, two notes to name the interval between or a note and an interval to find the note at the top
+// the questions start somewhere in the octave above middle C and always go up
+
+// how hard the questions get
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DrillTier {
+    // the intervals up from the root of a major scale
+    Diatonic,
+    // every interval inside the octave
+    Chromatic,
+    // and the 9ths, 11ths and 13ths past it
+    Compound,
+}
+
+pub const DRILL_TIERS: [DrillTier; 3] = [
+    DrillTier::Diatonic,
+    DrillTier::Chromatic,
+    DrillTier::Compound,
+];
+
+impl fmt::Display for DrillTier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrillTier::Diatonic => write!(f, "Diatonic"),
+            DrillTier::Chromatic => write!(f, "Chromatic"),
+            DrillTier::Compound => write!(f, "Compound"),
+        }
+    }
+}
+
+impl DrillTier {
+    pub fn intervals(&self) -> Vec<Interval> {
+        let diatonic = [
+            Interval::MajorSecond,
+            Interval::MajorThird,
+            Interval::PerfectFourth,
+            Interval::PerfectFifth,
+            Interval::MajorSixth,
+            Interval::MajorSeventh,
+        ];
+        let chromatic = [
+            Interval::MinorThird,
+            Interval::DiminishedFifth,
+            Interval::AugmentedFifth,
+            Interval::MinorSeventh,
+        ];
+        let compound = [
+            Interval::MinorNinth,
+            Interval::MajorNinth,
+            Interval::AugmentedNinth,
+            Interval::PerfectEleventh,
+            Interval::AugmentedEleventh,
+            Interval::MinorThirteenth,
+            Interval::MajorThirteenth,
+        ];
+
+        match self {
+            DrillTier::Diatonic => diatonic.to_vec(),
+            DrillTier::Chromatic => [diatonic.as_slice(), &chromatic].concat(),
+            DrillTier::Compound => [diatonic.as_slice(), &chromatic, &compound].concat(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DrillQuestion {
+    // what's the interval from one to the other
+    NameInterval { from: Pitch, to: Pitch },
+    // what note is the interval up from this one
+    FindNote { from: Pitch, interval: Interval },
+}
+
+impl fmt::Display for DrillQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrillQuestion::NameInterval { from, to } => {
+                write!(f, "What's the interval from {} up to {}?", from, to)
+            }
+            DrillQuestion::FindNote { from, interval } => {
+                let name = interval.to_string();
+                let article = match name.starts_with(['A', 'E', 'I', 'O', 'U']) {
+                    true => "an",
+                    false => "a",
+                };
+                write!(f, "What note is {} {} above {}?", article, name, from)
+            }
+        }
+    }
+}
+
+impl DrillQuestion {
+    // a question on one of the tier's intervals, either way round
+    pub fn random(tier: DrillTier, rng: &mut fastrand::Rng) -> DrillQuestion {
+        let intervals = tier.intervals();
+        let interval = intervals[rng.usize(..intervals.len())];
+        let from = Pitch(60 + rng.u8(..12));
+
+        match rng.bool() {
+            true => DrillQuestion::NameInterval {
+                from,
+                to: Pitch(from.0 + interval.semitones() as u8),
+            },
+            false => DrillQuestion::FindNote { from, interval },
+        }
+    }
+
+    // the answer as it would be written, a name for the interval or the note
+    pub fn answer(&self) -> String {
+        match self {
+            DrillQuestion::NameInterval { from, to } => {
+                interval_names((to.0 - from.0) as usize)[0].to_string()
+            }
+            DrillQuestion::FindNote { from, interval } => {
+                Note::from_midi(from.0 + interval.semitones() as u8).to_string()
+            }
+        }
+    }
+
+    // any of an interval's names will do (a tritone is a diminished 5th is an augmented 4th),
+    // and any spelling of the note (D# or Eb)
+    pub fn check(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+
+        match self {
+            DrillQuestion::NameInterval { from, to } => interval_names((to.0 - from.0) as usize)
+                .iter()
+                .any(|name| match name.len() {
+                    // short names are case sensitive, M3 isn't m3
+                    0..=3 => *name == answer,
+                    _ => name.eq_ignore_ascii_case(answer),
+                }),
+            DrillQuestion::FindNote { from, interval } => match Note::from_str(answer) {
+                Ok(note) => {
+                    PitchClass::from(note)
+                        == PitchClass::new(from.0 as usize + interval.semitones())
+                }
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+// what an interval of so many semitones is called, the usual name first then the other names and shorthand
+fn interval_names(semitones: usize) -> &'static [&'static str] {
+    match semitones {
+        2 => &["Major 2nd", "M2", "2"],
+        3 => &["Minor 3rd", "m3", "b3"],
+        4 => &["Major 3rd", "M3", "3"],
+        5 => &["Perfect 4th", "P4", "4"],
+        6 => &[
+            "Diminished 5th",
+            "Augmented 4th",
+            "Tritone",
+            "d5",
+            "A4",
+            "b5",
+            "#4",
+        ],
+        7 => &["Perfect 5th", "P5", "5"],
+        8 => &["Minor 6th", "Augmented 5th", "m6", "b6", "#5"],
+        9 => &["Major 6th", "Diminished 7th", "M6", "6"],
+        10 => &["Minor 7th", "m7", "b7"],
+        11 => &["Major 7th", "M7", "7"],
+        13 => &["Minor 9th", "m9", "b9"],
+        14 => &["Major 9th", "M9", "9"],
+        15 => &["Augmented 9th", "A9", "#9"],
+        17 => &["Perfect 11th", "P11", "11"],
+        18 => &["Augmented 11th", "A11", "#11"],
+        20 => &["Minor 13th", "m13", "b13"],
+        21 => &["Major 13th", "M13", "13"],
+        _ => &["Unknown"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // intervals
+    //
+
+    #[test]
+    fn test_intervals_tiers_build_up() {
+        let diatonic = DrillTier::Diatonic.intervals();
+        let chromatic = DrillTier::Chromatic.intervals();
+        let compound = DrillTier::Compound.intervals();
+
+        assert!(!diatonic.contains(&Interval::MinorThird));
+        assert!(chromatic.contains(&Interval::MinorThird));
+        assert!(diatonic.iter().all(|i| chromatic.contains(i)));
+        assert!(chromatic.iter().all(|i| compound.contains(i)));
+        assert!(compound.iter().any(|i| i.semitones() > 12));
+    }
+
+    //
+    // random
+    //
+
+    #[test]
+    fn test_random_stays_in_tier() {
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        for _ in 0..50 {
+            match DrillQuestion::random(DrillTier::Diatonic, &mut rng) {
+                DrillQuestion::NameInterval { from, to } => {
+                    assert!([2, 4, 5, 7, 9, 11].contains(&(to.0 - from.0)))
+                }
+                DrillQuestion::FindNote { interval, .. } => {
+                    assert!(DrillTier::Diatonic.intervals().contains(&interval))
+                }
+            }
+        }
+    }
+
+    //
+    // check
+    //
+
+    #[test]
+    fn test_check_interval_names() {
+        let question = DrillQuestion::NameInterval {
+            from: Pitch::new(&Note::C, 4),
+            to: Pitch::new(&Note::Fs, 4),
+        };
+
+        assert!(question.check("tritone"));
+        assert!(question.check("Diminished 5th"));
+        assert!(question.check("#4"));
+        assert!(!question.check("Perfect 4th"));
+        assert_eq!(question.answer(), "Diminished 5th");
+    }
+
+    #[test]
+    fn test_check_short_names_case_sensitive() {
+        let question = DrillQuestion::NameInterval {
+            from: Pitch::new(&Note::A, 4),
+            to: Pitch::new(&Note::C, 5),
+        };
+
+        assert!(question.check("m3"));
+        assert!(!question.check("M3"));
+    }
+
+    #[test]
+    fn test_check_compound() {
+        let question = DrillQuestion::NameInterval {
+            from: Pitch::new(&Note::C, 4),
+            to: Pitch::new(&Note::D, 5),
+        };
+
+        assert!(question.check("9"));
+        assert!(!question.check("2"));
+    }
+
+    #[test]
+    fn test_check_note_any_spelling() {
+        let question = DrillQuestion::FindNote {
+            from: Pitch::new(&Note::C, 4),
+            interval: Interval::MinorThird,
+        };
+
+        assert_eq!(question.to_string(), "What note is a Minor 3rd above C4?");
+        assert!(question.check("Eb"));
+        assert!(question.check("D#"));
+        assert!(!question.check("E"));
+        assert!(!question.check("H"));
+    }
+}
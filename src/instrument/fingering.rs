@@ -0,0 +1,347 @@
+use itertools::Itertools;
+
+use crate::{
+    instrument::fretboard::{Fingering, FretboardError, Tuning},
+    parser::chord_parser::identify_from_notes,
+    theory::{
+        chord::Chord,
+        interval::{get_interval, Interval},
+        note::Note,
+    },
+};
+
+// how far up the neck to look for shapes
+const MAX_FRET: u8 = 12;
+// the most frets a hand can cover without stretching
+const HAND_SPAN: u8 = 3;
+const FINGERS: usize = 4;
+
+// which shapes come first when there's more than one way to play a chord
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FingeringStrategy {
+    // tightest shapes, barres and movable forms without open strings
+    Compact,
+    // as many ringing open strings as possible, for folk and ambient playing
+    OpenStrings,
+}
+
+// every playable fingering of the chord, best first for the strategy
+// playable here means:
+// - all but two strings are played (every string with fewer than six), every string is muted or plays a chord tone, with no muted strings in between played ones
+// - the lowest note is the bass (or the root if there's no slash bass), unless the tuning is reentrant
+// - every chord tone is there, apart from the 5th which is often left out
+// - the fretted notes fit under one hand, with a barre across the lowest fret counting as one finger
+pub fn find_fingerings(
+    chord: &Chord,
+    tuning: &Tuning,
+    strategy: FingeringStrategy,
+) -> Vec<Fingering> {
+    let chord_tones: Vec<Note> = chord.chord_tones();
+
+    let required: Vec<Note> = std::iter::once(chord.root)
+        .chain(
+            chord
+                .intervals
+                .iter()
+                .filter(|i| **i != Interval::PerfectFifth)
+                .filter_map(|i| get_interval(&chord.root, *i).ok()),
+        )
+        .collect();
+
+    let bass = chord.bass.unwrap_or(chord.root);
+
+    let mut fingerings: Vec<Fingering> = vec![];
+
+    // slide a hand sized window up the neck, each string can be open, muted or fretted in the window
+    for position in 1..=MAX_FRET - HAND_SPAN {
+        let options: Vec<Vec<Option<u8>>> = tuning
+            .open_pitches()
+            .into_iter()
+            .map(|open| {
+                std::iter::once(None)
+                    .chain(std::iter::once(Some(0)))
+                    .chain((position..=position + HAND_SPAN).map(Some))
+                    .filter(|fret| match fret {
+                        Some(f) => chord_tones.contains(&Note::from_midi(open + f)),
+                        None => true,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for frets in cartesian(&options) {
+            let fingering = Fingering { frets };
+
+            if is_playable(&fingering, tuning, &required, &bass) && !fingerings.contains(&fingering)
+            {
+                fingerings.push(fingering);
+            }
+        }
+    }
+
+    match strategy {
+        FingeringStrategy::Compact => fingerings.sort_by_key(|f| {
+            (
+                f.open_strings(),
+                f.fret_span(),
+                usize::MAX - f.sounding_pitches(tuning).len(),
+                f.lowest_fret(),
+            )
+        }),
+        FingeringStrategy::OpenStrings => fingerings.sort_by_key(|f| {
+            (
+                usize::MAX - f.open_strings(),
+                f.highest_fret(),
+                usize::MAX - f.sounding_pitches(tuning).len(),
+            )
+        }),
+    }
+
+    fingerings
+}
+
+// the other way round, what chord does this fingering make
+// the lowest sounding note is the bass, so a fingering can come back as a slash chord
+pub fn identify_fingering(
+    fingering: &Fingering,
+    tuning: &Tuning,
+) -> Result<Vec<Chord>, FretboardError> {
+    if fingering.frets.len() != tuning.strings.len() {
+        return Err(FretboardError::InvalidFingering(format!(
+            "{} has {} strings but {} tuning has {}",
+            fingering,
+            fingering.frets.len(),
+            tuning.name,
+            tuning.strings.len()
+        )));
+    }
+
+    // guitar shapes double notes all the time, only the first (lowest) of each matters
+    let notes: Vec<Note> = fingering
+        .notes_low_to_high(tuning)
+        .into_iter()
+        .unique()
+        .collect();
+
+    Ok(identify_from_notes(&notes))
+}
+
+fn is_playable(fingering: &Fingering, tuning: &Tuning, required: &[Note], bass: &Note) -> bool {
+    let played: Vec<usize> = fingering
+        .frets
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.is_some())
+        .map(|(i, _)| i)
+        .collect();
+
+    // most of the strings and nothing muted in the middle
+    let (first, last) = match (played.first(), played.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return false,
+    };
+
+    // a uke has too few strings to leave any out
+    let min_strings = match tuning.strings.len() {
+        strings if strings >= 6 => strings - 2,
+        strings => strings,
+    };
+    if played.len() < min_strings || last - first + 1 != played.len() {
+        return false;
+    }
+
+    // a reentrant tuning is too high and close to have a bass, any inversion will do
+    let pitches = fingering.sounding_pitches(tuning);
+    let lowest = pitches.iter().min().copied().unwrap_or_default();
+    if !tuning.is_reentrant() && Note::from_midi(lowest) != *bass {
+        return false;
+    }
+
+    let notes = fingering.sounding_notes(tuning);
+    if !required.iter().all(|n| notes.contains(n)) {
+        return false;
+    }
+
+    // a barre on the lowest fret only takes one finger
+    let lowest_fret = fingering.lowest_fret();
+    let fretted: Vec<u8> = fingering
+        .frets
+        .iter()
+        .flatten()
+        .filter(|f| **f > 0)
+        .copied()
+        .collect();
+    let barre = fretted.iter().filter(|f| **f == lowest_fret).count() > 1;
+    let fingers = match barre {
+        true => fretted.iter().filter(|f| **f != lowest_fret).count() + 1,
+        false => fretted.len(),
+    };
+
+    fingers <= FINGERS && fingering.fret_span() <= HAND_SPAN
+}
+
+// every combination of one option per string
+fn cartesian(options: &[Vec<Option<u8>>]) -> Vec<Vec<Option<u8>>> {
+    options.iter().fold(vec![vec![]], |combinations, string| {
+        combinations
+            .iter()
+            .flat_map(|combination| {
+                string.iter().map(move |fret| {
+                    let mut next = combination.clone();
+                    next.push(*fret);
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instrument::fretboard::Instrument, parser::chord_parser::identify_from_name};
+    use std::str::FromStr;
+
+    fn fingerings_for(name: &str, strategy: FingeringStrategy) -> Vec<String> {
+        let chord = identify_from_name(name.to_string()).expect("hmm");
+
+        find_fingerings(&chord, &Tuning::standard_guitar(), strategy)
+            .iter()
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    //
+    // find_fingerings
+    //
+
+    #[test]
+    fn test_find_fingerings_open_strings_finds_cowboy_chords() {
+        assert_eq!(
+            fingerings_for("C", FingeringStrategy::OpenStrings)[0],
+            "x32010"
+        );
+        assert_eq!(
+            fingerings_for("G", FingeringStrategy::OpenStrings)[0],
+            "320003"
+        );
+        assert_eq!(
+            fingerings_for("Em", FingeringStrategy::OpenStrings)[0],
+            "022000"
+        );
+    }
+
+    #[test]
+    fn test_find_fingerings_open_strings_ranked_by_open_count() {
+        let chord = identify_from_name("D".to_string()).expect("hmm");
+
+        let ret = find_fingerings(
+            &chord,
+            &Tuning::standard_guitar(),
+            FingeringStrategy::OpenStrings,
+        );
+
+        assert_eq!(ret[0].to_string(), "xx0232");
+        assert!(ret.len() > 1);
+        for pair in ret.windows(2) {
+            assert!(pair[0].open_strings() >= pair[1].open_strings());
+        }
+    }
+
+    #[test]
+    fn test_find_fingerings_compact_prefers_tight_shapes() {
+        let ret = fingerings_for("F", FingeringStrategy::Compact);
+
+        assert_eq!(ret[0], "133211");
+        assert_ne!(
+            ret[0],
+            fingerings_for("F", FingeringStrategy::OpenStrings)[0]
+        );
+    }
+
+    // with a capo on 2 a D is played as a C shape
+    #[test]
+    fn test_find_fingerings_with_capo() {
+        let chord = identify_from_name("D".to_string()).expect("hmm");
+        let tuning = Tuning::standard_guitar().with_capo("2".parse().expect("hmm"));
+
+        let ret = find_fingerings(&chord, &tuning, FingeringStrategy::OpenStrings);
+
+        assert_eq!(ret[0].to_string(), "x32010");
+    }
+
+    #[test]
+    fn test_find_fingerings_respects_slash_bass() {
+        let chord = identify_from_name("D/F#".to_string()).expect("hmm");
+        let tuning = Tuning::standard_guitar();
+
+        let ret = find_fingerings(&chord, &tuning, FingeringStrategy::OpenStrings);
+
+        assert!(!ret.is_empty());
+        for fingering in ret {
+            assert_eq!(fingering.sounding_notes(&tuning)[0], Note::Fs);
+        }
+    }
+
+    #[test]
+    fn test_find_fingerings_ukulele() {
+        let tuning = Instrument::named("ukulele").expect("hmm").standard_tuning();
+        let first = |name: &str| {
+            let chord = identify_from_name(name.to_string()).expect("hmm");
+            find_fingerings(&chord, &tuning, FingeringStrategy::OpenStrings)[0].to_string()
+        };
+
+        assert_eq!(first("C"), "0003");
+        assert_eq!(first("F"), "2010");
+        assert_eq!(first("Am"), "2000");
+    }
+
+    //
+    // identify_fingering
+    //
+
+    #[test]
+    fn test_identify_fingering_open_chords() {
+        let tuning = Tuning::standard_guitar();
+
+        let ret =
+            identify_fingering(&Fingering::from_str("x32010").expect("hmm"), &tuning).expect("hmm");
+        assert_eq!(ret[0].name, "C");
+
+        let ret =
+            identify_fingering(&Fingering::from_str("3x0003").expect("hmm"), &tuning).expect("hmm");
+        assert_eq!(ret[0].name, "G");
+    }
+
+    #[test]
+    fn test_identify_fingering_names_bass() {
+        let ret = identify_fingering(
+            &Fingering::from_str("032010").expect("hmm"),
+            &Tuning::standard_guitar(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret[0].name, "C/E");
+    }
+
+    #[test]
+    fn test_identify_fingering_with_tuning() {
+        let ret = identify_fingering(
+            &Fingering::from_str("000232").expect("hmm"),
+            &Tuning::drop_d_guitar(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret[0].name, "D");
+    }
+
+    #[test]
+    fn test_identify_fingering_wrong_string_count() {
+        let ret = identify_fingering(
+            &Fingering::from_str("0232").expect("hmm"),
+            &Tuning::standard_guitar(),
+        );
+
+        assert!(ret.is_err());
+    }
+}
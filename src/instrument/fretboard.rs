@@ -0,0 +1,573 @@
+use itertools::Itertools;
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+use crate::theory::note::Note;
+
+#[derive(Debug)]
+pub enum FretboardError {
+    InvalidCapo(String),
+    InvalidFingering(String),
+}
+
+impl fmt::Display for FretboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FretboardError::InvalidCapo(capo) => write!(f, "bad capo: {}", capo),
+            FretboardError::InvalidFingering(fingering) => {
+                write!(f, "bad fingering: {}", fingering)
+            }
+        }
+    }
+}
+
+// a capo across some or all of the strings
+// strings are numbered the way guitarists count them, 1 is the highest string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capo {
+    pub fret: u8,
+    // None covers every string
+    pub strings: Option<RangeInclusive<usize>>,
+}
+
+impl Capo {
+    fn covers(&self, string_number: usize) -> bool {
+        match &self.strings {
+            Some(strings) => strings.contains(&string_number),
+            None => true,
+        }
+    }
+}
+
+// "2" for a full capo at the 2nd fret, "2:3-5" to only cover strings 3 to 5
+impl FromStr for Capo {
+    type Err = FretboardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FretboardError::InvalidCapo(s.to_string());
+
+        let (fret, strings) = match s.trim().split_once(':') {
+            Some((fret, strings)) => (fret, Some(strings)),
+            None => (s.trim(), None),
+        };
+
+        let fret: u8 = fret.trim().parse().map_err(|_| invalid())?;
+        if fret == 0 {
+            return Err(invalid());
+        }
+
+        let strings = match strings {
+            Some(strings) => {
+                let (low, high) = match strings.split_once('-') {
+                    Some((low, high)) => (low, high),
+                    None => (strings, strings),
+                };
+                let low: usize = low.trim().parse().map_err(|_| invalid())?;
+                let high: usize = high.trim().parse().map_err(|_| invalid())?;
+                if low == 0 || low > high {
+                    return Err(invalid());
+                }
+                Some(low..=high)
+            }
+            None => None,
+        };
+
+        Ok(Capo { fret, strings })
+    }
+}
+
+// the usual guitar tunings by name, open string pitches lowest first as midi numbers
+pub const GUITAR_TUNINGS: [(&str, &[u8]); 7] = [
+    ("Standard", &[40, 45, 50, 55, 59, 64]),
+    ("Drop D", &[38, 45, 50, 55, 59, 64]),
+    ("Half step down", &[39, 44, 49, 54, 58, 63]),
+    ("DADGAD", &[38, 45, 50, 55, 57, 62]),
+    ("Open G", &[38, 43, 50, 55, 59, 62]),
+    ("Open D", &[38, 45, 50, 54, 57, 62]),
+    ("Open E", &[40, 47, 52, 56, 59, 64]),
+];
+
+// strings in the order they're held, not pitch order, so standard GCEA has its G above the C
+pub const UKULELE_TUNINGS: [(&str, &[u8]); 3] = [
+    ("Standard", &[67, 60, 64, 69]),
+    ("Low G", &[55, 60, 64, 69]),
+    ("Baritone", &[50, 55, 59, 64]),
+];
+
+// a fretted instrument and the tunings it's played in, its standard tuning first
+// another instrument is another entry in INSTRUMENTS
+#[derive(Debug, PartialEq, Eq)]
+pub struct Instrument {
+    pub name: &'static str,
+    pub tunings: &'static [(&'static str, &'static [u8])],
+}
+
+pub const INSTRUMENTS: [Instrument; 2] = [
+    Instrument {
+        name: "guitar",
+        tunings: &GUITAR_TUNINGS,
+    },
+    Instrument {
+        name: "ukulele",
+        tunings: &UKULELE_TUNINGS,
+    },
+];
+
+impl Instrument {
+    // one of INSTRUMENTS by name ignoring case, uke for short
+    pub fn named(name: &str) -> Option<&'static Instrument> {
+        let name = match name.trim().to_lowercase().as_str() {
+            "uke" => "ukulele".to_string(),
+            name => name.to_string(),
+        };
+
+        INSTRUMENTS.iter().find(|i| i.name == name)
+    }
+
+    // one of its tunings, the name matched ignoring case so "open g" is Open G
+    pub fn tuning(&self, name: &str) -> Option<Tuning> {
+        self.tunings
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            .map(|(name, strings)| Tuning {
+                name,
+                strings: strings.to_vec(),
+                capo: None,
+            })
+    }
+
+    pub fn standard_tuning(&self) -> Tuning {
+        let (name, strings) = self.tunings[0];
+
+        Tuning {
+            name,
+            strings: strings.to_vec(),
+            capo: None,
+        }
+    }
+}
+
+// open string pitches as midi numbers, lowest string first
+// midi keeps the octave so we can tell which string is actually sounding the lowest note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tuning {
+    pub name: &'static str,
+    pub strings: Vec<u8>,
+    pub capo: Option<Capo>,
+}
+
+impl Tuning {
+    // E2 A2 D3 G3 B3 E4
+    pub fn standard_guitar() -> Tuning {
+        Tuning {
+            name: "Standard",
+            strings: vec![40, 45, 50, 55, 59, 64],
+            capo: None,
+        }
+    }
+
+    // low E down to D for a deeper bass
+    pub fn drop_d_guitar() -> Tuning {
+        Tuning {
+            name: "Drop D",
+            strings: vec![38, 45, 50, 55, 59, 64],
+            capo: None,
+        }
+    }
+
+    // one of GUITAR_TUNINGS, the name matched ignoring case so "open g" is Open G
+    pub fn guitar(name: &str) -> Option<Tuning> {
+        INSTRUMENTS[0].tuning(name)
+    }
+
+    // a string tuned above the one after it, like the high G on a ukulele,
+    // so the lowest string isn't always playing the lowest note
+    pub fn is_reentrant(&self) -> bool {
+        self.strings.windows(2).any(|pair| pair[0] > pair[1])
+    }
+
+    pub fn with_capo(mut self, capo: Capo) -> Tuning {
+        self.capo = Some(capo);
+        self
+    }
+
+    // what each string sounds when played "open", a capoed string rings at the capo
+    // frets on capoed strings are counted up from the capo, like playing a C shape with a capo on 2
+    pub fn open_pitches(&self) -> Vec<u8> {
+        let count = self.strings.len();
+
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(i, open)| match &self.capo {
+                Some(capo) if capo.covers(count - i) => open + capo.fret,
+                _ => *open,
+            })
+            .collect()
+    }
+}
+
+// what's played on each string, lowest string first, None for a muted string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingering {
+    pub frets: Vec<Option<u8>>,
+}
+
+impl Fingering {
+    // midi pitch of every string that's played, lowest string first
+    pub fn sounding_pitches(&self, tuning: &Tuning) -> Vec<u8> {
+        self.frets
+            .iter()
+            .zip(tuning.open_pitches())
+            .filter_map(|(fret, open)| fret.map(|f| open + f))
+            .collect()
+    }
+
+    // notes from the lowest pitch up, which isn't always string order when a low string is fretted high
+    pub fn notes_low_to_high(&self, tuning: &Tuning) -> Vec<Note> {
+        let mut pitches = self.sounding_pitches(tuning);
+        pitches.sort();
+
+        pitches.into_iter().map(Note::from_midi).collect()
+    }
+
+    pub fn sounding_notes(&self, tuning: &Tuning) -> Vec<Note> {
+        self.sounding_pitches(tuning)
+            .into_iter()
+            .map(Note::from_midi)
+            .collect()
+    }
+
+    pub fn open_strings(&self) -> usize {
+        self.frets.iter().filter(|f| **f == Some(0)).count()
+    }
+
+    // only fretted strings count, open strings can ring anywhere
+    pub fn fret_span(&self) -> u8 {
+        let fretted = self.frets.iter().flatten().filter(|f| **f > 0);
+        match (fretted.clone().min(), fretted.max()) {
+            (Some(low), Some(high)) => high - low,
+            _ => 0,
+        }
+    }
+
+    pub fn lowest_fret(&self) -> u8 {
+        self.frets
+            .iter()
+            .flatten()
+            .filter(|f| **f > 0)
+            .min()
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn highest_fret(&self) -> u8 {
+        self.frets.iter().flatten().max().copied().unwrap_or(0)
+    }
+
+    // which finger holds down each string, 1 the index to 4 the little finger, None when it's open or muted
+    // fingers go on in fret order then from the lowest string, with the index barring the lowest fret
+    // when more than one string is stopped there, the way the fingerings were chosen to fit a hand
+    pub fn fingers(&self) -> Vec<Option<u8>> {
+        let lowest = self.lowest_fret();
+        let barre = self.frets.iter().filter(|f| **f == Some(lowest)).count() > 1;
+
+        let mut fingers: Vec<Option<u8>> = vec![None; self.frets.len()];
+        let mut next = 1;
+        let order = self
+            .frets
+            .iter()
+            .enumerate()
+            .filter_map(|(string, fret)| fret.filter(|f| *f > 0).map(|f| (f, string)))
+            .sorted();
+
+        for (fret, string) in order {
+            fingers[string] = match barre && fret == lowest {
+                true => Some(1),
+                false => {
+                    next += 1;
+                    Some(next - 1 + u8::from(barre))
+                }
+            };
+        }
+
+        fingers
+    }
+}
+
+// x32010, or 8-10-10-9-8-8 once any fret needs two digits
+impl fmt::Display for Fingering {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let frets: Vec<String> = self
+            .frets
+            .iter()
+            .map(|fret| match fret {
+                Some(fret) => fret.to_string(),
+                None => "x".to_string(),
+            })
+            .collect();
+
+        if frets.iter().any(|f| f.len() > 1) {
+            write!(f, "{}", frets.join("-"))
+        } else {
+            write!(f, "{}", frets.join(""))
+        }
+    }
+}
+
+// the inverse of display, x32010 or 8-10-10-9-8-8, and spaces work as well as dashes, 8 10 10 9 8 8
+impl FromStr for Fingering {
+    type Err = FretboardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let parts: Vec<&str> = match s.contains(['-', ' ']) {
+            true => s
+                .split(|c: char| c == '-' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .collect(),
+            false => s
+                .char_indices()
+                .map(|(i, c)| &s[i..i + c.len_utf8()])
+                .collect(),
+        };
+
+        let frets = parts
+            .iter()
+            .map(|part| match part.trim() {
+                "x" | "X" => Ok(None),
+                fret => fret
+                    .parse::<u8>()
+                    .map(Some)
+                    .map_err(|_| FretboardError::InvalidFingering(s.to_string())),
+            })
+            .collect::<Result<Vec<Option<u8>>, FretboardError>>()?;
+
+        if frets.is_empty() {
+            return Err(FretboardError::InvalidFingering(s.to_string()));
+        }
+
+        Ok(Fingering { frets })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // sounding_notes
+    //
+
+    #[test]
+    fn test_sounding_notes_open_c() {
+        let fingering = Fingering {
+            frets: vec![None, Some(3), Some(2), Some(0), Some(1), Some(0)],
+        };
+
+        let ret = fingering.sounding_notes(&Tuning::standard_guitar());
+
+        assert_eq!(ret, vec![Note::C, Note::E, Note::G, Note::C, Note::E]);
+    }
+
+    #[test]
+    fn test_sounding_notes_full_capo() {
+        let fingering = Fingering {
+            frets: vec![None, Some(3), Some(2), Some(0), Some(1), Some(0)],
+        };
+        let tuning = Tuning::standard_guitar().with_capo(Capo::from_str("2").expect("hmm"));
+
+        let ret = fingering.sounding_notes(&tuning);
+
+        assert_eq!(ret, vec![Note::D, Note::Fs, Note::A, Note::D, Note::Fs]);
+    }
+
+    // the classic partial capo on strings 3 to 5 at the 2nd fret, open strings give an E sus shape
+    #[test]
+    fn test_sounding_notes_partial_capo() {
+        let fingering = Fingering {
+            frets: vec![Some(0), Some(0), Some(0), Some(0), Some(0), Some(0)],
+        };
+        let tuning = Tuning::standard_guitar().with_capo(Capo::from_str("2:3-5").expect("hmm"));
+
+        let ret = fingering.sounding_notes(&tuning);
+
+        assert_eq!(
+            ret,
+            vec![Note::E, Note::B, Note::E, Note::A, Note::B, Note::E]
+        );
+    }
+
+    #[test]
+    fn test_sounding_notes_drop_d() {
+        let fingering = Fingering {
+            frets: vec![Some(0), Some(0), Some(0), Some(2), Some(3), Some(2)],
+        };
+
+        let ret = fingering.sounding_notes(&Tuning::drop_d_guitar());
+
+        assert_eq!(ret[0], Note::D);
+    }
+
+    #[test]
+    fn test_notes_low_to_high_orders_by_pitch() {
+        // low E fretted at the 7th is a B above the open A string
+        let fingering = Fingering {
+            frets: vec![Some(7), Some(0), None, None, None, None],
+        };
+
+        let ret = fingering.notes_low_to_high(&Tuning::standard_guitar());
+
+        assert_eq!(ret, vec![Note::A, Note::B]);
+    }
+
+    //
+    // fingering from_str
+    //
+
+    #[test]
+    fn test_fingering_from_str_round_trips() {
+        for fingering in ["x32010", "3x0003", "8-10-10-9-8-8", "x-x-12-0-12-x"] {
+            let ret = Fingering::from_str(fingering).expect("hmm");
+            assert_eq!(ret.to_string(), fingering);
+        }
+    }
+
+    #[test]
+    fn test_fingering_from_str_spaces() {
+        let ret = Fingering::from_str("x 3 2 0 1 0").expect("hmm");
+        assert_eq!(ret.to_string(), "x32010");
+
+        let ret = Fingering::from_str("8 10 10  9 8 8").expect("hmm");
+        assert_eq!(ret.to_string(), "8-10-10-9-8-8");
+    }
+
+    #[test]
+    fn test_fingering_from_str_invalid() {
+        assert!(Fingering::from_str("").is_err());
+        assert!(Fingering::from_str("x3201a").is_err());
+        assert!(Fingering::from_str("8-10-y").is_err());
+    }
+
+    //
+    // fingers
+    //
+
+    #[test]
+    fn test_fingers_open_c() {
+        let ret = Fingering::from_str("x32010").expect("hmm").fingers();
+
+        assert_eq!(ret, vec![None, Some(3), Some(2), None, Some(1), None]);
+    }
+
+    #[test]
+    fn test_fingers_barre() {
+        // an F barre chord, the index across the 1st fret
+        let ret = Fingering::from_str("133211").expect("hmm").fingers();
+
+        assert_eq!(
+            ret,
+            vec![Some(1), Some(3), Some(4), Some(2), Some(1), Some(1)]
+        );
+    }
+
+    //
+    // guitar
+    //
+
+    #[test]
+    fn test_guitar_tuning_by_name() {
+        let ret = Tuning::guitar("open g").expect("hmm");
+        assert_eq!(ret.name, "Open G");
+        assert_eq!(
+            Fingering::from_str("000000")
+                .expect("hmm")
+                .sounding_notes(&ret),
+            vec![Note::D, Note::G, Note::D, Note::G, Note::B, Note::D]
+        );
+
+        assert_eq!(Tuning::guitar("Standard"), Some(Tuning::standard_guitar()));
+        assert!(Tuning::guitar("Open Q").is_none());
+    }
+
+    //
+    // Instrument named
+    //
+
+    #[test]
+    fn test_instrument_named() {
+        let ret = Instrument::named("Uke").expect("hmm");
+        assert_eq!(ret.name, "ukulele");
+        assert_eq!(
+            Fingering::from_str("0000")
+                .expect("hmm")
+                .sounding_notes(&ret.standard_tuning()),
+            vec![Note::G, Note::C, Note::E, Note::A]
+        );
+
+        assert_eq!(
+            Instrument::named("guitar").map(|i| i.standard_tuning()),
+            Some(Tuning::standard_guitar())
+        );
+        assert!(Instrument::named("banjo").is_none());
+    }
+
+    //
+    // is_reentrant
+    //
+
+    #[test]
+    fn test_is_reentrant() {
+        let ukulele = Instrument::named("ukulele").expect("hmm");
+
+        assert!(ukulele.standard_tuning().is_reentrant());
+        assert!(!ukulele.tuning("low g").expect("hmm").is_reentrant());
+        assert!(!Tuning::standard_guitar().is_reentrant());
+    }
+
+    //
+    // capo from_str
+    //
+
+    #[test]
+    fn test_capo_from_str_single_string() {
+        let ret = Capo::from_str("4:2").expect("hmm");
+
+        assert_eq!(
+            ret,
+            Capo {
+                fret: 4,
+                strings: Some(2..=2)
+            }
+        );
+    }
+
+    #[test]
+    fn test_capo_from_str_invalid() {
+        assert!(Capo::from_str("0").is_err());
+        assert!(Capo::from_str("2:5-3").is_err());
+        assert!(Capo::from_str("two").is_err());
+    }
+
+    //
+    // display
+    //
+
+    #[test]
+    fn test_display_single_digit_frets() {
+        let fingering = Fingering {
+            frets: vec![None, Some(3), Some(2), Some(0), Some(1), Some(0)],
+        };
+
+        assert_eq!(fingering.to_string(), "x32010");
+    }
+
+    #[test]
+    fn test_display_double_digit_frets() {
+        let fingering = Fingering {
+            frets: vec![Some(8), Some(10), Some(10), Some(9), Some(8), Some(8)],
+        };
+
+        assert_eq!(fingering.to_string(), "8-10-10-9-8-8");
+    }
+}
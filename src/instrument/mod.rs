@@ -0,0 +1,2 @@
+pub mod fingering;
+pub mod fretboard;
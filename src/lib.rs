@@ -0,0 +1,15 @@
+pub mod aliases;
+pub mod batch;
+pub mod catalog;
+pub mod config;
+pub mod dictionary;
+pub mod drill;
+pub mod instrument;
+pub mod midi_export;
+pub mod parser;
+pub mod prelude;
+pub mod render;
+pub mod report;
+pub mod schema;
+pub mod session;
+pub mod theory;
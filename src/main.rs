@@ -1,10 +1,77 @@
-use crate::cli::handle_menu;
+use crate::{
+    cli::{
+        export_chord_catalog, export_scale_catalog, fill_gaps, handle_menu, handle_repl,
+        identify_file, identify_lines, print_neck, LineInput,
+    },
+    output::Command,
+};
+use struck::parser::chord_parser::ParseOptions;
 mod cli;
-mod parser;
-mod theory;
+mod doctor;
+mod output;
+mod timing;
+mod tour;
 
 // const CHORD_FORMAT: &str = "[Root note] [quality (blank for major)]";
 
 fn main() {
-    handle_menu();
+    let options = match output::parse_args(std::env::args().skip(1)) {
+        Ok(options) => {
+            output::set_level(options.level);
+            output::set_format(options.format);
+            timing::set_enabled(options.timing);
+            cli::set_parse_options(ParseOptions {
+                mode: options.mode,
+                tensions: options.tensions,
+                roots: options.roots,
+            });
+            cli::set_pretty(options.pretty);
+            if options.no_color {
+                console::set_colors_enabled(false);
+            }
+            options
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!(
+                "usage: struck [dict|scales|doctor|tour|repl|fill \"C ? ? G7 C\"|chord Gm7|notes \"C E G\"|batch chords.txt|neck Am7] (- for chord or notes reads a line at a time from stdin) [-o brief|normal|full] [--format text|json|yaml|csv|table] [--timing] [--literal-tensions] [--strict|--lenient] [--pretty] [--no-color] [--lowercase-roots|--lowercase-minor] [--key C] [--allow m7,7,maj7] [--max-borrowed 1] [--instrument guitar|ukulele]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    // each command has its own kind of error, they all end up reported the same way
+    let res = match options.command {
+        Some(Command::Dict) => export_chord_catalog(options.format),
+        Some(Command::Scales) => export_scale_catalog(options.format),
+        Some(Command::Tour) => tour::run().map_err(|e| e.to_string()),
+        Some(Command::Fill(ref progression)) => {
+            fill_gaps(progression, &options).map_err(|e| e.to_string())
+        }
+        Some(Command::Chord(ref arg)) => {
+            identify_lines(LineInput::ChordNames, arg, options.instrument)
+        }
+        Some(Command::Notes(ref arg)) => identify_lines(LineInput::Notes, arg, options.instrument),
+        Some(Command::Batch(ref path)) => identify_file(path, options.format),
+        Some(Command::Neck(ref arg)) => print_neck(arg, options.instrument),
+        Some(Command::Repl) => {
+            handle_repl(options.instrument);
+            Ok(())
+        }
+        Some(Command::Doctor) => {
+            if !doctor::run() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        None => {
+            handle_menu();
+            Ok(())
+        }
+    };
+
+    if let Err(e) = res {
+        eprintln!("caught error: {}", e);
+        std::process::exit(1);
+    }
 }
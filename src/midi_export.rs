@@ -0,0 +1,801 @@
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use crate::theory::{chord::Chord, error::ChordParseError, voicing::Voicing};
+
+// a progression written out as a standard midi file to play along with, a bar of each chord
+// with a drum track on channel 10 if there's a style picked, so it's a whole backing track
+// https://www.midi.org/specifications-old/item/standard-midi-files-smf
+
+// ticks in a quarter note, divides evenly into 16ths and triplets
+const TICKS_PER_QUARTER: u32 = 480;
+const BEATS_PER_BAR: u32 = 4;
+const TICKS_PER_BAR: u32 = TICKS_PER_QUARTER * BEATS_PER_BAR;
+
+const CHORD_CHANNEL: u8 = 0;
+// channel 10 counting from 1 is general midi's drum channel
+const DRUM_CHANNEL: u8 = 9;
+// outer voices under an accent, and everything else
+const ACCENT_VELOCITY: u8 = 100;
+const CHORD_VELOCITY: u8 = 80;
+const INNER_VELOCITY: u8 = 60;
+// general midi's expression controller, for swelling into a chord
+const EXPRESSION: u8 = 11;
+const SWELL_STEPS: u32 = 8;
+// chords sit from the octave below middle C up
+const CHORD_OCTAVE: u8 = 3;
+
+// general midi drum notes
+const KICK: u8 = 36;
+const RIM: u8 = 37;
+const SNARE: u8 = 38;
+const CLOSED_HAT: u8 = 42;
+const PEDAL_HAT: u8 = 44;
+const RIDE: u8 = 51;
+
+#[derive(Debug)]
+pub enum MidiExportError {
+    Chord(ChordParseError),
+    Io(String),
+    InvalidStyle(String),
+    InvalidTempo(u32),
+}
+
+impl fmt::Display for MidiExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiExportError::Chord(e) => write!(f, "{}", e),
+            MidiExportError::Io(e) => write!(f, "couldn't write the file: {}", e),
+            MidiExportError::InvalidStyle(style) => write!(f, "{} isn't a style", style),
+            MidiExportError::InvalidTempo(tempo) => {
+                write!(f, "{} bpm is out of range, it goes from 1 to 400", tempo)
+            }
+        }
+    }
+}
+
+impl From<ChordParseError> for MidiExportError {
+    fn from(e: ChordParseError) -> Self {
+        MidiExportError::Chord(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DrumStyle {
+    Rock,
+    Swing,
+    Bossa,
+}
+
+pub const DRUM_STYLES: [DrumStyle; 3] = [DrumStyle::Rock, DrumStyle::Swing, DrumStyle::Bossa];
+
+impl fmt::Display for DrumStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrumStyle::Rock => write!(f, "rock"),
+            DrumStyle::Swing => write!(f, "swing"),
+            DrumStyle::Bossa => write!(f, "bossa"),
+        }
+    }
+}
+
+impl FromStr for DrumStyle {
+    type Err = MidiExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rock" => Ok(DrumStyle::Rock),
+            "swing" => Ok(DrumStyle::Swing),
+            "bossa" | "bossa nova" => Ok(DrumStyle::Bossa),
+            _ => Err(MidiExportError::InvalidStyle(s.to_string())),
+        }
+    }
+}
+
+// which voices of each chord stand out, the rest are played softer so a voicing is heard
+// as separate notes instead of one block
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Accent {
+    // every note the same
+    Even,
+    Bass,
+    // the top voice, where the melody usually sits
+    Top,
+    // bass and top over softer inner voices
+    #[default]
+    Outer,
+}
+
+pub const ACCENTS: [Accent; 4] = [Accent::Even, Accent::Bass, Accent::Top, Accent::Outer];
+
+impl fmt::Display for Accent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Accent::Even => write!(f, "even"),
+            Accent::Bass => write!(f, "bass"),
+            Accent::Top => write!(f, "top"),
+            Accent::Outer => write!(f, "bass and top"),
+        }
+    }
+}
+
+impl Accent {
+    // a velocity for each pitch of a voicing lowest first
+    pub fn velocities(&self, pitches: &[u8]) -> Vec<u8> {
+        let last = pitches.len().saturating_sub(1);
+
+        (0..pitches.len())
+            .map(|index| match self {
+                Accent::Even => CHORD_VELOCITY,
+                Accent::Bass if index == 0 => ACCENT_VELOCITY,
+                Accent::Top if index == last => ACCENT_VELOCITY,
+                Accent::Outer if index == 0 || index == last => ACCENT_VELOCITY,
+                _ => INNER_VELOCITY,
+            })
+            .collect()
+    }
+}
+
+// how each chord starts and stops
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Envelope {
+    // held for the whole bar
+    #[default]
+    Held,
+    // cut off after a beat, leaving space
+    Short,
+    // held, fading in over the first beat with the expression controller
+    Swell,
+}
+
+pub const ENVELOPES: [Envelope; 3] = [Envelope::Held, Envelope::Short, Envelope::Swell];
+
+impl fmt::Display for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Envelope::Held => write!(f, "held"),
+            Envelope::Short => write!(f, "short"),
+            Envelope::Swell => write!(f, "swell"),
+        }
+    }
+}
+
+impl Envelope {
+    fn length(&self) -> u32 {
+        match self {
+            Envelope::Short => TICKS_PER_QUARTER,
+            _ => TICKS_PER_BAR,
+        }
+    }
+}
+
+// what the chords are played on, as a general midi program so any synth or DAW loading the file
+// picks a sound close to it
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Preset {
+    #[default]
+    Piano,
+    // a soft sine-ish pad
+    Pad,
+    // the FM electric piano
+    ElectricPiano,
+    // plucked nylon strings
+    Guitar,
+}
+
+pub const PRESETS: [Preset; 4] = [
+    Preset::Piano,
+    Preset::Pad,
+    Preset::ElectricPiano,
+    Preset::Guitar,
+];
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Preset::Piano => write!(f, "piano"),
+            Preset::Pad => write!(f, "pad"),
+            Preset::ElectricPiano => write!(f, "electric piano"),
+            Preset::Guitar => write!(f, "guitar"),
+        }
+    }
+}
+
+impl Preset {
+    // general midi program numbers counting from 0, warm pad, electric piano 2 and nylon guitar
+    fn program(&self) -> u8 {
+        match self {
+            Preset::Piano => 0,
+            Preset::Pad => 89,
+            Preset::ElectricPiano => 5,
+            Preset::Guitar => 24,
+        }
+    }
+}
+
+// one drum played on some steps of the bar
+struct DrumPart {
+    drum: u8,
+    steps: &'static [u32],
+    velocity: u8,
+}
+
+impl DrumStyle {
+    // how many even steps a bar is split into, 16ths or for swing triplet 8ths
+    fn steps_per_bar(&self) -> u32 {
+        match self {
+            DrumStyle::Swing => 12,
+            _ => 16,
+        }
+    }
+
+    fn parts(&self) -> Vec<DrumPart> {
+        match self {
+            // kick on 1 and 3, snare on 2 and 4, 8ths on the hi-hat
+            DrumStyle::Rock => vec![
+                DrumPart {
+                    drum: KICK,
+                    steps: &[0, 8],
+                    velocity: 100,
+                },
+                DrumPart {
+                    drum: SNARE,
+                    steps: &[4, 12],
+                    velocity: 100,
+                },
+                DrumPart {
+                    drum: CLOSED_HAT,
+                    steps: &[0, 2, 4, 6, 8, 10, 12, 14],
+                    velocity: 70,
+                },
+            ],
+            // ding ding-a ding ding-a on the ride, hi-hat with the foot on 2 and 4, a feathered kick
+            DrumStyle::Swing => vec![
+                DrumPart {
+                    drum: RIDE,
+                    steps: &[0, 3, 5, 6, 9, 11],
+                    velocity: 80,
+                },
+                DrumPart {
+                    drum: PEDAL_HAT,
+                    steps: &[3, 9],
+                    velocity: 70,
+                },
+                DrumPart {
+                    drum: KICK,
+                    steps: &[0, 3, 6, 9],
+                    velocity: 40,
+                },
+            ],
+            // the kick going 1, and-of-2, 3, and-of-4 under a rim click clave
+            DrumStyle::Bossa => vec![
+                DrumPart {
+                    drum: KICK,
+                    steps: &[0, 3, 4, 7, 8, 11, 12, 15],
+                    velocity: 80,
+                },
+                DrumPart {
+                    drum: RIM,
+                    steps: &[0, 3, 6, 10, 13],
+                    velocity: 75,
+                },
+                DrumPart {
+                    drum: CLOSED_HAT,
+                    steps: &[0, 2, 4, 6, 8, 10, 12, 14],
+                    velocity: 60,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PracticeTrack {
+    pub chords: Vec<Chord>,
+    pub tempo_bpm: u32,
+    pub drums: Option<DrumStyle>,
+    pub accent: Accent,
+    pub envelope: Envelope,
+    pub preset: Preset,
+}
+
+// in the order they go when they land on the same tick, so a note played again straight after
+// isn't cut short and a swell starts from quiet
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum EventKind {
+    NoteOff,
+    Program,
+    Controller,
+    NoteOn,
+}
+
+// something happening some ticks into the track, a note or a controller and its value,
+// a program change only has the one data byte
+struct MidiEvent {
+    tick: u32,
+    kind: EventKind,
+    channel: u8,
+    data: [u8; 2],
+}
+
+impl MidiEvent {
+    fn note_on(tick: u32, channel: u8, note: u8, velocity: u8) -> MidiEvent {
+        MidiEvent {
+            tick,
+            kind: EventKind::NoteOn,
+            channel,
+            data: [note, velocity],
+        }
+    }
+
+    fn note_off(tick: u32, channel: u8, note: u8) -> MidiEvent {
+        MidiEvent {
+            tick,
+            kind: EventKind::NoteOff,
+            channel,
+            data: [note, 0],
+        }
+    }
+
+    fn data(&self) -> &[u8] {
+        match self.kind {
+            EventKind::Program => &self.data[..1],
+            _ => &self.data,
+        }
+    }
+
+    fn status(&self) -> u8 {
+        let kind = match self.kind {
+            EventKind::NoteOff => 0x80,
+            EventKind::NoteOn => 0x90,
+            EventKind::Controller => 0xB0,
+            EventKind::Program => 0xC0,
+        };
+
+        kind | self.channel
+    }
+}
+
+impl PracticeTrack {
+    pub fn new(chords: Vec<Chord>, tempo_bpm: u32, drums: Option<DrumStyle>) -> PracticeTrack {
+        PracticeTrack {
+            chords,
+            tempo_bpm,
+            drums,
+            accent: Accent::default(),
+            envelope: Envelope::default(),
+            preset: Preset::default(),
+        }
+    }
+
+    pub fn with_preset(mut self, preset: Preset) -> PracticeTrack {
+        self.preset = preset;
+        self
+    }
+
+    pub fn with_dynamics(mut self, accent: Accent, envelope: Envelope) -> PracticeTrack {
+        self.accent = accent;
+        self.envelope = envelope;
+        self
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MidiExportError> {
+        let bytes = self.to_midi()?;
+        fs::write(path, bytes).map_err(|e| MidiExportError::Io(e.to_string()))
+    }
+
+    // format 1, a track for the tempo and time signature, one for the chords and one for the drums if there are any
+    pub fn to_midi(&self) -> Result<Vec<u8>, MidiExportError> {
+        check_tempo(self.tempo_bpm)?;
+
+        let mut tracks = vec![tempo_track(self.tempo_bpm), track(self.chord_events())];
+        if let Some(style) = self.drums {
+            tracks.push(track(self.drum_events(style)));
+        }
+
+        Ok(standard_midi_file(tracks))
+    }
+
+    // a chord a bar, each voice at the accent's velocity and as long as the envelope holds it
+    fn chord_events(&self) -> Vec<MidiEvent> {
+        let mut events = vec![MidiEvent {
+            tick: 0,
+            kind: EventKind::Program,
+            channel: CHORD_CHANNEL,
+            data: [self.preset.program(), 0],
+        }];
+
+        for (bar, chord) in self.chords.iter().enumerate() {
+            let start = bar as u32 * TICKS_PER_BAR;
+            let pitches = chord.close_voicing(CHORD_OCTAVE).pitches;
+
+            for (pitch, velocity) in pitches.iter().zip(self.accent.velocities(&pitches)) {
+                events.push(MidiEvent::note_on(start, CHORD_CHANNEL, *pitch, velocity));
+                events.push(MidiEvent::note_off(
+                    start + self.envelope.length(),
+                    CHORD_CHANNEL,
+                    *pitch,
+                ));
+            }
+
+            if self.envelope == Envelope::Swell {
+                events.extend(swell(start));
+            }
+        }
+
+        events
+    }
+
+    // the style's bar played under every chord, each hit lasting a step
+    fn drum_events(&self, style: DrumStyle) -> Vec<MidiEvent> {
+        let step_ticks = TICKS_PER_BAR / style.steps_per_bar();
+        let mut events = vec![];
+
+        for bar in 0..self.chords.len() as u32 {
+            for part in style.parts() {
+                for step in part.steps {
+                    let start = bar * TICKS_PER_BAR + step * step_ticks;
+
+                    events.push(MidiEvent::note_on(
+                        start,
+                        DRUM_CHANNEL,
+                        part.drum,
+                        part.velocity,
+                    ));
+                    events.push(MidiEvent::note_off(
+                        start + step_ticks,
+                        DRUM_CHANNEL,
+                        part.drum,
+                    ));
+                }
+            }
+        }
+
+        events
+    }
+}
+
+// two voicings taking turns with a gap between so they can be told apart by ear,
+// a bar of the first, the gap, a bar of the second, the gap, and round again
+#[derive(Debug, Clone)]
+pub struct AbComparison {
+    pub a: Voicing,
+    pub b: Voicing,
+    pub tempo_bpm: u32,
+    pub gap_beats: u32,
+    // how many times each is played
+    pub rounds: u32,
+    pub preset: Preset,
+}
+
+impl AbComparison {
+    pub fn new(a: Voicing, b: Voicing, tempo_bpm: u32, gap_beats: u32) -> AbComparison {
+        AbComparison {
+            a,
+            b,
+            tempo_bpm,
+            gap_beats,
+            rounds: 4,
+            preset: Preset::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MidiExportError> {
+        let bytes = self.to_midi()?;
+        fs::write(path, bytes).map_err(|e| MidiExportError::Io(e.to_string()))
+    }
+
+    pub fn to_midi(&self) -> Result<Vec<u8>, MidiExportError> {
+        check_tempo(self.tempo_bpm)?;
+
+        Ok(standard_midi_file(vec![
+            tempo_track(self.tempo_bpm),
+            track(self.events()),
+        ]))
+    }
+
+    fn events(&self) -> Vec<MidiEvent> {
+        let turn = TICKS_PER_BAR + self.gap_beats * TICKS_PER_QUARTER;
+        let mut events = vec![MidiEvent {
+            tick: 0,
+            kind: EventKind::Program,
+            channel: CHORD_CHANNEL,
+            data: [self.preset.program(), 0],
+        }];
+
+        let voicings = [&self.a, &self.b];
+        for (index, voicing) in voicings
+            .iter()
+            .cycle()
+            .take(self.rounds as usize * 2)
+            .enumerate()
+        {
+            let start = index as u32 * turn;
+
+            for pitch in &voicing.pitches {
+                events.push(MidiEvent::note_on(
+                    start,
+                    CHORD_CHANNEL,
+                    *pitch,
+                    CHORD_VELOCITY,
+                ));
+                events.push(MidiEvent::note_off(
+                    start + TICKS_PER_BAR,
+                    CHORD_CHANNEL,
+                    *pitch,
+                ));
+            }
+        }
+
+        events
+    }
+}
+
+const END_OF_TRACK: [u8; 4] = [0x00, 0xFF, 0x2F, 0x00];
+
+fn check_tempo(tempo_bpm: u32) -> Result<(), MidiExportError> {
+    match tempo_bpm {
+        1..=400 => Ok(()),
+        _ => Err(MidiExportError::InvalidTempo(tempo_bpm)),
+    }
+}
+
+// format 1, the header and then each track in its chunk
+fn standard_midi_file(tracks: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut bytes = b"MThd".to_vec();
+    bytes.extend(6u32.to_be_bytes());
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend((tracks.len() as u16).to_be_bytes());
+    bytes.extend((TICKS_PER_QUARTER as u16).to_be_bytes());
+
+    for track in tracks {
+        bytes.extend(b"MTrk");
+        bytes.extend((track.len() as u32).to_be_bytes());
+        bytes.extend(track);
+    }
+
+    bytes
+}
+
+fn tempo_track(tempo_bpm: u32) -> Vec<u8> {
+    let micros_per_quarter = 60_000_000 / tempo_bpm;
+
+    let mut bytes = vec![0x00, 0xFF, 0x51, 0x03];
+    bytes.extend(&micros_per_quarter.to_be_bytes()[1..]);
+    // 4/4, 24 clocks a click, 8 32nds a quarter
+    bytes.extend([
+        0x00,
+        0xFF,
+        0x58,
+        0x04,
+        BEATS_PER_BAR as u8,
+        0x02,
+        0x18,
+        0x08,
+    ]);
+    bytes.extend(END_OF_TRACK);
+
+    bytes
+}
+
+// expression rising from quiet to full across the first beat of a bar
+fn swell(start: u32) -> Vec<MidiEvent> {
+    (0..=SWELL_STEPS)
+        .map(|step| MidiEvent {
+            tick: start + step * TICKS_PER_QUARTER / SWELL_STEPS,
+            kind: EventKind::Controller,
+            channel: CHORD_CHANNEL,
+            data: [EXPRESSION, (32 + step * 95 / SWELL_STEPS) as u8],
+        })
+        .collect()
+}
+
+// events in time order with the gap since the one before
+fn track(mut events: Vec<MidiEvent>) -> Vec<u8> {
+    events.sort_by_key(|e| (e.tick, e.kind));
+
+    let mut bytes = vec![];
+    let mut last_tick = 0;
+
+    for event in events {
+        bytes.extend(variable_length(event.tick - last_tick));
+        last_tick = event.tick;
+
+        bytes.push(event.status());
+        bytes.extend(event.data());
+    }
+
+    bytes.extend(END_OF_TRACK);
+
+    bytes
+}
+
+// seven bits a byte, biggest first, the top bit set on every byte but the last
+fn variable_length(mut value: u32) -> Vec<u8> {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    value >>= 7;
+
+    while value > 0 {
+        bytes.insert(0, (value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn progression(names: &[&str]) -> Vec<Chord> {
+        names
+            .iter()
+            .map(|n| identify_from_name(n.to_string()).expect("hmm"))
+            .collect()
+    }
+
+    fn track_count(bytes: &[u8]) -> u16 {
+        u16::from_be_bytes([bytes[10], bytes[11]])
+    }
+
+    //
+    // variable_length
+    //
+
+    #[test]
+    fn test_variable_length() {
+        assert_eq!(variable_length(0), vec![0x00]);
+        assert_eq!(variable_length(0x7F), vec![0x7F]);
+        assert_eq!(variable_length(0x80), vec![0x81, 0x00]);
+        assert_eq!(variable_length(1920), vec![0x8F, 0x00]);
+    }
+
+    //
+    // to_midi
+    //
+
+    #[test]
+    fn test_to_midi_chords_only() {
+        let ret = PracticeTrack::new(progression(&["C", "G"]), 120, None)
+            .to_midi()
+            .expect("hmm");
+
+        assert_eq!(&ret[..4], b"MThd");
+        assert_eq!(track_count(&ret), 2);
+        // 500000 microseconds a quarter at 120
+        assert!(ret
+            .windows(6)
+            .any(|w| w == [0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]));
+        // C3 on to start, the bass accented
+        assert!(ret.windows(3).any(|w| w == [0x90, 48, ACCENT_VELOCITY]));
+    }
+
+    #[test]
+    fn test_to_midi_drums_on_channel_ten() {
+        let ret = PracticeTrack::new(progression(&["C"]), 90, Some(DrumStyle::Rock))
+            .to_midi()
+            .expect("hmm");
+
+        assert_eq!(track_count(&ret), 3);
+        assert!(ret.windows(2).any(|w| w == [0x99, KICK]));
+        assert!(ret.windows(2).any(|w| w == [0x99, SNARE]));
+    }
+
+    #[test]
+    fn test_to_midi_invalid_tempo() {
+        assert!(PracticeTrack::new(progression(&["C"]), 0, None)
+            .to_midi()
+            .is_err());
+    }
+
+    #[test]
+    fn test_to_midi_swell() {
+        let ret = PracticeTrack::new(progression(&["C"]), 120, None)
+            .with_dynamics(Accent::Even, Envelope::Swell)
+            .to_midi()
+            .expect("hmm");
+
+        assert!(ret.windows(3).any(|w| w == [0xB0, EXPRESSION, 32]));
+        assert!(ret.windows(3).any(|w| w == [0xB0, EXPRESSION, 127]));
+    }
+
+    #[test]
+    fn test_to_midi_preset_program() {
+        let ret = PracticeTrack::new(progression(&["C"]), 120, None)
+            .with_preset(Preset::Guitar)
+            .to_midi()
+            .expect("hmm");
+
+        // the program change comes before the first note, with one data byte
+        assert!(ret.windows(4).any(|w| w == [0x00, 0xC0, 24, 0x00]));
+    }
+
+    //
+    // AbComparison
+    //
+
+    #[test]
+    fn test_ab_comparison_takes_turns() {
+        let chord = identify_from_name("Cmaj7".to_string()).expect("hmm");
+        let close = chord.close_voicing(4);
+        let drop2 = chord.drop2_voicing(4).expect("hmm");
+
+        let comparison = AbComparison::new(close, drop2, 120, 2);
+        let ons: Vec<(u32, u8)> = comparison
+            .events()
+            .iter()
+            .filter(|e| e.kind == EventKind::NoteOn)
+            .map(|e| (e.tick, e.data[0]))
+            .collect();
+
+        // G4 in the close voicing, G3 in the drop 2, a bar and two beats apart
+        assert!(ons.contains(&(0, 67)));
+        assert!(ons.contains(&(TICKS_PER_BAR + 2 * TICKS_PER_QUARTER, 55)));
+        assert_eq!(ons.len(), 4 * 2 * 4);
+        assert!(comparison.to_midi().is_ok());
+    }
+
+    //
+    // velocities
+    //
+
+    #[test]
+    fn test_velocities() {
+        let pitches = [48, 52, 55, 60];
+
+        assert_eq!(
+            Accent::Outer.velocities(&pitches),
+            vec![
+                ACCENT_VELOCITY,
+                INNER_VELOCITY,
+                INNER_VELOCITY,
+                ACCENT_VELOCITY
+            ]
+        );
+        assert_eq!(
+            Accent::Top.velocities(&pitches),
+            vec![
+                INNER_VELOCITY,
+                INNER_VELOCITY,
+                INNER_VELOCITY,
+                ACCENT_VELOCITY
+            ]
+        );
+        assert_eq!(Accent::Even.velocities(&pitches), vec![CHORD_VELOCITY; 4]);
+    }
+
+    //
+    // chord_events
+    //
+
+    #[test]
+    fn test_chord_events_short_envelope() {
+        let practice = PracticeTrack::new(progression(&["C"]), 120, None)
+            .with_dynamics(Accent::Even, Envelope::Short);
+        let ret = practice.chord_events();
+
+        assert!(ret
+            .iter()
+            .filter(|e| e.kind == EventKind::NoteOff)
+            .all(|e| e.tick == TICKS_PER_QUARTER));
+    }
+
+    //
+    // drum_events
+    //
+
+    #[test]
+    fn test_drum_events_every_bar() {
+        let practice = PracticeTrack::new(progression(&["C", "F", "G"]), 120, None);
+
+        for style in DRUM_STYLES {
+            let hits_per_bar: usize = style.parts().iter().map(|p| p.steps.len()).sum();
+            let ret = practice.drum_events(style);
+
+            assert_eq!(ret.len(), hits_per_bar * 3 * 2);
+            assert!(ret
+                .iter()
+                .all(|e| e.tick <= 3 * TICKS_PER_BAR && e.channel == DRUM_CHANNEL));
+        }
+    }
+}
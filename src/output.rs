@@ -0,0 +1,422 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use struck::{
+    instrument::fretboard::Instrument,
+    parser::chord_parser::{ParseMode, RootCase},
+    theory::naming::TensionReading,
+};
+
+// how much every command prints, picked once with -o when starting up
+// brief is just the chord symbols, normal adds the notes and intervals,
+// full adds diagrams and how the chord relates to others (alternatives, voicings)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub enum OutputLevel {
+    Brief,
+    #[default]
+    Normal,
+    Full,
+}
+
+impl FromStr for OutputLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "brief" => Ok(OutputLevel::Brief),
+            "normal" => Ok(OutputLevel::Normal),
+            "full" => Ok(OutputLevel::Full),
+            _ => Err(format!(
+                "unknown output level {}, expected brief, normal or full",
+                s
+            )),
+        }
+    }
+}
+
+// how results are written out, text or a table for reading and json, yaml or csv for other programs
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Yaml,
+    Csv,
+    // columns lined up for reading in a terminal
+    Table,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "csv" => Ok(Format::Csv),
+            "table" => Ok(Format::Table),
+            _ => Err(format!(
+                "unknown format {}, expected text, json, yaml, csv or table",
+                s
+            )),
+        }
+    }
+}
+
+// something to do straight away instead of bringing up the menu
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    // every chord struck knows, struck dict
+    Dict,
+    // every scale on every tonic, struck scales
+    Scales,
+    // checks the config files and terminal, struck doctor
+    Doctor,
+    // a walk through the main features, struck tour
+    Tour,
+    // the gaps in a progression filled in, struck fill "C ? ? G7 C"
+    Fill(String),
+    // a chord name or, with -, one a line from stdin, struck chord Gm7
+    Chord(String),
+    // notes or midi numbers the same way, struck notes "C E G"
+    Notes(String),
+    // every line of a file of chord names or note lists, struck batch chords.txt
+    Batch(String),
+    // a prompt reading chord names, notes and commands a line at a time, struck repl
+    Repl,
+    // a chord or scale all over the neck, struck neck Am7 or struck neck "A blues"
+    Neck(String),
+}
+
+static LEVEL: OnceLock<OutputLevel> = OnceLock::new();
+
+pub fn set_level(level: OutputLevel) {
+    // only ever set once at startup, a second go is ignored
+    let _ = LEVEL.set(level);
+}
+
+// whether output meant for this level should be printed
+pub fn shows(level: OutputLevel) -> bool {
+    *LEVEL.get().unwrap_or(&OutputLevel::default()) >= level
+}
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+pub fn set_format(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+// how chords are printed, text unless --format asks for something for other programs
+pub fn format() -> Format {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+// everything that can be picked on the command line
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Options {
+    pub level: OutputLevel,
+    pub timing: bool,
+    // --literal-tensions reads a G13 as just the 7th and 13th
+    pub tensions: TensionReading,
+    // --lenient skips over what it can't read in a chord name rather than rejecting it
+    pub mode: ParseMode,
+    // --lowercase-roots reads gm7 as Gm7, --lowercase-minor reads c as C minor and c7 as Cm7
+    pub roots: RootCase,
+    // --pretty writes chord names with the music symbols, B♭Δ7 rather than Bbmaj7
+    pub pretty: bool,
+    // --no-color leaves out the colours even on a terminal, they're already left out when piped
+    pub no_color: bool,
+    pub format: Format,
+    pub command: Option<Command>,
+    // for fill, the key to fill in (worked out from the chords without one),
+    // the chord qualities it can use (m7,7,maj7) and how many chords from outside the key
+    pub key: Option<String>,
+    pub allow: Option<String>,
+    pub max_borrowed: usize,
+    // for chord and notes the instrument to print shapes for (--instrument ukulele), and the neck to map for neck
+    pub instrument: Option<&'static Instrument>,
+}
+
+// -o brief, --output full or --output=normal, --timing, --format json and a command like dict
+// or fill with its progression
+pub fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => match args.next() {
+                Some(value) => options.level = OutputLevel::from_str(&value)?,
+                None => return Err(format!("{} needs a level: brief, normal or full", arg)),
+            },
+            "--timing" => options.timing = true,
+            "--literal-tensions" => options.tensions = TensionReading::Literal,
+            "--lenient" => options.mode = ParseMode::Lenient,
+            "--strict" => options.mode = ParseMode::Strict,
+            "--pretty" => options.pretty = true,
+            "--no-color" => options.no_color = true,
+            "--lowercase-roots" => options.roots = RootCase::Either,
+            "--lowercase-minor" => options.roots = RootCase::LowercaseMinor,
+            "--format" => match args.next() {
+                Some(value) => options.format = Format::from_str(&value)?,
+                None => {
+                    return Err(format!(
+                        "{} needs a format: text, json, yaml, csv or table",
+                        arg
+                    ))
+                }
+            },
+            "dict" if options.command.is_none() => options.command = Some(Command::Dict),
+            "scales" if options.command.is_none() => options.command = Some(Command::Scales),
+            "doctor" if options.command.is_none() => options.command = Some(Command::Doctor),
+            "tour" if options.command.is_none() => options.command = Some(Command::Tour),
+            "repl" if options.command.is_none() => options.command = Some(Command::Repl),
+            "fill" if options.command.is_none() => match args.next() {
+                Some(progression) => options.command = Some(Command::Fill(progression)),
+                None => return Err("fill needs a progression, e.g. \"C ? ? G7 C\"".to_string()),
+            },
+            "chord" if options.command.is_none() => match args.next() {
+                Some(name) => options.command = Some(Command::Chord(name)),
+                None => {
+                    return Err("chord needs a chord name, or - to read them from stdin".to_string())
+                }
+            },
+            "notes" if options.command.is_none() => match args.next() {
+                Some(notes) => options.command = Some(Command::Notes(notes)),
+                None => {
+                    return Err("notes needs some notes, or - to read them from stdin".to_string())
+                }
+            },
+            "batch" if options.command.is_none() => match args.next() {
+                Some(path) => options.command = Some(Command::Batch(path)),
+                None => return Err("batch needs a file of chord names or notes".to_string()),
+            },
+            "neck" if options.command.is_none() => match args.next() {
+                Some(chord_or_scale) => options.command = Some(Command::Neck(chord_or_scale)),
+                None => {
+                    return Err("neck needs a chord or scale, e.g. Am7 or \"A minor\"".to_string())
+                }
+            },
+            "--key" => match args.next() {
+                Some(value) => options.key = Some(value),
+                None => return Err(format!("{} needs a key, e.g. C or F#m", arg)),
+            },
+            "--allow" => match args.next() {
+                Some(value) => options.allow = Some(value),
+                None => return Err(format!("{} needs chord qualities, e.g. m7,7,maj7", arg)),
+            },
+            "--instrument" => match args.next().map(|v| Instrument::named(&v)) {
+                Some(Some(instrument)) => options.instrument = Some(instrument),
+                _ => return Err(format!("{} needs an instrument: guitar or ukulele", arg)),
+            },
+            "--max-borrowed" => match args.next().map(|v| v.parse::<usize>()) {
+                Some(Ok(value)) => options.max_borrowed = value,
+                _ => return Err(format!("{} needs a number of chords", arg)),
+            },
+            _ => match arg.strip_prefix("--output=") {
+                Some(value) => options.level = OutputLevel::from_str(value)?,
+                None => return Err(format!("unknown argument {}", arg)),
+            },
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    //
+    // parse_args
+    //
+
+    #[test]
+    fn test_parse_args_defaults_to_normal() {
+        assert_eq!(
+            parse_args(args(&[])).expect("hmm").level,
+            OutputLevel::Normal
+        );
+    }
+
+    #[test]
+    fn test_parse_args_levels() {
+        assert_eq!(
+            parse_args(args(&["-o", "brief"])).expect("hmm").level,
+            OutputLevel::Brief
+        );
+        assert_eq!(
+            parse_args(args(&["--output", "full"])).expect("hmm").level,
+            OutputLevel::Full
+        );
+        assert_eq!(
+            parse_args(args(&["--output=normal"])).expect("hmm").level,
+            OutputLevel::Normal
+        );
+    }
+
+    #[test]
+    fn test_parse_args_timing() {
+        assert!(!parse_args(args(&[])).expect("hmm").timing);
+
+        let ret = parse_args(args(&["--timing", "-o", "brief"])).expect("hmm");
+        assert!(ret.timing);
+        assert_eq!(ret.level, OutputLevel::Brief);
+    }
+
+    #[test]
+    fn test_parse_args_literal_tensions() {
+        assert_eq!(
+            parse_args(args(&[])).expect("hmm").tensions,
+            TensionReading::Implied
+        );
+        assert_eq!(
+            parse_args(args(&["--literal-tensions"]))
+                .expect("hmm")
+                .tensions,
+            TensionReading::Literal
+        );
+    }
+
+    #[test]
+    fn test_parse_args_parse_mode() {
+        assert_eq!(parse_args(args(&[])).expect("hmm").mode, ParseMode::Strict);
+        assert_eq!(
+            parse_args(args(&["--lenient"])).expect("hmm").mode,
+            ParseMode::Lenient
+        );
+        assert_eq!(
+            parse_args(args(&["--lenient", "--strict"]))
+                .expect("hmm")
+                .mode,
+            ParseMode::Strict
+        );
+    }
+
+    #[test]
+    fn test_parse_args_root_case() {
+        assert_eq!(parse_args(args(&[])).expect("hmm").roots, RootCase::Upper);
+        assert_eq!(
+            parse_args(args(&["--lowercase-roots"])).expect("hmm").roots,
+            RootCase::Either
+        );
+        assert_eq!(
+            parse_args(args(&["--lowercase-minor"])).expect("hmm").roots,
+            RootCase::LowercaseMinor
+        );
+    }
+
+    #[test]
+    fn test_parse_args_pretty() {
+        assert!(!parse_args(args(&[])).expect("hmm").pretty);
+        assert!(parse_args(args(&["--pretty"])).expect("hmm").pretty);
+    }
+
+    #[test]
+    fn test_parse_args_no_color() {
+        assert!(!parse_args(args(&[])).expect("hmm").no_color);
+        assert!(parse_args(args(&["--no-color"])).expect("hmm").no_color);
+    }
+
+    #[test]
+    fn test_parse_args_dict_command() {
+        let ret = parse_args(args(&[])).expect("hmm");
+        assert_eq!(ret.command, None);
+        assert_eq!(ret.format, Format::Text);
+
+        let ret = parse_args(args(&["dict", "--format", "json"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Dict));
+        assert_eq!(ret.format, Format::Json);
+
+        let ret = parse_args(args(&["--format", "csv", "scales"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Scales));
+        assert_eq!(ret.format, Format::Csv);
+
+        let ret = parse_args(args(&["dict", "--format", "yaml"])).expect("hmm");
+        assert_eq!(ret.format, Format::Yaml);
+
+        let ret = parse_args(args(&["dict", "--format", "table"])).expect("hmm");
+        assert_eq!(ret.format, Format::Table);
+
+        assert_eq!(
+            parse_args(args(&["doctor"])).expect("hmm").command,
+            Some(Command::Doctor)
+        );
+        assert_eq!(
+            parse_args(args(&["tour"])).expect("hmm").command,
+            Some(Command::Tour)
+        );
+    }
+
+    #[test]
+    fn test_parse_args_fill_command() {
+        let ret = parse_args(args(&[
+            "fill",
+            "C ? ? G7 C",
+            "--key",
+            "C",
+            "--allow",
+            "m7,7",
+            "--max-borrowed",
+            "1",
+        ]))
+        .expect("hmm");
+
+        assert_eq!(ret.command, Some(Command::Fill("C ? ? G7 C".to_string())));
+        assert_eq!(ret.key, Some("C".to_string()));
+        assert_eq!(ret.allow, Some("m7,7".to_string()));
+        assert_eq!(ret.max_borrowed, 1);
+
+        assert!(parse_args(args(&["fill"])).is_err());
+        assert!(parse_args(args(&["fill", "C ?", "--max-borrowed", "lots"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_chord_and_notes_commands() {
+        let ret = parse_args(args(&["chord", "-", "-o", "brief"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Chord("-".to_string())));
+        assert_eq!(ret.level, OutputLevel::Brief);
+
+        let ret = parse_args(args(&["notes", "C E G"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Notes("C E G".to_string())));
+
+        assert!(parse_args(args(&["chord"])).is_err());
+
+        let ret = parse_args(args(&["batch", "chords.txt", "--format", "csv"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Batch("chords.txt".to_string())));
+        assert!(parse_args(args(&["batch"])).is_err());
+
+        let ret = parse_args(args(&["neck", "A blues"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Neck("A blues".to_string())));
+        assert!(parse_args(args(&["neck"])).is_err());
+
+        let ret = parse_args(args(&["repl", "-o", "brief"])).expect("hmm");
+        assert_eq!(ret.command, Some(Command::Repl));
+    }
+
+    #[test]
+    fn test_parse_args_instrument() {
+        let ret = parse_args(args(&["chord", "F", "--instrument", "ukulele"])).expect("hmm");
+        assert_eq!(ret.instrument.map(|i| i.name), Some("ukulele"));
+
+        assert!(parse_args(args(&["chord", "F", "--instrument", "banjo"])).is_err());
+        assert!(parse_args(args(&["chord", "F", "--instrument"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse_args(args(&["-o"])).is_err());
+        assert!(parse_args(args(&["-o", "loud"])).is_err());
+        assert!(parse_args(args(&["--verbose"])).is_err());
+        assert!(parse_args(args(&["dict", "--format", "xml"])).is_err());
+        assert!(parse_args(args(&["dict", "dict"])).is_err());
+        assert!(parse_args(args(&["dict", "scales"])).is_err());
+    }
+}
@@ -1,29 +1,82 @@
 use itertools::Itertools;
-use regex::Regex;
-use std::{
-    fmt::{self, format, write},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-use crate::theory::{
-    self,
-    chord::{
-        derive_chord_quality_from_intervals, find_all_intervals_from_root_and_notes,
-        get_add_interval_from_add, get_notes_from_root_and_intervals, Chord, ChordBuilder,
-        ChordQuality, SeventhType, SuspendedType, TriadQuality,
+use crate::{
+    aliases::Aliases,
+    parser::{
+        chord_tokenizer::{tokenize_as, Spanned, Token},
+        suggest::suggest_qualities,
+    },
+    theory::{
+        chord::{
+            derive_chord_quality_from_intervals, find_all_intervals_from_root_and_notes,
+            find_all_intervals_from_root_and_pitches, find_stacked_structure,
+            get_add_interval_from_add, get_notes_from_root_and_intervals, get_notes_with_bass,
+            get_stacked_notes, Alternative, Chord, ChordBuilder, ChordQuality, ChordStructure,
+            Inversion, SeventhType, SuspendedType, TriadQuality,
+        },
+        error::{ChordParseError, Span},
+        extensions::{EleventhKind, Extensions, NinthKind, Omission, SeventhKind, ThirteenthKind},
+        interval::Interval,
+        naming::{name_chord, name_stacked_chord, TensionReading},
+        note::Note,
+        polychord::Polychord,
+        spelling::spell_name,
     },
-    error::ChordParseError,
-    interval::{find_interval, get_interval, Interval},
-    note::Note,
 };
 
+// what identify_from_root_and_notes calls notes it can't find a name for
+pub const AMBIGUOUS_NAME: &str = "Ambiguous";
+
+// strictly every character of a name has to mean something, so a script can trust what it gets back
+// leniently anything not understood is skipped and the rest is read as best it can be, Gxyz7 is a G7
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ParseMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+// whether a root can be written lowercase, gm7 for Gm7, and if it's then read the fake book way
+// where a lowercase root is a minor chord, c is C minor and c7 is Cm7
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RootCase {
+    #[default]
+    Upper,
+    Either,
+    LowercaseMinor,
+}
+
+// how chord names are read, taken by the parsing entry points
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    pub tensions: TensionReading,
+    pub roots: RootCase,
+}
+
+// a root or bass note, lowercase too if the options allow it, and whether it was lowercase
+fn read_note(s: &str, roots: RootCase) -> Option<(Note, bool)> {
+    match s.chars().next() {
+        Some(c @ 'a'..='g') if roots != RootCase::Upper => {
+            let upper = format!("{}{}", c.to_ascii_uppercase(), &s[c.len_utf8()..]);
+            Some((Note::from_str(&upper).ok()?, true))
+        }
+        _ => Some((Note::from_str(s).ok()?, false)),
+    }
+}
+
 pub fn parse_chord_quality(s: &str) -> Result<ChordQuality, ChordParseError> {
     match s {
+        "5" => Ok(ChordQuality::Power),
+        "maj" => Ok(ChordQuality::Major),
         "m" => Ok(ChordQuality::Minor),
         "dim" => Ok(ChordQuality::Diminished),
         "aug" => Ok(ChordQuality::Augmented),
         "sus2" => Ok(ChordQuality::Suspended(SuspendedType::Sus2)),
         "sus4" => Ok(ChordQuality::Suspended(SuspendedType::Sus4)),
+        // plain sus is short for sus4
+        "sus" => Ok(ChordQuality::Suspended(SuspendedType::Sus4)),
         "aug7" => Ok(ChordQuality::Seventh(SeventhType::Augmented)),
         "m7" => Ok(ChordQuality::Seventh(SeventhType::Minor)),
         "7" => Ok(ChordQuality::Seventh(SeventhType::Dominant)),
@@ -37,211 +90,1043 @@ pub fn parse_chord_quality(s: &str) -> Result<ChordQuality, ChordParseError> {
 }
 // }
 
+// every version of the degree an omission removes, no3 drops whichever 3rd the chord has
+pub fn get_omitted_intervals(degree: &str) -> Vec<Interval> {
+    match degree {
+        "3" => vec![Interval::MinorThird, Interval::MajorThird],
+        "5" => vec![
+            Interval::DiminishedFifth,
+            Interval::PerfectFifth,
+            Interval::AugmentedFifth,
+        ],
+        _ => vec![],
+    }
+}
+
+// the root at the front of a chord name and everything after it, a sharp or flat straight after it is part of it
+// so Bb13 is B flat 13, the b13 alteration goes in brackets B(b13)
+// counted in characters rather than bytes so a B♭ root is two like Bb
+pub fn read_root(symbol: &str, roots: RootCase) -> Option<((Note, bool), &str)> {
+    let ends: Vec<usize> = symbol
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take(2)
+        .collect();
+
+    ends.into_iter()
+        .rev()
+        .map(|len| (&symbol[..len], &symbol[len..]))
+        .find_map(|(root, rest)| Some((read_note(root, roots)?, rest)))
+}
+
+// pull the bass off the end of a slash chord, C/E => (C, Some(E))
+// no slash means no bass, but a slash with anything other than a note after it is an error
+// except the slash in a 6/9 chord, C6/9 is the C6 with a 9 and has no bass (C6/9/E does)
+pub fn split_slash_bass(
+    chord_name: &str,
+    roots: RootCase,
+) -> Result<(&str, Option<Note>), ChordParseError> {
+    match chord_name.rsplit_once('/') {
+        Some((symbol, figure)) if symbol.ends_with('6') && figure.starts_with('9') => {
+            Ok((chord_name, None))
+        }
+        Some((symbol, bass)) => match read_note(bass.trim(), roots) {
+            Some((n, _)) => Ok((symbol, Some(n))),
+            None => Err(ChordParseError::UnexpectedToken(
+                format!("couldn't identify bass note {}", bass),
+                Span::new(chord_name, symbol.len() + 1, chord_name.len()),
+                vec![],
+            )),
+        },
+        None => Ok((chord_name, None)),
+    }
+}
+
+// the natural interval an alteration replaces, and the altered interval it's replaced with
+pub fn get_alteration_intervals(accidental: &str, degree: &str) -> Option<(Interval, Interval)> {
+    match (accidental, degree) {
+        ("b", "5") => Some((Interval::PerfectFifth, Interval::DiminishedFifth)),
+        ("#", "5") => Some((Interval::PerfectFifth, Interval::AugmentedFifth)),
+        ("b", "9") => Some((Interval::MajorNinth, Interval::MinorNinth)),
+        ("#", "9") => Some((Interval::MajorNinth, Interval::AugmentedNinth)),
+        ("#", "11") => Some((Interval::PerfectEleventh, Interval::AugmentedEleventh)),
+        ("b", "13") => Some((Interval::MajorThirteenth, Interval::MinorThirteenth)),
+        _ => None,
+    }
+}
+
 // take a note as a root, take some notes, work out what chord it could be
-pub fn identify_from_root_and_notes(root: &Note, notes: &Vec<Note>) -> Chord {
-    let chord_builder = ChordBuilder::new();
+pub fn identify_from_root_and_notes(root: &Note, notes: &[Note]) -> Chord {
+    let intervals = find_all_intervals_from_root_and_notes(root, notes.to_vec());
+    identify_from_root_and_intervals(root, notes, intervals)
+}
 
-    let intervals = find_all_intervals_from_root_and_notes(root, notes.clone());
+// the notes (from the bass up) with the intervals worked out from them, named
+fn identify_from_root_and_intervals(
+    root: &Note,
+    notes: &[Note],
+    intervals: Vec<Interval>,
+) -> Chord {
+    let chord_builder = ChordBuilder::new();
 
     // identify chord quality, gives us a foundation for naming
     let chord_quality = derive_chord_quality_from_intervals(&intervals);
 
-    // TODO: maybe move this to function later
-    let chord_name = match chord_quality {
-        ChordQuality::Ambiguous => "Ambiguous".to_string(),
-        ChordQuality::Minor => format!("{}m", root),
-        ChordQuality::Major => format!("{}", root),
-        ChordQuality::Diminished => format!("{}dim", root),
-        ChordQuality::Augmented => format!("{}aug", root),
-        ChordQuality::Seventh(seventh_type) => match seventh_type {
-            SeventhType::Augmented => format!("{}aug7", root),
-            SeventhType::Major => format!("{}maj7", root),
-            SeventhType::HalfDiminished => format!("{}○7", root),
-            SeventhType::Minor => format!("{}m7", root),
-            SeventhType::Diminished => format!("{}dim7", root),
-            SeventhType::Dominant => format!("{}7", root),
-            SeventhType::Suspended(suspended_type) => match suspended_type {
-                SuspendedType::Sus2 => format!("{}7sus2", root),
-                SuspendedType::Sus4 => format!("{}7sus4", root),
-            },
-        },
+    // the naming engine works from the intervals themselves, so it can name things like C7(no3)
+    // that don't have a ChordQuality of their own
+    let chord_name = name_chord(root, &intervals).unwrap_or(AMBIGUOUS_NAME.to_string());
+
+    // notes voiced as a stack of 4ths or 5ths up from the root are named for how they're built,
+    // D G C F could be spelled as some kind of Dm7 but it's a D quartal chord
+    if notes.first() == Some(root) {
+        if let Some(structure) = find_stacked_structure(notes) {
+            return chord_builder
+                .root(*root)
+                .name(name_stacked_chord(
+                    root,
+                    structure,
+                    notes.iter().unique().count(),
+                ))
+                .notes(notes.iter().copied().unique().collect())
+                .intervals(intervals)
+                .triad_quality(TriadQuality::from(chord_quality))
+                .structure(structure)
+                .build();
+        }
+    }
+
+    // the first note given is the lowest, if it isn't the root then this is a slash chord
+    let bass = notes.first().copied().filter(|n| n != root);
 
-        ChordQuality::Suspended(SuspendedType::Sus2) => format!("{}sus2", root),
-        ChordQuality::Suspended(SuspendedType::Sus4) => format!("{}sus4", root),
+    let chord_name = match bass {
+        Some(bass) if chord_name != AMBIGUOUS_NAME => format!("{}/{}", chord_name, bass),
+        _ => chord_name,
     };
 
     chord_builder
         .root(*root)
+        .bass(bass)
         .name(chord_name)
-        // .notes(notes) TODO::notes on
+        .notes(notes.iter().copied().unique().collect())
         .intervals(intervals)
-        .chord_quality(chord_quality)
         .triad_quality(TriadQuality::from(chord_quality))
         .build()
 }
 
+// how likely a reading of some notes is, out of 100
+// the plainest name wins: root position over inversions over slash chords,
+// and every add, omission or alteration it takes to spell makes it less likely
+pub fn score_chord(chord: &Chord) -> u8 {
+    if chord.name == AMBIGUOUS_NAME {
+        return 0;
+    }
+
+    let mut score: i32 = 100;
+
+    // the naming engine can spell chords the quality couldn't place, like C7(no3), they're less likely
+    if chord.chord_quality() == ChordQuality::Ambiguous
+        && chord.structure == ChordStructure::Tertian
+    {
+        score -= 20;
+    }
+
+    score -= match chord.inversion() {
+        Some(Inversion::RootPosition) => 0,
+        Some(_) => 15,
+        None => 30,
+    };
+
+    if chord.structure != ChordStructure::Tertian {
+        score -= 10;
+    }
+
+    // only what's after the root, a Bb or F# root is no less likely for being on a black key
+    let symbol = chord.symbol_over_bass();
+    let symbol = read_root(symbol, RootCase::Upper).map_or(symbol, |(_, rest)| rest);
+    let modifiers = symbol.matches("add").count()
+        + symbol.matches("no").count()
+        + symbol.matches(['b', '#']).count();
+    score -= 10 * modifiers as i32;
+
+    score.clamp(1, 100) as u8
+}
+
+// every reading but the chord's own, best first
+// its own is the one on the same root and bass, whatever it's named, Eb6 written is D#6 read from its notes
+fn alternatives_for(chord: &Chord, readings: &[Chord]) -> Vec<Alternative> {
+    readings
+        .iter()
+        .filter(|r| (r.root, r.bass) != (chord.root, chord.bass))
+        .map(|r| Alternative {
+            name: r.name.clone(),
+            score: score_chord(r),
+        })
+        .collect()
+}
+
+// try each of the notes as the root, keeping any that make a recognisable chord (tertian or not)
+// the first note is treated as the bass, so candidates with another root come back as slash chords
+// the most likely reading comes first and each one carries the rest as its alternatives
+// a note given twice (E B E) is only tried as the root the once
+pub fn identify_from_notes(notes: &[Note]) -> Vec<Chord> {
+    ranked(
+        notes
+            .iter()
+            .unique()
+            .map(|root| identify_from_root_and_notes(root, notes))
+            .collect(),
+    )
+}
+
+// the same from midi pitches, where how far above the root each note is voiced says
+// whether it's a 2nd or a 9th rather than the order they're given in, 60 64 67 74 is Cadd9
+// and 60 62 64 67 Cadd2, the lowest pitch is the bass
+pub fn identify_from_pitches(pitches: &[u8]) -> Vec<Chord> {
+    let mut pitches = pitches.to_vec();
+    pitches.sort();
+    let notes: Vec<Note> = pitches
+        .iter()
+        .map(|p| Note::from_midi(*p))
+        .unique()
+        .collect();
+
+    ranked(
+        notes
+            .iter()
+            .map(|root| {
+                let intervals = find_all_intervals_from_root_and_pitches(root, &pitches);
+                identify_from_root_and_intervals(root, &notes, intervals)
+            })
+            .collect(),
+    )
+}
+
+// readings of the same notes, best first and each with the others as its alternatives
+// unlikely ones are left out unless there is nothing better, nobody means A#sus2(b5)/B# by C E A#
+fn ranked(readings: Vec<Chord>) -> Vec<Chord> {
+    let mut readings: Vec<Chord> = readings
+        .into_iter()
+        .filter(|chord| chord.name != AMBIGUOUS_NAME)
+        .collect();
+
+    // stable so equally likely readings keep the order the notes were given in
+    readings.sort_by_key(|r| std::cmp::Reverse(score_chord(r)));
+    let readings: Vec<Chord> = readings
+        .into_iter()
+        .enumerate()
+        .filter(|(index, r)| *index == 0 || score_chord(r) >= LEAST_LIKELY_READING)
+        .map(|(_, r)| r)
+        .collect();
+
+    let alternatives: Vec<Vec<Alternative>> = readings
+        .iter()
+        .map(|r| alternatives_for(r, &readings))
+        .collect();
+
+    readings
+        .into_iter()
+        .zip(alternatives)
+        .map(|(mut reading, alternatives)| {
+            reading.alternatives = alternatives;
+            reading
+        })
+        .collect()
+}
+
+// the stack goes round every note before coming back to the root so there's no more than 12
+// without a number it's three high, the smallest stack that's still a chord
+fn parse_stacked(
+    chord_name: String,
+    root: Note,
+    bass: Option<Note>,
+    structure: ChordStructure,
+    count: Option<usize>,
+) -> Result<Chord, ChordParseError> {
+    if bass.is_some() {
+        return Err(ChordParseError::InvalidChordName(format!(
+            "{} is a stack from its root, it can't have another bass",
+            chord_name
+        )));
+    }
+
+    let count = match count {
+        None => 3,
+        Some(count) if (3..=12).contains(&count) => count,
+        Some(count) => {
+            return Err(ChordParseError::InvalidChordName(format!(
+                "a stack has 3 to 12 notes, not {}",
+                count
+            )))
+        }
+    };
+
+    let notes = get_stacked_notes(&root, structure, count);
+    let intervals = find_all_intervals_from_root_and_notes(&root, notes.clone());
+    let chord_quality = derive_chord_quality_from_intervals(&intervals);
+
+    Ok(ChordBuilder::new()
+        .name(chord_name)
+        .root(root)
+        .notes(notes)
+        .intervals(intervals)
+        .triad_quality(TriadQuality::from(chord_quality))
+        .structure(structure)
+        .written(true)
+        .build())
+}
+
+// how much a reading loses for ignoring which note is actually in the bass
+const BASS_IGNORED_PENALTY: u8 = 25;
+
+// the lowest score a reading can have and still be given as well as the best one
+const LEAST_LIKELY_READING: u8 = 60;
+
+// every name the notes could plausibly go by, not just one per root, most likely first
+// each note gets a go as the root over the bass that was actually played (a slash chord if they differ)
+// and in its own root position, which is how it'd be written if the voicing didn't matter
+// e.g. A C E G is an Am7, a C6/A, or a C6 if you don't mind the A underneath
+// spelled with the fewest accidentals, so Bb D F is a Bb rather than an A#
+pub fn enumerate_chord_names(notes: &[Note]) -> Vec<Alternative> {
+    let unique_notes: Vec<Note> = notes.iter().copied().unique().collect();
+
+    let mut names: Vec<Alternative> = vec![];
+    for (index, root) in unique_notes.iter().enumerate() {
+        let as_played = identify_from_root_and_notes(root, notes);
+        names.push(Alternative {
+            name: spell_name(&as_played, None),
+            score: score_chord(&as_played),
+        });
+
+        if index == 0 {
+            continue;
+        }
+
+        // the same notes in the same order round, just starting from this root
+        let rotated: Vec<Note> = unique_notes[index..]
+            .iter()
+            .chain(&unique_notes[..index])
+            .copied()
+            .collect();
+        let root_position = identify_from_root_and_notes(root, &rotated);
+        names.push(Alternative {
+            name: spell_name(&root_position, None),
+            score: score_chord(&root_position)
+                .saturating_sub(BASS_IGNORED_PENALTY)
+                .max(1),
+        });
+    }
+
+    names.retain(|n| n.name != AMBIGUOUS_NAME);
+
+    // stable so equally likely names keep the order the notes were given in,
+    // and a name reached more than once keeps its best score
+    names.sort_by_key(|n| std::cmp::Reverse(n.score));
+    names
+        .into_iter()
+        .unique_by(|n| n.name.clone())
+        .enumerate()
+        .filter(|(index, n)| *index == 0 || n.score >= LEAST_LIKELY_READING)
+        .map(|(_, n)| n)
+        .collect()
+}
+
+// naming and parsing have to agree, whatever symbol a chord is given must read back as the same chord
+// anyone adding a quality or spelling to either side can check it against this
+pub fn verify_symbol_roundtrip(chord: &Chord) -> Result<(), ChordParseError> {
+    let parsed = parse_symbol(
+        chord.symbol().to_string(),
+        &Aliases::default(),
+        ParseOptions::default(),
+    )?;
+
+    match parsed.sounds_like(chord) {
+        true => Ok(()),
+        false => Err(ChordParseError::InvalidChordName(format!(
+            "{} ({}) reads back as {}",
+            chord.symbol(),
+            chord.notes.iter().join(" "),
+            parsed.notes.iter().join(" ")
+        ))),
+    }
+}
+
 // TODO: need better naming than identify_x
 // maybe pub fn from_name ?
-// TODO: clean up pulling from name so that no part of string is left unaccounted for
-// that way can reject unrecognized features
+// every character of the name has to mean something, anything it doesn't recognise is an error
+// identify_from_name_as with lenient ParseOptions reads past it instead
 pub fn identify_from_name(chord_name: String) -> Result<Chord, ChordParseError> {
-    // TODO: seems like diologuer has options for adding validators so try split validation and move there
+    identify_from_name_with_aliases(chord_name, &Aliases::default())
+}
 
-    // sharps before normals so we don't pick up only note
-    let root_re = Regex::new(r"(A#|A|B|C#|C|D#|D|E|F#|F|G#|G)").unwrap();
+// same again but reading the user's own shorthand too, with "^ = maj7" C^ is a Cmaj7
+pub fn identify_from_name_with_aliases(
+    chord_name: String,
+    aliases: &Aliases,
+) -> Result<Chord, ChordParseError> {
+    identify_from_name_as(chord_name, aliases, ParseOptions::default())
+}
 
-    let root = match root_re.find(&chord_name) {
-        Some(mat) => match Note::from_str(mat.as_str()) {
-            Ok(n) => n,
-            Err(e) => {
-                return Err(ChordParseError::InvalidChordName(
-                    "couldn't identify root note in string".to_string(),
-                ))
-            }
+// and with a G13 read literally as just the 7th and 13th, or unknown text skipped over, if asked
+pub fn identify_from_name_as(
+    chord_name: String,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Chord, ChordParseError> {
+    let chord = parse_symbol(chord_name, aliases, options)?;
+
+    // a name can still be ambiguous about what's going on, C6 has the same notes as Am7/C
+    // so the other readings of its notes are the alternatives
+    let alternatives = alternatives_for(&chord, &identify_from_notes(&chord.notes));
+
+    Ok(Chord {
+        alternatives,
+        ..chord
+    })
+}
+
+// every plausible way of reading a name, the one the options give first
+// where conventions differ each is tried: tensions read the other way (G13 with or without its 9th and 11th),
+// a bare 9, 11 or 13 as an add the way some charts mean it (C9 as Cadd9),
+// and a sharp or flat after the root as an alteration instead (Bb13 as B(b13))
+// readings with the same notes are only given once, the others named so they can only be read the one way
+pub fn identify_all_from_name(
+    chord_name: String,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Vec<Chord>, ChordParseError> {
+    let first = identify_from_name_as(chord_name.clone(), aliases, options)?;
+
+    let tensions = match options.tensions {
+        TensionReading::Implied => TensionReading::Literal,
+        TensionReading::Literal => TensionReading::Implied,
+    };
+    let others = [(
+        chord_name.clone(),
+        ParseOptions {
+            tensions,
+            ..options
+        },
+    )]
+    .into_iter()
+    .chain(extension_as_add(&chord_name).map(|name| (name, options)))
+    .chain(accidental_as_alteration(&chord_name).map(|name| (name, options)))
+    .filter_map(|(name, options)| identify_from_name_as(name, aliases, options).ok());
+
+    let same_notes = |a: &Chord, b: &Chord| {
+        a.bass == b.bass
+            && a.notes
+                .iter()
+                .sorted_by_key(|n| n.position())
+                .eq(b.notes.iter().sorted_by_key(|n| n.position()))
+    };
+
+    let mut readings = vec![first];
+    for chord in others {
+        if readings.iter().any(|r| same_notes(r, &chord)) {
+            continue;
+        }
+        // with the root spelled as it was typed, a Bb13 read another way is still Bb something
+        let root = split_written_root(&chord.name)
+            .map(|(letter, accidental, _)| {
+                format!(
+                    "{}{}",
+                    letter,
+                    accidental.map(String::from).unwrap_or_default()
+                )
+            })
+            .filter(|root| Note::from_str(root).is_ok_and(|n| n == chord.root))
+            .unwrap_or(chord.root.to_string());
+        let name = match (name_chord(&chord.root, &chord.intervals), chord.bass) {
+            (Some(name), Some(bass)) => format!("{}/{}", name, bass),
+            (Some(name), None) => name,
+            (None, _) => chord.name.clone(),
+        };
+        let name = match name.strip_prefix(&chord.root.to_string()) {
+            Some(rest) => format!("{}{}", root, rest),
+            None => name,
+        };
+        readings.push(Chord { name, ..chord });
+    }
+
+    Ok(readings)
+}
+
+// the root of a name as it's written, with its sharp or flat if it has one, and what comes after
+fn split_written_root(chord_name: &str) -> Option<(&str, Option<char>, &str)> {
+    let mut chars = chord_name.char_indices();
+    let (_, letter) = chars.next()?;
+    if !letter.is_ascii_alphabetic() {
+        return None;
+    }
+
+    match chars.next() {
+        Some((at, accidental @ ('b' | '#' | '♭' | '♯'))) => Some((
+            &chord_name[..at],
+            Some(accidental),
+            &chord_name[at + accidental.len_utf8()..],
+        )),
+        _ => Some((&chord_name[..1], None, &chord_name[1..])),
+    }
+}
+
+// the number straight after the root, or after an m for a minor, split from what's either side of it
+fn split_number(rest: &str) -> Option<(&str, &str, &str)> {
+    let start = rest.find(|c: char| c.is_ascii_digit())?;
+    let end = rest[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(rest.len(), |end| start + end);
+
+    Some((&rest[..start], &rest[start..end], &rest[end..]))
+}
+
+// C9 as Cadd9, Cm11 as Cmadd11, the number read as an add rather than the top of a stack
+fn extension_as_add(chord_name: &str) -> Option<String> {
+    let (root, accidental, rest) = split_written_root(chord_name.trim())?;
+    let (quality, number, after) = split_number(rest)?;
+
+    (matches!(quality, "" | "m" | "min" | "-" | "−") && matches!(number, "9" | "11" | "13")).then(
+        || {
+            format!(
+                "{}{}{}add{}{}",
+                root,
+                accidental.map(String::from).unwrap_or_default(),
+                quality,
+                number,
+                after
+            )
         },
+    )
+}
+
+// Bb13 as B(b13), the sharp or flat taken off the root and put on the number after it
+fn accidental_as_alteration(chord_name: &str) -> Option<String> {
+    let (root, accidental, rest) = split_written_root(chord_name.trim())?;
+    let accidental = accidental?;
+    let (before, number, after) = split_number(rest)?;
+
+    before
+        .is_empty()
+        .then(|| format!("{}({}{}){}", root, accidental, number, after))
+}
+
+// upper and lower halves of a polychord, D|C or D over C (maybe with triad on the end) are both D over C
+// None when it's just a chord, a plain slash like D/C is a bass note not a second chord
+pub fn split_polychord(chord_name: &str) -> Option<(&str, &str)> {
+    let (upper, lower) = chord_name
+        .split_once('|')
+        .or_else(|| chord_name.split_once(" over "))?;
+
+    let lower = lower.trim();
+    let lower = lower.strip_suffix("triad").unwrap_or(lower);
+
+    Some((upper.trim(), lower.trim()))
+}
+
+// each half is read as a chord of its own, slash basses and aliases included
+pub fn identify_polychord(
+    chord_name: String,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Polychord, ChordParseError> {
+    let (upper, lower) = match split_polychord(&chord_name) {
+        Some(halves) => halves,
         None => {
-            return Err(ChordParseError::InvalidChordName(
-                "couldn't identify root note in string".to_string(),
-            ))
+            return Err(ChordParseError::InvalidChordName(format!(
+                "{} isn't two chords, write them upper|lower",
+                chord_name
+            )))
         }
     };
 
-    // TODO: refactor cleaner
-    let chord_quality_re = Regex::new(r"(dim|m|aug|sus2|sus4)").unwrap();
-
-    let mut chord_quality = match chord_quality_re.find(&chord_name) {
-        Some(chord_quality_match) => {
-            let str = chord_quality_match.as_str();
-            match parse_chord_quality(str) {
-                Ok(c) => c,
-                Err(_) => {
-                    return Err(ChordParseError::InvalidChordName(
-                        "couldn't identify root note in string".to_string(),
-                    ))
-                }
+    // each half's errors point at it in the whole name
+    let half = |name: &str| {
+        let offset = name.as_ptr() as usize - chord_name.as_ptr() as usize;
+        parse_symbol(name.to_string(), aliases, options).map_err(|e| e.within(&chord_name, offset))
+    };
+
+    Ok(Polychord::new(half(upper)?, half(lower)?))
+}
+
+// what the tokens after the root say, before any of it is turned into intervals
+#[derive(Debug, Default)]
+struct SymbolParts {
+    // m, maj, dim or aug, whichever comes first
+    quality: Option<Token>,
+    sus: Option<SuspendedType>,
+    power: bool,
+    // the 7th, 9th, 11th or 13th the chord goes up to and the quality written straight before it,
+    // which for maj or M says it's a major 7th
+    extension: Option<(Option<Token>, u8)>,
+    sixth: bool,
+    alterations: Vec<(char, u8)>,
+    omissions: Vec<u8>,
+    adds: Vec<Interval>,
+    stacked: Option<(ChordStructure, Option<usize>)>,
+}
+
+// the tokens one after another, every one of them used or it's an error
+// a name goes quality, then the number it goes up to, then any sus, alterations, omissions and adds in any order
+// C, Cm, Cmaj7, CmM7, C7sus4, Cm7b5, C6add9, C9(no3), with a 5 straight after the root a power chord
+// the quality and number can come later too as long as they're only written the once,
+// Cadd11m7 is read the same as Cm7add11 and Csus4 7 as C7sus4
+struct SymbolReader<'a> {
+    // what was tokenized, the spans in errors are into this
+    rest: &'a str,
+    mode: ParseMode,
+    tokens: &'a [Spanned],
+    at: usize,
+    parts: SymbolParts,
+    // whether a quality's been written, a lowercase minor root doesn't count so cmaj7 can still be major
+    quality_written: bool,
+}
+
+impl<'a> SymbolReader<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.at).map(|s| &s.token)
+    }
+
+    // an error pointing at the token it's on
+    fn error(&self, reason: String) -> ChordParseError {
+        let token = &self.tokens[self.at];
+        ChordParseError::UnexpectedToken(
+            reason,
+            Span::new(self.rest, token.start, token.end),
+            vec![],
+        )
+    }
+
+    // everything from here on, none of it fits where it's written
+    fn unexpected(&self) -> ChordParseError {
+        let start = self.tokens[self.at].start;
+        let end = self.tokens.last().map_or(start, |t| t.end);
+        let span = Span::new(self.rest, start, end);
+        ChordParseError::UnexpectedToken(
+            format!("didn't expect {} where it is", span.token()),
+            span,
+            vec![],
+        )
+    }
+
+    // whether the number the chord goes up to (or its 6th) has been read
+    fn extended(&self) -> bool {
+        self.parts.extension.is_some() || self.parts.sixth || self.parts.power
+    }
+
+    fn read(mut self) -> Result<SymbolParts, ChordParseError> {
+        if let Some(Token::Stacked(structure, count)) = self.peek() {
+            self.parts.stacked = Some((*structure, *count));
+            self.at += 1;
+        } else if let Some(Token::Degree(5)) = self.peek() {
+            self.parts.power = true;
+            self.at += 1;
+        } else {
+            self.quality();
+            self.extension();
+        }
+
+        // leniently a modifier that can't be used is passed over, C7no7b9 is a C7b9
+        while self.at < self.tokens.len() {
+            match (self.modifier(), self.mode) {
+                (Ok(()), _) => {}
+                (Err(e), ParseMode::Strict) => return Err(e),
+                (Err(_), ParseMode::Lenient) => self.at += 1,
             }
         }
-        None => {
-            // if there's no chord quality subscript showing up in the name, it would have to be a major triad
-            // => this chord has a major triad quality we don't know about 7s, 9s etc yet so this would have to be a major triad
-            ChordQuality::Major
+
+        Ok(self.parts)
+    }
+
+    // m, maj, dim, aug or sus, and the M in CmM7 saying the 7th to come is major
+    fn quality(&mut self) {
+        match self.peek() {
+            Some(Token::Sus(sus)) => {
+                self.parts.sus = Some(*sus);
+                self.at += 1;
+            }
+            Some(token @ (Token::Minor | Token::Major | Token::Diminished | Token::Augmented)) => {
+                self.parts.quality = Some(token.clone());
+                self.quality_written = true;
+                self.at += 1;
+            }
+            // ø is a half diminished 7th whether the 7 is written or not, Cø and Cø7 are both Cm7b5
+            Some(Token::HalfDiminished) => {
+                self.parts.quality = Some(Token::Minor);
+                self.quality_written = true;
+                self.parts.extension = Some((Some(Token::Minor), 7));
+                self.parts.alterations.push(('b', 5));
+                self.at += 1;
+                if let Some(Token::Degree(7)) = self.peek() {
+                    self.at += 1;
+                }
+            }
+            _ => {}
         }
-    };
+    }
 
-    let mut intervals: Vec<Interval> = Vec::from(chord_quality);
-
-    // sharps before for priority match
-    // now we have base qualities aug, sus etc from above
-    // we try to enrich with 7th quality
-    // the regex below will catch all 7, 9, 11s => catches all 7 variations
-    // TODO: ^ for string start but watch Xm and Xaug7
-    let extension_quality_re =
-        Regex::new(r"(aug|dim|C#|C|D#|D|E|F#|F|G#|G|A#|A|B|m)(7|9|11)").unwrap();
-    // TODO: loop over all to catch things like G7dim9
-    chord_quality = match extension_quality_re.captures(&chord_name) {
-        Some(extension_captures) => {
-            // TODO: clean up, feels weird to be putting notes here
-            // if we just hang on chord quality here we'll miss the things like G7dim9, Gdim9
-            match &extension_captures[2] {
-                "7" => match chord_quality {
-                    // fully diminished needs diminished 7th
-                    ChordQuality::Diminished => {
-                        intervals.push(Interval::DiminishedSeventh);
-                    }
+    // what comes straight after the quality, the 6 of a 6th chord (with a 9 after for a 6/9)
+    // or the number the chord stacks up to
+    fn extension(&mut self) {
+        if self.parts.extension.is_some() {
+            return;
+        }
 
-                    _ => intervals.push(Interval::MinorSeventh),
-                },
-                // TODO: it might be that 9s/11s can take a modifier like G7aug9, look into this
-                // for now A gdim9 is treated like a Gdim7add9
-                "9" => {
-                    match chord_quality {
-                        ChordQuality::Diminished => {
-                            intervals.push(Interval::DiminishedSeventh);
-                        }
-
-                        _ => intervals.push(Interval::MinorSeventh),
+        let marker = match self.peek() {
+            // Δ is a major 7th, on its own CΔ is CΔ7, and in CΔ9 it says the 7th under the 9 is major
+            Some(Token::Delta) => {
+                self.at += 1;
+                match self.peek() {
+                    Some(Token::Degree(7 | 9 | 11 | 13)) => Some(Token::Major),
+                    _ => {
+                        self.parts.extension = Some((Some(Token::Major), 7));
+                        return;
                     }
-
-                    intervals.push(Interval::MajorNinth);
                 }
-                "11" => {
-                    match chord_quality {
-                        ChordQuality::Diminished => {
-                            intervals.push(Interval::DiminishedSeventh);
-                            intervals.push(Interval::MinorNinth);
-                        }
-
-                        _ => {
-                            intervals.push(Interval::MinorSeventh);
-                            intervals.push(Interval::MajorNinth);
-                        }
+            }
+            Some(token @ (Token::MajorSeventh | Token::Major)) => {
+                match self.tokens.get(self.at + 1).map(|s| &s.token) {
+                    Some(Token::Degree(7 | 9 | 11 | 13)) => {
+                        self.at += 1;
+                        Some(token.clone())
                     }
-
-                    intervals.push(Interval::PerfectEleventh);
+                    _ => return,
                 }
-                _ => {}
-            };
+            }
+            _ => self.parts.quality.clone(),
+        };
 
-            // make sure intervals are unique
-            intervals.dedup();
+        match self.peek() {
+            Some(Token::Degree(6)) if self.parts.sus.is_none() => {
+                self.parts.sixth = true;
+                self.at += 1;
+                if let Some(Token::Degree(9)) = self.peek() {
+                    self.parts.adds.push(Interval::MajorNinth);
+                    self.at += 1;
+                }
+            }
+            Some(Token::Degree(degree @ (7 | 9 | 11 | 13))) => {
+                self.parts.extension = Some((marker, *degree));
+                self.at += 1;
+            }
+            _ => {}
+        }
+    }
 
-            // if there's an extension, the chord quality is affected
-            // TODO: might be cleaner to just recalc quality on intervals here instead
-            match chord_quality {
-                ChordQuality::Suspended(suspended_type) => {
-                    ChordQuality::Seventh(SeventhType::Suspended(suspended_type))
+    fn modifier(&mut self) -> Result<(), ChordParseError> {
+        match self.peek() {
+            Some(Token::Sus(sus)) if self.parts.sus.is_none() => {
+                self.parts.sus = Some(*sus);
+                self.at += 1;
+                // a 7 can come after the sus as well as before it, Csus47
+                if self.parts.extension.is_none() {
+                    self.extension();
+                }
+            }
+            Some(Token::Alteration(accidental, degree)) => {
+                if get_alteration_intervals(&accidental.to_string(), &degree.to_string()).is_none()
+                {
+                    return Err(
+                        self.error(format!("unsupported alteration {}{}", accidental, degree))
+                    );
+                }
+                self.parts.alterations.push((*accidental, *degree));
+                self.at += 1;
+            }
+            Some(Token::Omit(degree @ (3 | 5))) => {
+                self.parts.omissions.push(*degree);
+                self.at += 1;
+            }
+            Some(Token::Omit(degree)) => {
+                return Err(self.error(format!(
+                    "only the 3rd or 5th can be left out, not the {}",
+                    degree
+                )))
+            }
+            Some(Token::Add(accidental, degree)) => {
+                let written = format!(
+                    "{}{}",
+                    accidental.map(String::from).unwrap_or_default(),
+                    degree
+                );
+                match get_add_interval_from_add(&written) {
+                    Interval::Unknown => {
+                        return Err(self.error(format!("unsupported add{}", written)))
+                    }
+                    interval => self.parts.adds.push(interval),
+                }
+                self.at += 1;
+            }
+            // a quality or number written after the modifiers, the m7 in Cadd11m7
+            Some(
+                Token::Minor
+                | Token::Major
+                | Token::MajorSeventh
+                | Token::Delta
+                | Token::Diminished
+                | Token::Augmented
+                | Token::HalfDiminished,
+            ) if !self.quality_written && self.parts.sus.is_none() && !self.extended() => {
+                let at = self.at;
+                self.quality();
+                self.extension();
+                if self.at == at {
+                    return Err(self.unexpected());
                 }
-                ChordQuality::Minor => ChordQuality::Seventh(SeventhType::Minor),
-                ChordQuality::Major => ChordQuality::Seventh(SeventhType::Major),
-                ChordQuality::Diminished => ChordQuality::Seventh(SeventhType::Diminished),
-                ChordQuality::Augmented => ChordQuality::Seventh(SeventhType::Augmented),
-
-                // TODO: rest of these after
-                // ChordQuality::Diminished=> ChordQuality::Diminished,
-                _ => chord_quality,
             }
+            Some(Token::Degree(6 | 7 | 9 | 11 | 13)) if !self.extended() => {
+                let at = self.at;
+                self.extension();
+                if self.at == at {
+                    return Err(self.unexpected());
+                }
+            }
+            // a 7alt leaves which alterations up to the player, it's spelled with all of them
+            // bar the #11 as the b5 is the same note, the notes of the altered scale
+            Some(Token::Altered) => {
+                self.parts
+                    .alterations
+                    .extend([('b', 5), ('b', 9), ('#', 9), ('b', 13)]);
+                self.at += 1;
+            }
+            _ => return Err(self.unexpected()),
+        }
+
+        Ok(())
+    }
+}
+
+// just what the symbol says, without looking for other readings of the notes
+fn parse_symbol(
+    chord_name: String,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Chord, ChordParseError> {
+    // a slash bass like the E in C/E sits on the end of the name
+    let (symbol, bass) = split_slash_bass(&chord_name, options.roots)?;
+    let start = symbol.len() - symbol.trim_start().len();
+    let symbol = symbol.trim();
+
+    let ((root, lowercase), raw_rest) = read_root(symbol, options.roots).ok_or_else(|| {
+        let end = symbol
+            .chars()
+            .next()
+            .map_or(start, |c| start + c.len_utf8());
+        ChordParseError::UnexpectedToken(
+            "couldn't identify root note in string".to_string(),
+            Span::new(&chord_name, start, end),
+            vec![],
+        )
+    })?;
+
+    // everything after the root describes the chord, aliases only apply here so they can't eat into the root
+    let rest = aliases.expand(raw_rest);
+    let offset = start + symbol.len() - raw_rest.len();
+    // errors point into the name as it was typed, where an alias was expanded
+    // there's no telling which part of what was typed it came from so it's all of it
+    // and they come with the nearest names that would have worked, Gmn7 might be Gm7
+    let within_name = |e: ChordParseError| match e {
+        ChordParseError::UnexpectedToken(reason, span, _) => {
+            let span = match rest != raw_rest {
+                true => Span::new(&chord_name, offset, offset + raw_rest.len()),
+                false => span.within(&chord_name, offset),
+            };
+            // the root and any slash bass as they were typed around the nearest qualities
+            let root = &symbol[..symbol.len() - raw_rest.len()];
+            let bass = chord_name[start + symbol.len()..].trim_start();
+            let suggestions = suggest_qualities(raw_rest)
+                .into_iter()
+                .map(|q| format!("{}{}{}", root, q, bass))
+                .collect();
+            ChordParseError::UnexpectedToken(reason, span, suggestions)
         }
-        None => chord_quality,
+        e => e,
     };
 
-    let triad_quality = TriadQuality::from(chord_quality);
-
-    // TODO: maybe this should come before chord quality because adds might be just adding a -
-    // minor 7th for example which makes it a dominant if it's a major triad quality
-    // TODO: allow more adds
-    // matches certain numbers found after add
-    // rust regex doesn't have look before
-    let add_re = Regex::new(r"(add)(7|9|11)").unwrap();
-    let add_degree = match add_re.captures(&chord_name) {
-        Some(add_captures) => match get_add_interval_from_add(&add_captures[2]) {
-            Interval::Unknown => None,
-            interval => Some(interval),
+    let tokens = tokenize_as(&rest, options.mode).map_err(within_name)?;
+    let parts = SymbolReader {
+        rest: &rest,
+        mode: options.mode,
+        tokens: &tokens,
+        at: 0,
+        // read the fake book way a lowercase root makes it minor unless the name says otherwise
+        parts: SymbolParts {
+            quality: (lowercase && options.roots == RootCase::LowercaseMinor)
+                .then_some(Token::Minor),
+            ..SymbolParts::default()
         },
-        None => None,
+        quality_written: false,
+    }
+    .read()
+    .map_err(within_name)?;
+
+    // a stacked chord says how it's built rather than what's in it, D quartal 4 is D G C F
+    if let Some((structure, count)) = parts.stacked {
+        return parse_stacked(chord_name.clone(), root, bass, structure, count);
+    }
+
+    // a sus wins wherever it's written, the maj in Cmaj9sus4 only says which 7th
+    // with no quality written it would have to be a major triad
+    let quality = match (parts.sus, parts.power, &parts.quality) {
+        (Some(SuspendedType::Sus2), _, _) => "sus2",
+        (Some(SuspendedType::Sus4), _, _) => "sus4",
+        (None, true, _) => "5",
+        (None, false, Some(Token::Minor)) => "m",
+        (None, false, Some(Token::Diminished)) => "dim",
+        (None, false, Some(Token::Augmented)) => "aug",
+        _ => "maj",
     };
+    let mut triad_quality = TriadQuality::from(parse_chord_quality(quality)?);
+
+    // the triad from the quality, everything past it is built up in the extensions
+    let mut intervals: Vec<Interval> = Vec::from(triad_quality);
+    let mut extensions = Extensions::default();
+
+    if let Some((marker, degree)) = &parts.extension {
+        // maj only ever changes the 7th, anything stacked above it is unchanged
+        // the 7th is a layer of its own on top of the triad, a Cm with a maj7 is a minor major 7th
+        extensions.seventh = Some(match (marker, triad_quality) {
+            (Some(Token::Major | Token::MajorSeventh), _) => SeventhKind::Major,
+            // fully diminished needs diminished 7th
+            (_, TriadQuality::Diminished) => SeventhKind::Diminished,
+            _ => SeventhKind::Minor,
+        });
+
+        match degree {
+            // a Gdim9 is treated like a Gdim7add9
+            9 => extensions.ninths.push(NinthKind::Natural),
+            // literally there's nothing between the 7th and the tension written
+            11 | 13 if options.tensions == TensionReading::Literal => match degree {
+                13 => extensions.thirteenths.push(ThirteenthKind::Natural),
+                // a sus4's 11th is the 4th it already has
+                _ if triad_quality == TriadQuality::Suspended(SuspendedType::Sus4) => {}
+                _ => extensions.elevenths.push(EleventhKind::Natural),
+            },
+            11 | 13 => {
+                match triad_quality {
+                    TriadQuality::Diminished => extensions.ninths.push(NinthKind::Flat),
+                    _ => extensions.ninths.push(NinthKind::Natural),
+                }
+
+                // a sus4 already has its 11th, it's the suspended 4th,
+                // and a 13 over a major 3rd leaves the 11th out as it clashes
+                let is_sus4 = triad_quality == TriadQuality::Suspended(SuspendedType::Sus4);
+                let clashes = *degree == 13 && intervals.contains(&Interval::MajorThird);
+                if !is_sus4 && !clashes {
+                    extensions.elevenths.push(EleventhKind::Natural);
+                }
+
+                if *degree == 13 {
+                    extensions.thirteenths.push(ThirteenthKind::Natural);
+                }
+            }
+            _ => {}
+        };
+    }
+
+    // alterations raise or lower a degree that's already in the chord (C7b9 swaps the 9th for a b9)
+    // or add the altered degree if it isn't (C7#11 is a C7 with a #11 on top)
+    for (accidental, degree) in &parts.alterations {
+        let (natural, altered) =
+            match get_alteration_intervals(&accidental.to_string(), &degree.to_string()) {
+                Some(res) => res,
+                None => {
+                    return Err(ChordParseError::InvalidChordName(format!(
+                        "unsupported alteration {}{}",
+                        accidental, degree
+                    )))
+                }
+            };
+
+        // the 9th, 11th and 13th are tensions, only a 5th changes the triad
+        if extensions.alter(altered) {
+            continue;
+        }
 
-    match add_degree {
-        Some(interval) => {
-            // with another interval we might be changing the chord quality
-            // an example of this is typing Gadd7 (G major triad added 7th(minor)) => G7 dominant chord
-            // if it's 'normal' 7 we'll have the 7th from above
-            if !intervals.contains(&interval) {
-                intervals.push(interval);
-                chord_quality = derive_chord_quality_from_intervals(&intervals);
+        // a new one slots in by size
+        match intervals.iter().position(|i| *i == natural) {
+            Some(index) => intervals[index] = altered,
+            None => {
+                let index = intervals
+                    .iter()
+                    .position(|i| i.semitones() > altered.semitones())
+                    .unwrap_or(intervals.len());
+                intervals.insert(index, altered);
             }
         }
-        None => {}
+
+        // an altered 5th changes the foundation of the chord, e.g. Cm7b5 is half diminished
+        let with_extensions: Vec<Interval> = intervals
+            .iter()
+            .copied()
+            .chain(extensions.intervals())
+            .collect();
+        triad_quality = TriadQuality::from(derive_chord_quality_from_intervals(&with_extensions));
+    }
+
+    // omissions take a chord tone back out, G7no5 is a G7 without the D
+    // the quality stays as named, C(no3) is still a C major just with the 3rd left out
+    for degree in &parts.omissions {
+        let omitted = get_omitted_intervals(&degree.to_string());
+        intervals.retain(|i| !omitted.contains(i));
+        extensions.omissions.push(match degree {
+            3 => Omission::Third,
+            _ => Omission::Fifth,
+        });
+    }
+
+    // a 6 straight after the root or the m is a 6th chord, C6 and Cm6 are just the triad with the 6th added
+    let add_degrees: Vec<Interval> = parts
+        .sixth
+        .then_some(Interval::MajorSixth)
+        .into_iter()
+        .chain(parts.adds.iter().copied())
+        .collect();
+
+    // with another interval we might be changing the chord quality
+    // an example of this is typing Gadd7 (G major triad added 7th(minor)) => G7 dominant chord
+    // if it's 'normal' 7 we'll have the 7th from above
+    let mut added = false;
+    for interval in add_degrees {
+        if !intervals.contains(&interval) && !extensions.intervals().contains(&interval) {
+            extensions.add(interval);
+            added = true;
+        }
+    }
+
+    let mut intervals: Vec<Interval> = intervals
+        .into_iter()
+        .chain(extensions.intervals())
+        .collect();
+
+    // adds slot in by size so add2 and add4 sit below the 3rd
+    if added {
+        intervals.sort_by_key(|i| i.semitones());
+
+        // an add like Csus2add4 has more going on than derive can make sense of,
+        // in that case the quality from the name still stands
+        triad_quality = match derive_chord_quality_from_intervals(&intervals) {
+            ChordQuality::Ambiguous => triad_quality,
+            derived => TriadQuality::from(derived),
+        };
     }
 
-    let notes = get_notes_from_root_and_intervals(&root, &intervals);
+    let mut notes = get_notes_from_root_and_intervals(&root, &intervals)?;
+
+    // C/C is just C
+    let bass = bass.filter(|b| *b != root);
+
+    if let Some(bass) = bass {
+        notes = get_notes_with_bass(&bass, &notes);
+    }
 
     Ok(ChordBuilder::new()
         .name(chord_name)
         .root(root)
+        .bass(bass)
         .intervals(intervals)
+        .extensions(extensions)
         .notes(notes)
-        .chord_quality(chord_quality)
         .triad_quality(triad_quality)
+        .written(true)
         .build())
 }
 
@@ -261,7 +1146,10 @@ mod tests {
         let ret = identify_from_root_and_notes(&root, &notes);
 
         assert_eq!(ret.triad_quality, TriadQuality::Minor);
-        assert_eq!(ret.chord_quality, ChordQuality::Seventh(SeventhType::Minor));
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Minor)
+        );
         assert_eq!(
             ret.intervals,
             vec![
@@ -283,11 +1171,14 @@ mod tests {
         let ret = identify_from_name("Gsus2".to_string()).expect("hmm");
         assert_eq!(ret.name, "Gsus2");
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Suspended(SuspendedType::Sus2)
         );
         assert_eq!(ret.notes, vec![Note::G, Note::A, Note::D]);
-        assert_eq!(ret.triad_quality, TriadQuality::Ambiguous);
+        assert_eq!(
+            ret.triad_quality,
+            TriadQuality::Suspended(SuspendedType::Sus2)
+        );
         assert_eq!(ret.root, Note::G);
         assert_eq!(
             ret.intervals,
@@ -299,7 +1190,7 @@ mod tests {
     fn test_identify_from_name_gm() {
         let ret = identify_from_name("Gm".to_string()).expect("hmm");
         assert_eq!(ret.name, "Gm");
-        assert_eq!(ret.chord_quality, ChordQuality::Minor);
+        assert_eq!(ret.chord_quality(), ChordQuality::Minor);
         assert_eq!(ret.notes, vec![Note::G, Note::As, Note::D]);
         assert_eq!(ret.triad_quality, TriadQuality::Minor);
         assert_eq!(ret.root, Note::G);
@@ -314,7 +1205,10 @@ mod tests {
         let ret = identify_from_name("Gm7".to_string()).expect("hmm");
         assert_eq!(ret.name, "Gm7");
         assert_eq!(ret.root, Note::G);
-        assert_eq!(ret.chord_quality, ChordQuality::Seventh(SeventhType::Minor));
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Minor)
+        );
         assert_eq!(ret.triad_quality, TriadQuality::Minor);
         assert_eq!(
             ret.intervals,
@@ -333,7 +1227,7 @@ mod tests {
         assert_eq!(ret.name, "Gaug7");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Augmented)
         );
         assert_eq!(ret.triad_quality, TriadQuality::Augmented);
@@ -354,7 +1248,7 @@ mod tests {
         assert_eq!(ret.name, "Gdim7");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Diminished)
         );
         assert_eq!(ret.triad_quality, TriadQuality::Diminished);
@@ -375,7 +1269,7 @@ mod tests {
         assert_eq!(ret.name, "Gadd7");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Dominant)
         );
         assert_eq!(ret.triad_quality, TriadQuality::Major);
@@ -396,10 +1290,13 @@ mod tests {
         assert_eq!(ret.name, "G7sus2");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus2))
         );
-        assert_eq!(ret.triad_quality, TriadQuality::Ambiguous);
+        assert_eq!(
+            ret.triad_quality,
+            TriadQuality::Suspended(SuspendedType::Sus2)
+        );
         assert_eq!(
             ret.intervals,
             vec![
@@ -417,10 +1314,13 @@ mod tests {
         assert_eq!(ret.name, "G7sus2add11");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus2))
         );
-        assert_eq!(ret.triad_quality, TriadQuality::Ambiguous);
+        assert_eq!(
+            ret.triad_quality,
+            TriadQuality::Suspended(SuspendedType::Sus2)
+        );
         assert_eq!(
             ret.intervals,
             vec![
@@ -443,7 +1343,7 @@ mod tests {
         assert_eq!(ret.name, "Gdim11");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Diminished)
         );
         assert_eq!(ret.triad_quality, TriadQuality::Diminished);
@@ -470,7 +1370,7 @@ mod tests {
         assert_eq!(ret.name, "Gaug11");
         assert_eq!(ret.root, Note::G);
         assert_eq!(
-            ret.chord_quality,
+            ret.chord_quality(),
             ChordQuality::Seventh(SeventhType::Augmented)
         );
         assert_eq!(ret.triad_quality, TriadQuality::Augmented);
@@ -490,4 +1390,1162 @@ mod tests {
             vec![Note::G, Note::B, Note::Ds, Note::F, Note::A, Note::C]
         );
     }
+
+    //
+    // parenthesized extensions
+    //
+
+    #[test]
+    fn test_identify_from_name_c7_b9_parenthesized_matches_inline() {
+        let parenthesized = identify_from_name("C7(b9)".to_string()).expect("hmm");
+        let inline = identify_from_name("C7b9".to_string()).expect("hmm");
+
+        assert_eq!(parenthesized.name, "C7(b9)");
+        assert_eq!(
+            parenthesized.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Dominant)
+        );
+        assert_eq!(
+            parenthesized.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh,
+                Interval::MinorNinth
+            ]
+        );
+        assert_eq!(parenthesized.intervals, inline.intervals);
+        assert_eq!(parenthesized.notes, inline.notes);
+        assert_eq!(
+            parenthesized.notes,
+            vec![Note::C, Note::E, Note::G, Note::As, Note::Cs]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_cmaj7_sharp11() {
+        let ret = identify_from_name("Cmaj7(#11)".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::C);
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Major)
+        );
+        assert_eq!(ret.triad_quality, TriadQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MajorSeventh,
+                Interval::AugmentedEleventh
+            ]
+        );
+        assert_eq!(
+            ret.notes,
+            vec![Note::C, Note::E, Note::G, Note::B, Note::Fs]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_g13_altered_list() {
+        let ret = identify_from_name("G13(b9,#11)".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::G);
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Dominant)
+        );
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh,
+                Interval::MinorNinth,
+                Interval::AugmentedEleventh,
+                Interval::MajorThirteenth
+            ]
+        );
+        assert_eq!(
+            ret.notes,
+            vec![
+                Note::G,
+                Note::B,
+                Note::D,
+                Note::F,
+                Note::Gs,
+                Note::Cs,
+                Note::E
+            ]
+        );
+        assert_eq!(
+            ret.intervals,
+            identify_from_name("G13b9#11".to_string())
+                .expect("hmm")
+                .intervals
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_altered_fifth_changes_quality() {
+        let ret = identify_from_name("Cm7(b5)".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::HalfDiminished)
+        );
+        assert_eq!(ret.triad_quality, TriadQuality::Diminished);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MinorThird,
+                Interval::DiminishedFifth,
+                Interval::MinorSeventh
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_unsupported_alteration() {
+        let ret = identify_from_name("C7(#13)".to_string());
+        assert!(ret.is_err());
+    }
+
+    //
+    // slash chords
+    //
+
+    #[test]
+    fn test_identify_from_root_and_notes_first_inversion_is_slash_chord() {
+        let root = Note::C;
+        let notes = vec![Note::E, Note::G, Note::C];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "C/E");
+        assert_eq!(ret.bass, Some(Note::E));
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![Interval::MajorThird, Interval::PerfectFifth]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_root_position_has_no_bass() {
+        let root = Note::C;
+        let notes = vec![Note::C, Note::E, Note::G];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "C");
+        assert_eq!(ret.bass, None);
+    }
+
+    #[test]
+    fn test_identify_from_name_slash_chord_tone_bass() {
+        let ret = identify_from_name("G/B".to_string()).expect("hmm");
+        assert_eq!(ret.name, "G/B");
+        assert_eq!(ret.root, Note::G);
+        assert_eq!(ret.bass, Some(Note::B));
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(ret.notes, vec![Note::B, Note::G, Note::D]);
+    }
+
+    #[test]
+    fn test_identify_from_name_slash_non_chord_tone_bass() {
+        let ret = identify_from_name("Am/G".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::A);
+        assert_eq!(ret.bass, Some(Note::G));
+        assert_eq!(ret.chord_quality(), ChordQuality::Minor);
+        assert_eq!(ret.notes, vec![Note::G, Note::A, Note::C, Note::E]);
+    }
+
+    #[test]
+    fn test_identify_from_name_slash_sharp_bass_not_taken_as_root() {
+        let ret = identify_from_name("D/F#".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::D);
+        assert_eq!(ret.bass, Some(Note::Fs));
+        assert_eq!(ret.notes, vec![Note::Fs, Note::D, Note::A]);
+    }
+
+    #[test]
+    fn test_identify_from_name_slash_invalid_bass() {
+        let ret = identify_from_name("C/X".to_string());
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_identify_from_name_rejects_unknown_text() {
+        assert!(identify_from_name("Gxyz7".to_string()).is_err());
+        assert!(identify_from_name("C7+".to_string()).is_err());
+        assert!(identify_from_name("Cno7".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_identify_from_name_as_lenient() {
+        let lenient = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..ParseOptions::default()
+        };
+        let read = |name: &str| {
+            identify_from_name_as(name.to_string(), &Aliases::default(), lenient).expect("hmm")
+        };
+
+        assert_eq!(read("Gxyz7").notes, read("G7").notes);
+        assert_eq!(read("C7no7b9").notes, read("C7b9").notes);
+        // the root still has to be there
+        assert!(identify_from_name_as("xyz7".to_string(), &Aliases::default(), lenient).is_err());
+    }
+
+    #[test]
+    fn test_identify_from_name_error_spans() {
+        let span = |name: &str| match identify_from_name(name.to_string()) {
+            Err(e) => e.span().map(|s| (s.start, s.end)),
+            Ok(_) => panic!("expected {} not to parse", name),
+        };
+
+        assert_eq!(span("Gxyz7"), Some((1, 4)));
+        assert_eq!(span("Cadd8"), Some((1, 5)));
+        assert_eq!(span(" Hm7"), Some((1, 2)));
+        assert_eq!(span("C/X"), Some((2, 3)));
+        assert_eq!(span("Cm7maj"), Some((3, 6)));
+    }
+
+    #[test]
+    fn test_identify_from_name_did_you_mean() {
+        let suggestions = |name: &str| match identify_from_name(name.to_string()) {
+            Err(e) => e.suggestions().to_vec(),
+            Ok(_) => panic!("expected {} not to parse", name),
+        };
+
+        assert_eq!(suggestions("Gmn7"), vec!["Gm7"]);
+        assert_eq!(suggestions("Csus3"), vec!["Csus2", "Csus4"]);
+        assert_eq!(suggestions("Bbmn7/F"), vec!["Bbm7/F"]);
+        assert!(suggestions("Gxyzzy7").is_empty());
+    }
+
+    #[test]
+    fn test_identify_from_name_music_symbols() {
+        let notes = |name: &str| identify_from_name(name.to_string()).expect("hmm").notes;
+
+        assert_eq!(notes("B♭Δ7"), notes("Bbmaj7"));
+        assert_eq!(notes("BbΔ"), notes("Bbmaj7"));
+        assert_eq!(notes("CmΔ7"), notes("CmM7"));
+        assert_eq!(notes("C°7"), notes("Cdim7"));
+        assert_eq!(notes("F♯ø7"), notes("F#m7b5"));
+        assert_eq!(notes("F♯ø"), notes("F#m7b5"));
+        assert_eq!(notes("C+"), notes("Caug"));
+        assert_eq!(notes("C−7"), notes("Cm7"));
+        assert_eq!(notes("C7+5"), notes("C7#5"));
+        assert_eq!(notes("C7−9"), notes("C7b9"));
+        assert_eq!(notes("D/F♯"), notes("D/F#"));
+    }
+
+    #[test]
+    fn test_identify_from_name_modifiers_in_any_order() {
+        let notes = |name: &str| identify_from_name(name.to_string()).expect("hmm").notes;
+
+        assert_eq!(notes("Csus4 7"), notes("C7sus4"));
+        assert_eq!(notes("Cadd11m7"), notes("Cm7add11"));
+        assert_eq!(notes("Cb5m7"), notes("Cm7b5"));
+        assert_eq!(notes("C(no3)9"), notes("C9no3"));
+        assert_eq!(notes("Cadd9maj7"), notes("Cmaj7add9"));
+        assert_eq!(notes("C(#9)7"), notes("C7#9"));
+        // but only the once
+        assert!(identify_from_name("Cm7m".to_string()).is_err());
+        assert!(identify_from_name("C7add9 9".to_string()).is_err());
+        assert!(identify_from_name("Cadd9M".to_string()).is_err());
+    }
+
+    //
+    // identify_all_from_name
+    //
+
+    #[test]
+    fn test_identify_all_from_name_ambiguous() {
+        let names = |name: &str| {
+            identify_all_from_name(
+                name.to_string(),
+                &Aliases::default(),
+                ParseOptions::default(),
+            )
+            .expect("hmm")
+            .into_iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("C9"), vec!["C9", "Cadd9"]);
+        assert_eq!(names("G13"), vec!["G13", "G7add13", "Gadd13"]);
+        assert_eq!(names("Bb13"), vec!["Bb13", "Bb7add13", "Bbadd13", "B(b13)"]);
+        assert_eq!(names("Cmaj7"), vec!["Cmaj7"]);
+    }
+
+    #[test]
+    fn test_identify_from_name_as_lowercase_roots() {
+        let read = |name: &str, roots: RootCase| {
+            let options = ParseOptions {
+                roots,
+                ..ParseOptions::default()
+            };
+            identify_from_name_as(name.to_string(), &Aliases::default(), options).map(|c| c.notes)
+        };
+        let notes = |name: &str| identify_from_name(name.to_string()).expect("hmm").notes;
+
+        assert!(read("gm7", RootCase::Upper).is_err());
+        assert_eq!(read("gm7", RootCase::Either).expect("hmm"), notes("Gm7"));
+        assert_eq!(
+            read("bb7/f", RootCase::Either).expect("hmm"),
+            notes("Bb7/F")
+        );
+        assert_eq!(read("c", RootCase::Either).expect("hmm"), notes("C"));
+    }
+
+    #[test]
+    fn test_identify_from_name_as_lowercase_minor() {
+        let read = |name: &str| {
+            let options = ParseOptions {
+                roots: RootCase::LowercaseMinor,
+                ..ParseOptions::default()
+            };
+            identify_from_name_as(name.to_string(), &Aliases::default(), options)
+                .expect("hmm")
+                .notes
+        };
+        let notes = |name: &str| identify_from_name(name.to_string()).expect("hmm").notes;
+
+        assert_eq!(read("c"), notes("Cm"));
+        assert_eq!(read("c7"), notes("Cm7"));
+        assert_eq!(read("f#m7b5"), notes("F#m7b5"));
+        assert_eq!(read("cdim"), notes("Cdim"));
+        assert_eq!(read("C7"), notes("C7"));
+    }
+
+    #[test]
+    fn test_identify_from_name_min() {
+        let ret = identify_from_name("Cmin7".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::Ds, Note::G, Note::As]);
+    }
+
+    //
+    // omissions
+    //
+
+    #[test]
+    fn test_identify_from_name_g7no5() {
+        let ret = identify_from_name("G7no5".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Dominant)
+        );
+        assert_eq!(
+            ret.intervals,
+            vec![Interval::MajorThird, Interval::MinorSeventh]
+        );
+        assert_eq!(ret.notes, vec![Note::G, Note::B, Note::F]);
+    }
+
+    #[test]
+    fn test_identify_from_name_parenthesized_no3() {
+        let ret = identify_from_name("C(no3)".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(ret.intervals, vec![Interval::PerfectFifth]);
+        assert_eq!(ret.notes, vec![Note::C, Note::G]);
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_seventh_missing_fifth() {
+        let root = Note::C;
+        let notes = vec![Note::C, Note::E, Note::As];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "C7(no5)");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Dominant)
+        );
+    }
+
+    //
+    // power chords
+    //
+
+    #[test]
+    fn test_identify_from_name_c5() {
+        let ret = identify_from_name("C5".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Power);
+        assert_eq!(ret.triad_quality, TriadQuality::Power);
+        assert_eq!(ret.intervals, vec![Interval::PerfectFifth]);
+        assert_eq!(ret.notes, vec![Note::C, Note::G]);
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_power_with_doubled_octave() {
+        let root = Note::E;
+        let notes = vec![Note::E, Note::B, Note::E];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "E5");
+        assert_eq!(ret.chord_quality(), ChordQuality::Power);
+    }
+
+    //
+    // add intervals
+    //
+
+    #[test]
+    fn test_identify_from_name_cadd2() {
+        let ret = identify_from_name("Cadd2".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorSecond,
+                Interval::MajorThird,
+                Interval::PerfectFifth
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::D, Note::E, Note::G]);
+    }
+
+    #[test]
+    fn test_identify_from_name_cmadd4() {
+        let ret = identify_from_name("Cmadd4".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Minor);
+        assert_eq!(ret.notes, vec![Note::C, Note::Ds, Note::F, Note::G]);
+    }
+
+    #[test]
+    fn test_identify_from_name_sixth_chords() {
+        let ret = identify_from_name("C6".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::A]);
+
+        let ret = identify_from_name("Cm6".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::Ds, Note::G, Note::A]);
+
+        let ret = identify_from_name("C6add9".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::A, Note::D]);
+    }
+
+    #[test]
+    fn test_identify_from_name_six_nine() {
+        let ret = identify_from_name("C6/9".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::A, Note::D]);
+        assert_eq!(ret.bass, None);
+
+        let ret = identify_from_name("Cm6/9".to_string()).expect("hmm");
+        assert_eq!(
+            ret.notes,
+            vec![Note::C, Note::Ds, Note::G, Note::A, Note::D]
+        );
+
+        let ret = identify_from_name("C6/9/E".to_string()).expect("hmm");
+        assert_eq!(ret.bass, Some(Note::E));
+        assert_eq!(ret.notes[0], Note::E);
+    }
+
+    #[test]
+    fn test_identify_from_name_cadd6() {
+        let ret = identify_from_name("Cadd6".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MajorSixth
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::A]);
+    }
+
+    #[test]
+    fn test_identify_from_name_cadd_sharp11() {
+        let ret = identify_from_name("Cadd#11".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::AugmentedEleventh
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::Fs]);
+    }
+
+    #[test]
+    fn test_identify_from_name_cadd13() {
+        let ret = identify_from_name("Cadd13".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MajorThirteenth
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::A]);
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_names_adds() {
+        let root = Note::C;
+        let notes = vec![Note::C, Note::E, Note::G, Note::A];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "C6");
+        assert_eq!(
+            identify_from_name(ret.name).expect("hmm").notes,
+            vec![Note::C, Note::E, Note::G, Note::A]
+        );
+    }
+
+    //
+    // multiple adds
+    //
+
+    #[test]
+    fn test_identify_from_name_cadd9add13() {
+        let ret = identify_from_name("Cadd9add13".to_string()).expect("hmm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Major);
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MajorNinth,
+                Interval::MajorThirteenth
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::D, Note::A]);
+    }
+
+    #[test]
+    fn test_identify_from_name_csus2add11add13() {
+        let ret = identify_from_name("Csus2add11add13".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Suspended(SuspendedType::Sus2)
+        );
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::MajorSecond,
+                Interval::PerfectFifth,
+                Interval::PerfectEleventh,
+                Interval::MajorThirteenth
+            ]
+        );
+        assert_eq!(ret.notes, vec![Note::C, Note::D, Note::G, Note::F, Note::A]);
+    }
+
+    #[test]
+    fn test_identify_from_name_repeated_add_only_added_once() {
+        let ret = identify_from_name("Cadd9add9".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::E, Note::G, Note::D]);
+    }
+
+    //
+    // minor major sevenths
+    //
+
+    #[test]
+    fn test_identify_from_name_minor_major_seventh_spellings() {
+        for name in ["Cm(maj7)", "CmM7", "Cmmaj7"] {
+            let ret = identify_from_name(name.to_string()).expect("hmm");
+            assert_eq!(
+                ret.chord_quality(),
+                ChordQuality::Seventh(SeventhType::MinorMajor)
+            );
+            assert_eq!(ret.triad_quality, TriadQuality::Minor);
+            assert_eq!(
+                ret.intervals,
+                vec![
+                    Interval::MinorThird,
+                    Interval::PerfectFifth,
+                    Interval::MajorSeventh
+                ]
+            );
+            assert_eq!(ret.notes, vec![Note::C, Note::Ds, Note::G, Note::B]);
+        }
+    }
+
+    #[test]
+    fn test_identify_from_name_capital_m_is_major_seventh() {
+        let ret = identify_from_name("CM7".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Major)
+        );
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_minor_major_seventh() {
+        let root = Note::C;
+        let notes = vec![Note::C, Note::Ds, Note::G, Note::B];
+
+        let ret = identify_from_root_and_notes(&root, &notes);
+
+        assert_eq!(ret.name, "Cm(maj7)");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::MinorMajor)
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_sus_defaults_to_sus4() {
+        let ret = identify_from_name("Gsus".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Suspended(SuspendedType::Sus4)
+        );
+        assert_eq!(ret.notes, vec![Note::G, Note::C, Note::D]);
+
+        let ret = identify_from_name("G7sus".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus4))
+        );
+        assert_eq!(ret.notes, vec![Note::G, Note::C, Note::D, Note::F]);
+    }
+
+    #[test]
+    fn test_identify_from_name_g9sus4() {
+        let ret = identify_from_name("G9sus4".to_string()).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus4))
+        );
+        assert_eq!(ret.notes, vec![Note::G, Note::C, Note::D, Note::F, Note::A]);
+    }
+
+    // the sus4 is the 11th so it isn't stacked a second time
+    // the sus is the quality wherever it's written, the maj only picks the 7th
+    #[test]
+    fn test_identify_from_name_maj9sus4() {
+        let ret = identify_from_name("Cmaj9sus4".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::F, Note::G, Note::B, Note::D]);
+    }
+
+    #[test]
+    fn test_identify_from_name_extensions() {
+        let ret = identify_from_name("G13(#11)".to_string()).expect("hmm");
+        assert_eq!(ret.extensions.to_string(), "7 9 #11 13");
+
+        let ret = identify_from_name("Cadd9(no5)".to_string()).expect("hmm");
+        assert_eq!(ret.extensions.adds, vec![Interval::MajorNinth]);
+        assert_eq!(ret.extensions.omissions, vec![Omission::Fifth]);
+
+        let ret = identify_from_name("Gadd7".to_string()).expect("hmm");
+        assert_eq!(ret.extensions.seventh, Some(SeventhKind::Minor));
+    }
+
+    #[test]
+    fn test_identify_from_name_7alt() {
+        let ret = identify_from_name("G7alt".to_string()).expect("hmm");
+
+        assert_eq!(ret.name, "G7alt");
+        assert_eq!(
+            ret.notes,
+            vec![
+                Note::G,
+                Note::B,
+                Note::Cs,
+                Note::F,
+                Note::Gs,
+                Note::As,
+                Note::Ds
+            ]
+        );
+    }
+
+    //
+    // identify_from_name_as
+    //
+
+    #[test]
+    fn test_identify_from_name_as_implied_stacks_tensions() {
+        let ret = identify_from_name_as(
+            "G13".to_string(),
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(
+            ret.notes,
+            vec![Note::G, Note::B, Note::D, Note::F, Note::A, Note::E]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_name_as_literal() {
+        let literal = |name: &str| {
+            identify_from_name_as(
+                name.to_string(),
+                &Aliases::default(),
+                ParseOptions {
+                    tensions: TensionReading::Literal,
+                    ..ParseOptions::default()
+                },
+            )
+            .expect("hmm")
+            .notes
+        };
+
+        assert_eq!(
+            literal("G13"),
+            vec![Note::G, Note::B, Note::D, Note::F, Note::E]
+        );
+        assert_eq!(
+            literal("Gm11"),
+            vec![Note::G, Note::As, Note::D, Note::F, Note::C]
+        );
+        assert_eq!(
+            literal("G13add9"),
+            vec![Note::G, Note::B, Note::D, Note::F, Note::A, Note::E]
+        );
+        // a 9 has nothing under it to leave out
+        assert_eq!(
+            literal("G9"),
+            vec![Note::G, Note::B, Note::D, Note::F, Note::A]
+        );
+    }
+
+    // a 13 over a major 3rd leaves the 11th out
+    #[test]
+    fn test_identify_from_name_c13_has_no_eleventh() {
+        let ret = identify_from_name("C13".to_string()).expect("hmm");
+        assert_eq!(
+            ret.notes,
+            vec![Note::C, Note::E, Note::G, Note::As, Note::D, Note::A]
+        );
+
+        let ret = identify_from_name("Cm13".to_string()).expect("hmm");
+        assert!(ret.notes.contains(&Note::F));
+    }
+
+    #[test]
+    fn test_identify_from_name_g13sus4() {
+        let ret = identify_from_name("G13sus4".to_string()).expect("hmm");
+        assert_eq!(
+            ret.intervals,
+            vec![
+                Interval::PerfectFourth,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh,
+                Interval::MajorNinth,
+                Interval::MajorThirteenth,
+            ]
+        );
+        assert_eq!(
+            ret.notes,
+            vec![Note::G, Note::C, Note::D, Note::F, Note::A, Note::E]
+        );
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_sus_extensions() {
+        let ret =
+            identify_from_root_and_notes(&Note::G, &[Note::G, Note::C, Note::D, Note::F, Note::A]);
+        assert_eq!(ret.name, "G9sus4");
+
+        // voiced with the 4th up top as an 11th
+        let ret =
+            identify_from_root_and_notes(&Note::G, &[Note::G, Note::D, Note::F, Note::A, Note::C]);
+        assert_eq!(ret.name, "G9sus4");
+
+        let ret = identify_from_root_and_notes(
+            &Note::G,
+            &[Note::G, Note::C, Note::D, Note::F, Note::A, Note::E],
+        );
+        assert_eq!(ret.name, "G13sus4");
+    }
+
+    #[test]
+    fn test_identify_from_root_and_notes_quartal() {
+        let ret = identify_from_root_and_notes(&Note::D, &[Note::D, Note::G, Note::C, Note::F]);
+        assert_eq!(ret.name, "D quartal 4");
+        assert_eq!(ret.structure, ChordStructure::Quartal);
+        assert_eq!(ret.bass, None);
+    }
+
+    #[test]
+    fn test_identify_from_name_stacked() {
+        let ret = identify_from_name("D quartal 4".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::D, Note::G, Note::C, Note::F]);
+        assert_eq!(ret.structure, ChordStructure::Quartal);
+
+        let ret = identify_from_name("C quintal".to_string()).expect("hmm");
+        assert_eq!(ret.notes, vec![Note::C, Note::G, Note::D]);
+
+        assert!(identify_from_name("C quartal 2".to_string()).is_err());
+        assert!(identify_from_name("C quartal/E".to_string()).is_err());
+    }
+
+    // only the bottom note roots the stack, the rest read it as some tertian slash chord
+    #[test]
+    fn test_identify_from_notes_quartal_and_quintal() {
+        let ret = identify_from_notes(&[Note::D, Note::G, Note::C, Note::F]);
+        assert_eq!(ret[0].name, "D quartal 4");
+        assert_eq!(
+            ret.iter()
+                .filter(|c| c.structure != ChordStructure::Tertian)
+                .count(),
+            1
+        );
+
+        let ret = identify_from_notes(&[Note::C, Note::G, Note::D, Note::A, Note::E]);
+        assert_eq!(ret[0].name, "C quintal 5");
+        assert_eq!(ret[0].structure, ChordStructure::Quintal);
+    }
+
+    // the same notes in close position are tertian again, C D G is Csus2
+    #[test]
+    fn test_identify_from_root_and_notes_stack_needs_stacked_voicing() {
+        let ret = identify_from_root_and_notes(&Note::C, &[Note::C, Note::D, Note::G]);
+        assert_eq!(ret.name, "Csus2");
+        assert_eq!(ret.structure, ChordStructure::Tertian);
+    }
+
+    //
+    // identify_from_name_with_aliases
+    //
+
+    #[test]
+    fn test_identify_from_name_with_aliases() {
+        let aliases = Aliases::from_str("^ = maj7\nh7 = m7b5\n").expect("hmm");
+
+        let ret = identify_from_name_with_aliases("C^".to_string(), &aliases).expect("hmm");
+        assert_eq!(ret.name, "C^");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::Major)
+        );
+
+        let ret = identify_from_name_with_aliases("Bh7".to_string(), &aliases).expect("hmm");
+        assert_eq!(
+            ret.chord_quality(),
+            ChordQuality::Seventh(SeventhType::HalfDiminished)
+        );
+        assert_eq!(ret.notes, vec![Note::B, Note::D, Note::F, Note::A]);
+    }
+
+    //
+    // score_chord
+    //
+
+    #[test]
+    fn test_score_chord_prefers_root_position_and_plain_names() {
+        let plain = identify_from_name("C".to_string()).expect("hmm");
+        let inverted = identify_from_name("C/E".to_string()).expect("hmm");
+        let slash = identify_from_name("C/D".to_string()).expect("hmm");
+        let added = identify_from_name("Cadd9".to_string()).expect("hmm");
+
+        assert_eq!(score_chord(&plain), 100);
+        assert!(score_chord(&plain) > score_chord(&inverted));
+        assert!(score_chord(&inverted) > score_chord(&slash));
+        assert!(score_chord(&plain) > score_chord(&added));
+    }
+
+    #[test]
+    fn test_score_chord_black_key_roots() {
+        let score = |name: &str| score_chord(&identify_from_name(name.to_string()).expect("hmm"));
+
+        assert_eq!(score("Bb"), 100);
+        assert_eq!(score("F#m7"), score("Gm7"));
+        assert_eq!(score("Eb7b9"), score("E7b9"));
+    }
+
+    //
+    // identify_from_pitches
+    //
+
+    #[test]
+    fn test_identify_from_pitches() {
+        let name = |pitches: &[u8]| identify_from_pitches(pitches)[0].name.clone();
+
+        assert_eq!(name(&[60, 64, 67, 70]), "C7");
+        assert_eq!(name(&[60, 64, 67, 74]), "Cadd9");
+        assert_eq!(name(&[60, 74, 76, 79]), "Cadd9");
+        assert_eq!(name(&[60, 62, 64, 67]), "Cadd2");
+        // the lowest pitch is the bass however they're given
+        assert_eq!(name(&[64, 67, 72, 52]), "C/E");
+    }
+
+    //
+    // alternatives
+    //
+
+    #[test]
+    fn test_identify_from_notes_ranks_readings() {
+        // A minor 7 given from the C, C6 in root position beats Am7/C
+        let ret = identify_from_notes(&[Note::C, Note::E, Note::G, Note::A]);
+
+        assert_eq!(ret[0].name, "C6");
+        assert_eq!(
+            ret[0].alternatives,
+            vec![Alternative {
+                name: "Am7/C".to_string(),
+                score: 85
+            }]
+        );
+        assert_eq!(ret[1].alternatives[0].name, "C6");
+    }
+
+    #[test]
+    fn test_identify_from_notes_repeated_note() {
+        let ret = identify_from_notes(&[Note::E, Note::B, Note::E]);
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].name, "E5");
+        assert!(ret[0].alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_identify_from_notes_drops_unlikely_readings() {
+        let ret = identify_from_notes(&[Note::C, Note::E, Note::As]);
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].name, "C7(no5)");
+
+        // the best reading stays even when it's unlikely itself
+        let ret = identify_from_notes(&[Note::C, Note::D, Note::E]);
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(enumerate_chord_names(&[Note::C, Note::D, Note::E]).len(), 1);
+    }
+
+    #[test]
+    fn test_identify_from_name_carries_alternatives() {
+        let ret = identify_from_name("Am7".to_string()).expect("hmm");
+
+        assert_eq!(ret.alternatives.len(), 1);
+        assert_eq!(ret.alternatives[0].name, "C6/A");
+
+        // the name itself is never one of its own alternatives
+        let ret = identify_from_name("Cm(maj7)".to_string()).expect("hmm");
+        assert!(!ret.alternatives.is_empty());
+        assert!(ret.alternatives.iter().all(|a| a.name != ret.name));
+        assert!(ret
+            .alternatives
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    // chords without a ChordQuality of their own still get a name from the naming engine
+    #[test]
+    fn test_identify_from_root_and_notes_names_beyond_quality() {
+        let ret = identify_from_root_and_notes(&Note::C, &[Note::C, Note::G, Note::As]);
+        assert_eq!(ret.name, "C7(no3)");
+
+        let ret = identify_from_root_and_notes(
+            &Note::C,
+            &[Note::C, Note::E, Note::G, Note::As, Note::Cs],
+        );
+        assert_eq!(ret.name, "C7(b9)");
+
+        let ret = identify_from_root_and_notes(&Note::C, &[Note::C, Note::E, Note::Gs, Note::B]);
+        assert_eq!(ret.name, "Cmaj7(#5)");
+    }
+
+    //
+    // enumerate_chord_names
+    //
+
+    #[test]
+    fn test_enumerate_chord_names_every_spelling() {
+        let ret = enumerate_chord_names(&[Note::A, Note::C, Note::E, Note::G]);
+        let names: Vec<&str> = ret.iter().map(|n| n.name.as_str()).collect();
+
+        assert_eq!(names[..3], ["Am7", "C6/A", "C6"]);
+        assert!(ret.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn test_enumerate_chord_names_no_repeats() {
+        // C is the bass so its root position reading is the one played
+        let ret = enumerate_chord_names(&[Note::C, Note::E, Note::G, Note::C]);
+
+        assert_eq!(ret[0].name, "C");
+        assert_eq!(ret.iter().filter(|n| n.name == "C").count(), 1);
+    }
+
+    //
+    // verify_symbol_roundtrip
+    //
+
+    // every combination of chord tones the naming engine will put a name to, on every root
+    // has to parse back from that name to the same notes
+    #[test]
+    fn test_verify_symbol_roundtrip_every_named_chord() {
+        use Interval::*;
+
+        let thirds = [
+            None,
+            Some(MinorThird),
+            Some(MajorThird),
+            Some(PerfectFourth),
+            Some(MajorSecond),
+        ];
+        let fifths = [
+            None,
+            Some(DiminishedFifth),
+            Some(PerfectFifth),
+            Some(AugmentedFifth),
+        ];
+        let sixths = [None, Some(MajorSixth)];
+        let sevenths = [
+            None,
+            Some(DiminishedSeventh),
+            Some(MinorSeventh),
+            Some(MajorSeventh),
+        ];
+        let ninths = [
+            None,
+            Some(MinorNinth),
+            Some(MajorNinth),
+            Some(AugmentedNinth),
+        ];
+        let elevenths = [None, Some(PerfectEleventh), Some(AugmentedEleventh)];
+        let thirteenths = [None, Some(MinorThirteenth), Some(MajorThirteenth)];
+
+        let mut failures: Vec<String> = vec![];
+        let mut checked = 0;
+
+        for (third, fifth, sixth, seventh, ninth, eleventh, thirteenth) in itertools::iproduct!(
+            thirds,
+            fifths,
+            sixths,
+            sevenths,
+            ninths,
+            elevenths,
+            thirteenths
+        ) {
+            let intervals: Vec<Interval> =
+                [third, fifth, sixth, seventh, ninth, eleventh, thirteenth]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+            // spelling a chord is the same work whatever the root, every root gets its own test below
+            for root in [Note::C] {
+                let notes = get_notes_from_root_and_intervals(&root, &intervals).expect("hmm");
+                let chord = identify_from_root_and_notes(&root, &notes);
+                if chord.name == AMBIGUOUS_NAME || notes.iter().unique().count() != notes.len() {
+                    continue;
+                }
+
+                checked += 1;
+                if let Err(e) = verify_symbol_roundtrip(&chord) {
+                    failures.push(format!("{:?}", e));
+                }
+            }
+        }
+
+        assert!(checked > 0);
+        assert!(
+            failures.is_empty(),
+            "{} of {} failed: {:#?}",
+            failures.len(),
+            checked,
+            failures
+        );
+    }
+
+    #[test]
+    fn test_verify_symbol_roundtrip_every_root() {
+        use crate::theory::interval::OCTAVE;
+
+        for root in OCTAVE.iter() {
+            for notes in [
+                get_stacked_notes(root, ChordStructure::Quartal, 4),
+                identify_from_name(format!("{}m7b5", root))
+                    .expect("hmm")
+                    .notes,
+                identify_from_name(format!("{}13(#11)", root))
+                    .expect("hmm")
+                    .notes,
+            ] {
+                let chord = identify_from_root_and_notes(root, &notes);
+                verify_symbol_roundtrip(&chord).expect("hmm");
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_symbol_roundtrip_mismatch() {
+        let mut chord = identify_from_name("C7".to_string()).expect("hmm");
+        chord.name = "Cmaj7".to_string();
+
+        assert!(verify_symbol_roundtrip(&chord).is_err());
+    }
+
+    //
+    // identify_polychord
+    //
+
+    #[test]
+    fn test_identify_polychord_both_syntaxes() {
+        for name in ["D|C", "D over C", "D over C triad"] {
+            let ret = identify_polychord(
+                name.to_string(),
+                &Aliases::default(),
+                ParseOptions::default(),
+            )
+            .expect("hmm");
+
+            assert_eq!(ret.name, "D|C");
+            assert_eq!(ret.upper.notes, vec![Note::D, Note::Fs, Note::A]);
+            assert_eq!(ret.lower.notes, vec![Note::C, Note::E, Note::G]);
+        }
+    }
+
+    #[test]
+    fn test_identify_polychord_slash_is_not_a_polychord() {
+        assert_eq!(split_polychord("D/C"), None);
+        assert!(identify_polychord(
+            "D/C".to_string(),
+            &Aliases::default(),
+            ParseOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_identify_polychord_halves_can_be_anything() {
+        let ret = identify_polychord(
+            "Ebmaj7 | C7/G".to_string(),
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret.lower.bass, Some(Note::G));
+        assert_eq!(ret.notes[0], Note::G);
+        assert!(identify_polychord(
+            "D|H".to_string(),
+            &Aliases::default(),
+            ParseOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_identify_from_name_flat_root() {
+        let ret = identify_from_name("Ebm".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::Ds);
+        assert_eq!(ret.notes, vec![Note::Ds, Note::Fs, Note::As]);
+
+        // a b after the root is the root's flat, not a b13
+        let ret = identify_from_name("Bb13".to_string()).expect("hmm");
+        assert_eq!(ret.root, Note::As);
+        assert_eq!(ret.extensions.to_string(), "7 9 13");
+    }
 }
@@ -0,0 +1,339 @@
+use crate::{
+    parser::chord_parser::ParseMode,
+    theory::{
+        chord::{ChordStructure, SuspendedType},
+        error::{ChordParseError, Span},
+    },
+};
+
+// what comes after the root of a chord name, read a character at a time into the pieces the parser works with
+// every character has to be part of something it knows, so Gxyz7 is an error rather than a G7
+// brackets, commas and spaces only separate things, C7(b9, #11) is C7b9#11
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Token {
+    // m, or − straight after the root
+    Minor,
+    // maj
+    Major,
+    // a capital M, only ever a major 7th as in CM7 or CmM7
+    MajorSeventh,
+    // Δ, a major 7th that doesn't need the 7 written
+    Delta,
+    // dim or °
+    Diminished,
+    // ø, the half diminished m7b5
+    HalfDiminished,
+    // aug, or + straight after the root
+    Augmented,
+    // sus2, sus4, or sus on its own for a sus4
+    Sus(SuspendedType),
+    // a bare number, 5, 6, 7, 9, 11 or 13
+    Degree(u8),
+    // b9, #11, with ♭ ♯ and after the quality − + read as b and #
+    Alteration(char, u8),
+    // add9, add#11
+    Add(Option<char>, u8),
+    // no3, no5
+    Omit(u8),
+    // quartal or quintal with how many notes, quartal5
+    Stacked(ChordStructure, Option<usize>),
+    // alt, the player's choice of altered 5ths and 9ths
+    Altered,
+}
+
+// a token and where it was, as byte offsets into what was tokenized
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Spanned {
+    pub token: Token,
+    pub start: usize,
+    pub end: usize,
+}
+
+// the words, longest first wherever one starts another, sus2 before sus and maj before m
+const WORDS: [(&str, Token); 13] = [
+    ("alt", Token::Altered),
+    ("maj", Token::Major),
+    ("min", Token::Minor),
+    ("dim", Token::Diminished),
+    ("aug", Token::Augmented),
+    ("sus2", Token::Sus(SuspendedType::Sus2)),
+    ("sus4", Token::Sus(SuspendedType::Sus4)),
+    ("sus", Token::Sus(SuspendedType::Sus4)),
+    ("m", Token::Minor),
+    ("M", Token::MajorSeventh),
+    ("Δ", Token::Delta),
+    ("°", Token::Diminished),
+    ("ø", Token::HalfDiminished),
+];
+
+// a chord degree at the front of the input and how many bytes it took, 11 and 13 before 1
+fn degree(input: &str) -> Option<(u8, usize)> {
+    ["13", "11", "2", "3", "4", "5", "6", "7", "9"]
+        .into_iter()
+        .find(|d| input.starts_with(d))
+        .and_then(|d| Some((d.parse().ok()?, d.len())))
+}
+
+// the sharp or flat an accidental is, the music symbols for them as well as # and b
+// after the root + and − are sharps and flats too, C7+5 and C7−9
+fn accidental(c: char, first: bool) -> Option<char> {
+    match c {
+        'b' | '♭' => Some('b'),
+        '#' | '♯' => Some('#'),
+        '+' if !first => Some('#'),
+        '−' if !first => Some('b'),
+        _ => None,
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '(' | ')' | ',' | ' ')
+}
+
+// from a character it can't read up to the next one it can, the xyz in Gxyz7
+fn unrecognised(input: &str, at: usize) -> ChordParseError {
+    let end = input[at..]
+        .char_indices()
+        .skip(1)
+        .map(|(i, _)| at + i)
+        .find(|i| next_token(input, *i, false).is_some())
+        .unwrap_or(input.len());
+
+    let span = Span::new(input, at, end);
+    ChordParseError::UnexpectedToken(
+        format!("couldn't make sense of {}", span.token()),
+        span,
+        vec![],
+    )
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Spanned>, ChordParseError> {
+    tokenize_as(input, ParseMode::Strict)
+}
+
+// leniently whatever it can't make sense of is stepped over a character at a time, Gxyz7 is a G7
+pub fn tokenize_as(input: &str, mode: ParseMode) -> Result<Vec<Spanned>, ChordParseError> {
+    let mut tokens: Vec<Spanned> = vec![];
+    let mut at = 0;
+
+    while let Some(c) = input[at..].chars().next() {
+        // the slash of a 6/9 is just between the two, the same as C69
+        let six_nine = c == '/' && input[..at].ends_with('6') && input[at + 1..].starts_with('9');
+        if is_separator(c) || six_nine {
+            at += c.len_utf8();
+            continue;
+        }
+
+        let (token, len) = match (next_token(input, at, tokens.is_empty()), mode) {
+            (Some(next), _) => next,
+            (None, ParseMode::Strict) => return Err(unrecognised(input, at)),
+            (None, ParseMode::Lenient) => {
+                at += c.len_utf8();
+                continue;
+            }
+        };
+
+        tokens.push(Spanned {
+            token,
+            start: at,
+            end: at + len,
+        });
+        at += len;
+    }
+
+    Ok(tokens)
+}
+
+// the token starting at a byte offset and how many bytes it takes up,
+// first for the one straight after the root where + and − are aug and minor, C+ and C−7
+fn next_token(input: &str, at: usize, first: bool) -> Option<(Token, usize)> {
+    let rest = &input[at..];
+
+    let next = if first && rest.starts_with('+') {
+        (Token::Augmented, '+'.len_utf8())
+    } else if first && rest.starts_with('−') {
+        (Token::Minor, '−'.len_utf8())
+    } else if let Some(stacked) = ["quartal", "quintal"]
+        .into_iter()
+        .find(|s| rest.starts_with(s))
+    {
+        let structure = match stacked {
+            "quartal" => ChordStructure::Quartal,
+            _ => ChordStructure::Quintal,
+        };
+        // the count can be spaced out from it, D quartal 4
+        let after = &rest[stacked.len()..];
+        let spaces = after.len() - after.trim_start_matches(' ').len();
+        let digits = after[spaces..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        match digits {
+            0 => (Token::Stacked(structure, None), stacked.len()),
+            _ => (
+                Token::Stacked(
+                    structure,
+                    Some(after[spaces..spaces + digits].parse().ok()?),
+                ),
+                stacked.len() + spaces + digits,
+            ),
+        }
+    } else if let Some(after) = rest.strip_prefix("add") {
+        let written = after
+            .chars()
+            .next()
+            .filter(|c| accidental(*c, false).is_some());
+        let skip = written.map_or(0, |a| a.len_utf8());
+        let (number, len) = degree(&after[skip..])?;
+        (
+            Token::Add(written.and_then(|c| accidental(c, false)), number),
+            3 + skip + len,
+        )
+    } else if let Some(after) = rest.strip_prefix("no") {
+        let (number, len) = degree(after)?;
+        (Token::Omit(number), 2 + len)
+    } else if let Some((word, token)) = WORDS.iter().find(|(w, _)| rest.starts_with(w)) {
+        (token.clone(), word.len())
+    } else if let Some((c, sign)) = rest
+        .chars()
+        .next()
+        .and_then(|c| Some((c, accidental(c, first)?)))
+    {
+        let (number, len) = degree(&rest[c.len_utf8()..])?;
+        (Token::Alteration(sign, number), c.len_utf8() + len)
+    } else {
+        degree(rest).map(|(number, len)| (Token::Degree(number), len))?
+    };
+
+    Some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Token> {
+        tokenize(input)
+            .expect("hmm")
+            .into_iter()
+            .map(|s| s.token)
+            .collect()
+    }
+
+    //
+    // tokenize
+    //
+
+    #[test]
+    fn test_tokenize_quality_and_degree() {
+        assert_eq!(tokens("maj7"), vec![Token::Major, Token::Degree(7)]);
+        assert_eq!(
+            tokens("mM7"),
+            vec![Token::Minor, Token::MajorSeventh, Token::Degree(7)]
+        );
+        assert_eq!(tokens("13"), vec![Token::Degree(13)]);
+    }
+
+    #[test]
+    fn test_tokenize_modifiers() {
+        assert_eq!(
+            tokens("7sus4b9add#11no5"),
+            vec![
+                Token::Degree(7),
+                Token::Sus(SuspendedType::Sus4),
+                Token::Alteration('b', 9),
+                Token::Add(Some('#'), 11),
+                Token::Omit(5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_stacked() {
+        assert_eq!(
+            tokens("quartal5"),
+            vec![Token::Stacked(ChordStructure::Quartal, Some(5))]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_spans() {
+        let ret = tokenize("m7b5").expect("hmm");
+
+        assert_eq!((ret[2].start, ret[2].end), (2, 4));
+    }
+
+    #[test]
+    fn test_tokenize_as_lenient_skips_unknown() {
+        let ret: Vec<Token> = tokenize_as("xyz7+", ParseMode::Lenient)
+            .expect("hmm")
+            .into_iter()
+            .map(|s| s.token)
+            .collect();
+
+        assert_eq!(ret, vec![Token::Degree(7)]);
+    }
+
+    #[test]
+    fn test_tokenize_separators() {
+        assert_eq!(
+            tokens("7(b9, #11)"),
+            vec![
+                Token::Degree(7),
+                Token::Alteration('b', 9),
+                Token::Alteration('#', 11),
+            ]
+        );
+        assert_eq!(
+            tokens(" quartal 4"),
+            vec![Token::Stacked(ChordStructure::Quartal, Some(4))]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_music_symbols() {
+        assert_eq!(tokens("Δ7"), vec![Token::Delta, Token::Degree(7)]);
+        assert_eq!(tokens("°7"), vec![Token::Diminished, Token::Degree(7)]);
+        assert_eq!(tokens("ø"), vec![Token::HalfDiminished]);
+        assert_eq!(
+            tokens("7♭9add♯11"),
+            vec![
+                Token::Degree(7),
+                Token::Alteration('b', 9),
+                Token::Add(Some('#'), 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_plus_and_minus() {
+        assert_eq!(tokens("+7"), vec![Token::Augmented, Token::Degree(7)]);
+        assert_eq!(tokens("−7"), vec![Token::Minor, Token::Degree(7)]);
+        assert_eq!(
+            tokens("7+5−9"),
+            vec![
+                Token::Degree(7),
+                Token::Alteration('#', 5),
+                Token::Alteration('b', 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unknown_span() {
+        match tokenize("xyz7") {
+            Err(ChordParseError::UnexpectedToken(_, span, _)) => {
+                assert_eq!((span.start, span.end), (0, 3))
+            }
+            ret => panic!("expected an unexpected token, got {:?}", ret),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_unknown() {
+        assert!(tokenize("xyz7").is_err());
+        assert!(tokenize("7+").is_err());
+        assert!(tokenize("add").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    aliases::Aliases,
+    parser::chord_parser::{identify_from_name_as, ParseOptions},
+    theory::{
+        chord::Chord, error::ChordParseError, key::Key, progression::Progression,
+        transpose::transpose_name,
+    },
+};
+
+// songs written out in ChordPro, lyrics with the chords in brackets where they change
+// and directives in braces for everything else
+// {title: Amazing Grace}
+// {key: G}
+// {start_of_verse}
+// A[G]mazing [G7]grace, how [C]sweet the [G]sound
+// {end_of_verse}
+// each line with chords on it is taken as a bar, and the verses, choruses and bridges as sections
+// https://www.chordpro.org/chordpro/chordpro-introduction/
+
+#[derive(Debug)]
+pub enum ChordProError {
+    Io(String),
+    Chord(ChordParseError),
+}
+
+impl fmt::Display for ChordProError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChordProError::Io(e) => write!(f, "couldn't read the file: {}", e),
+            ChordProError::Chord(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ChordParseError> for ChordProError {
+    fn from(e: ChordParseError) -> Self {
+        ChordProError::Chord(e)
+    }
+}
+
+// a verse, chorus or bridge, named for the label on its directive if it has one ({start_of_verse: Verse 2})
+// None for the chords outside of any
+#[derive(Debug, Clone)]
+pub struct SongSection {
+    pub name: Option<String>,
+    pub progression: Progression,
+}
+
+#[derive(Debug, Clone)]
+pub struct Song {
+    pub title: Option<String>,
+    // as the {key} directive has it, None without one
+    pub key: Option<Key>,
+    pub sections: Vec<SongSection>,
+}
+
+impl Song {
+    pub fn load(
+        path: &Path,
+        aliases: &Aliases,
+        options: ParseOptions,
+    ) -> Result<Song, ChordProError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(identify_chordpro(&contents, aliases, options)?),
+            Err(e) => Err(ChordProError::Io(e.to_string())),
+        }
+    }
+
+    // the whole song as one progression, the bars counting on through the sections
+    pub fn progression(&self) -> Progression {
+        let mut chords: Vec<Chord> = vec![];
+        let mut bars: Vec<usize> = vec![];
+        let mut beats: Vec<usize> = vec![];
+
+        for section in &self.sections {
+            let first = bars.last().map_or(0, |b| b + 1);
+            chords.extend(section.progression.chords.iter().cloned());
+            bars.extend(section.progression.bars.iter().map(|b| first + b));
+            beats.extend(section.progression.beats.iter().copied());
+        }
+
+        Progression {
+            chords,
+            bars,
+            beats,
+        }
+    }
+
+    // every different chord once, in the order they first come, the set to print diagrams for
+    pub fn chord_set(&self) -> Vec<&Chord> {
+        self.sections
+            .iter()
+            .flat_map(|s| s.progression.chords.iter())
+            .unique_by(|c| c.name.clone())
+            .collect()
+    }
+}
+
+// the name and value of a directive line, {title: Song} is (title, Some(Song)) and {soc} is (soc, None)
+fn directive(line: &str) -> Option<(&str, Option<&str>)> {
+    let inner = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+
+    Some(match inner.split_once(':') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (inner.trim(), None),
+    })
+}
+
+// the section a directive starts, start_of_chorus or its short soc, None for anything else
+fn section_start(name: &str) -> Option<&str> {
+    match name {
+        "soc" => Some("chorus"),
+        "sov" => Some("verse"),
+        "sob" => Some("bridge"),
+        name => name.strip_prefix("start_of_"),
+    }
+}
+
+fn is_section_end(name: &str) -> bool {
+    matches!(name, "eoc" | "eov" | "eob") || name.starts_with("end_of_")
+}
+
+// the chords in brackets along a line of lyrics, with where each one starts in the line
+// [*Riff] is an annotation rather than a chord and N.C. is no chord at all
+fn bracketed(line: &str) -> Vec<(usize, &str)> {
+    let mut chords = vec![];
+    let mut rest = line;
+
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']') else {
+            break;
+        };
+        let name = &rest[open + 1..open + close];
+        let start = line.len() - rest.len() + open + 1;
+        if !name.starts_with('*') && !matches!(name.trim(), "" | "N.C." | "NC") {
+            chords.push((start, name));
+        }
+        rest = &rest[open + close + 1..];
+    }
+
+    chords
+}
+
+pub fn identify_chordpro(
+    input: &str,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Song, ChordParseError> {
+    let mut title = None;
+    let mut key = None;
+    let mut sections: Vec<SongSection> = vec![];
+    let mut current = SongSection {
+        name: None,
+        progression: Progression::new(vec![]),
+    };
+
+    // a section only makes it into the song if it has chords in it
+    let mut finish = |section: SongSection| {
+        if !section.progression.is_empty() {
+            sections.push(section);
+        }
+    };
+
+    for line in input.lines() {
+        if let Some((name, value)) = directive(line) {
+            match (name, value) {
+                ("title" | "t", Some(value)) => title = Some(value.to_string()),
+                ("key", Some(value)) => {
+                    key = Some(Key::from_str(value).map_err(|e| {
+                        ChordParseError::InvalidChordName(format!("bad key directive: {}", e))
+                    })?)
+                }
+                (name, value) if section_start(name).is_some() => {
+                    let name = value.filter(|v| !v.is_empty()).or(section_start(name));
+                    finish(std::mem::replace(
+                        &mut current,
+                        SongSection {
+                            name: name.map(String::from),
+                            progression: Progression::new(vec![]),
+                        },
+                    ));
+                }
+                (name, _) if is_section_end(name) => {
+                    finish(std::mem::replace(
+                        &mut current,
+                        SongSection {
+                            name: None,
+                            progression: Progression::new(vec![]),
+                        },
+                    ));
+                }
+                // everything else is about how it looks on the page
+                _ => {}
+            }
+            continue;
+        }
+
+        // # starts a comment line
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let names = bracketed(line);
+        if names.is_empty() {
+            continue;
+        }
+
+        let bar = current.progression.bar_count();
+        for (start, name) in names {
+            // an error points at the chord in its line
+            let chord = identify_from_name_as(name.to_string(), aliases, options)
+                .map_err(|e| e.within(line, start))?;
+            current.progression.chords.push(chord);
+            current.progression.bars.push(bar);
+            current.progression.beats.push(1);
+        }
+    }
+
+    finish(current);
+
+    Ok(Song {
+        title,
+        key,
+        sections,
+    })
+}
+
+// the same file with every chord moved by some semitones and the key directive with them,
+// the lyrics and everything else left just as they were
+pub fn transpose_chordpro(input: &str, semitones: i32) -> String {
+    input
+        .lines()
+        .map(|line| match directive(line) {
+            Some(("key", Some(value))) => format!("{{key: {}}}", transpose_name(value, semitones)),
+            Some(_) => line.to_string(),
+            None => {
+                let mut moved = String::new();
+                let mut last = 0;
+                for (start, name) in bracketed(line) {
+                    moved.push_str(&line[last..start]);
+                    moved.push_str(&transpose_name(name, semitones));
+                    last = start + name.len();
+                }
+                moved.push_str(&line[last..]);
+                moved
+            }
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::note::Note;
+
+    const SONG: &str = "{title: Amazing Grace}
+{key: G}
+# from the hymnal
+{start_of_verse}
+A[G]mazing [G7]grace, how [C]sweet the [G]sound
+That [G]saved a wretch like [D]me [D7]
+{end_of_verse}
+{start_of_chorus: Last time}
+[*slowly]I [G]once was [Em]lost, but [G/D]now am [D]found [N.C.]
+{end_of_chorus}";
+
+    fn song() -> Song {
+        identify_chordpro(SONG, &Aliases::default(), ParseOptions::default()).expect("hmm")
+    }
+
+    //
+    // identify_chordpro
+    //
+
+    #[test]
+    fn test_identify_chordpro_directives() {
+        let ret = song();
+
+        assert_eq!(ret.title.as_deref(), Some("Amazing Grace"));
+        assert_eq!(ret.key, Some(Key::major(Note::G)));
+    }
+
+    #[test]
+    fn test_identify_chordpro_sections() {
+        let ret = song();
+
+        assert_eq!(
+            ret.sections
+                .iter()
+                .map(|s| s.name.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("verse"), Some("Last time")]
+        );
+        assert_eq!(
+            ret.sections[0].progression.to_string(),
+            "| G G7 C G | G D D7 |"
+        );
+        assert_eq!(ret.sections[1].progression.len(), 4);
+    }
+
+    #[test]
+    fn test_identify_chordpro_whole_song() {
+        let ret = song();
+
+        assert_eq!(ret.progression().bar_count(), 3);
+        assert_eq!(
+            ret.chord_set()
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["G", "G7", "C", "D", "D7", "Em", "G/D"]
+        );
+    }
+
+    #[test]
+    fn test_identify_chordpro_error_in_line() {
+        let ret = identify_chordpro(
+            "{title: Typo}\nsome [C]words [Gxyz7]here",
+            &Aliases::default(),
+            ParseOptions::default(),
+        );
+
+        match ret {
+            Err(e) => {
+                let span = e.span().expect("hmm");
+                assert_eq!(span.input, "some [C]words [Gxyz7]here");
+                assert_eq!(span.token(), "xyz");
+            }
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    //
+    // transpose_chordpro
+    //
+
+    #[test]
+    fn test_transpose_chordpro() {
+        let ret = transpose_chordpro("{key: G}\nA[G]mazing [D/F#]grace [*riff]", 2);
+
+        assert_eq!(ret, "{key: A}\nA[A]mazing [E/G#]grace [*riff]");
+    }
+
+    #[test]
+    fn test_transpose_chordpro_music_symbols() {
+        let ret = transpose_chordpro("[B♭Δ7]Fly me [E♭7♯9]to the moon", 2);
+
+        assert_eq!(ret, "[CΔ7]Fly me [F7♯9]to the moon");
+    }
+}
@@ -1 +1,7 @@
 pub mod chord_parser;
+pub mod chord_tokenizer;
+pub mod chordpro_parser;
+pub mod note_parser;
+pub mod progression_parser;
+pub mod roman_parser;
+pub mod suggest;
@@ -0,0 +1,111 @@
+use crate::theory::{
+    error::{NoteParseError, Span},
+    note::Note,
+};
+
+// the highest midi note, G9
+const MIDI_MAX: u8 = 127;
+
+// notes split by spaces or commas, C E G or C, E, G, in the order they're written
+// a note that doesn't read is an error pointing at it in the whole list
+pub fn identify_notes(input: &str) -> Result<Vec<Note>, NoteParseError> {
+    split_list(input)
+        .map(|(start, token)| Note::parse(token).map_err(|e| e.within(input, start)))
+        .collect()
+}
+
+// midi note numbers split the same way, 60 64 67 70 is C4 E4 G4 A#4, kept as pitches so the octaves aren't lost
+pub fn identify_midi_pitches(input: &str) -> Result<Vec<u8>, NoteParseError> {
+    split_list(input)
+        .map(|(start, token)| match token.parse::<u8>() {
+            Ok(pitch) if pitch <= MIDI_MAX => Ok(pitch),
+            _ => Err(NoteParseError::InvalidNoteStringValue(Span::new(
+                input,
+                start,
+                start + token.len(),
+            ))),
+        })
+        .collect()
+}
+
+// each part of a list between spaces and commas with where it starts
+fn split_list(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut parts = vec![];
+    let mut start = 0;
+
+    for (at, c) in input.char_indices().chain([(input.len(), ' ')]) {
+        if c.is_whitespace() || c == ',' {
+            if at > start {
+                parts.push((start, &input[start..at]));
+            }
+            start = at + c.len_utf8();
+        }
+    }
+
+    parts.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // identify_notes
+    //
+
+    #[test]
+    fn test_identify_notes_spaces() {
+        let ret = identify_notes("A# B  C").expect("hmm");
+
+        assert_eq!(ret, vec![Note::As, Note::B, Note::C]);
+    }
+
+    #[test]
+    fn test_identify_notes_commas() {
+        let ret = identify_notes("C,E, G ,Bb").expect("hmm");
+
+        assert_eq!(ret, vec![Note::C, Note::E, Note::G, Note::As]);
+    }
+
+    #[test]
+    fn test_identify_notes_empty() {
+        let ret = identify_notes("  ").expect("hmm");
+
+        assert!(ret.is_empty());
+    }
+
+    #[test]
+    fn test_identify_notes_bad_note() {
+        let ret = identify_notes("C E, Hb G");
+
+        match ret {
+            Err(e) => {
+                let span = e.span().expect("hmm");
+                assert_eq!(span.input, "C E, Hb G");
+                assert_eq!(span.token(), "Hb");
+            }
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    //
+    // identify_midi_pitches
+    //
+
+    #[test]
+    fn test_identify_midi_pitches() {
+        let ret = identify_midi_pitches("60 64, 67 70").expect("hmm");
+
+        assert_eq!(ret, vec![60, 64, 67, 70]);
+    }
+
+    #[test]
+    fn test_identify_midi_pitches_out_of_range() {
+        for (input, token) in [("60 128 67", "128"), ("60 -4", "-4"), ("60 E4", "E4")] {
+            match identify_midi_pitches(input) {
+                Err(e) => assert_eq!(e.span().expect("hmm").token(), token),
+                Ok(_) => panic!("expected {} not to parse", input),
+            }
+        }
+    }
+}
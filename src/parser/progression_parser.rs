@@ -0,0 +1,241 @@
+use crate::{
+    aliases::Aliases,
+    parser::chord_parser::{identify_from_name_as, ParseOptions},
+    theory::{chord::Chord, error::ChordParseError, progression::Progression},
+};
+
+// chords split by spaces or commas, with | between bars if there are any
+// the bar lines on the very ends are optional and an empty bar (| |) is skipped over
+// written out like a chart, | C . . . | Am7 D7 | % |, a dot is a beat the chord before carries on for,
+// so a bar of only dots (or dots before its first chord) holds the last chord over the bar line,
+// a bar of % is the bar before again, and a section between |: and :| is played twice
+// (a :| with no |: goes back to the start)
+pub fn identify_progression(
+    input: &str,
+    aliases: &Aliases,
+    options: ParseOptions,
+) -> Result<Progression, ChordParseError> {
+    // each chord with the beats it's written for
+    let mut written_bars: Vec<Vec<(Chord, usize)>> = vec![];
+    let mut repeat_from = 0;
+
+    for bar in input.split('|') {
+        let (starts_repeat, bar) = match bar.trim_start().strip_prefix(':') {
+            Some(bar) => (true, bar),
+            None => (false, bar),
+        };
+        let (ends_repeat, bar) = match bar.trim_end().strip_suffix(':') {
+            Some(bar) => (true, bar),
+            None => (false, bar),
+        };
+
+        if starts_repeat {
+            repeat_from = written_bars.len();
+        }
+
+        let names: Vec<&str> = bar
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        let mut chords: Vec<(Chord, usize)> = vec![];
+        // dots before the bar's first chord, the last chord held over
+        let mut held = 0;
+        for name in &names {
+            match (*name, chords.last_mut()) {
+                ("%", _) => {}
+                (".", Some((_, beats))) => *beats += 1,
+                (".", None) => held += 1,
+                _ => {
+                    // an error in one chord points at it in the whole progression
+                    let offset = name.as_ptr() as usize - input.as_ptr() as usize;
+                    let chord = identify_from_name_as(name.to_string(), aliases, options)
+                        .map_err(|e| e.within(input, offset))?;
+                    chords.push((chord, 1));
+                }
+            }
+        }
+
+        if names.contains(&"%") {
+            if !chords.is_empty() {
+                return Err(ChordParseError::InvalidChordName(format!(
+                    "a % repeats the whole bar, it can't go with chords in {}",
+                    bar.trim()
+                )));
+            }
+            match written_bars.last() {
+                Some(previous) => written_bars.push(previous.clone()),
+                None => {
+                    return Err(ChordParseError::InvalidChordName(
+                        "the first bar has nothing before it to repeat".to_string(),
+                    ))
+                }
+            }
+        } else {
+            if held > 0 {
+                match written_bars.iter().rev().find_map(|bar| bar.last()) {
+                    Some((last, _)) => chords.insert(0, (last.clone(), held)),
+                    None => {
+                        return Err(ChordParseError::InvalidChordName(
+                            "the first bar has no chord before it to hold".to_string(),
+                        ))
+                    }
+                }
+            }
+            // | | is nothing at all
+            if !chords.is_empty() {
+                written_bars.push(chords);
+            }
+        }
+
+        if ends_repeat {
+            let section = written_bars[repeat_from..].to_vec();
+            written_bars.extend(section);
+            repeat_from = written_bars.len();
+        }
+    }
+
+    let mut chords: Vec<Chord> = vec![];
+    let mut bars: Vec<usize> = vec![];
+    let mut beats: Vec<usize> = vec![];
+    for (bar, written) in written_bars.into_iter().enumerate() {
+        for (chord, held) in written {
+            chords.push(chord);
+            bars.push(bar);
+            beats.push(held);
+        }
+    }
+
+    if chords.is_empty() {
+        return Err(ChordParseError::InvalidChordName(
+            "no chords in the progression".to_string(),
+        ));
+    }
+
+    Ok(Progression {
+        chords,
+        bars,
+        beats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // identify_progression
+    //
+
+    #[test]
+    fn test_identify_progression_commas() {
+        let ret = identify_progression("C, Am, F, G", &Aliases::default(), ParseOptions::default())
+            .expect("hmm");
+
+        assert_eq!(ret.len(), 4);
+        assert_eq!(ret.chords[1].name, "Am");
+    }
+
+    #[test]
+    fn test_identify_progression_empty_bars() {
+        let ret = identify_progression(
+            "|| C | | G ||",
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret.bars, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_identify_progression_chart() {
+        let ret = identify_progression(
+            "| C . . . | Am7 D7 | % | G . |",
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret.to_string(), "| C . . . | Am7 D7 | Am7 D7 | G . |");
+        assert_eq!(ret.beats, vec![4, 1, 1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_identify_progression_beat_dots() {
+        let ret = identify_progression("C . G .", &Aliases::default(), ParseOptions::default())
+            .expect("hmm");
+
+        assert_eq!(ret.beats, vec![2, 2]);
+        assert_eq!(ret.to_string(), "C . G .");
+    }
+
+    #[test]
+    fn test_identify_progression_held_over() {
+        let ret = identify_progression(
+            "| C . F . | . . . . | . . G . |",
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        let names: Vec<&str> = ret.chords.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["C", "F", "F", "F", "G"]);
+        assert_eq!(ret.bars, vec![0, 0, 1, 2, 2]);
+        assert_eq!(ret.beats, vec![2, 2, 4, 2, 2]);
+    }
+
+    #[test]
+    fn test_identify_progression_repeat_signs() {
+        let ret = identify_progression(
+            "| C |: F | G :| C |",
+            &Aliases::default(),
+            ParseOptions::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(ret.to_string(), "| C | F | G | F | G | C |");
+
+        let ret = identify_progression("C | G :|", &Aliases::default(), ParseOptions::default())
+            .expect("hmm");
+
+        assert_eq!(ret.bar_count(), 4);
+    }
+
+    #[test]
+    fn test_identify_progression_bad_repeats() {
+        assert!(
+            identify_progression("| % | C |", &Aliases::default(), ParseOptions::default())
+                .is_err()
+        );
+        assert!(
+            identify_progression("| C | G % |", &Aliases::default(), ParseOptions::default())
+                .is_err()
+        );
+        assert!(
+            identify_progression("| . . | C |", &Aliases::default(), ParseOptions::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_identify_progression_error_span() {
+        let ret =
+            identify_progression("C | Am Gxyz7", &Aliases::default(), ParseOptions::default());
+
+        match ret {
+            Err(e) => assert_eq!(e.span().map(|s| s.token()), Some("xyz")),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_identify_progression_invalid() {
+        assert!(
+            identify_progression("C H G", &Aliases::default(), ParseOptions::default()).is_err()
+        );
+        assert!(
+            identify_progression(" | | ", &Aliases::default(), ParseOptions::default()).is_err()
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use regex::Regex;
+use std::{str::FromStr, sync::LazyLock};
+
+use crate::theory::{
+    chord::Chord, error::ChordParseError, key::Key, note::Note, pitch_class::PitchClass,
+};
+
+use super::chord_parser::identify_from_name;
+
+// chords written as Roman numerals in a key, the other way round from theory::roman_numeral
+// "V7 in C" is G7 and "ii-V-I in Bb" is Cm F A#, an accidental moves the degree of the key's own scale
+// so bVII in C major is A# and #vii in A minor is G#, the same as the analysis writes them
+// figures after the numeral are inversions like in figured bass, I6 is C/E not C6
+// Nashville numbers are read here too, a digit for the degree and the chord symbol after, 2m7 or 5/7
+
+// compiled the once rather than for every numeral read, longest first so IV isn't read as I then V
+static NUMERAL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(b|#)?(VII|VI|IV|V|III|II|I|vii|vi|iv|v|iii|ii|i)(maj|o|°|\+|ø)?(\d*)(sus2|sus4)?$",
+    )
+    .expect("numeral regex doesn't compile")
+});
+
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(b|#)?([1-7])([^/]*)(?:/(b|#)?([1-7]))?$")
+        .expect("Nashville number regex doesn't compile")
+});
+
+// the symbol for each quality mark and figure, as the chord parser reads them
+fn symbol(upper: bool, mark: &str, figure: &str) -> Option<&'static str> {
+    let symbol = match (mark, figure, upper) {
+        ("", "" | "6" | "64", true) => "",
+        ("", "" | "6" | "64", false) => "m",
+        ("o" | "°", "" | "6" | "64", _) => "dim",
+        ("+", "" | "6" | "64", _) => "aug",
+        ("", "7" | "65" | "43" | "42", true) => "7",
+        ("", "7" | "65" | "43" | "42", false) => "m7",
+        ("o" | "°", "7" | "65" | "43" | "42", _) => "dim7",
+        // ø on its own is still a half diminished 7th
+        ("ø", "" | "7" | "65" | "43" | "42", _) => "m7b5",
+        ("+", "7" | "65" | "43" | "42", _) => "aug7",
+        ("maj", "7" | "65" | "43" | "42", true) => "maj7",
+        ("maj", "7" | "65" | "43" | "42", false) => "m(maj7)",
+        ("", "9", true) => "9",
+        ("", "9", false) => "m9",
+        ("maj", "9", true) => "maj9",
+        ("", "11", true) => "11",
+        ("", "11", false) => "m11",
+        ("", "13", true) => "13",
+        ("", "13", false) => "m13",
+        _ => return None,
+    };
+
+    Some(symbol)
+}
+
+// which chord tone goes in the bass, counted from the root
+fn inversion_from_figure(figure: &str) -> usize {
+    match figure {
+        "6" | "65" => 1,
+        "64" | "43" => 2,
+        "42" => 3,
+        _ => 0,
+    }
+}
+
+// the note on a degree of the key, counted from 0, moved a semitone by a b or #
+fn degree_note(accidental: Option<&str>, degree: usize, key: &Key) -> Note {
+    let scale_note = PitchClass::from(key.notes()[degree]);
+
+    match accidental {
+        Some("b") => scale_note.down(1),
+        Some("#") => scale_note.up(1),
+        _ => scale_note,
+    }
+    .note()
+}
+
+// one numeral like bVII, ii7, V65 or viio in the key
+pub fn chord_from_numeral(numeral: &str, key: &Key) -> Result<Chord, ChordParseError> {
+    let invalid =
+        || ChordParseError::InvalidChordName(format!("{} isn't a Roman numeral", numeral));
+
+    let caps = NUMERAL_RE.captures(numeral.trim()).ok_or_else(invalid)?;
+    let accidental = caps.get(1).map(|m| m.as_str());
+    let degree_str = &caps[2];
+    let mark = caps.get(3).map_or("", |m| m.as_str());
+    let figure = caps.get(4).map_or("", |m| m.as_str());
+    let sus = caps.get(5).map(|m| m.as_str());
+
+    let upper = degree_str.chars().all(|c| c.is_uppercase());
+    let degree = ["I", "II", "III", "IV", "V", "VI", "VII"]
+        .iter()
+        .position(|d| d.eq_ignore_ascii_case(degree_str))
+        .ok_or_else(invalid)?;
+
+    let root = degree_note(accidental, degree, key);
+
+    let symbol = match (sus, mark, figure) {
+        (Some(sus), "", "") => sus.to_string(),
+        (Some(sus), "", "7") => format!("7{}", sus),
+        (Some(_), _, _) => return Err(invalid()),
+        (None, mark, figure) => symbol(upper, mark, figure).ok_or_else(invalid)?.to_string(),
+    };
+
+    let chord = from_degree(format!("{}{}", root, symbol))?;
+
+    match inversion_from_figure(figure) {
+        0 => Ok(chord),
+        tone => match chord.chord_tones().get(tone) {
+            Some(bass) => from_degree(format!("{}/{}", chord.name, bass)),
+            None => Err(invalid()),
+        },
+    }
+}
+
+// one Nashville number like 1, 2m7, b7 or 5/7, the degree then the chord symbol as it'd be written
+// after a root, with a dash as a minor (2-)
+pub fn chord_from_nashville(number: &str, key: &Key) -> Result<Chord, ChordParseError> {
+    let invalid =
+        || ChordParseError::InvalidChordName(format!("{} isn't a Nashville number", number));
+
+    let caps = NUMBER_RE.captures(number.trim()).ok_or_else(invalid)?;
+    let degree = |i: usize| caps[i].parse::<usize>().map_err(|_| invalid());
+
+    let root = degree_note(caps.get(1).map(|m| m.as_str()), degree(2)? - 1, key);
+    let symbol = match caps[3].strip_prefix('-') {
+        Some(rest) => format!("m{}", rest),
+        None => caps[3].to_string(),
+    };
+    let bass = match caps.get(5) {
+        Some(_) => format!(
+            "/{}",
+            degree_note(caps.get(4).map(|m| m.as_str()), degree(5)? - 1, key)
+        ),
+        None => String::new(),
+    };
+
+    from_degree(format!("{}{}{}", root, symbol, bass))
+}
+
+// the name's put together from the degree's note, so its root is spelled for the key rather than as the sharp it's named with
+fn from_degree(name: String) -> Result<Chord, ChordParseError> {
+    Ok(Chord {
+        written: false,
+        ..identify_from_name(name)?
+    })
+}
+
+// whatever's before the last " in " and the key after it
+fn split_key(input: &str) -> Result<(&str, Key), ChordParseError> {
+    let (chords, key) = input.rsplit_once(" in ").ok_or_else(|| {
+        ChordParseError::InvalidChordName(format!("{} needs a key, e.g. ii-V-I in C", input.trim()))
+    })?;
+
+    let key = Key::from_str(key)
+        .map_err(|_| ChordParseError::InvalidChordName(format!("{} isn't a key", key.trim())))?;
+
+    Ok((chords, key))
+}
+
+// a Nashville chart "1 4 5 in G" or "1 6m 2m7 5/7 in D", numbers split by spaces or commas
+// (not dashes, 2- is a minor)
+pub fn identify_from_nashville(input: &str) -> Result<(Key, Vec<Chord>), ChordParseError> {
+    let (numbers, key) = split_key(input)?;
+
+    let chords = numbers
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|n| !n.is_empty())
+        .map(|n| chord_from_nashville(n, &key))
+        .collect::<Result<Vec<Chord>, ChordParseError>>()?;
+
+    if chords.is_empty() {
+        return Err(ChordParseError::InvalidChordName(
+            "no numbers before the key".to_string(),
+        ));
+    }
+
+    Ok((key, chords))
+}
+
+// numbers or numerals, whichever the first one is
+pub fn is_nashville(input: &str) -> bool {
+    input
+        .trim_start()
+        .trim_start_matches(['b', '#'])
+        .starts_with(|c: char| c.is_ascii_digit())
+}
+
+// "V7 in C" or a progression "ii-V-I in Bb", numerals split by dashes, commas or spaces
+pub fn identify_from_roman(input: &str) -> Result<(Key, Vec<Chord>), ChordParseError> {
+    let (numerals, key) = split_key(input)?;
+
+    let chords = numerals
+        .split(|c: char| c == '-' || c == ',' || c.is_whitespace())
+        .filter(|n| !n.is_empty())
+        .map(|n| chord_from_numeral(n, &key))
+        .collect::<Result<Vec<Chord>, ChordParseError>>()?;
+
+    if chords.is_empty() {
+        return Err(ChordParseError::InvalidChordName(
+            "no numerals before the key".to_string(),
+        ));
+    }
+
+    Ok((key, chords))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{nashville::nashville_number, roman_numeral::roman_numeral};
+
+    fn names(input: &str) -> Vec<String> {
+        let (_, chords) = identify_from_roman(input).expect("hmm");
+        chords.into_iter().map(|c| c.name).collect()
+    }
+
+    //
+    // identify_from_roman
+    //
+
+    #[test]
+    fn test_identify_from_roman() {
+        assert_eq!(names("V7 in C"), vec!["G7"]);
+        assert_eq!(names("ii-V-I in Bb"), vec!["Cm", "F", "A#"]);
+        assert_eq!(names("i, iv, V7 in Am"), vec!["Am", "Dm", "E7"]);
+    }
+
+    #[test]
+    fn test_identify_from_roman_accidentals() {
+        assert_eq!(names("I bVII IV in C"), vec!["C", "A#", "F"]);
+        assert_eq!(names("#viio7 in A minor"), vec!["G#dim7"]);
+    }
+
+    #[test]
+    fn test_identify_from_roman_no_key() {
+        assert!(identify_from_roman("ii V I").is_err());
+        assert!(identify_from_roman("ii V I in H").is_err());
+    }
+
+    //
+    // identify_from_nashville
+    //
+
+    #[test]
+    fn test_identify_from_nashville() {
+        let (_, chords) = identify_from_nashville("1 4 5 in G").expect("hmm");
+        let names: Vec<String> = chords.into_iter().map(|c| c.name).collect();
+
+        assert_eq!(names, vec!["G", "C", "D"]);
+    }
+
+    #[test]
+    fn test_identify_from_nashville_symbols() {
+        let (_, chords) = identify_from_nashville("2m7, 2-, b7 5/7 in G").expect("hmm");
+        let names: Vec<String> = chords.into_iter().map(|c| c.name).collect();
+
+        assert_eq!(names, vec!["Am7", "Am", "F", "D/F#"]);
+    }
+
+    #[test]
+    fn test_identify_from_nashville_invalid() {
+        assert!(identify_from_nashville("8 in G").is_err());
+        assert!(identify_from_nashville("1 4 5").is_err());
+    }
+
+    // and back to the same numbers
+    #[test]
+    fn test_chord_from_nashville_roundtrip() {
+        let key = Key::major(Note::D);
+
+        for number in ["1", "2m7", "4maj7", "5/7", "b7", "6m"] {
+            let chord = chord_from_nashville(number, &key).expect("hmm");
+            assert_eq!(nashville_number(&chord, &key), number);
+        }
+    }
+
+    //
+    // is_nashville
+    //
+
+    #[test]
+    fn test_is_nashville() {
+        assert!(is_nashville("1 4 5 in G"));
+        assert!(is_nashville("b7 in C"));
+        assert!(!is_nashville("bVII in C"));
+        assert!(!is_nashville("ii-V-I in Bb"));
+    }
+
+    //
+    // chord_from_numeral
+    //
+
+    #[test]
+    fn test_chord_from_numeral_qualities() {
+        let key = Key::major(Note::C);
+        let name = |n: &str| chord_from_numeral(n, &key).expect("hmm").name;
+
+        assert_eq!(name("viio"), "Bdim");
+        assert_eq!(name("viiø7"), "Bm7b5");
+        assert_eq!(name("IVmaj7"), "Fmaj7");
+        assert_eq!(name("V7sus4"), "G7sus4");
+        assert_eq!(name("ii9"), "Dm9");
+    }
+
+    #[test]
+    fn test_chord_from_numeral_inversions() {
+        let key = Key::major(Note::C);
+        let name = |n: &str| chord_from_numeral(n, &key).expect("hmm").name;
+
+        assert_eq!(name("I6"), "C/E");
+        assert_eq!(name("I64"), "C/G");
+        assert_eq!(name("V42"), "G7/F");
+    }
+
+    #[test]
+    fn test_chord_from_numeral_invalid() {
+        let key = Key::major(Note::C);
+
+        assert!(chord_from_numeral("IIV", &key).is_err());
+        assert!(chord_from_numeral("Imaj", &key).is_err());
+        assert!(chord_from_numeral("X", &key).is_err());
+    }
+
+    // reading a numeral back should give the same numeral
+    #[test]
+    fn test_chord_from_numeral_roundtrip() {
+        let key = Key::minor(Note::A);
+
+        for numeral in ["i", "iio", "III", "iv7", "V65", "bII", "#viio7"] {
+            let chord = chord_from_numeral(numeral, &key).expect("hmm");
+            assert_eq!(roman_numeral(&chord, &key).to_string(), numeral);
+        }
+    }
+}
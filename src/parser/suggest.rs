@@ -0,0 +1,77 @@
+use crate::catalog::CATALOG_QUALITIES;
+
+// the qualities closest to what was typed after a root that didn't make sense, so Gmn7 can be a Gm7
+// close is by edit distance, the fewest characters added, taken away or swapped for another
+// https://en.wikipedia.org/wiki/Levenshtein_distance
+
+// any further off than this and it's more a different chord than a typo
+const MAX_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 3;
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    // the distances from the start of a so far to every start of b, a row at a time
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let swap = previous[j] + usize::from(ca != *cb);
+            row.push(swap.min(previous[j + 1] + 1).min(row[j] + 1));
+        }
+        previous = row;
+    }
+
+    previous[b.len()]
+}
+
+// the known qualities nearest to the one typed, the nearest ones only and in the catalog's order,
+// sus3 is as near to sus2 as sus4 so it's both
+pub fn suggest_qualities(quality: &str) -> Vec<&'static str> {
+    let distances: Vec<(usize, &str)> = CATALOG_QUALITIES
+        .iter()
+        .map(|q| (edit_distance(quality, q), *q))
+        .filter(|(d, _)| *d > 0 && *d <= MAX_DISTANCE)
+        .collect();
+
+    let nearest = distances.iter().map(|(d, _)| *d).min();
+
+    distances
+        .into_iter()
+        .filter(|(d, _)| Some(*d) == nearest)
+        .map(|(_, q)| q)
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // edit_distance
+    //
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("mn7", "m7"), 1);
+        assert_eq!(edit_distance("sus3", "sus4"), 1);
+        assert_eq!(edit_distance("", "maj7"), 4);
+        assert_eq!(edit_distance("m7", "m7"), 0);
+    }
+
+    //
+    // suggest_qualities
+    //
+
+    #[test]
+    fn test_suggest_qualities_nearest() {
+        assert_eq!(suggest_qualities("mn7"), vec!["m7"]);
+        assert_eq!(suggest_qualities("sus3"), vec!["sus2", "sus4"]);
+    }
+
+    #[test]
+    fn test_suggest_qualities_too_far() {
+        assert!(suggest_qualities("xyzzy").is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+// the types and functions that stay put between versions, use struck::prelude::* to get them all
+// anything else can still be used from its own module but might move or change shape as the theory gets redesigned
+// when one of these does change the old way keeps working for a version, marked #[deprecated] with what to use instead
+
+pub use crate::{
+    aliases::Aliases,
+    parser::chord_parser::{identify_from_name, identify_from_notes, identify_from_root_and_notes},
+    theory::{
+        chord::{
+            Chord, ChordBuilder, ChordQuality, ChordStructure, Inversion, SeventhType,
+            SuspendedType, TriadQuality,
+        },
+        chord_scale::ChordScale,
+        error::{ChordParseError, KeyParseError, NoteParseError, ScaleParseError},
+        extensions::Extensions,
+        interval::Interval,
+        key::{Key, KeyQuality},
+        naming::{name_chord, TensionReading},
+        note::Note,
+        pitch_class::PitchClass,
+        scale::Scale,
+    },
+};
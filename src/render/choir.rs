@@ -0,0 +1,69 @@
+use crate::theory::{chord::Chord, interval::semitones_between, note::Note, solfege::get_syllable};
+
+// what each voice is singing, top voice first, e.g. G7 in C:
+// F  fa   7th
+// D  re   5th
+// B  ti   3rd
+// G  sol  root
+pub fn render_choir_voices(tonic: &Note, chord: &Chord) -> String {
+    chord
+        .notes
+        .iter()
+        .rev()
+        .map(|note| {
+            format!(
+                "{:<3}{:<5}{}",
+                note.to_string(),
+                get_syllable(tonic, note),
+                get_voice_function(chord, note)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// the part of the chord a note is, a bass that isn't a chord tone is just the bass
+fn get_voice_function(chord: &Chord, note: &Note) -> &'static str {
+    if *note == chord.root {
+        return "root";
+    }
+
+    let semitones = semitones_between(&chord.root, note);
+
+    match chord
+        .intervals
+        .iter()
+        .find(|i| i.semitones() % 12 == semitones)
+    {
+        Some(interval) => interval.chord_tone_name(),
+        None => "bass",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    //
+    // render_choir_voices
+    //
+
+    #[test]
+    fn test_render_choir_voices_g7_in_c() {
+        let chord = identify_from_name("G7".to_string()).expect("hmm");
+
+        let ret = render_choir_voices(&Note::C, &chord);
+
+        assert_eq!(ret, "F  fa   7th\nD  re   5th\nB  ti   3rd\nG  sol  root");
+    }
+
+    #[test]
+    fn test_render_choir_voices_non_chord_tone_bass() {
+        let chord = identify_from_name("Am/G".to_string()).expect("hmm");
+
+        let ret = render_choir_voices(&Note::C, &chord);
+
+        assert_eq!(ret, "E  mi   5th\nC  do   3rd\nA  la   root\nG  sol  bass");
+    }
+}
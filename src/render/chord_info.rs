@@ -0,0 +1,176 @@
+use console::Style;
+
+use crate::theory::{
+    chord::{Chord, ChordQuality, ChordStructure, TriadQuality},
+    interval::get_interval,
+    key::Key,
+    spelling::spell_chord,
+};
+
+// a chord laid out to read at a glance rather than one long blob, e.g. Cmaj7/E:
+// Cmaj7/E  [Major 7th] [Major triad]
+// Bass: E
+// Extensions: maj7
+//   Root         C
+//   Major 3rd    E
+//   Perfect 5th  G
+//   Major 7th    B
+// with colour the root stands out in the name and its row, and the badges are coloured by the triad
+// name is passed in as it's to be shown, with music symbols or not, and the notes are spelled for the key if there is one
+pub fn render_chord_info(chord: &Chord, name: &str, key: Option<&Key>, colored: bool) -> String {
+    let root = Style::new().cyan().bold().force_styling(colored);
+    let bold = Style::new().bold().force_styling(colored);
+    let dim = Style::new().dim().force_styling(colored);
+    let badge = badge_style(chord.triad_quality).force_styling(colored);
+
+    let (written_root, rest) = name.split_at(written_root_len(name));
+    let mut header = format!("{}{}", root.apply_to(written_root), bold.apply_to(rest));
+
+    let quality = chord.chord_quality();
+    header.push_str(&format!("  {}", badge.apply_to(format!("[{}]", quality))));
+    if matches!(quality, ChordQuality::Seventh(_)) {
+        header.push_str(&format!(
+            " {}",
+            badge.apply_to(format!("[{} triad]", chord.triad_quality))
+        ));
+    }
+    if chord.structure != ChordStructure::Tertian {
+        header.push_str(&format!(
+            " {}",
+            badge.apply_to(format!("[{}]", chord.structure))
+        ));
+    }
+
+    let mut lines = vec![header];
+
+    // every chord tone on its own letter, the 3rd of an Ebm is a Gb
+    let spelled = spell_chord(chord, key);
+    let spell = |note| {
+        spelled
+            .iter()
+            .find(|n| n.note() == note)
+            .map_or(note.to_string(), |n| n.to_string())
+    };
+
+    if let Some(bass) = chord.bass {
+        lines.push(format!("{} {}", dim.apply_to("Bass:"), spell(bass)));
+    }
+    if !chord.extensions.is_empty() {
+        lines.push(format!(
+            "{} {}",
+            dim.apply_to("Extensions:"),
+            chord.extensions
+        ));
+    }
+
+    let mut rows = vec![("Root".to_string(), spell(chord.root))];
+    for interval in &chord.intervals {
+        let note = match get_interval(&chord.root, *interval) {
+            Ok(note) => spell(note),
+            Err(_) => "?".to_string(),
+        };
+        rows.push((interval.to_string(), note));
+    }
+
+    // the notes in a column past the longest interval name
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    for (index, (label, note)) in rows.into_iter().enumerate() {
+        let row = format!("  {:<width$} {}", label, note, width = width);
+        lines.push(match index {
+            0 => root.apply_to(row).to_string(),
+            _ => row,
+        });
+    }
+
+    lines.join("\n")
+}
+
+// green for major, blue for minor and so on, the same colour for every badge on the chord
+fn badge_style(triad: TriadQuality) -> Style {
+    match triad {
+        TriadQuality::Major => Style::new().green(),
+        TriadQuality::Minor => Style::new().blue(),
+        TriadQuality::Diminished => Style::new().red(),
+        TriadQuality::Augmented => Style::new().magenta(),
+        TriadQuality::Suspended(_) | TriadQuality::Power | TriadQuality::Ambiguous => {
+            Style::new().yellow()
+        }
+    }
+}
+
+// the letter and any sharps or flats at the front of a name, Bb in Bbm7 and B♭ in B♭Δ7
+fn written_root_len(name: &str) -> usize {
+    name.char_indices()
+        .skip(1)
+        .find(|(_, c)| !matches!(c, '#' | 'b' | '♯' | '♭'))
+        .map_or(name.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    //
+    // render_chord_info
+    //
+
+    #[test]
+    fn test_render_chord_info_plain() {
+        let chord = identify_from_name("Cmaj7/E".to_string()).expect("hmm");
+
+        let ret = render_chord_info(&chord, &chord.name, None, false);
+
+        assert_eq!(
+            ret,
+            "Cmaj7/E  [Major 7th] [Major triad]
+Bass: E
+Extensions: maj7
+  Root         C
+  Major 3rd    E
+  Perfect 5th  G
+  Major 7th    B"
+        );
+    }
+
+    #[test]
+    fn test_render_chord_info_colored() {
+        let chord = identify_from_name("Bbm".to_string()).expect("hmm");
+
+        let ret = render_chord_info(&chord, &chord.name, None, true);
+
+        assert!(ret.contains('\u{1b}'));
+        assert!(console::strip_ansi_codes(&ret).starts_with("Bbm  [Minor]"));
+    }
+
+    #[test]
+    fn test_render_chord_info_spelled() {
+        let chord = identify_from_name("Ebm".to_string()).expect("hmm");
+
+        let ret = render_chord_info(&chord, &chord.name, None, false);
+
+        assert_eq!(
+            ret,
+            "Ebm  [Minor]
+  Root         Eb
+  Minor 3rd    Gb
+  Perfect 5th  Bb"
+        );
+    }
+
+    //
+    // written_root_len
+    //
+
+    #[test]
+    fn test_written_root_len() {
+        assert_eq!(written_root_len("Bbm7"), 2);
+        assert_eq!(written_root_len("B♭Δ7"), "B♭".len());
+        assert_eq!(written_root_len("C"), 1);
+    }
+}
@@ -0,0 +1,90 @@
+use std::f64::consts::PI;
+
+use crate::theory::{
+    circle_of_fifths::key_at,
+    key::{Key, KeyQuality},
+};
+
+// the size of the drawing, characters are about twice as tall as they are wide so it's squashed to look round
+const WIDTH: usize = 47;
+const HEIGHT: usize = 19;
+const MAJOR_RADIUS: (f64, f64) = (20.0, 9.0);
+const MINOR_RADIUS: (f64, f64) = (11.0, 5.0);
+
+// the circle like a clock with C at the top, majors round the outside and their relative minors inside
+// the chosen key is in [brackets] and the keys closely related to it in (parentheses)
+//                        [C]
+//             (F)                     (G)
+//                       (Am)
+//                 (Dm)         (Em)
+pub fn render_circle_of_fifths(key: &Key) -> String {
+    let related = key.closely_related();
+    let mut grid = vec![vec![' '; WIDTH]; HEIGHT];
+
+    for position in 0..12 {
+        for (quality, (radius_x, radius_y)) in [
+            (KeyQuality::Major, MAJOR_RADIUS),
+            (KeyQuality::Minor, MINOR_RADIUS),
+        ] {
+            let at = key_at(position, quality);
+            let name = match quality {
+                KeyQuality::Major => at.tonic.to_string(),
+                KeyQuality::Minor => format!("{}m", at.tonic),
+            };
+            let label = if at == *key {
+                format!("[{}]", name)
+            } else if related.contains(&at) {
+                format!("({})", name)
+            } else {
+                name
+            };
+
+            let angle = position as f64 * PI / 6.0;
+            let x = (WIDTH / 2) as f64 + radius_x * angle.sin();
+            let y = (HEIGHT / 2) as f64 - radius_y * angle.cos();
+
+            let start = (x.round() as usize).saturating_sub(label.len() / 2);
+            let row = &mut grid[y.round() as usize];
+            for (offset, c) in label.chars().enumerate() {
+                if let Some(cell) = row.get_mut(start + offset) {
+                    *cell = c;
+                }
+            }
+        }
+    }
+
+    grid.iter()
+        .map(|row| row.iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::note::Note;
+
+    //
+    // render_circle_of_fifths
+    //
+
+    #[test]
+    fn test_render_circle_of_fifths_marks_key_and_neighbours() {
+        let ret = render_circle_of_fifths(&Key::major(Note::C));
+
+        assert_eq!(ret.lines().count(), HEIGHT);
+        assert!(ret.lines().next().expect("hmm").trim() == "[C]");
+        for related in ["(G)", "(F)", "(Am)", "(Em)", "(Dm)"] {
+            assert!(ret.contains(related));
+        }
+        assert!(!ret.contains("(D)"));
+    }
+
+    #[test]
+    fn test_render_circle_of_fifths_every_key_shown_once() {
+        let ret = render_circle_of_fifths(&Key::minor(Note::Fs));
+
+        assert!(ret.contains("[F#m]"));
+        assert_eq!(ret.matches('m').count(), 12);
+    }
+}
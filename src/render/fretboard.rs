@@ -0,0 +1,208 @@
+use crate::{
+    instrument::fretboard::{Fingering, Tuning},
+    theory::{note::Note, pitch_class::PitchClass},
+};
+
+// frets drawn below the nut even when the shape needs fewer, so diagrams line up
+const FRETS_SHOWN: u8 = 4;
+
+// a chord box the way chord books draw them, strings across from the lowest and frets down,
+// x over a muted string, o over an open one and the finger that holds each stopped string, e.g. x32010:
+// x     o   o
+// ===========
+// | | | | 1 |
+// | | 2 | | |
+// | 3 | | | |
+// | | | | | |
+// E A D G B E
+// shapes too high up the neck to fit from the nut start at their lowest fret, marked beside it (5fr)
+pub fn render_chord_diagram(fingering: &Fingering, tuning: &Tuning) -> String {
+    let first = match fingering.highest_fret() <= FRETS_SHOWN {
+        true => 1,
+        false => fingering.lowest_fret(),
+    };
+    let last = fingering.highest_fret().max(first + FRETS_SHOWN - 1);
+    let fingers = fingering.fingers();
+    let width = (fingering.frets.len() * 2).saturating_sub(1);
+
+    let mut lines: Vec<String> = vec![];
+
+    let markers = fingering
+        .frets
+        .iter()
+        .map(|fret| match fret {
+            None => "x",
+            Some(0) => "o",
+            Some(_) => " ",
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    lines.push(markers.trim_end().to_string());
+
+    lines.push(match first {
+        1 => "=".repeat(width),
+        _ => "-".repeat(width),
+    });
+
+    for fret in first..=last {
+        let row = fingering
+            .frets
+            .iter()
+            .zip(&fingers)
+            .map(|(f, finger)| match (f, finger) {
+                (Some(f), Some(finger)) if *f == fret => finger.to_string(),
+                _ => "|".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        lines.push(match first > 1 && fret == first {
+            true => format!("{} {}fr", row, fret),
+            false => row,
+        });
+    }
+
+    lines.push(
+        tuning
+            .strings
+            .iter()
+            .map(|s| PitchClass::new(*s as usize).note().to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    lines.join("\n")
+}
+
+// where some notes are up and down the whole neck, highest string at the top the way tab has it,
+// each fret with the note it plays if it's one of them and the root in brackets, e.g. C major on a uke:
+//     0     1    2    3    4    5    6    7    8    9    10   11   12
+// A  -A--||----|-B--|[C]-|----|-D--|----|-E--|-F--|----|-G--|----|-A--|
+// E  -E--||-F--|----|-G--|----|-A--|----|-B--|[C]-|----|-D--|----|-E--|
+// C  [C]-||----|-D--|----|-E--|-F--|----|-G--|----|-A--|----|-B--|[C]-|
+// G  -G--||----|-A--|----|-B--|[C]-|----|-D--|----|-E--|-F--|----|-G--|
+pub fn render_neck(tones: &[Note], root: Note, tuning: &Tuning, frets: u8) -> String {
+    let mut lines = vec![format!(
+        "   {:^4}  {}",
+        0,
+        (1..=frets)
+            .map(|f| format!("{:^4} ", f))
+            .collect::<String>()
+    )
+    .trim_end()
+    .to_string()];
+
+    for open in tuning.open_pitches().iter().rev() {
+        let cells: Vec<String> = (0..=frets)
+            .map(|fret| {
+                let note = Note::from_midi(open + fret);
+                match note {
+                    note if note == root => format!("{:-<4}", format!("[{}]", note)),
+                    note if tones.contains(&note) => format!("{:-^4}", note.to_string()),
+                    _ => "----".to_string(),
+                }
+            })
+            .collect();
+
+        lines.push(format!(
+            "{:<3}{}||{}|",
+            PitchClass::new(*open as usize).note().to_string(),
+            cells[0],
+            cells[1..].join("|")
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fretboard::Instrument;
+    use std::str::FromStr;
+
+    //
+    // render_chord_diagram
+    //
+
+    #[test]
+    fn test_render_chord_diagram_open_chord() {
+        let fingering = Fingering::from_str("x32010").expect("hmm");
+
+        let ret = render_chord_diagram(&fingering, &Tuning::standard_guitar());
+
+        assert_eq!(
+            ret,
+            "x     o   o
+===========
+| | | | 1 |
+| | 2 | | |
+| 3 | | | |
+| | | | | |
+E A D G B E"
+        );
+    }
+
+    #[test]
+    fn test_render_chord_diagram_up_the_neck() {
+        let fingering = Fingering::from_str("8-10-10-9-8-8").expect("hmm");
+
+        let ret = render_chord_diagram(&fingering, &Tuning::standard_guitar());
+
+        assert_eq!(
+            ret,
+            "
+-----------
+1 | | | 1 1 8fr
+| | | 2 | |
+| 3 4 | | |
+| | | | | |
+E A D G B E"
+        );
+    }
+
+    //
+    // render_neck
+    //
+
+    #[test]
+    fn test_render_neck_ukulele_c_major() {
+        let tuning = Instrument::named("ukulele").expect("hmm").standard_tuning();
+        let scale = [
+            Note::C,
+            Note::D,
+            Note::E,
+            Note::F,
+            Note::G,
+            Note::A,
+            Note::B,
+        ];
+
+        let ret = render_neck(&scale, Note::C, &tuning, 12);
+
+        assert_eq!(
+            ret,
+            "    0     1    2    3    4    5    6    7    8    9    10   11   12
+A  -A--||----|-B--|[C]-|----|-D--|----|-E--|-F--|----|-G--|----|-A--|
+E  -E--||-F--|----|-G--|----|-A--|----|-B--|[C]-|----|-D--|----|-E--|
+C  [C]-||----|-D--|----|-E--|-F--|----|-G--|----|-A--|----|-B--|[C]-|
+G  -G--||----|-A--|----|-B--|[C]-|----|-D--|----|-E--|-F--|----|-G--|"
+        );
+    }
+
+    #[test]
+    fn test_render_neck_chord_tones() {
+        let ret = render_neck(
+            &[Note::G, Note::B, Note::D],
+            Note::G,
+            &Tuning::standard_guitar(),
+            5,
+        );
+
+        assert_eq!(ret.lines().count(), 7);
+        assert_eq!(
+            ret.lines().last().expect("hmm"),
+            "E  ----||----|----|[G]-|----|----|"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::theory::{note::Note, pitch_class::PitchClass};
+
+const PRESSED: &str = "●";
+
+// where each note sits on the keyboard counting semitones up from the C below the lowest note
+// every note is placed as close as it can be above the one before, so the voicing reads bottom to top
+pub fn get_key_positions(voicing: &[Note]) -> Vec<usize> {
+    let mut positions: Vec<usize> = vec![];
+
+    for note in voicing {
+        let pitch_class = PitchClass::from(*note).value() as usize;
+
+        let position = match positions.last() {
+            Some(previous) => {
+                // next position above the previous note with this pitch class
+                let mut position = previous - previous % 12 + pitch_class;
+                if position <= *previous {
+                    position += 12;
+                }
+                position
+            }
+            None => pitch_class,
+        };
+
+        positions.push(position);
+    }
+
+    positions
+}
+
+// a strip of keys with the pressed ones marked, whole octaves from C, e.g. C/E:
+// |C |C#|D |D#|E |F |F#|G |G#|A |A#|B |C |C#|D |D#|E |F |F#|G |G#|A |A#|B |
+// |  |  |  |  |● |  |  |● |  |  |  |  |● |  |  |  |  |  |  |  |  |  |  |  |
+pub fn render_keyboard(voicing: &[Note]) -> String {
+    let positions = get_key_positions(voicing);
+    let octaves = positions.iter().max().map(|p| p / 12 + 1).unwrap_or(1);
+
+    let mut names = String::from("|");
+    let mut pressed = String::from("|");
+
+    for position in 0..octaves * 12 {
+        names.push_str(&format!(
+            "{:<2}|",
+            PitchClass::new(position).note().to_string()
+        ));
+
+        let marker = if positions.contains(&position) {
+            PRESSED
+        } else {
+            " "
+        };
+        pressed.push_str(&format!("{:<2}|", marker));
+    }
+
+    format!("{}\n{}", names, pressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // get_key_positions
+    //
+
+    #[test]
+    fn test_get_key_positions_root_position() {
+        let ret = get_key_positions(&[Note::C, Note::E, Note::G]);
+
+        assert_eq!(ret, vec![0, 4, 7]);
+    }
+
+    #[test]
+    fn test_get_key_positions_inversion_wraps_octave() {
+        let ret = get_key_positions(&[Note::G, Note::C, Note::E]);
+
+        assert_eq!(ret, vec![7, 12, 16]);
+    }
+
+    //
+    // render_keyboard
+    //
+
+    #[test]
+    fn test_render_keyboard_single_octave() {
+        let ret = render_keyboard(&[Note::C, Note::E, Note::G]);
+
+        assert_eq!(
+            ret,
+            "|C |C#|D |D#|E |F |F#|G |G#|A |A#|B |\n|● |  |  |  |● |  |  |● |  |  |  |  |"
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod choir;
+pub mod chord_info;
+pub mod circle;
+pub mod fretboard;
+pub mod keyboard;
+pub mod timeline;
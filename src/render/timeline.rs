@@ -0,0 +1,115 @@
+use crate::theory::chord::Chord;
+
+// a chord held over a stretch of time, this is the shape analysis of MIDI or audio produces
+// times are in milliseconds from the start of the recording
+#[derive(Debug)]
+pub struct TimedChord {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub chord: Chord,
+}
+
+const BAR_CHAR: char = '█';
+const EMPTY_CHAR: char = ' ';
+
+// gantt style chart, one row per chord with a bar showing when it sounds
+// e.g. with a width of 12:
+// C  |██████      | 0.00s-1.00s
+// Am |      ██████| 1.00s-2.00s
+pub fn render_timeline(segments: &[TimedChord], width: usize) -> String {
+    if segments.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let start = segments.iter().map(|s| s.start_ms).min().unwrap_or(0);
+    let end = segments.iter().map(|s| s.end_ms).max().unwrap_or(0);
+    // guard against every segment being zero length
+    let duration = (end - start).max(1);
+
+    let label_width = segments
+        .iter()
+        .map(|s| s.chord.name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    // work out which column a time lands in, rounding so touching segments don't overlap or gap
+    let column =
+        |ms: u64| ((ms - start) as usize * width + duration as usize / 2) / duration as usize;
+
+    segments
+        .iter()
+        .map(|segment| {
+            let bar_start = column(segment.start_ms);
+            // always draw at least one block so very short chords are still visible
+            let bar_end = column(segment.end_ms).max(bar_start + 1).min(width);
+            let bar_start = bar_start.min(bar_end - 1);
+
+            let bar: String = (0..width)
+                .map(|i| {
+                    if i >= bar_start && i < bar_end {
+                        BAR_CHAR
+                    } else {
+                        EMPTY_CHAR
+                    }
+                })
+                .collect();
+
+            format!(
+                "{:label_width$} |{}| {}-{}",
+                segment.chord.name,
+                bar,
+                format_seconds(segment.start_ms),
+                format_seconds(segment.end_ms),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_seconds(ms: u64) -> String {
+    format!("{:.2}s", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn timed(name: &str, start_ms: u64, end_ms: u64) -> TimedChord {
+        TimedChord {
+            start_ms,
+            end_ms,
+            chord: identify_from_name(name.to_string()).expect("hmm"),
+        }
+    }
+
+    //
+    // render_timeline
+    //
+
+    #[test]
+    fn test_render_timeline_even_segments() {
+        let segments = vec![timed("C", 0, 1000), timed("Am", 1000, 2000)];
+
+        let ret = render_timeline(&segments, 4);
+
+        assert_eq!(ret, "C  |██  | 0.00s-1.00s\nAm |  ██| 1.00s-2.00s");
+    }
+
+    #[test]
+    fn test_render_timeline_short_segment_still_visible() {
+        let segments = vec![timed("G7", 0, 10), timed("C", 10, 10000)];
+
+        let ret = render_timeline(&segments, 10);
+
+        assert_eq!(
+            ret,
+            "G7 |█         | 0.00s-0.01s\nC  |██████████| 0.01s-10.00s"
+        );
+    }
+
+    #[test]
+    fn test_render_timeline_empty() {
+        assert_eq!(render_timeline(&[], 10), "");
+    }
+}
@@ -0,0 +1,238 @@
+use serde::Serialize;
+use std::fmt;
+
+use crate::{
+    catalog,
+    theory::{
+        chord::Chord,
+        spelling::{spell_chord, spell_name, spell_reading},
+    },
+};
+
+// what struck works out about a chord, written plainly for other programs and editors to read
+// (struck --format json, yaml or csv), the same names and spellings the text output uses
+
+#[derive(Debug)]
+pub enum ReportError {
+    Serialize(String),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::Serialize(e) => write!(f, "couldn't write the output: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChordReport {
+    pub symbol: String,
+    pub root: String,
+    // the lowest note when it isn't the root, null for root position
+    pub bass: Option<String>,
+    pub quality: String,
+    pub notes: Vec<String>,
+    pub intervals: Vec<String>,
+    // what's past the triad, 7 b9 or add9
+    pub extensions: Vec<String>,
+    // other names for the same notes, best first
+    pub alternatives: Vec<String>,
+}
+
+// the notes spelled the way the chord has them, Ebm is Eb Gb Bb, and the other readings the same way
+impl From<&Chord> for ChordReport {
+    fn from(chord: &Chord) -> Self {
+        let spelled = spell_chord(chord, None);
+        let spell = |note| {
+            spelled
+                .iter()
+                .find(|n| n.note() == note)
+                .map_or(note.to_string(), |n| n.to_string())
+        };
+
+        ChordReport {
+            symbol: spell_name(chord, None),
+            root: spell(chord.root),
+            bass: chord.bass.map(spell),
+            quality: chord.chord_quality().to_string(),
+            notes: spelled.iter().map(|n| n.to_string()).collect(),
+            intervals: chord.intervals.iter().map(|i| i.to_string()).collect(),
+            extensions: chord.extensions.parts(),
+            alternatives: chord
+                .alternatives
+                .iter()
+                .map(|a| spell_reading(&a.name, None))
+                .collect(),
+        }
+    }
+}
+
+// a list of chords, as an array even when there's only the one so it always reads the same way
+pub fn to_json(reports: &[ChordReport]) -> Result<String, ReportError> {
+    serde_json::to_string_pretty(reports).map_err(|e| ReportError::Serialize(e.to_string()))
+}
+
+// the one chord on a line of its own, json lines for reading a stream of chords one at a time
+pub fn to_json_line(report: &ChordReport) -> Result<String, ReportError> {
+    serde_json::to_string(report).map_err(|e| ReportError::Serialize(e.to_string()))
+}
+
+pub fn to_yaml(reports: &[ChordReport]) -> Result<String, ReportError> {
+    serde_yaml::to_string(reports).map_err(|e| ReportError::Serialize(e.to_string()))
+}
+
+// one chord a row, for a spreadsheet of a whole list of chords
+pub fn to_csv(reports: &[ChordReport]) -> String {
+    let rows = reports.iter().map(|report| {
+        [
+            report.symbol.clone(),
+            report.root.clone(),
+            report.bass.clone().unwrap_or_default(),
+            report.quality.clone(),
+            report.notes.join(" "),
+            report.intervals.join(" "),
+            report.extensions.join(" "),
+            report.alternatives.join(" "),
+        ]
+    });
+
+    catalog::to_csv(
+        &[
+            "symbol",
+            "root",
+            "bass",
+            "quality",
+            "notes",
+            "intervals",
+            "extensions",
+            "alternatives",
+        ],
+        rows,
+    )
+}
+
+// lined up in columns to read in a terminal, for comparing the candidates for some notes
+// or the chords through a progression
+pub fn to_table(reports: &[ChordReport]) -> String {
+    let rows = reports.iter().map(|report| {
+        [
+            report.symbol.clone(),
+            report.quality.clone(),
+            report.intervals.join(", "),
+            report.notes.join(" "),
+        ]
+    });
+
+    catalog::to_table(&["Name", "Quality", "Intervals", "Notes"], rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    //
+    // from
+    //
+
+    #[test]
+    fn test_chord_report_from_slash_chord() {
+        let chord = identify_from_name("C7b9/E".to_string()).expect("hmm");
+
+        let ret = ChordReport::from(&chord);
+
+        assert_eq!(ret.symbol, "C7b9/E");
+        assert_eq!(ret.root, "C");
+        assert_eq!(ret.bass.as_deref(), Some("E"));
+        assert_eq!(ret.extensions, vec!["7", "b9"]);
+        assert_eq!(ret.notes.first().map(String::as_str), Some("E"));
+    }
+
+    #[test]
+    fn test_chord_report_json() {
+        let chord = identify_from_name("Am".to_string()).expect("hmm");
+
+        let ret = serde_json::to_value(ChordReport::from(&chord)).expect("hmm");
+
+        assert_eq!(ret["symbol"], "Am");
+        assert_eq!(ret["bass"], serde_json::Value::Null);
+        assert_eq!(ret["notes"], serde_json::json!(["A", "C", "E"]));
+    }
+
+    #[test]
+    fn test_chord_report_spelled() {
+        let chord = identify_from_name("Ebm/Gb".to_string()).expect("hmm");
+
+        let ret = ChordReport::from(&chord);
+
+        assert_eq!(ret.root, "Eb");
+        assert_eq!(ret.bass.as_deref(), Some("Gb"));
+        assert_eq!(ret.notes, vec!["Gb", "Eb", "Bb"]);
+    }
+
+    #[test]
+    fn test_chord_report_spelled_alternatives() {
+        let chord = identify_from_name("Eb6".to_string()).expect("hmm");
+
+        let ret = ChordReport::from(&chord);
+
+        assert_eq!(ret.alternatives, vec!["Cm7/Eb"]);
+    }
+
+    //
+    // to_yaml
+    //
+
+    #[test]
+    fn test_to_yaml() {
+        let chord = identify_from_name("G7/B".to_string()).expect("hmm");
+
+        let ret = to_yaml(&[ChordReport::from(&chord)]).expect("hmm");
+
+        assert!(ret.starts_with("- symbol: G7/B\n"));
+        assert!(ret.contains("  bass: B\n"));
+        assert!(ret.contains("  extensions:\n  - '7'\n"));
+    }
+
+    //
+    // to_csv
+    //
+
+    #[test]
+    fn test_to_csv() {
+        let chords = ["C", "G7/B"].map(|name| identify_from_name(name.to_string()).expect("hmm"));
+        let reports: Vec<ChordReport> = chords.iter().map(ChordReport::from).collect();
+
+        let ret = to_csv(&reports);
+        let lines: Vec<&str> = ret.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "symbol,root,bass,quality,notes,intervals,extensions,alternatives"
+        );
+        assert!(lines[1].starts_with("C,C,,Major,C E G,"));
+        assert!(lines[2].starts_with("G7/B,G,B,Dominant 7th,B G D F,"));
+    }
+
+    //
+    // to_table
+    //
+
+    #[test]
+    fn test_to_table() {
+        let chords = ["C", "Am7"].map(|name| identify_from_name(name.to_string()).expect("hmm"));
+        let reports: Vec<ChordReport> = chords.iter().map(ChordReport::from).collect();
+
+        let ret = to_table(&reports);
+
+        assert_eq!(
+            ret,
+            "Name  Quality    Intervals                          Notes
+----  ---------  ---------------------------------  -------
+C     Major      Major 3rd, Perfect 5th             C E G
+Am7   Minor 7th  Minor 3rd, Perfect 5th, Minor 7th  A C E G
+"
+        );
+    }
+}
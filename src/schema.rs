@@ -0,0 +1,152 @@
+// every file struck saves starts with a header saying what it is and which version of the layout it's in,
+// e.g. "# struck dictionary v1", files from before there were versions just say "# struck dictionary" and count as 0
+// loading brings an older file up to date one version at a time before reading it, so whatever an older
+// struck saved keeps loading as the layout grows
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SchemaError {
+    // saved by a newer struck than this one, there's no knowing what's changed
+    TooNew(u32),
+    Migration(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::TooNew(version) => {
+                write!(
+                    f,
+                    "saved by a newer struck (v{}), it needs upgrading to read",
+                    version
+                )
+            }
+            SchemaError::Migration(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+// takes the lines of one version (without the header) to the next
+pub type Migration = fn(&str) -> Result<String, String>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Schema {
+    pub kind: &'static str,
+    // the first takes version 0 to 1, the second 1 to 2 and so on, so the current version is how many there are
+    pub migrations: &'static [Migration],
+}
+
+// version 1 only added the version to the header, the lines are the same as they were
+pub fn unversioned_to_v1(body: &str) -> Result<String, String> {
+    Ok(body.to_string())
+}
+
+impl Schema {
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    pub fn header(&self) -> String {
+        format!("# struck {} v{}", self.kind, self.version())
+    }
+
+    // the version in the header on the first line, 0 when it doesn't say
+    pub fn written_version(&self, contents: &str) -> Result<u32, SchemaError> {
+        let version = contents
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty())
+            .and_then(|l| l.strip_prefix(&format!("# struck {}", self.kind)))
+            .and_then(|rest| rest.trim().strip_prefix('v'))
+            .map(|v| {
+                v.parse::<u32>()
+                    .map_err(|_| SchemaError::Migration(format!("unreadable version v{}", v)))
+            })
+            .transpose()?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    // contents in whatever version they were saved as, brought up to this version with the header to match
+    pub fn migrate(&self, contents: &str) -> Result<String, SchemaError> {
+        let written = self.written_version(contents)?;
+
+        if written > self.version() {
+            return Err(SchemaError::TooNew(written));
+        }
+
+        let header_prefix = format!("# struck {}", self.kind);
+        let mut body: String = contents
+            .lines()
+            .skip_while(|l| l.trim().is_empty())
+            .enumerate()
+            .filter(|(index, l)| !(*index == 0 && l.trim().starts_with(&header_prefix)))
+            .map(|(_, l)| format!("{}\n", l))
+            .collect();
+
+        for migration in &self.migrations[written as usize..] {
+            body = migration(&body).map_err(SchemaError::Migration)?;
+        }
+
+        Ok(format!("{}\n{}", self.header(), body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doubled(body: &str) -> Result<String, String> {
+        Ok(body.lines().map(|l| format!("{} {}\n", l, l)).collect())
+    }
+
+    const SCHEMA: Schema = Schema {
+        kind: "test",
+        migrations: &[unversioned_to_v1, doubled],
+    };
+
+    //
+    // written_version
+    //
+
+    #[test]
+    fn test_written_version() {
+        assert_eq!(SCHEMA.written_version("# struck test v2\na\n"), Ok(2));
+        assert_eq!(SCHEMA.written_version("# struck test\na\n"), Ok(0));
+        assert_eq!(SCHEMA.written_version("a\n"), Ok(0));
+        assert!(SCHEMA.written_version("# struck test vx\n").is_err());
+    }
+
+    //
+    // migrate
+    //
+
+    #[test]
+    fn test_migrate_runs_every_step_from_the_written_version() {
+        assert_eq!(
+            SCHEMA.migrate("# struck test\na\n").expect("hmm"),
+            "# struck test v2\na a\n"
+        );
+        assert_eq!(
+            SCHEMA.migrate("# struck test v1\na\n").expect("hmm"),
+            "# struck test v2\na a\n"
+        );
+    }
+
+    #[test]
+    fn test_migrate_current_version_unchanged() {
+        assert_eq!(
+            SCHEMA.migrate("# struck test v2\na a\n").expect("hmm"),
+            "# struck test v2\na a\n"
+        );
+    }
+
+    #[test]
+    fn test_migrate_newer_version() {
+        assert_eq!(
+            SCHEMA.migrate("# struck test v3\n"),
+            Err(SchemaError::TooNew(3))
+        );
+    }
+}
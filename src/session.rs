@@ -0,0 +1,403 @@
+use std::{fmt, fs, path::Path, str::FromStr, time::Instant};
+
+use itertools::Itertools;
+
+use crate::{
+    parser::chord_parser::identify_from_notes,
+    render::timeline::TimedChord,
+    schema::{unversioned_to_v1, Schema, SchemaError},
+    theory::{chord::Chord, note::Note, voicing::Voicing},
+};
+
+// a listen mode session is kept as the raw midi it heard, so identification can be re-run later
+// with different settings (like the segmentation window) without having to play it all again
+
+const SESSION_SCHEMA: Schema = Schema {
+    kind: "session",
+    migrations: &[unversioned_to_v1],
+};
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(String),
+    InvalidLine(String),
+    Schema(SchemaError),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionError::Io(e) => write!(f, "{}", e),
+            SessionError::InvalidLine(line) => write!(f, "couldn't read the line {}", line),
+            SessionError::Schema(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<SchemaError> for SessionError {
+    fn from(e: SchemaError) -> Self {
+        SessionError::Schema(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MidiEventKind {
+    NoteOn,
+    NoteOff,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MidiEvent {
+    // milliseconds since the session started
+    pub time_ms: u64,
+    pub kind: MidiEventKind,
+    pub note: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Session {
+    pub events: Vec<MidiEvent>,
+}
+
+// timestamps events as they come in, for listen mode to hand each midi message to
+pub struct SessionRecorder {
+    started: Instant,
+    session: Session,
+}
+
+impl Default for SessionRecorder {
+    fn default() -> Self {
+        SessionRecorder::new()
+    }
+}
+
+impl SessionRecorder {
+    pub fn new() -> SessionRecorder {
+        SessionRecorder {
+            started: Instant::now(),
+            session: Session::default(),
+        }
+    }
+
+    pub fn note_on(&mut self, note: u8) {
+        self.record(MidiEventKind::NoteOn, note);
+    }
+
+    pub fn note_off(&mut self, note: u8) {
+        self.record(MidiEventKind::NoteOff, note);
+    }
+
+    fn record(&mut self, kind: MidiEventKind, note: u8) {
+        self.session.events.push(MidiEvent {
+            time_ms: self.started.elapsed().as_millis() as u64,
+            kind,
+            note,
+        });
+    }
+
+    pub fn finish(self) -> Session {
+        self.session
+    }
+}
+
+// a note from when it's pressed to when it's released
+struct HeldNote {
+    start_ms: u64,
+    end_ms: u64,
+    note: u8,
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Session, SessionError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Session::from_str(&contents),
+            Err(e) => Err(SessionError::Io(e.to_string())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        fs::write(path, self.to_string()).map_err(|e| SessionError::Io(e.to_string()))
+    }
+
+    fn end_ms(&self) -> u64 {
+        self.events.iter().map(|e| e.time_ms).max().unwrap_or(0)
+    }
+
+    // pair up each note on with the note off that follows it
+    // anything still held when the session ends is treated as held until the last event
+    fn held_notes(&self) -> Vec<HeldNote> {
+        let mut events = self.events.clone();
+        events.sort_by_key(|e| e.time_ms);
+
+        let mut held: Vec<HeldNote> = vec![];
+        let mut sounding: Vec<(u8, u64)> = vec![];
+
+        for event in events {
+            match event.kind {
+                MidiEventKind::NoteOn => sounding.push((event.note, event.time_ms)),
+                MidiEventKind::NoteOff => {
+                    if let Some(index) = sounding.iter().position(|(n, _)| *n == event.note) {
+                        let (note, start_ms) = sounding.remove(index);
+                        held.push(HeldNote {
+                            start_ms,
+                            end_ms: event.time_ms,
+                            note,
+                        });
+                    }
+                }
+            }
+        }
+
+        let end_ms = self.end_ms();
+        held.extend(sounding.into_iter().map(|(note, start_ms)| HeldNote {
+            start_ms,
+            end_ms,
+            note,
+        }));
+
+        held
+    }
+
+    // chop the session into windows, identify whatever's sounding in each one and join up
+    // neighbouring windows that came out as the same chord
+    // a bigger window smooths over passing notes, a smaller one catches faster changes
+    pub fn identify(&self, window_ms: u64) -> Vec<TimedChord> {
+        let window_ms = window_ms.max(1);
+        let held = self.held_notes();
+        let start_ms = held.iter().map(|h| h.start_ms).min().unwrap_or(0);
+        let end_ms = self.end_ms();
+
+        let mut timeline: Vec<TimedChord> = vec![];
+        let mut window_start = start_ms;
+
+        while window_start < end_ms {
+            let window_end = window_start + window_ms;
+
+            let mut sounding: Vec<u8> = held
+                .iter()
+                .filter(|h| h.start_ms < window_end && h.end_ms > window_start)
+                .map(|h| h.note)
+                .collect();
+
+            // lowest first so the bass comes first
+            sounding.sort();
+
+            if let Some(chord) = identify_window(&sounding) {
+                match timeline.last_mut() {
+                    Some(last) if last.chord.name == chord.name && last.end_ms == window_start => {
+                        last.end_ms = window_end.min(end_ms);
+                    }
+                    _ => timeline.push(TimedChord {
+                        start_ms: window_start,
+                        end_ms: window_end.min(end_ms),
+                        chord,
+                    }),
+                }
+            }
+
+            window_start = window_end;
+        }
+
+        timeline
+    }
+
+    // the most notes held down at once, for saving a played voicing
+    // ties go to the earliest so the first chord played wins
+    pub fn fullest_voicing(&self) -> Option<Voicing> {
+        let held = self.held_notes();
+
+        held.iter()
+            .map(|h| h.start_ms)
+            .unique()
+            .sorted()
+            .map(|time_ms| {
+                let mut pitches: Vec<u8> = held
+                    .iter()
+                    .filter(|h| h.start_ms <= time_ms && h.end_ms > time_ms)
+                    .map(|h| h.note)
+                    .collect();
+                pitches.sort();
+                pitches.dedup();
+                pitches
+            })
+            .rev()
+            .max_by_key(|pitches| pitches.len())
+            .filter(|pitches| !pitches.is_empty())
+            .map(|pitches| Voicing { pitches })
+    }
+}
+
+fn identify_window(midi_notes: &[u8]) -> Option<Chord> {
+    let mut notes: Vec<Note> = vec![];
+    for note in midi_notes.iter().map(|n| Note::from_midi(*n)) {
+        if !notes.contains(&note) {
+            notes.push(note);
+        }
+    }
+
+    // readings come back most likely first, which already favours the bass as the root
+    identify_from_notes(&notes).into_iter().next()
+}
+
+// one event per line, e.g. "1500 on 60"
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", SESSION_SCHEMA.header())?;
+
+        for event in &self.events {
+            let kind = match event.kind {
+                MidiEventKind::NoteOn => "on",
+                MidiEventKind::NoteOff => "off",
+            };
+
+            writeln!(f, "{} {} {}", event.time_ms, kind, event.note)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Session {
+    type Err = SessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = &SESSION_SCHEMA.migrate(s)?;
+        let mut events = vec![];
+
+        for line in s.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || SessionError::InvalidLine(line.to_string());
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [time_ms, kind, note] = parts[..] else {
+                return Err(invalid());
+            };
+
+            let kind = match kind {
+                "on" => MidiEventKind::NoteOn,
+                "off" => MidiEventKind::NoteOff,
+                _ => return Err(invalid()),
+            };
+
+            events.push(MidiEvent {
+                time_ms: time_ms.parse().map_err(|_| invalid())?,
+                kind,
+                note: note.parse().map_err(|_| invalid())?,
+            });
+        }
+
+        Ok(Session { events })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(session: &mut Session, notes: &[u8], start_ms: u64, end_ms: u64) {
+        for note in notes {
+            session.events.push(MidiEvent {
+                time_ms: start_ms,
+                kind: MidiEventKind::NoteOn,
+                note: *note,
+            });
+            session.events.push(MidiEvent {
+                time_ms: end_ms,
+                kind: MidiEventKind::NoteOff,
+                note: *note,
+            });
+        }
+    }
+
+    //
+    // to_string / from_str
+    //
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut session = Session::default();
+        press(&mut session, &[60, 64, 67], 0, 1000);
+
+        let ret = Session::from_str(&session.to_string()).expect("hmm");
+
+        assert_eq!(ret, session);
+    }
+
+    #[test]
+    fn test_session_from_str_invalid_line() {
+        let ret = Session::from_str("# struck session\n0 on\n");
+
+        assert!(ret.is_err());
+    }
+
+    //
+    // identify
+    //
+
+    #[test]
+    fn test_identify_joins_windows_of_the_same_chord() {
+        let mut session = Session::default();
+        // C major then F major, a second each
+        press(&mut session, &[60, 64, 67], 0, 1000);
+        press(&mut session, &[65, 69, 72], 1000, 2000);
+
+        let ret = session.identify(250);
+
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[0].chord.name, "C");
+        assert_eq!((ret[0].start_ms, ret[0].end_ms), (0, 1000));
+        assert_eq!(ret[1].chord.name, "F");
+        assert_eq!((ret[1].start_ms, ret[1].end_ms), (1000, 2000));
+    }
+
+    #[test]
+    fn test_identify_window_size_changes_segmentation() {
+        let mut session = Session::default();
+        // an arpeggiated C major, one note every 100ms
+        press(&mut session, &[60], 0, 100);
+        press(&mut session, &[64], 100, 200);
+        press(&mut session, &[67], 200, 300);
+
+        // too small to hear more than one note at a time
+        assert!(session.identify(100).is_empty());
+
+        let ret = session.identify(300);
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].chord.name, "C");
+    }
+
+    #[test]
+    fn test_identify_lowest_note_is_bass() {
+        let mut session = Session::default();
+        press(&mut session, &[52, 60, 67], 0, 1000);
+
+        let ret = session.identify(500);
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].chord.name, "C/E");
+    }
+
+    //
+    // fullest_voicing
+    //
+
+    #[test]
+    fn test_fullest_voicing_picks_most_notes_held() {
+        let mut session = Session::default();
+        press(&mut session, &[60, 64], 0, 500);
+        press(&mut session, &[40, 52, 56, 62, 67], 500, 1500);
+        press(&mut session, &[60, 64, 67], 1500, 2000);
+
+        let ret = session.fullest_voicing().expect("hmm");
+
+        assert_eq!(ret.pitches, vec![40, 52, 56, 62, 67]);
+    }
+
+    #[test]
+    fn test_fullest_voicing_empty_session() {
+        assert_eq!(Session::default().fullest_voicing(), None);
+    }
+}
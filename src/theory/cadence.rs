@@ -0,0 +1,222 @@
+use std::fmt;
+
+use crate::theory::{
+    chord::{Chord, TriadQuality},
+    key::{Key, KeyQuality},
+    pitch_class::PitchClass,
+    progression::Progression,
+};
+
+// the chords a phrase ends on, the skeleton of functional harmony
+// authentic is V to I, plagal IV to I, half stops on the V, and deceptive goes from V to vi instead of home
+// https://en.wikipedia.org/wiki/Cadence
+
+// phrases are taken as 4 bars long when there are bar lines, otherwise it's just the end
+const PHRASE_BARS: usize = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Cadence {
+    Authentic,
+    Plagal,
+    Half,
+    Deceptive,
+}
+
+impl fmt::Display for Cadence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cadence::Authentic => write!(f, "authentic"),
+            Cadence::Plagal => write!(f, "plagal"),
+            Cadence::Half => write!(f, "half"),
+            Cadence::Deceptive => write!(f, "deceptive"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CadenceMatch {
+    pub cadence: Cadence,
+    // the chord it lands on, the one before it is where it comes from
+    pub at: usize,
+}
+
+// semitones from the tonic up to the chord's root
+fn from_tonic(chord: &Chord, key: &Key) -> usize {
+    PitchClass::from(key.tonic).distance(&PitchClass::from(chord.root))
+}
+
+// I in major, i in minor, or a major I at the end of a minor piece (the picardy 3rd)
+fn is_tonic(chord: &Chord, key: &Key) -> bool {
+    from_tonic(chord, key) == 0
+        && matches!(
+            chord.triad_quality,
+            TriadQuality::Major | TriadQuality::Minor
+        )
+}
+
+// V, V7, or the viio that does the same job of leading back home
+fn is_dominant(chord: &Chord, key: &Key) -> bool {
+    match from_tonic(chord, key) {
+        7 => chord.triad_quality == TriadQuality::Major,
+        11 => chord.triad_quality == TriadQuality::Diminished,
+        _ => false,
+    }
+}
+
+fn is_subdominant(chord: &Chord, key: &Key) -> bool {
+    from_tonic(chord, key) == 5
+        && matches!(
+            chord.triad_quality,
+            TriadQuality::Major | TriadQuality::Minor
+        )
+}
+
+fn is_submediant(chord: &Chord, key: &Key) -> bool {
+    let degree = match key.quality {
+        KeyQuality::Major => 9,
+        KeyQuality::Minor => 8,
+    };
+
+    from_tonic(chord, key) == degree
+}
+
+// the cadence going from one chord to the next makes, if any
+pub fn cadence(from: &Chord, to: &Chord, key: &Key) -> Option<Cadence> {
+    if is_dominant(from, key) {
+        if is_tonic(to, key) {
+            return Some(Cadence::Authentic);
+        }
+        if is_submediant(to, key) {
+            return Some(Cadence::Deceptive);
+        }
+    }
+    // stopping on the V itself, not the viio, and not just V moving to V7
+    if from_tonic(to, key) == 7 && is_dominant(to, key) && !is_dominant(from, key) {
+        return Some(Cadence::Half);
+    }
+    if is_subdominant(from, key) && is_tonic(to, key) {
+        return Some(Cadence::Plagal);
+    }
+
+    None
+}
+
+// the last chord of every phrase, every 4th bar and the very end
+pub fn phrase_ends(progression: &Progression) -> Vec<usize> {
+    let mut ends: Vec<usize> = (0..progression.len())
+        .filter(|i| match progression.bars.get(i + 1) {
+            Some(next) => progression.bars[*i] != *next && next % PHRASE_BARS == 0,
+            None => false,
+        })
+        .collect();
+    if !progression.is_empty() {
+        ends.push(progression.len() - 1);
+    }
+
+    ends
+}
+
+// the cadences at the ends of the phrases, in order
+pub fn find_cadences(progression: &Progression, key: &Key) -> Vec<CadenceMatch> {
+    phrase_ends(progression)
+        .into_iter()
+        .filter(|at| *at > 0)
+        .filter_map(|at| {
+            cadence(&progression.chords[at - 1], &progression.chords[at], key)
+                .map(|cadence| CadenceMatch { cadence, at })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::note::Note};
+    use std::str::FromStr;
+
+    fn chord(name: &str) -> Chord {
+        identify_from_name(name.to_string()).expect("hmm")
+    }
+
+    //
+    // cadence
+    //
+
+    #[test]
+    fn test_cadence_each_kind() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(
+            cadence(&chord("G7"), &chord("C"), &key),
+            Some(Cadence::Authentic)
+        );
+        assert_eq!(
+            cadence(&chord("F"), &chord("C"), &key),
+            Some(Cadence::Plagal)
+        );
+        assert_eq!(
+            cadence(&chord("Dm"), &chord("G"), &key),
+            Some(Cadence::Half)
+        );
+        assert_eq!(
+            cadence(&chord("G"), &chord("Am"), &key),
+            Some(Cadence::Deceptive)
+        );
+        assert_eq!(cadence(&chord("C"), &chord("F"), &key), None);
+    }
+
+    #[test]
+    fn test_cadence_minor_key() {
+        let key = Key::minor(Note::A);
+
+        assert_eq!(
+            cadence(&chord("E7"), &chord("Am"), &key),
+            Some(Cadence::Authentic)
+        );
+        assert_eq!(
+            cadence(&chord("E"), &chord("F"), &key),
+            Some(Cadence::Deceptive)
+        );
+    }
+
+    //
+    // find_cadences
+    //
+
+    #[test]
+    fn test_find_cadences_at_phrase_ends() {
+        let progression =
+            Progression::from_str("| C | Am | F | G | C | F | G7 | C |").expect("hmm");
+
+        let ret = find_cadences(&progression, &Key::major(Note::C));
+
+        assert_eq!(
+            ret,
+            vec![
+                CadenceMatch {
+                    cadence: Cadence::Half,
+                    at: 3
+                },
+                CadenceMatch {
+                    cadence: Cadence::Authentic,
+                    at: 7
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_cadences_no_bars_only_the_end() {
+        let progression = Progression::from_str("C G Am F C").expect("hmm");
+
+        let ret = find_cadences(&progression, &Key::major(Note::C));
+
+        assert_eq!(
+            ret,
+            vec![CadenceMatch {
+                cadence: Cadence::Plagal,
+                at: 4
+            }]
+        );
+    }
+}
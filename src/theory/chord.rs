@@ -1,15 +1,13 @@
 use itertools::Itertools;
-use regex::Regex;
-use std::{
-    fmt::{self, format, write},
-    str::FromStr,
-};
+use std::fmt;
 
 use crate::theory::{
     self,
     error::ChordParseError,
-    interval::{find_interval, get_interval, Interval},
+    extensions::{Extensions, SeventhKind},
+    interval::{find_interval, get_interval, semitones_between, Interval},
     note::Note,
+    pitch_class::PitchClass,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -18,11 +16,12 @@ pub enum SuspendedType {
     Sus4,
 }
 
-// TODO: half diminished etc
+// a triad and the 7th on it rolled into one, what ChordQuality::Seventh holds
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SeventhType {
     Minor,
     Major,
+    MinorMajor, // minor triad with a major 7th
     Dominant,
     Augmented,
     HalfDiminished,
@@ -35,7 +34,8 @@ pub enum SeventhType {
 // worth noting name base starts with the chord quality which is based on triad quality
 // the quality of the chord is determined only up to the 7th. After that these are 'pure extensions' that don't change the quality of the chord.
 // a 7th can be considered an extension, but it still impacts chord quality
-// TODO: look into this
+// a chord keeps its quality in layers, the TriadQuality, then the 7th and tensions in its Extensions,
+// this is the two bottom layers flattened into the one name, see layers and from_layers to go between them
 #[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
 pub enum ChordQuality {
     #[default]
@@ -45,44 +45,96 @@ pub enum ChordQuality {
     Augmented,  // triad quality
     Suspended(SuspendedType),
     Seventh(SeventhType),
+    Power, // just the root and 5th, no 3rd to give it a triad quality
     Ambiguous,
 }
 
-#[derive(Debug, PartialEq, Eq, Default)]
+// what the chord's built on before any 7th, a sus or power chord has no 3rd but it's still what the rest sits on
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
 pub enum TriadQuality {
     #[default]
     Major,
     Minor,
     Diminished,
     Augmented,
+    Suspended(SuspendedType),
+    Power,
     Ambiguous,
 }
 
-impl From<ChordQuality> for TriadQuality {
-    fn from(chord_quality: ChordQuality) -> Self {
-        match chord_quality {
-            ChordQuality::Major | ChordQuality::Seventh(SeventhType::Dominant) => {
-                TriadQuality::Major
-            }
-            ChordQuality::Minor | ChordQuality::Seventh(SeventhType::Minor) => TriadQuality::Minor,
-            ChordQuality::Diminished => TriadQuality::Diminished,
-            ChordQuality::Augmented | ChordQuality::Seventh(SeventhType::Augmented) => {
-                TriadQuality::Augmented
+impl ChordQuality {
+    // the triad and the 7th on it, Dominant 7th is a major triad with a minor 7th
+    pub fn layers(&self) -> (TriadQuality, Option<SeventhKind>) {
+        match self {
+            ChordQuality::Major => (TriadQuality::Major, None),
+            ChordQuality::Minor => (TriadQuality::Minor, None),
+            ChordQuality::Diminished => (TriadQuality::Diminished, None),
+            ChordQuality::Augmented => (TriadQuality::Augmented, None),
+            ChordQuality::Suspended(suspended_type) => {
+                (TriadQuality::Suspended(*suspended_type), None)
             }
-            ChordQuality::Suspended(..) | ChordQuality::Ambiguous => TriadQuality::Ambiguous,
+            ChordQuality::Power => (TriadQuality::Power, None),
+            ChordQuality::Ambiguous => (TriadQuality::Ambiguous, None),
             ChordQuality::Seventh(seventh_type) => match seventh_type {
-                SeventhType::Augmented => TriadQuality::Augmented,
-                SeventhType::Diminished | SeventhType::HalfDiminished => TriadQuality::Diminished,
-                SeventhType::Major | SeventhType::Dominant => TriadQuality::Major,
-                SeventhType::Minor => TriadQuality::Minor,
-                SeventhType::Suspended(..) => TriadQuality::Ambiguous,
+                SeventhType::Minor => (TriadQuality::Minor, Some(SeventhKind::Minor)),
+                SeventhType::Major => (TriadQuality::Major, Some(SeventhKind::Major)),
+                SeventhType::MinorMajor => (TriadQuality::Minor, Some(SeventhKind::Major)),
+                SeventhType::Dominant => (TriadQuality::Major, Some(SeventhKind::Minor)),
+                SeventhType::Augmented => (TriadQuality::Augmented, Some(SeventhKind::Minor)),
+                SeventhType::HalfDiminished => (TriadQuality::Diminished, Some(SeventhKind::Minor)),
+                SeventhType::Diminished => {
+                    (TriadQuality::Diminished, Some(SeventhKind::Diminished))
+                }
+                SeventhType::Suspended(suspended_type) => (
+                    TriadQuality::Suspended(*suspended_type),
+                    Some(SeventhKind::Minor),
+                ),
             },
         }
     }
+
+    // and back, a 7th with no name of its own on that triad (the maj7 on an augmented triad) leaves just the triad's
+    pub fn from_layers(triad: TriadQuality, seventh: Option<SeventhKind>) -> ChordQuality {
+        let seventh_type = match (triad, seventh) {
+            (TriadQuality::Major, Some(SeventhKind::Minor)) => Some(SeventhType::Dominant),
+            (TriadQuality::Major, Some(SeventhKind::Major)) => Some(SeventhType::Major),
+            (TriadQuality::Minor, Some(SeventhKind::Minor)) => Some(SeventhType::Minor),
+            (TriadQuality::Minor, Some(SeventhKind::Major)) => Some(SeventhType::MinorMajor),
+            (TriadQuality::Diminished, Some(SeventhKind::Minor)) => {
+                Some(SeventhType::HalfDiminished)
+            }
+            (TriadQuality::Diminished, Some(SeventhKind::Diminished)) => {
+                Some(SeventhType::Diminished)
+            }
+            (TriadQuality::Augmented, Some(SeventhKind::Minor)) => Some(SeventhType::Augmented),
+            (TriadQuality::Suspended(suspended_type), Some(SeventhKind::Minor)) => {
+                Some(SeventhType::Suspended(suspended_type))
+            }
+            _ => None,
+        };
+
+        if let Some(seventh_type) = seventh_type {
+            return ChordQuality::Seventh(seventh_type);
+        }
+
+        match triad {
+            TriadQuality::Major => ChordQuality::Major,
+            TriadQuality::Minor => ChordQuality::Minor,
+            TriadQuality::Diminished => ChordQuality::Diminished,
+            TriadQuality::Augmented => ChordQuality::Augmented,
+            TriadQuality::Suspended(suspended_type) => ChordQuality::Suspended(suspended_type),
+            TriadQuality::Power => ChordQuality::Power,
+            TriadQuality::Ambiguous => ChordQuality::Ambiguous,
+        }
+    }
+}
+
+impl From<ChordQuality> for TriadQuality {
+    fn from(chord_quality: ChordQuality) -> Self {
+        chord_quality.layers().0
+    }
 }
 
-// TODO: maybe want like secondary quality or something to hold 7s, 9s etc
-// TODO: maybe there's a better way than duping this for chord and triat
 impl fmt::Display for ChordQuality {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -91,16 +143,12 @@ impl fmt::Display for ChordQuality {
             ChordQuality::Diminished => write!(f, "Diminished"),
             ChordQuality::Augmented => write!(f, "Augmented"),
 
-            // TODO: 7, 9, 11 that are in stacked thirds are just X9
-            // ChordQuality::Dominant => write!(f, "Dominant"),
-            // ChordQuality::MinorSeventh => write!(f, "Minor Seventh"),
-            // ChordQuality::AugmentedSeventh => write!(f, "Augmented Seventh"),
-            // ChordQuality::SuspendedSeventh(..) => write!(f, "Suspended Seventh"), // TODO: diff 2 4 ?
             ChordQuality::Seventh(seventh_type) => match seventh_type {
                 SeventhType::Augmented => write!(f, "Augmented 7th"),
                 SeventhType::Diminished => write!(f, "Diminished 7th"),
                 SeventhType::Major => write!(f, "Major 7th"),
                 SeventhType::Minor => write!(f, "Minor 7th"),
+                SeventhType::MinorMajor => write!(f, "Minor Major 7th"),
                 SeventhType::HalfDiminished => write!(f, "Half Diminished 7th"),
                 SeventhType::Dominant => write!(f, "Dominant 7th"),
                 SeventhType::Suspended(suspended_type) => match suspended_type {
@@ -114,48 +162,201 @@ impl fmt::Display for ChordQuality {
                 SuspendedType::Sus4 => write!(f, "Suspended Fourth"),
             },
 
+            ChordQuality::Power => write!(f, "Power"),
             ChordQuality::Ambiguous => write!(f, "Ambiguous"),
         }
     }
 }
+
 impl fmt::Display for TriadQuality {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             TriadQuality::Major => write!(f, "Major"),
-            TriadQuality::Minor => write!(f, "Mior"),
+            TriadQuality::Minor => write!(f, "Minor"),
             TriadQuality::Diminished => write!(f, "Diminished"),
-            TriadQuality::Augmented => write!(f, "Augmened"),
+            TriadQuality::Augmented => write!(f, "Augmented"),
+            TriadQuality::Suspended(suspended_type) => match suspended_type {
+                SuspendedType::Sus2 => write!(f, "Suspended Second"),
+                SuspendedType::Sus4 => write!(f, "Suspended Fourth"),
+            },
+            TriadQuality::Power => write!(f, "Power"),
             TriadQuality::Ambiguous => write!(f, "Ambiguous"),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum AddInterval {
-    Interval(Interval),
-    None,
+// how the chord is built up, most chords stack 3rds but some are stacked 4ths or 5ths
+// https://en.wikipedia.org/wiki/Quartal_and_quintal_harmony
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub enum ChordStructure {
+    #[default]
+    Tertian,
+    Quartal,
+    Quintal,
+}
+
+impl fmt::Display for ChordStructure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChordStructure::Tertian => write!(f, "tertian"),
+            ChordStructure::Quartal => write!(f, "quartal"),
+            ChordStructure::Quintal => write!(f, "quintal"),
+        }
+    }
+}
+
+impl ChordStructure {
+    // the interval every note is stacked on, tertian chords mix major and minor 3rds so don't have just the one
+    pub fn step(&self) -> Option<Interval> {
+        match self {
+            ChordStructure::Tertian => None,
+            ChordStructure::Quartal => Some(Interval::PerfectFourth),
+            ChordStructure::Quintal => Some(Interval::PerfectFifth),
+        }
+    }
+}
+
+// another way the same notes could be named, scored out of 100 for how likely a reading it is
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Alternative {
+    pub name: String,
+    pub score: u8,
 }
 
 // https://en.wikipedia.org/wiki/Chord_notation
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Chord {
     pub name: String,
     pub root: Note,
+    // the lowest note when it isn't the root e.g. the E in C/E, None for root position
+    pub bass: Option<Note>,
     pub notes: Vec<Note>,
     pub triad_quality: TriadQuality,
-    pub chord_quality: ChordQuality,
-    pub add_degree: Option<AddInterval>,
     pub intervals: Vec<theory::interval::Interval>,
+    // what the intervals past the triad are, the 7th, tensions, adds and omissions
+    pub extensions: Extensions,
+    pub structure: ChordStructure,
+    // best first, empty when nothing else fits
+    pub alternatives: Vec<Alternative>,
+    // the name's as it was written, so its root and bass are spelled that way
+    // false when the name was worked out from notes or a degree and only ever has sharps
+    pub written: bool,
+}
+
+// which chord tone is in the bass
+// https://en.wikipedia.org/wiki/Inversion_(music)#Inversions_of_chords
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Inversion {
+    RootPosition,
+    First,  // 3rd in the bass (or the suspended note for sus chords)
+    Second, // 5th in the bass
+    Third,  // 7th in the bass
+}
+
+impl fmt::Display for Inversion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Inversion::RootPosition => write!(f, "root position"),
+            Inversion::First => write!(f, "first inversion"),
+            Inversion::Second => write!(f, "second inversion"),
+            Inversion::Third => write!(f, "third inversion"),
+        }
+    }
+}
+
+impl Chord {
+    // the triad and the 7th on it as the one quality, a Cm7 is Minor 7th
+    pub fn chord_quality(&self) -> ChordQuality {
+        ChordQuality::from_layers(self.triad_quality, self.extensions.seventh)
+    }
+
+    // the name as it'd be written on a chart, e.g. C7(b9)/E
+    pub fn symbol(&self) -> &str {
+        &self.name
+    }
+
+    // the name without its slash bass, C/E is C, but a 6/9's slash is part of it so C6/9 stays whole
+    pub fn symbol_over_bass(&self) -> &str {
+        match (self.bass, self.name.rsplit_once('/')) {
+            (Some(_), Some((symbol, _))) => symbol,
+            _ => &self.name,
+        }
+    }
+
+    // the same notes on the same root over the same bass, however each was spelled or put together
+    pub fn sounds_like(&self, other: &Chord) -> bool {
+        let same_notes = self.notes.iter().all(|n| other.notes.contains(n))
+            && other.notes.iter().all(|n| self.notes.contains(n));
+
+        self.root == other.root && self.bass == other.bass && same_notes
+    }
+
+    // the root and a note for every interval, without any slash bass
+    // a built chord never has an Unknown interval, if one got in somehow it's left out rather than guessed at
+    pub fn chord_tones(&self) -> Vec<Note> {
+        std::iter::once(self.root)
+            .chain(
+                self.intervals
+                    .iter()
+                    .filter_map(|i| get_interval(&self.root, *i).ok()),
+            )
+            .collect()
+    }
+
+    // a bass that isn't one of the chord tones (like the G in Am/G) is just a slash chord, not an inversion
+    pub fn inversion(&self) -> Option<Inversion> {
+        let bass = match self.bass {
+            Some(bass) => bass,
+            None => return Some(Inversion::RootPosition),
+        };
+
+        let semitones = semitones_between(&self.root, &bass);
+
+        if !self
+            .intervals
+            .iter()
+            .any(|i| i.semitones() % 12 == semitones)
+        {
+            return None;
+        }
+
+        match find_interval(&self.root, &bass) {
+            Interval::MajorSecond
+            | Interval::MinorThird
+            | Interval::MajorThird
+            | Interval::PerfectFourth => Some(Inversion::First),
+            Interval::DiminishedFifth | Interval::PerfectFifth | Interval::AugmentedFifth => {
+                Some(Inversion::Second)
+            }
+            Interval::DiminishedSeventh | Interval::MinorSeventh | Interval::MajorSeventh => {
+                Some(Inversion::Third)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Chord {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(bass) = self.bass {
+            writeln!(f, "Information on chord {}\nBass: {}", self.name, bass)?;
+        } else {
+            writeln!(f, "Information on chord {}", self.name)?;
+        }
+
+        if self.structure != ChordStructure::Tertian {
+            writeln!(f, "Structure: {}", self.structure)?;
+        }
+
+        if !self.extensions.is_empty() {
+            writeln!(f, "Extensions: {}", self.extensions)?;
+        }
+
         write!(
             f,
-            "Information on chord {}\nRoot: {}\nChord quality: {}\nTriad quality: {:?}\nIntervals: {:?}\nNotes: {:?}",
-            self.name,
+            "Root: {}\nChord quality: {}\nTriad quality: {:?}\nIntervals: {:?}\nNotes: {:?}",
             self.root,
-            self.chord_quality,
+            self.chord_quality(),
             self.triad_quality,
             // TODO: fix print formatting
             self.intervals.iter().format(", "),
@@ -164,80 +365,47 @@ impl fmt::Display for Chord {
     }
 }
 
-impl From<ChordQuality> for Vec<Interval> {
-    fn from(chord_quality: ChordQuality) -> Self {
-        match chord_quality {
-            ChordQuality::Minor => vec![Interval::MinorThird, Interval::PerfectFifth],
-            ChordQuality::Major => vec![Interval::MajorThird, Interval::PerfectFifth],
-            ChordQuality::Diminished => vec![Interval::MinorThird, Interval::DiminishedFifth],
-            ChordQuality::Augmented => vec![Interval::MajorThird, Interval::AugmentedFifth],
-
-            ChordQuality::Seventh(seventh_type) => match seventh_type {
-                SeventhType::Dominant => vec![
-                    Interval::MajorThird,
-                    Interval::PerfectFifth,
-                    Interval::DiminishedSeventh,
-                ],
-                SeventhType::Augmented => vec![
-                    Interval::MajorThird,
-                    Interval::AugmentedFifth,
-                    Interval::MinorSeventh,
-                ],
-                SeventhType::Diminished => vec![
-                    Interval::MinorThird,
-                    Interval::DiminishedFifth,
-                    Interval::DiminishedSeventh,
-                ],
-
-                SeventhType::HalfDiminished => vec![
-                    Interval::MinorThird,
-                    Interval::DiminishedFifth,
-                    Interval::MinorSeventh,
-                ],
-
-                SeventhType::Minor => vec![
-                    Interval::MinorThird,
-                    Interval::PerfectFifth,
-                    Interval::MinorSeventh,
-                ],
-                SeventhType::Major => vec![
-                    Interval::MajorThird,
-                    Interval::PerfectFifth,
-                    Interval::MinorSeventh,
-                ],
-
-                SeventhType::Suspended(suspended_type) => match suspended_type {
-                    SuspendedType::Sus2 => vec![
-                        Interval::MajorSecond,
-                        Interval::PerfectFifth,
-                        Interval::MinorSeventh,
-                    ],
-                    SuspendedType::Sus4 => vec![
-                        Interval::MajorSecond,
-                        Interval::PerfectFifth,
-                        Interval::MinorSeventh,
-                    ],
-                },
-            },
-
-            ChordQuality::Suspended(suspended_type) => match suspended_type {
+impl From<TriadQuality> for Vec<Interval> {
+    fn from(triad_quality: TriadQuality) -> Self {
+        match triad_quality {
+            TriadQuality::Minor => vec![Interval::MinorThird, Interval::PerfectFifth],
+            TriadQuality::Major => vec![Interval::MajorThird, Interval::PerfectFifth],
+            TriadQuality::Diminished => vec![Interval::MinorThird, Interval::DiminishedFifth],
+            TriadQuality::Augmented => vec![Interval::MajorThird, Interval::AugmentedFifth],
+            TriadQuality::Suspended(suspended_type) => match suspended_type {
                 SuspendedType::Sus2 => vec![Interval::MajorSecond, Interval::PerfectFifth],
                 SuspendedType::Sus4 => vec![Interval::PerfectFourth, Interval::PerfectFifth],
             },
-            _ => vec![],
+            TriadQuality::Power => vec![Interval::PerfectFifth],
+            TriadQuality::Ambiguous => vec![],
         }
     }
 }
 
+impl From<ChordQuality> for Vec<Interval> {
+    fn from(chord_quality: ChordQuality) -> Self {
+        let (triad, seventh) = chord_quality.layers();
+
+        Vec::from(triad)
+            .into_iter()
+            .chain(seventh.map(Interval::from))
+            .collect()
+    }
+}
+
 #[derive(Default)]
 pub struct ChordBuilder {
     name: String,
     root: Note,
+    bass: Option<Note>,
     notes: Vec<Note>,
     intervals: Vec<Interval>,
-    add_degree: Option<AddInterval>,
+    // worked out from the intervals when not given
+    extensions: Option<Extensions>,
     triad_quality: TriadQuality,
-    chord_quality: ChordQuality,
+    structure: ChordStructure,
+    alternatives: Vec<Alternative>,
+    written: bool,
 }
 
 impl ChordBuilder {
@@ -245,11 +413,14 @@ impl ChordBuilder {
         ChordBuilder {
             name: String::from("empty"),
             root: Note::default(),
+            bass: None,
             notes: Vec::new(),
-            add_degree: None,
+            extensions: None,
             triad_quality: TriadQuality::default(),
-            chord_quality: ChordQuality::default(),
             intervals: Vec::new(),
+            structure: ChordStructure::default(),
+            alternatives: Vec::new(),
+            written: false,
         }
     }
 
@@ -258,6 +429,11 @@ impl ChordBuilder {
         self
     }
 
+    pub fn bass(mut self, bass: Option<Note>) -> ChordBuilder {
+        self.bass = bass;
+        self
+    }
+
     pub fn notes(mut self, notes: Vec<Note>) -> ChordBuilder {
         self.notes = notes;
         self
@@ -268,8 +444,10 @@ impl ChordBuilder {
         self
     }
 
+    // from before the quality was split into layers, the 7th now comes from the intervals
+    #[deprecated(note = "use triad_quality, the 7th comes from the intervals or extensions")]
     pub fn chord_quality(mut self, chord_quality: ChordQuality) -> ChordBuilder {
-        self.chord_quality = chord_quality;
+        self.triad_quality = TriadQuality::from(chord_quality);
         self
     }
 
@@ -278,20 +456,50 @@ impl ChordBuilder {
         self
     }
 
+    pub fn extensions(mut self, extensions: Extensions) -> ChordBuilder {
+        self.extensions = Some(extensions);
+        self
+    }
+
     pub fn name(mut self, name: String) -> ChordBuilder {
         self.name = name;
         self
     }
 
+    pub fn structure(mut self, structure: ChordStructure) -> ChordBuilder {
+        self.structure = structure;
+        self
+    }
+
+    pub fn alternatives(mut self, alternatives: Vec<Alternative>) -> ChordBuilder {
+        self.alternatives = alternatives;
+        self
+    }
+
+    pub fn written(mut self, written: bool) -> ChordBuilder {
+        self.written = written;
+        self
+    }
+
     pub fn build(self) -> Chord {
+        // a stack of 4ths or 5ths isn't built on a triad so has nothing past one
+        let extensions = match (self.extensions, self.structure) {
+            (Some(extensions), _) => extensions,
+            (None, ChordStructure::Tertian) => Extensions::from_intervals(&self.intervals),
+            (None, _) => Extensions::default(),
+        };
+
         Chord {
             name: self.name,
             root: self.root,
+            bass: self.bass,
             notes: self.notes,
             intervals: self.intervals,
             triad_quality: self.triad_quality,
-            chord_quality: self.chord_quality,
-            add_degree: self.add_degree,
+            extensions,
+            structure: self.structure,
+            alternatives: self.alternatives,
+            written: self.written,
         }
     }
 }
@@ -306,40 +514,178 @@ impl ChordBuilder {
 // but for now we'll rely on the order of the notes given to infer the octave, as in if the semitones before are greater than the one we're on, it's an octave shift.
 // e.g. if the 2nd interval is preceeded by any fifth or 7th -> it's not a 2nd, it's a ninth
 pub fn find_all_intervals_from_root_and_notes(root: &Note, notes: Vec<Note>) -> Vec<Interval> {
-    // go through each note finding what interval it is
-    let mut intervals: Vec<Interval> = notes
+    // anything given before the root is voiced below it, like the E in C/E or any inversion
+    // those notes can't be 9ths, 11ths etc so they're just counted as the plain interval up from the root
+    let root_position = notes.iter().position(|n| n == root).unwrap_or(0);
+    let (below_root, from_root) = notes.split_at(root_position);
+
+    // go through each note finding how many semitones up from the root it is
+    let semitones: Vec<usize> = from_root
         .iter()
-        .skip(1)
-        .map(|n| find_interval(root, &n))
+        .filter(|n| *n != root)
+        .map(|n| semitones_between(root, n))
         .collect();
 
     // cheese to make sure 2nd, 4th is correctly reassigned to 9, 11
     // find the index where the intervals are going down i.e. 5th to a 2nd
     // tells us we need octave shift for rest
-    if intervals.len() >= 2 {
-        let mut shift_index = 0;
+    let shift_index = semitones
+        .windows(2)
+        .rposition(|pair| pair[1] < pair[0])
+        .map(|i| i + 1);
 
-        for (i, e) in intervals.iter().skip(1).enumerate() {
-            if e < intervals.get(i + 1 - 1).expect("TODO: if less than 2") {
-                shift_index = i + 1;
+    let mut intervals: Vec<Interval> = semitones
+        .iter()
+        .enumerate()
+        .map(|(i, semitones)| {
+            let shifted = Interval::from(semitones + 12);
+
+            match shift_index {
+                // a semitone above the root can only be a b9
+                _ if *semitones == 1 => Interval::MinorNinth,
+                // only 2nds, 4ths and 6ths turn into something new an octave up,
+                // a 3rd or 5th voiced higher (like the B on top of G D G B) is still a 3rd or 5th
+                Some(shift_index) if i >= shift_index && shifted != Interval::Unknown => shifted,
+                _ => Interval::from(*semitones),
+            }
+        })
+        .collect();
+
+    settle_sharp_ninths(&mut intervals);
+
+    intervals.extend(
+        below_root
+            .iter()
+            .filter(|n| *n != root)
+            .map(|n| find_interval(root, n)),
+    );
+
+    settle_intervals(intervals)
+}
+
+// the same for a chord actually voiced, midi pitches, where there's no need to guess the octave
+// a note more than an octave above the root is a 9th, 11th or 13th wherever it comes,
+// so C4 D5 E5 G5 is an add9 where C D E G would be an add2
+// a note only voiced below the root is the plain interval up to it, like the E in C/E
+pub fn find_all_intervals_from_root_and_pitches(root: &Note, pitches: &[u8]) -> Vec<Interval> {
+    let root_pitch = match pitches
+        .iter()
+        .filter(|p| Note::from_midi(**p) == *root)
+        .min()
+    {
+        Some(pitch) => *pitch,
+        None => return vec![],
+    };
+
+    // each note counts once, from its lowest pitch above the root if it has one
+    let mut above: Vec<u8> = pitches
+        .iter()
+        .copied()
+        .filter(|p| *p > root_pitch)
+        .collect();
+    above.sort();
+    let above: Vec<u8> = above.into_iter().unique_by(|p| p % 12).collect();
+
+    let mut intervals: Vec<Interval> = above
+        .iter()
+        .filter(|p| Note::from_midi(**p) != *root)
+        .map(|p| {
+            let semitones = (p - root_pitch) as usize;
+            let shifted = Interval::from(semitones % 12 + 12);
+            match semitones {
+                // a semitone above the root can only be a b9
+                s if s % 12 == 1 => Interval::MinorNinth,
+                s if s > 12 && shifted != Interval::Unknown => shifted,
+                s => Interval::from(s % 12),
+            }
+        })
+        .collect();
+
+    settle_sharp_ninths(&mut intervals);
+
+    intervals.extend(
+        pitches
+            .iter()
+            .map(|p| Note::from_midi(*p))
+            .filter(|n| n != root && !above.iter().any(|p| Note::from_midi(*p) == *n))
+            .unique()
+            .map(|n| find_interval(root, &n)),
+    );
+
+    settle_intervals(intervals)
+}
+
+// a #9 is only a #9 next to a major 3rd, on its own it's a minor 3rd voiced up high
+fn settle_sharp_ninths(intervals: &mut [Interval]) {
+    if intervals.len() >= 2 && !intervals.contains(&Interval::MajorThird) {
+        for interval in intervals.iter_mut() {
+            if *interval == Interval::AugmentedNinth {
+                *interval = Interval::MinorThird;
             }
         }
+    }
+}
 
-        if shift_index > 0 {
-            for i in shift_index..intervals.len() {
-                intervals[i] = Interval::from(intervals[i] as usize + 12)
+fn settle_intervals(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    // 9 semitones is only a diminished 7th when it's stacked on a diminished 5th, otherwise it's a 6th
+    if !intervals.contains(&Interval::DiminishedFifth) {
+        for interval in intervals.iter_mut() {
+            if *interval == Interval::DiminishedSeventh {
+                *interval = Interval::MajorSixth;
             }
         }
     }
 
+    intervals.sort_by_key(|i| i.semitones());
     intervals.dedup();
-    return intervals;
+    intervals
+}
+
+// notes given from the bass up that keep stacking the same perfect 4th (D G C F) or 5th (C G D A)
+// need at least three notes, two notes a 4th apart is just an interval
+pub fn find_stacked_structure(notes: &[Note]) -> Option<ChordStructure> {
+    if notes.len() < 3 {
+        return None;
+    }
+
+    let steps: Vec<usize> = notes
+        .windows(2)
+        .map(|pair| semitones_between(&pair[0], &pair[1]))
+        .collect();
+
+    if steps
+        .iter()
+        .all(|s| *s == Interval::PerfectFourth.semitones())
+    {
+        Some(ChordStructure::Quartal)
+    } else if steps
+        .iter()
+        .all(|s| *s == Interval::PerfectFifth.semitones())
+    {
+        Some(ChordStructure::Quintal)
+    } else {
+        None
+    }
+}
+
+// this many notes stacked up from the root, 4 quartal from D is D G C F
+// a tertian chord isn't one interval stacked up so there's only the root
+pub fn get_stacked_notes(root: &Note, structure: ChordStructure, count: usize) -> Vec<Note> {
+    let step = match structure.step() {
+        Some(step) => step,
+        None => return vec![*root],
+    };
+
+    std::iter::successors(Some(PitchClass::from(*root)), |n| Some(*n + step))
+        .take(count)
+        .map(|n| n.note())
+        .collect()
 }
 
 // take list of notes, a root, work out whether it could be major, minor, dim, sus, aug
 // once we have the start, we can check later if there's a 7th or other add
 // for now it just picks from major, minor, diminished, aug...
-pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQuality {
+pub fn derive_chord_quality_from_intervals(intervals: &[Interval]) -> ChordQuality {
     // TODO: ignore power chords for now
 
     // https://musictheory.pugetsound.edu/mt21c/TriadsIntroduction.html
@@ -350,17 +696,20 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
     // for 7th chords, the 5th can be omitted
 
     let has_second = intervals.contains(&Interval::MajorSecond);
-    let has_fourth = intervals.contains(&Interval::PerfectFourth);
     let has_minor_third = intervals.contains(&Interval::MinorThird);
     let has_major_third = intervals.contains(&Interval::MajorThird);
+    // without a 3rd an 11th is the suspended 4th voiced up high, G D F A C is a G9sus4
+    let has_fourth = intervals.contains(&Interval::PerfectFourth)
+        || (intervals.contains(&Interval::PerfectEleventh) && !has_minor_third && !has_major_third);
     let has_diminished_fifth = intervals.contains(&Interval::DiminishedFifth);
     let has_perfect_fifth = intervals.contains(&Interval::PerfectFifth);
     let has_augmented_fifth = intervals.contains(&Interval::AugmentedFifth);
     let has_minor_seventh = intervals.contains(&Interval::MinorSeventh);
+    let has_major_seventh = intervals.contains(&Interval::MajorSeventh);
 
     // TODO: clean up this match maze
     match (has_minor_third, has_major_third) {
-        (true, true) => return ChordQuality::Ambiguous,
+        (true, true) => ChordQuality::Ambiguous,
         (false, false) => {
             // if no minor or major 3rd it's either suspended, an omited 5th 7, or ambiguous
             if !has_perfect_fifth {
@@ -371,6 +720,11 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                 return ChordQuality::Ambiguous;
             };
 
+            // a lone 5th (doubling the root an octave up doesn't add anything) is a power chord
+            if intervals.len() == 1 {
+                return ChordQuality::Power;
+            }
+
             if has_second {
                 if has_minor_seventh {
                     return ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus2));
@@ -387,7 +741,7 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                 return ChordQuality::Suspended(SuspendedType::Sus4);
             }
 
-            return ChordQuality::Ambiguous;
+            ChordQuality::Ambiguous
         }
         (true, false) => {
             if has_perfect_fifth {
@@ -395,6 +749,10 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                     return ChordQuality::Seventh(SeventhType::Minor);
                 }
 
+                if has_major_seventh {
+                    return ChordQuality::Seventh(SeventhType::MinorMajor);
+                }
+
                 return ChordQuality::Minor;
             } else if has_diminished_fifth && !has_augmented_fifth {
                 if has_minor_seventh {
@@ -408,7 +766,11 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                 return ChordQuality::Seventh(SeventhType::Minor);
             }
 
-            return ChordQuality::Ambiguous;
+            if has_major_seventh {
+                return ChordQuality::Seventh(SeventhType::MinorMajor);
+            }
+
+            ChordQuality::Ambiguous
         }
         (false, true) => {
             if has_perfect_fifth {
@@ -416,10 +778,14 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                     return ChordQuality::Seventh(SeventhType::Dominant);
                 }
 
+                if has_major_seventh {
+                    return ChordQuality::Seventh(SeventhType::Major);
+                }
+
                 return ChordQuality::Major;
             } else if has_augmented_fifth && !has_diminished_fifth {
                 if has_minor_seventh {
-                    ChordQuality::Seventh(SeventhType::Augmented);
+                    return ChordQuality::Seventh(SeventhType::Augmented);
                 }
 
                 return ChordQuality::Augmented;
@@ -429,27 +795,59 @@ pub fn derive_chord_quality_from_intervals(intervals: &Vec<Interval>) -> ChordQu
                 return ChordQuality::Seventh(SeventhType::Dominant);
             }
 
-            return ChordQuality::Ambiguous;
+            if has_major_seventh {
+                return ChordQuality::Seventh(SeventhType::Major);
+            }
+
+            ChordQuality::Ambiguous
         }
     }
-
-    // return ChordQuality::Ambiguous;
 }
 
 // TODO: look into whether we need triad quality, look into generating scale as context for intervals
 pub fn get_add_interval_from_add(add_str: &str) -> Interval {
     match add_str {
-        // TODO: need more of these
+        "2" => Interval::MajorSecond,
+        "4" => Interval::PerfectFourth,
+        "6" => Interval::MajorSixth,
         "7" => Interval::MinorSeventh,
         "9" => Interval::MajorNinth,
         "11" => Interval::PerfectEleventh,
+        "#11" => Interval::AugmentedEleventh,
+        "13" => Interval::MajorThirteenth,
         _ => Interval::Unknown, // TODO: look into this
     }
 }
 
-pub fn get_notes_from_root_and_intervals(root: &Note, intervals: &Vec<Interval>) -> Vec<Note> {
-    std::iter::once(root)
-        .chain(intervals.iter().map(|i| get_interval(&root, i.clone())))
+// the other way round, what to write after add to get this interval
+pub fn get_add_from_interval(interval: &Interval) -> Option<&'static str> {
+    match interval {
+        Interval::MajorSecond => Some("2"),
+        Interval::PerfectFourth => Some("4"),
+        Interval::MajorSixth => Some("6"),
+        Interval::MinorSeventh => Some("7"),
+        Interval::MajorNinth => Some("9"),
+        Interval::PerfectEleventh => Some("11"),
+        Interval::AugmentedEleventh => Some("#11"),
+        Interval::MajorThirteenth => Some("13"),
+        _ => None,
+    }
+}
+
+pub fn get_notes_from_root_and_intervals(
+    root: &Note,
+    intervals: &[Interval],
+) -> Result<Vec<Note>, ChordParseError> {
+    std::iter::once(Ok(*root))
+        .chain(intervals.iter().map(|i| get_interval(root, *i)))
+        .collect()
+}
+
+// the bass goes first, with the rest of the chord above it
+// a bass that's already a chord tone isn't repeated, one that isn't (like the G in Am/G) is just added underneath
+pub fn get_notes_with_bass(bass: &Note, notes: &[Note]) -> Vec<Note> {
+    std::iter::once(bass)
+        .chain(notes.iter().filter(|n| *n != bass))
         .cloned()
         .collect()
 }
@@ -458,6 +856,155 @@ pub fn get_notes_from_root_and_intervals(root: &Note, intervals: &Vec<Interval>)
 mod tests {
     use super::*;
 
+    fn chord_with_bass(root: Note, bass: Option<Note>, intervals: Vec<Interval>) -> Chord {
+        ChordBuilder::new()
+            .root(root)
+            .bass(bass)
+            .intervals(intervals)
+            .build()
+    }
+
+    //
+    // ChordQuality::layers
+    //
+
+    #[test]
+    fn test_chord_quality_layers_roundtrip() {
+        let qualities = [
+            ChordQuality::Major,
+            ChordQuality::Minor,
+            ChordQuality::Diminished,
+            ChordQuality::Augmented,
+            ChordQuality::Suspended(SuspendedType::Sus2),
+            ChordQuality::Power,
+            ChordQuality::Ambiguous,
+            ChordQuality::Seventh(SeventhType::Dominant),
+            ChordQuality::Seventh(SeventhType::MinorMajor),
+            ChordQuality::Seventh(SeventhType::HalfDiminished),
+            ChordQuality::Seventh(SeventhType::Diminished),
+            ChordQuality::Seventh(SeventhType::Suspended(SuspendedType::Sus4)),
+        ];
+
+        for quality in qualities {
+            let (triad, seventh) = quality.layers();
+            assert_eq!(ChordQuality::from_layers(triad, seventh), quality);
+        }
+    }
+
+    #[test]
+    fn test_chord_quality_layers_seventh() {
+        let ret = ChordQuality::Seventh(SeventhType::HalfDiminished).layers();
+
+        assert_eq!(ret, (TriadQuality::Diminished, Some(SeventhKind::Minor)));
+    }
+
+    //
+    // ChordQuality::from_layers
+    //
+
+    #[test]
+    fn test_chord_quality_from_layers_unnamed_seventh() {
+        let ret = ChordQuality::from_layers(TriadQuality::Augmented, Some(SeventhKind::Major));
+
+        assert_eq!(ret, ChordQuality::Augmented);
+    }
+
+    //
+    // ChordBuilder::chord_quality
+    //
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_chord_builder_chord_quality_shim() {
+        let chord = ChordBuilder::new()
+            .chord_quality(ChordQuality::Seventh(SeventhType::HalfDiminished))
+            .intervals(vec![
+                Interval::MinorThird,
+                Interval::DiminishedFifth,
+                Interval::MinorSeventh,
+            ])
+            .build();
+
+        assert_eq!(chord.triad_quality, TriadQuality::Diminished);
+        assert_eq!(
+            chord.chord_quality(),
+            ChordQuality::Seventh(SeventhType::HalfDiminished)
+        );
+    }
+
+    //
+    // Chord::chord_quality
+    //
+
+    #[test]
+    fn test_chord_quality_from_intervals() {
+        let chord = ChordBuilder::new()
+            .triad_quality(TriadQuality::Minor)
+            .intervals(vec![
+                Interval::MinorThird,
+                Interval::PerfectFifth,
+                Interval::MajorSeventh,
+            ])
+            .build();
+
+        assert_eq!(
+            chord.chord_quality(),
+            ChordQuality::Seventh(SeventhType::MinorMajor)
+        );
+    }
+
+    //
+    // inversion
+    //
+
+    #[test]
+    fn test_inversion_root_position() {
+        let chord = chord_with_bass(
+            Note::C,
+            None,
+            vec![Interval::MajorThird, Interval::PerfectFifth],
+        );
+
+        assert_eq!(chord.inversion(), Some(Inversion::RootPosition));
+    }
+
+    #[test]
+    fn test_inversion_first() {
+        let chord = chord_with_bass(
+            Note::C,
+            Some(Note::E),
+            vec![Interval::MajorThird, Interval::PerfectFifth],
+        );
+
+        assert_eq!(chord.inversion(), Some(Inversion::First));
+    }
+
+    #[test]
+    fn test_inversion_third_g7_over_f() {
+        let chord = chord_with_bass(
+            Note::G,
+            Some(Note::F),
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh,
+            ],
+        );
+
+        assert_eq!(chord.inversion(), Some(Inversion::Third));
+    }
+
+    #[test]
+    fn test_inversion_non_chord_tone_bass() {
+        let chord = chord_with_bass(
+            Note::A,
+            Some(Note::G),
+            vec![Interval::MinorThird, Interval::PerfectFifth],
+        );
+
+        assert_eq!(chord.inversion(), None);
+    }
+
     //
     // find_all_intervals_from_root_and_notes
     //
@@ -482,6 +1029,60 @@ mod tests {
         );
     }
 
+    //
+    // find_stacked_structure
+    //
+
+    #[test]
+    fn test_find_stacked_structure_quartal() {
+        let ret = find_stacked_structure(&[Note::D, Note::G, Note::C, Note::F]);
+        assert_eq!(ret, Some(ChordStructure::Quartal));
+    }
+
+    #[test]
+    fn test_find_stacked_structure_quintal() {
+        let ret = find_stacked_structure(&[Note::C, Note::G, Note::D, Note::A]);
+        assert_eq!(ret, Some(ChordStructure::Quintal));
+    }
+
+    #[test]
+    fn test_find_stacked_structure_tertian_or_too_short() {
+        assert_eq!(find_stacked_structure(&[Note::C, Note::E, Note::G]), None);
+        assert_eq!(find_stacked_structure(&[Note::D, Note::G]), None);
+    }
+
+    // open voicings put the 3rd above the 5th, that's still a 3rd not an extension
+    #[test]
+    fn test_find_all_intervals_from_root_and_notes_spread_voicing() {
+        let ret = find_all_intervals_from_root_and_notes(&Note::G, vec![Note::G, Note::D, Note::B]);
+        assert_eq!(ret, vec![Interval::MajorThird, Interval::PerfectFifth]);
+
+        let ret = find_all_intervals_from_root_and_notes(&Note::A, vec![Note::A, Note::E, Note::C]);
+        assert_eq!(ret, vec![Interval::MinorThird, Interval::PerfectFifth]);
+    }
+
+    #[test]
+    fn test_find_all_intervals_from_root_and_notes_first_inversion() {
+        // C/E
+        let root = Note::C;
+        let notes = vec![Note::E, Note::G, root];
+
+        let ret = find_all_intervals_from_root_and_notes(&root, notes);
+
+        assert_eq!(ret, vec![Interval::MajorThird, Interval::PerfectFifth]);
+    }
+
+    #[test]
+    fn test_find_all_intervals_from_root_and_notes_second_inversion() {
+        // C/G
+        let root = Note::C;
+        let notes = vec![Note::G, root, Note::E];
+
+        let ret = find_all_intervals_from_root_and_notes(&root, notes);
+
+        assert_eq!(ret, vec![Interval::MajorThird, Interval::PerfectFifth]);
+    }
+
     #[test]
     fn test_find_all_intervals_from_root_and_notes_gm11_missing_5th() {
         // Gdim11
@@ -501,6 +1102,52 @@ mod tests {
         );
     }
 
+    //
+    // find_all_intervals_from_root_and_pitches
+    //
+
+    #[test]
+    fn test_find_all_intervals_from_root_and_pitches_ninth_by_octave() {
+        // C4 D5 E5 G5, the D is a 9th even though it's the next note up
+        let ret = find_all_intervals_from_root_and_pitches(&Note::C, &[60, 74, 76, 79]);
+
+        assert_eq!(
+            ret,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MajorNinth
+            ]
+        );
+
+        // C4 D4 E4 G4 keeps it a 2nd
+        let ret = find_all_intervals_from_root_and_pitches(&Note::C, &[60, 62, 64, 67]);
+
+        assert_eq!(
+            ret,
+            vec![
+                Interval::MajorSecond,
+                Interval::MajorThird,
+                Interval::PerfectFifth
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_all_intervals_from_root_and_pitches_bass_below_root() {
+        // E3 C4 G4 Bb4, the E under the root is its 3rd
+        let ret = find_all_intervals_from_root_and_pitches(&Note::C, &[52, 60, 67, 70]);
+
+        assert_eq!(
+            ret,
+            vec![
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh
+            ]
+        );
+    }
+
     //
     // get_notes_from_root_and_intervals
     //
@@ -516,7 +1163,7 @@ mod tests {
             Interval::PerfectEleventh,
         ];
 
-        let ret = get_notes_from_root_and_intervals(&root, &intervals);
+        let ret = get_notes_from_root_and_intervals(&root, &intervals).expect("hmm");
 
         assert_eq!(
             ret,
@@ -524,6 +1171,24 @@ mod tests {
         );
     }
 
+    //
+    // get_notes_with_bass
+    //
+
+    #[test]
+    fn test_get_notes_with_bass_chord_tone() {
+        let ret = get_notes_with_bass(&Note::E, &[Note::C, Note::E, Note::G]);
+
+        assert_eq!(ret, vec![Note::E, Note::C, Note::G]);
+    }
+
+    #[test]
+    fn test_get_notes_with_bass_non_chord_tone() {
+        let ret = get_notes_with_bass(&Note::G, &[Note::A, Note::C, Note::E]);
+
+        assert_eq!(ret, vec![Note::G, Note::A, Note::C, Note::E]);
+    }
+
     //
     // derive_chord_quality_from_intervals
     //
@@ -551,6 +1216,28 @@ mod tests {
         assert_eq!(ret, ChordQuality::Major);
     }
 
+    #[test]
+    fn test_derive_chord_quality_from_intervals_minor_major_seventh() {
+        let intervals = vec![
+            Interval::MinorThird,
+            Interval::PerfectFifth,
+            Interval::MajorSeventh,
+        ];
+
+        let ret = derive_chord_quality_from_intervals(&intervals);
+
+        assert_eq!(ret, ChordQuality::Seventh(SeventhType::MinorMajor));
+    }
+
+    #[test]
+    fn test_derive_chord_quality_from_intervals_power() {
+        let intervals = vec![Interval::PerfectFifth];
+
+        let ret = derive_chord_quality_from_intervals(&intervals);
+
+        assert_eq!(ret, ChordQuality::Power);
+    }
+
     #[test]
     fn test_derive_chord_quality_from_intervals_omitted_5th_7th() {
         let intervals = vec![Interval::MajorThird, Interval::MinorSeventh];
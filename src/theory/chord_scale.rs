@@ -0,0 +1,418 @@
+use itertools::Itertools;
+use std::{fmt, str::FromStr};
+
+use crate::theory::{
+    chord::Chord,
+    error::ScaleParseError,
+    interval::OCTAVE,
+    key::Key,
+    note::Note,
+    pitch_class::PitchClass,
+    scale::{Scale, SCALES},
+    spelling::{spell_in_key, SpelledNote},
+};
+
+// the scales that have every note of a chord in them, what to play over it
+// the ones starting on the chord's root come first as they're the chord-scale proper,
+// G mixolydian over a G7, then the rest it sits in, like the C major scale the G7 is the V of
+// https://en.wikipedia.org/wiki/Chord-scale_system
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ChordScale {
+    pub root: Note,
+    pub scale: Scale,
+}
+
+// G Mixolydian
+impl fmt::Display for ChordScale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            spell_in_key(self.root, &self.spelling_key()),
+            self.scale
+        )
+    }
+}
+
+// and back, a root then the scale's name, C harmonic minor, F# dorian or Bb blues
+impl FromStr for ChordScale {
+    type Err = ScaleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ScaleParseError::InvalidScale(s.trim().to_string());
+
+        let (root, scale) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid)?;
+        let root = Note::parse(root).map_err(|_| invalid())?;
+
+        Ok(ChordScale {
+            root,
+            scale: Scale::from_str(scale)?,
+        })
+    }
+}
+
+impl ChordScale {
+    pub fn notes(&self) -> Vec<Note> {
+        self.scale.notes(&self.root)
+    }
+
+    // the key whose signature it's written in, a mode's parent major's (D dorian's is C major's)
+    // and the minors their own minor key's, the rest aren't written in a key
+    pub fn key(&self) -> Option<Key> {
+        match self.scale {
+            Scale::NaturalMinor | Scale::HarmonicMinor | Scale::MelodicMinor => {
+                Some(Key::minor(self.root))
+            }
+            scale => scale.parent_major(&self.root).map(Key::major),
+        }
+    }
+
+    // the notes written the way its key has them, F minor's Ab Bb Db and Eb
+    // the scales that aren't in a key go by their formula from the root, a letter for each degree,
+    // so the blues on A has its b5 as an Eb rather than a D# next to the D
+    pub fn spelled_notes(&self) -> Vec<SpelledNote> {
+        let key = self.spelling_key();
+        if self.key().is_some() {
+            return self
+                .notes()
+                .into_iter()
+                .map(|n| spell_in_key(n, &key))
+                .collect();
+        }
+
+        let root = spell_in_key(self.root, &key);
+        self.notes()
+            .into_iter()
+            .zip(self.scale.formula())
+            .map(|(note, degree)| {
+                let number: usize = degree.trim_start_matches(['b', '#']).parse().unwrap_or(1);
+                SpelledNote::on_letter(note, root.letter.up(number.saturating_sub(1)))
+            })
+            .collect()
+    }
+
+    // the scales that aren't written in a key have their root as the major or minor key on it would,
+    // whichever 3rd they have, so the blues on Eb starts on an Eb not a D#
+    fn spelling_key(&self) -> Key {
+        let root = PitchClass::from(self.root);
+        let notes = self.notes();
+        let minor = notes.contains(&root.up(3).note()) && !notes.contains(&root.up(4).note());
+
+        self.key().unwrap_or(match minor {
+            true => Key::minor(self.root),
+            false => Key::major(self.root),
+        })
+    }
+
+    // the other scales made of just the same notes, the modes of a major scale or a whole tone a step up
+    pub fn same_notes(&self) -> Vec<ChordScale> {
+        let notes = self.notes();
+
+        scales_containing(&notes)
+            .into_iter()
+            .filter(|m| m.missing.is_empty() && m.scale.notes().len() == notes.len())
+            .map(|m| m.scale)
+            .filter(|scale| scale != self)
+            .collect()
+    }
+
+    fn contains(&self, notes: &[Note]) -> bool {
+        let scale_notes: Vec<PitchClass> = self.notes().into_iter().map(PitchClass::from).collect();
+
+        notes
+            .iter()
+            .all(|n| scale_notes.contains(&PitchClass::from(*n)))
+    }
+}
+
+// every scale on every root with all of the chord's notes in, on the chord's root first
+// then going up from it, each root's scales in the order of SCALES
+pub fn chord_scales(chord: &Chord) -> Vec<ChordScale> {
+    let start = OCTAVE.iter().position(|n| *n == chord.root).unwrap_or(0);
+
+    OCTAVE
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(OCTAVE.len())
+        .flat_map(|root| {
+            SCALES.iter().map(|scale| ChordScale {
+                root: *root,
+                scale: *scale,
+            })
+        })
+        .filter(|chord_scale| chord_scale.contains(&chord.notes))
+        .collect()
+}
+
+// a scale some notes were looked up in, how many of them it has and the ones it doesn't
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScaleMatch {
+    pub scale: ChordScale,
+    pub matched: usize,
+    pub missing: Vec<Note>,
+}
+
+// the other way round, every scale with any of some notes (from a riff, say) in, the most of them first
+// then the smaller scales as they pin the notes down more, C major before the C bebop major,
+// then going up from the first note given, that being the likeliest home
+pub fn scales_containing(notes: &[Note]) -> Vec<ScaleMatch> {
+    let notes: Vec<Note> = notes.iter().copied().unique().collect();
+    let start = notes
+        .first()
+        .and_then(|first| OCTAVE.iter().position(|n| n == first))
+        .unwrap_or(0);
+
+    let mut matches: Vec<ScaleMatch> = OCTAVE
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(OCTAVE.len())
+        .flat_map(|root| {
+            SCALES.iter().map(|scale| ChordScale {
+                root: *root,
+                scale: *scale,
+            })
+        })
+        .map(|scale| {
+            let missing: Vec<Note> = notes
+                .iter()
+                .filter(|n| !scale.contains(&[**n]))
+                .copied()
+                .collect();
+
+            ScaleMatch {
+                scale,
+                matched: notes.len() - missing.len(),
+                missing,
+            }
+        })
+        .filter(|m| m.matched > 0)
+        .collect();
+
+    // stable, so equal ones keep going up from the first note
+    matches.sort_by_key(|m| (std::cmp::Reverse(m.matched), m.scale.notes().len()));
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn scales_over(name: &str) -> Vec<String> {
+        chord_scales(&identify_from_name(name.to_string()).expect("hmm"))
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    //
+    // from_str
+    //
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            ChordScale::from_str("C harmonic minor").expect("hmm"),
+            ChordScale {
+                root: Note::C,
+                scale: Scale::HarmonicMinor
+            }
+        );
+        assert_eq!(
+            ChordScale::from_str("Bb blues").expect("hmm").to_string(),
+            "Bb Blues"
+        );
+    }
+
+    #[test]
+    fn test_from_str_roundtrip() {
+        let scale = ChordScale {
+            root: Note::Fs,
+            scale: Scale::Dorian,
+        };
+
+        assert_eq!(
+            ChordScale::from_str(&scale.to_string()).expect("hmm"),
+            scale
+        );
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!(ChordScale::from_str("dorian").is_err());
+        assert!(ChordScale::from_str("H dorian").is_err());
+        assert!(ChordScale::from_str("C dorianish").is_err());
+    }
+
+    //
+    // chord_scales
+    //
+
+    #[test]
+    fn test_chord_scales_dominant() {
+        let ret = scales_over("G7");
+
+        assert_eq!(ret[0], "G Mixolydian");
+        assert!(ret.contains(&"C Major".to_string()));
+        assert!(ret.contains(&"G Half-Whole Diminished".to_string()));
+        assert!(!ret.contains(&"G Major".to_string()));
+    }
+
+    #[test]
+    fn test_chord_scales_altered() {
+        let ret = scales_over("G7alt");
+
+        assert_eq!(ret[0], "G Altered");
+        assert!(!ret.contains(&"G Mixolydian".to_string()));
+    }
+
+    #[test]
+    fn test_chord_scales_root_first() {
+        let ret = chord_scales(&identify_from_name("Dm7".to_string()).expect("hmm"));
+
+        assert_eq!(ret[0].root, Note::D);
+        assert!(ret
+            .iter()
+            .skip_while(|s| s.root == Note::D)
+            .all(|s| s.root != Note::D));
+    }
+
+    //
+    // scales_containing
+    //
+
+    #[test]
+    fn test_scales_containing_every_note_first() {
+        let notes = [
+            Note::C,
+            Note::D,
+            Note::E,
+            Note::F,
+            Note::G,
+            Note::A,
+            Note::B,
+        ];
+        let ret = scales_containing(&notes);
+
+        assert_eq!(ret[0].scale.to_string(), "C Major");
+        assert_eq!(ret[0].matched, 7);
+        assert!(ret[0].missing.is_empty());
+        assert!(ret.windows(2).all(|w| w[0].matched >= w[1].matched));
+    }
+
+    #[test]
+    fn test_scales_containing_riff() {
+        // the minor pentatonic lick, the blues scale is the smallest with all of it
+        let notes = [Note::A, Note::C, Note::D, Note::Ds, Note::E, Note::G];
+        let ret = scales_containing(&notes);
+
+        assert_eq!(ret[0].scale.to_string(), "A Blues");
+    }
+
+    #[test]
+    fn test_scales_containing_missing() {
+        let ret = scales_containing(&[Note::C, Note::Cs, Note::D]);
+        let c_major = ret
+            .iter()
+            .find(|m| m.scale.to_string() == "C Major")
+            .expect("hmm");
+
+        assert_eq!(c_major.matched, 2);
+        assert_eq!(c_major.missing, vec![Note::Cs]);
+    }
+
+    //
+    // key
+    //
+
+    #[test]
+    fn test_key() {
+        let key = |name: &str| ChordScale::from_str(name).expect("hmm").key();
+
+        assert_eq!(key("D dorian"), Some(Key::major(Note::C)));
+        assert_eq!(key("E major"), Some(Key::major(Note::E)));
+        assert_eq!(key("A harmonic minor"), Some(Key::minor(Note::A)));
+        assert_eq!(key("F minor"), Some(Key::minor(Note::F)));
+        assert_eq!(key("C whole tone"), None);
+    }
+
+    //
+    // same_notes
+    //
+
+    #[test]
+    fn test_same_notes_modes() {
+        let ret = ChordScale::from_str("C major").expect("hmm").same_notes();
+
+        assert_eq!(
+            ret.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            vec![
+                "D Dorian",
+                "E Phrygian",
+                "F Lydian",
+                "G Mixolydian",
+                "A Natural Minor",
+                "B Locrian",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same_notes_symmetric_scales() {
+        let ret = ChordScale::from_str("C whole tone")
+            .expect("hmm")
+            .same_notes();
+
+        assert_eq!(ret.len(), 5);
+        assert!(ret.iter().all(|s| s.scale == Scale::WholeTone));
+    }
+
+    //
+    // spelled_notes
+    //
+
+    #[test]
+    fn test_spelled_notes_f_minor() {
+        let ret = ChordScale::from_str("F minor")
+            .expect("hmm")
+            .spelled_notes();
+
+        assert_eq!(
+            ret.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            vec!["F", "G", "Ab", "Bb", "C", "Db", "Eb"]
+        );
+    }
+
+    #[test]
+    fn test_spelled_notes_without_a_key() {
+        let ret = ChordScale::from_str("C blues")
+            .expect("hmm")
+            .spelled_notes();
+
+        assert_eq!(
+            ret.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            vec!["C", "Eb", "F", "Gb", "G", "Bb"]
+        );
+    }
+
+    #[test]
+    fn test_spelled_notes_by_formula() {
+        let spelled = |scale: &str| {
+            ChordScale::from_str(scale)
+                .expect("hmm")
+                .spelled_notes()
+                .iter()
+                .join(" ")
+        };
+
+        assert_eq!(spelled("A blues"), "A C D Eb E G");
+        assert_eq!(spelled("C whole tone"), "C D E F# G# Bb");
+    }
+}
@@ -0,0 +1,151 @@
+use crate::theory::{
+    key::{Key, KeyQuality},
+    pitch_class::PitchClass,
+};
+
+// https://en.wikipedia.org/wiki/Circle_of_fifths
+// the keys a 5th apart go round in a circle, each clockwise step a sharp more (or a flat less)
+// a minor key sits in the same place as its relative major, they share a key signature
+
+// the key in a place on the circle, counting clockwise steps from C (or A minor)
+// seven semitones a step, and 7 * 7 is 49 which is 1 round the octave, so it's the same 7 both ways
+pub fn key_at(position: usize, quality: KeyQuality) -> Key {
+    let major = Key::major(PitchClass::new(position * 7).note());
+
+    match quality {
+        KeyQuality::Major => major,
+        KeyQuality::Minor => major.relative(),
+    }
+}
+
+impl Key {
+    // clockwise steps from C, 0 to 11
+    pub fn circle_position(&self) -> usize {
+        let major = match self.quality {
+            KeyQuality::Major => *self,
+            KeyQuality::Minor => self.relative(),
+        };
+
+        PitchClass::from(major.tonic).value() as usize * 7 % 12
+    }
+
+    // sharps in the key signature, or flats as a negative, F# and Gb both being 6 away it goes by sharps
+    pub fn signature(&self) -> i32 {
+        match self.circle_position() as i32 {
+            position if position <= 6 => position,
+            position => position - 12,
+        }
+    }
+
+    // a 5th down (the subdominant side) then a 5th up (the dominant side)
+    pub fn fifth_neighbours(&self) -> [Key; 2] {
+        let position = self.circle_position();
+
+        [
+            key_at((position + 11) % 12, self.quality),
+            key_at((position + 1) % 12, self.quality),
+        ]
+    }
+
+    // steps round the circle the shorter way, relative keys being 0 apart
+    pub fn fifths_distance(&self, other: &Key) -> usize {
+        let apart = (self.circle_position() + 12 - other.circle_position()) % 12;
+        apart.min(12 - apart)
+    }
+
+    // the keys a signature at most one accidental different, what a piece usually modulates to
+    // the relative key, both neighbours and their relatives
+    pub fn closely_related(&self) -> Vec<Key> {
+        let [down, up] = self.fifth_neighbours();
+
+        vec![self.relative(), down, down.relative(), up, up.relative()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::note::Note;
+
+    //
+    // circle_position
+    //
+
+    #[test]
+    fn test_circle_position_round_the_circle() {
+        for position in 0..12 {
+            assert_eq!(
+                key_at(position, KeyQuality::Major).circle_position(),
+                position
+            );
+            assert_eq!(
+                key_at(position, KeyQuality::Minor).circle_position(),
+                position
+            );
+        }
+
+        assert_eq!(Key::major(Note::G).circle_position(), 1);
+        assert_eq!(Key::major(Note::F).circle_position(), 11);
+        assert_eq!(Key::minor(Note::A).circle_position(), 0);
+    }
+
+    //
+    // signature
+    //
+
+    #[test]
+    fn test_signature_sharps_and_flats() {
+        assert_eq!(Key::major(Note::C).signature(), 0);
+        assert_eq!(Key::major(Note::E).signature(), 4);
+        assert_eq!(Key::major(Note::As).signature(), -2);
+        assert_eq!(Key::minor(Note::D).signature(), -1);
+    }
+
+    //
+    // fifth_neighbours
+    //
+
+    #[test]
+    fn test_fifth_neighbours() {
+        assert_eq!(
+            Key::major(Note::C).fifth_neighbours(),
+            [Key::major(Note::F), Key::major(Note::G)]
+        );
+        assert_eq!(
+            Key::minor(Note::A).fifth_neighbours(),
+            [Key::minor(Note::D), Key::minor(Note::E)]
+        );
+    }
+
+    //
+    // fifths_distance
+    //
+
+    #[test]
+    fn test_fifths_distance() {
+        let c = Key::major(Note::C);
+
+        assert_eq!(c.fifths_distance(&Key::minor(Note::A)), 0);
+        assert_eq!(c.fifths_distance(&Key::major(Note::D)), 2);
+        assert_eq!(c.fifths_distance(&Key::major(Note::As)), 2);
+        assert_eq!(c.fifths_distance(&Key::major(Note::Fs)), 6);
+    }
+
+    //
+    // closely_related
+    //
+
+    #[test]
+    fn test_closely_related_c_major() {
+        assert_eq!(
+            Key::major(Note::C).closely_related(),
+            vec![
+                Key::minor(Note::A),
+                Key::major(Note::F),
+                Key::minor(Note::D),
+                Key::major(Note::G),
+                Key::minor(Note::E)
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use itertools::Itertools;
+
+use crate::theory::{chord::Chord, note::Note, pitch_class::PitchClass};
+
+// out of the 100 a similarity score goes up to, how much comes from sharing notes,
+// the rest is how close the roots are
+const SHARED_TONES_WEIGHT: f64 = 70.0;
+const ROOT_DISTANCE_WEIGHT: f64 = 30.0;
+
+impl Chord {
+    // notes both chords have, in this chord's order
+    // C and Am share C and E, the more there are the less moves going from one to the other
+    pub fn common_tones(&self, other: &Chord) -> Vec<Note> {
+        self.notes
+            .iter()
+            .unique()
+            .filter(|n| other.notes.contains(n))
+            .copied()
+            .collect()
+    }
+
+    // notes in this chord the other one doesn't have
+    pub fn differing_tones(&self, other: &Chord) -> Vec<Note> {
+        self.notes
+            .iter()
+            .unique()
+            .filter(|n| !other.notes.contains(n))
+            .copied()
+            .collect()
+    }
+
+    // how alike two chords are out of 100, mostly shared tones out of all the notes between them
+    // with the rest for how near the roots are, either way round the octave (a tritone being furthest)
+    // C to Am is closer than C to F#, even though neither shares every note
+    pub fn similarity(&self, other: &Chord) -> u8 {
+        let all_tones = self.notes.iter().chain(&other.notes).unique().count();
+        if all_tones == 0 {
+            return 0;
+        }
+
+        let shared = self.common_tones(other).len() as f64 / all_tones as f64;
+
+        let root_distance =
+            PitchClass::from(self.root).nearest_distance(&PitchClass::from(other.root)) as f64;
+        let root_closeness = 1.0 - root_distance / 6.0;
+
+        (shared * SHARED_TONES_WEIGHT + root_closeness * ROOT_DISTANCE_WEIGHT).round() as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::chord_parser::identify_from_name;
+
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        identify_from_name(name.to_string()).expect("hmm")
+    }
+
+    //
+    // common_tones
+    //
+
+    #[test]
+    fn test_common_tones_relative_minor() {
+        assert_eq!(
+            chord("C").common_tones(&chord("Am")),
+            vec![Note::C, Note::E]
+        );
+        assert_eq!(chord("C").differing_tones(&chord("Am")), vec![Note::G]);
+        assert_eq!(chord("Am").differing_tones(&chord("C")), vec![Note::A]);
+    }
+
+    #[test]
+    fn test_common_tones_none_shared() {
+        assert!(chord("C").common_tones(&chord("F#")).is_empty());
+    }
+
+    //
+    // similarity
+    //
+
+    #[test]
+    fn test_similarity_same_chord() {
+        assert_eq!(chord("G7").similarity(&chord("G7")), 100);
+    }
+
+    #[test]
+    fn test_similarity_orders_neighbours() {
+        let c = chord("C");
+
+        assert!(c.similarity(&chord("Am")) > c.similarity(&chord("F")));
+        assert!(c.similarity(&chord("F")) > c.similarity(&chord("F#")));
+        assert_eq!(c.similarity(&chord("F#")), 0);
+    }
+}
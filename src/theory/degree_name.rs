@@ -0,0 +1,101 @@
+use crate::theory::{
+    key::Key,
+    note::Note,
+    pitch_class::PitchClass,
+    roman_numeral::{scale_degree, Accidental},
+};
+
+// the old names for the degrees of a key, tonic up to leading tone
+// the 7th is only a leading tone a semitone under the tonic, a tone under it's the subtonic,
+// so the natural minor has a subtonic and the harmonic minor's raised 7th is the leading tone again
+// https://en.wikipedia.org/wiki/Degree_(music)
+pub const DEGREE_NAMES: [&str; 6] = [
+    "tonic",
+    "supertonic",
+    "mediant",
+    "subdominant",
+    "dominant",
+    "submediant",
+];
+
+// None for notes off the key other than the two 7ths
+pub fn degree_name(note: &Note, key: &Key) -> Option<&'static str> {
+    match PitchClass::from(*note).distance(&PitchClass::from(key.tonic)) {
+        1 => return Some("leading tone"),
+        2 => return Some("subtonic"),
+        _ => (),
+    }
+
+    match scale_degree(note, key) {
+        (None, degree) => DEGREE_NAMES.get(degree).copied(),
+        _ => None,
+    }
+}
+
+// the name, or the degree as a number with its accidental (b2, #4) when it hasn't got one
+pub fn describe_degree(note: &Note, key: &Key) -> String {
+    match (degree_name(note, key), scale_degree(note, key)) {
+        (Some(name), _) => name.to_string(),
+        (None, (Some(Accidental::Flat), degree)) => format!("b{}", degree + 1),
+        (None, (Some(Accidental::Sharp), degree)) => format!("#{}", degree + 1),
+        (None, (None, degree)) => (degree + 1).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // degree_name
+    //
+
+    #[test]
+    fn test_degree_name_major() {
+        let key = Key::major(Note::D);
+        let ret: Vec<Option<&str>> = key.notes().iter().map(|n| degree_name(n, &key)).collect();
+
+        assert_eq!(
+            ret,
+            vec![
+                Some("tonic"),
+                Some("supertonic"),
+                Some("mediant"),
+                Some("subdominant"),
+                Some("dominant"),
+                Some("submediant"),
+                Some("leading tone"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_degree_name_sevenths() {
+        let key = Key::minor(Note::A);
+
+        assert_eq!(degree_name(&Note::G, &key), Some("subtonic"));
+        assert_eq!(degree_name(&Note::Gs, &key), Some("leading tone"));
+        assert_eq!(
+            degree_name(&Note::As, &Key::major(Note::C)),
+            Some("subtonic")
+        );
+    }
+
+    #[test]
+    fn test_degree_name_chromatic() {
+        assert_eq!(degree_name(&Note::Fs, &Key::major(Note::C)), None);
+    }
+
+    //
+    // describe_degree
+    //
+
+    #[test]
+    fn test_describe_degree() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(describe_degree(&Note::G, &key), "dominant");
+        assert_eq!(describe_degree(&Note::Fs, &key), "#4");
+        assert_eq!(describe_degree(&Note::Gs, &key), "b6");
+    }
+}
@@ -1,10 +1,238 @@
+use std::fmt;
+
+// where in what was typed something went wrong, as byte offsets so the bad part can be pointed at
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Span {
+    pub input: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(input: &str, start: usize, end: usize) -> Span {
+        Span {
+            input: input.to_string(),
+            start,
+            end,
+        }
+    }
+
+    // the part that went wrong
+    pub fn token(&self) -> &str {
+        self.input.get(self.start..self.end).unwrap_or("")
+    }
+
+    // the same span in the longer input this one was part of, starting at offset into it
+    pub fn within(self, input: &str, offset: usize) -> Span {
+        Span::new(input, self.start + offset, self.end + offset)
+    }
+
+    // the input with carets under the part that went wrong
+    // Gxyz7
+    //  ^^^
+    pub fn caret(&self) -> String {
+        let before = self.input.get(..self.start).unwrap_or("").chars().count();
+        let width = self.token().chars().count().max(1);
+
+        format!(
+            "{}\n{}{}",
+            self.input,
+            " ".repeat(before),
+            "^".repeat(width)
+        )
+    }
+}
+
 #[derive(Debug)]
 pub enum NoteParseError {
-    InvalidNoteStringValue(String),
+    InvalidNoteStringValue(Span),
+}
+
+impl NoteParseError {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            NoteParseError::InvalidNoteStringValue(span) => Some(span),
+        }
+    }
+
+    // what went wrong put plainly, for a report or a prompt rather than a debug print
+    pub fn message(&self) -> String {
+        match self {
+            NoteParseError::InvalidNoteStringValue(span) => {
+                format!("{} isn't a note", span.token())
+            }
+        }
+    }
+
+    // the error against the longer input the note sat in, one of a list of notes
+    pub fn within(self, input: &str, offset: usize) -> NoteParseError {
+        match self {
+            NoteParseError::InvalidNoteStringValue(span) => {
+                NoteParseError::InvalidNoteStringValue(span.within(input, offset))
+            }
+        }
+    }
+}
+
+impl fmt::Display for NoteParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
 }
 
 #[derive(Debug)]
 pub enum ChordParseError {
     InvalidChordName(String),
+    // an interval with no note to land on, like Interval::Unknown
+    InvalidInterval(String),
+    // the part of the name that couldn't be read and why, Gxyz7 has xyz,
+    // and the names it was closest to, Gmn7 is nearly a Gm7
+    UnexpectedToken(String, Span, Vec<String>),
     // TODO: maybe NoteParseError(NoteParseError),
 }
+
+impl ChordParseError {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            ChordParseError::UnexpectedToken(_, span, _) => Some(span),
+            _ => None,
+        }
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        match self {
+            ChordParseError::UnexpectedToken(_, _, suggestions) => suggestions,
+            _ => &[],
+        }
+    }
+
+    // what went wrong put plainly with any suggestions, for a report or a prompt rather than a debug print
+    pub fn message(&self) -> String {
+        match self {
+            ChordParseError::UnexpectedToken(reason, _, suggestions) if !suggestions.is_empty() => {
+                format!("{}, did you mean {}?", reason, suggestions.join(" or "))
+            }
+            ChordParseError::UnexpectedToken(reason, _, _) => reason.clone(),
+            ChordParseError::InvalidChordName(reason)
+            | ChordParseError::InvalidInterval(reason) => reason.clone(),
+        }
+    }
+
+    // the error against the longer input what failed to parse sat in, a chord in a progression
+    pub fn within(self, input: &str, offset: usize) -> ChordParseError {
+        match self {
+            ChordParseError::UnexpectedToken(reason, span, suggestions) => {
+                ChordParseError::UnexpectedToken(reason, span.within(input, offset), suggestions)
+            }
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for ChordParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+#[derive(Debug)]
+pub enum KeyParseError {
+    InvalidKey(String),
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyParseError::InvalidKey(key) => write!(f, "{} isn't a key", key.trim()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ScaleParseError {
+    InvalidScale(String),
+}
+
+impl fmt::Display for ScaleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScaleParseError::InvalidScale(scale) => write!(f, "{} isn't a scale", scale),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // caret
+    //
+
+    #[test]
+    fn test_caret_under_span() {
+        let span = Span::new("Gxyz7", 1, 4);
+
+        assert_eq!(span.token(), "xyz");
+        assert_eq!(span.caret(), "Gxyz7\n ^^^");
+    }
+
+    #[test]
+    fn test_caret_within() {
+        let span = Span::new("Hm7", 0, 1).within("C Am Hm7", 5);
+
+        assert_eq!(span.caret(), "C Am Hm7\n     ^");
+    }
+
+    //
+    // message
+    //
+
+    #[test]
+    fn test_message_with_suggestions() {
+        let e = ChordParseError::UnexpectedToken(
+            "couldn't make sense of n".to_string(),
+            Span::new("Gmn7", 2, 3),
+            vec!["Gm7".to_string(), "Gmaj7".to_string()],
+        );
+
+        assert_eq!(
+            e.message(),
+            "couldn't make sense of n, did you mean Gm7 or Gmaj7?"
+        );
+    }
+
+    #[test]
+    fn test_message_note() {
+        let e = NoteParseError::InvalidNoteStringValue(Span::new("C E H", 4, 5));
+
+        assert_eq!(e.message(), "H isn't a note");
+    }
+
+    //
+    // fmt
+    //
+
+    #[test]
+    fn test_fmt_is_the_message() {
+        let e = ChordParseError::UnexpectedToken(
+            "couldn't make sense of xyz".to_string(),
+            Span::new("Gxyz7", 1, 4),
+            vec![],
+        );
+
+        assert_eq!(e.to_string(), "couldn't make sense of xyz");
+    }
+
+    #[test]
+    fn test_fmt_key_and_scale() {
+        assert_eq!(
+            KeyParseError::InvalidKey("H major".to_string()).to_string(),
+            "H major isn't a key"
+        );
+        assert_eq!(
+            ScaleParseError::InvalidScale("C blah".to_string()).to_string(),
+            "C blah isn't a scale"
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use itertools::Itertools;
+use std::fmt;
+
+use crate::theory::{chord::get_add_from_interval, interval::Interval};
+
+// everything a chord has past its triad, kept as what each note is rather than a flat list of intervals
+// the 7th, then the tensions stacked on it, then anything added to a chord without one (Cadd9, C6)
+// and the chord tones left out, so C7(b9,#11)no5 is a minor 7th, a b9, a #11 and no 5th
+// the parser builds these up from the symbol and every other chord works them out from its intervals
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SeventhKind {
+    Diminished,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NinthKind {
+    Flat,
+    Natural,
+    Sharp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum EleventhKind {
+    Natural,
+    Sharp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ThirteenthKind {
+    Flat,
+    Natural,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Omission {
+    Third,
+    Fifth,
+}
+
+// the tensions can come more than once, a 7alt has both a b9 and a #9
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Extensions {
+    pub seventh: Option<SeventhKind>,
+    pub ninths: Vec<NinthKind>,
+    pub elevenths: Vec<EleventhKind>,
+    pub thirteenths: Vec<ThirteenthKind>,
+    // as written, an add2 stays a 2nd under the 3rd rather than becoming a 9th
+    pub adds: Vec<Interval>,
+    pub omissions: Vec<Omission>,
+}
+
+impl From<SeventhKind> for Interval {
+    fn from(kind: SeventhKind) -> Self {
+        match kind {
+            SeventhKind::Diminished => Interval::DiminishedSeventh,
+            SeventhKind::Minor => Interval::MinorSeventh,
+            SeventhKind::Major => Interval::MajorSeventh,
+        }
+    }
+}
+
+impl From<NinthKind> for Interval {
+    fn from(kind: NinthKind) -> Self {
+        match kind {
+            NinthKind::Flat => Interval::MinorNinth,
+            NinthKind::Natural => Interval::MajorNinth,
+            NinthKind::Sharp => Interval::AugmentedNinth,
+        }
+    }
+}
+
+impl From<EleventhKind> for Interval {
+    fn from(kind: EleventhKind) -> Self {
+        match kind {
+            EleventhKind::Natural => Interval::PerfectEleventh,
+            EleventhKind::Sharp => Interval::AugmentedEleventh,
+        }
+    }
+}
+
+impl From<ThirteenthKind> for Interval {
+    fn from(kind: ThirteenthKind) -> Self {
+        match kind {
+            ThirteenthKind::Flat => Interval::MinorThirteenth,
+            ThirteenthKind::Natural => Interval::MajorThirteenth,
+        }
+    }
+}
+
+impl Extensions {
+    // what each interval past the triad is, over a 7th a 2nd, 4th or 6th is the 9th, 11th or 13th
+    // voiced low, without one the natural tensions are adds and only the altered ones count as tensions
+    pub fn from_intervals(intervals: &[Interval]) -> Extensions {
+        let has = |interval: Interval| intervals.contains(&interval);
+        let has_third = has(Interval::MinorThird) || has(Interval::MajorThird);
+        let diminished_triad = has(Interval::MinorThird)
+            && has(Interval::DiminishedFifth)
+            && !has(Interval::PerfectFifth);
+
+        let mut extensions = Extensions {
+            seventh: if has(Interval::MinorSeventh) {
+                Some(SeventhKind::Minor)
+            } else if has(Interval::MajorSeventh) {
+                Some(SeventhKind::Major)
+            } else if diminished_triad && has(Interval::DiminishedSeventh) {
+                Some(SeventhKind::Diminished)
+            } else {
+                None
+            },
+            ..Default::default()
+        };
+
+        // the sus note stands in for the 3rd, it isn't anything extra
+        let sus = if has_third {
+            None
+        } else if has(Interval::PerfectFourth) {
+            Some(Interval::PerfectFourth)
+        } else if has(Interval::PerfectEleventh) {
+            Some(Interval::PerfectEleventh)
+        } else {
+            Some(Interval::MajorSecond)
+        };
+        let seventh = extensions.seventh.map(Interval::from);
+
+        for interval in intervals {
+            let in_triad = matches!(
+                interval,
+                Interval::MinorThird
+                    | Interval::MajorThird
+                    | Interval::DiminishedFifth
+                    | Interval::PerfectFifth
+                    | Interval::AugmentedFifth
+            );
+            if in_triad || Some(*interval) == seventh || Some(*interval) == sus {
+                continue;
+            }
+
+            let over_seventh = extensions.seventh.is_some();
+            match interval {
+                Interval::MinorNinth => extensions.ninths.push(NinthKind::Flat),
+                Interval::AugmentedNinth => extensions.ninths.push(NinthKind::Sharp),
+                Interval::AugmentedEleventh => extensions.elevenths.push(EleventhKind::Sharp),
+                Interval::MinorThirteenth => extensions.thirteenths.push(ThirteenthKind::Flat),
+                Interval::MajorNinth | Interval::MajorSecond if over_seventh => {
+                    extensions.ninths.push(NinthKind::Natural)
+                }
+                Interval::PerfectEleventh | Interval::PerfectFourth if over_seventh => {
+                    extensions.elevenths.push(EleventhKind::Natural)
+                }
+                Interval::MajorThirteenth | Interval::MajorSixth if over_seventh => {
+                    extensions.thirteenths.push(ThirteenthKind::Natural)
+                }
+                interval => extensions.adds.push(*interval),
+            }
+        }
+
+        // a major and minor 3rd together is a major 3rd with a #9
+        if has(Interval::MajorThird) && has(Interval::MinorThird) {
+            extensions.ninths.push(NinthKind::Sharp);
+        }
+
+        let has_fifth = has(Interval::DiminishedFifth)
+            || has(Interval::PerfectFifth)
+            || has(Interval::AugmentedFifth);
+        // a lone 5th is a power chord, there's no 3rd to leave out
+        if !has_third && sus.is_some_and(|s| !has(s)) && intervals != [Interval::PerfectFifth] {
+            extensions.omissions.push(Omission::Third);
+        }
+        if !has_fifth {
+            extensions.omissions.push(Omission::Fifth);
+        }
+
+        extensions.tidy();
+        extensions
+    }
+
+    // an add written into the symbol, over a 7th the natural tensions take their place above it
+    // and an added 7th is the chord's 7th (Gadd7 is a G7 spelled out)
+    pub fn add(&mut self, interval: Interval) {
+        match (interval, self.seventh) {
+            (Interval::MinorSeventh, None) => self.seventh = Some(SeventhKind::Minor),
+            (Interval::MajorNinth, Some(_)) => self.ninths.push(NinthKind::Natural),
+            (Interval::PerfectEleventh, Some(_)) => self.elevenths.push(EleventhKind::Natural),
+            (Interval::AugmentedEleventh, _) => self.elevenths.push(EleventhKind::Sharp),
+            (Interval::MajorThirteenth, Some(_)) => self.thirteenths.push(ThirteenthKind::Natural),
+            _ => self.adds.push(interval),
+        }
+
+        self.tidy();
+    }
+
+    // an altered tension swaps out the natural one if it's there, otherwise it's added,
+    // so C9(b9) ends up with just the b9 and C7(#11) gets one on top
+    // false for intervals that aren't a tension
+    pub fn alter(&mut self, altered: Interval) -> bool {
+        match altered {
+            Interval::MinorNinth | Interval::AugmentedNinth => {
+                let kind = match altered {
+                    Interval::MinorNinth => NinthKind::Flat,
+                    _ => NinthKind::Sharp,
+                };
+                match self.ninths.iter().position(|n| *n == NinthKind::Natural) {
+                    Some(index) => self.ninths[index] = kind,
+                    None => self.ninths.push(kind),
+                }
+            }
+            Interval::AugmentedEleventh => {
+                match self
+                    .elevenths
+                    .iter()
+                    .position(|e| *e == EleventhKind::Natural)
+                {
+                    Some(index) => self.elevenths[index] = EleventhKind::Sharp,
+                    None => self.elevenths.push(EleventhKind::Sharp),
+                }
+            }
+            Interval::MinorThirteenth => {
+                match self
+                    .thirteenths
+                    .iter()
+                    .position(|t| *t == ThirteenthKind::Natural)
+                {
+                    Some(index) => self.thirteenths[index] = ThirteenthKind::Flat,
+                    None => self.thirteenths.push(ThirteenthKind::Flat),
+                }
+            }
+            _ => return false,
+        }
+
+        self.tidy();
+        true
+    }
+
+    // the intervals these put on top of a triad, smallest first
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = self.stacked();
+        intervals.extend(&self.adds);
+        intervals.sort_by_key(|i| i.semitones());
+        intervals.dedup();
+        intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Extensions::default()
+    }
+
+    // the 7th and the tensions over it, without the adds
+    fn stacked(&self) -> Vec<Interval> {
+        self.seventh
+            .map(Interval::from)
+            .into_iter()
+            .chain(self.ninths.iter().map(|n| Interval::from(*n)))
+            .chain(self.elevenths.iter().map(|e| Interval::from(*e)))
+            .chain(self.thirteenths.iter().map(|t| Interval::from(*t)))
+            .collect()
+    }
+
+    // one of each in order of size, with no add doubling something already there
+    fn tidy(&mut self) {
+        self.ninths = sorted_unique(&self.ninths);
+        self.elevenths = sorted_unique(&self.elevenths);
+        self.thirteenths = sorted_unique(&self.thirteenths);
+
+        let stacked = self.stacked();
+        self.adds.retain(|a| !stacked.contains(a));
+        self.adds.sort_by_key(|a| a.semitones());
+        self.adds.dedup();
+    }
+}
+
+fn sorted_unique<T: Copy + Eq + std::hash::Hash>(kinds: &[T]) -> Vec<T>
+where
+    Interval: From<T>,
+{
+    kinds
+        .iter()
+        .copied()
+        .unique()
+        .sorted_by_key(|k| Interval::from(*k).semitones())
+        .collect()
+}
+
+impl Extensions {
+    // each part the way it'd go in a chord symbol, 7 b9 #11 add13 no5
+    pub fn parts(&self) -> Vec<String> {
+        let seventh = self.seventh.map(|s| match s {
+            SeventhKind::Diminished => "dim7",
+            SeventhKind::Minor => "7",
+            SeventhKind::Major => "maj7",
+        });
+        let ninths = self.ninths.iter().map(|n| match n {
+            NinthKind::Flat => "b9",
+            NinthKind::Natural => "9",
+            NinthKind::Sharp => "#9",
+        });
+        let elevenths = self.elevenths.iter().map(|e| match e {
+            EleventhKind::Natural => "11",
+            EleventhKind::Sharp => "#11",
+        });
+        let thirteenths = self.thirteenths.iter().map(|t| match t {
+            ThirteenthKind::Flat => "b13",
+            ThirteenthKind::Natural => "13",
+        });
+        let omissions = self.omissions.iter().map(|o| match o {
+            Omission::Third => "no3",
+            Omission::Fifth => "no5",
+        });
+
+        seventh
+            .into_iter()
+            .chain(ninths)
+            .chain(elevenths)
+            .chain(thirteenths)
+            .map(String::from)
+            .chain(self.adds.iter().map(|a| match get_add_from_interval(a) {
+                Some(add) => format!("add{}", add),
+                None => format!("add {}", a),
+            }))
+            .chain(omissions.map(String::from))
+            .collect()
+    }
+}
+
+impl fmt::Display for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.parts().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Interval::*;
+
+    //
+    // from_intervals
+    //
+
+    #[test]
+    fn test_from_intervals_stacked() {
+        let ret = Extensions::from_intervals(&[
+            MajorThird,
+            PerfectFifth,
+            MinorSeventh,
+            MinorNinth,
+            AugmentedEleventh,
+            MajorThirteenth,
+        ]);
+
+        assert_eq!(ret.seventh, Some(SeventhKind::Minor));
+        assert_eq!(ret.ninths, vec![NinthKind::Flat]);
+        assert_eq!(ret.elevenths, vec![EleventhKind::Sharp]);
+        assert_eq!(ret.thirteenths, vec![ThirteenthKind::Natural]);
+        assert!(ret.adds.is_empty());
+        assert_eq!(ret.to_string(), "7 b9 #11 13");
+    }
+
+    #[test]
+    fn test_from_intervals_adds_without_a_seventh() {
+        let ret = Extensions::from_intervals(&[MajorThird, PerfectFifth, MajorNinth]);
+
+        assert_eq!(ret.seventh, None);
+        assert_eq!(ret.adds, vec![MajorNinth]);
+        assert_eq!(ret.to_string(), "add9");
+    }
+
+    #[test]
+    fn test_from_intervals_low_tensions_over_a_seventh() {
+        let ret =
+            Extensions::from_intervals(&[MajorSecond, MajorThird, PerfectFifth, MinorSeventh]);
+
+        assert_eq!(ret.ninths, vec![NinthKind::Natural]);
+    }
+
+    #[test]
+    fn test_from_intervals_sus_and_omissions() {
+        assert!(Extensions::from_intervals(&[PerfectFourth, PerfectFifth]).is_empty());
+        assert!(Extensions::from_intervals(&[PerfectFifth]).is_empty());
+        assert_eq!(
+            Extensions::from_intervals(&[MajorThird, MinorSeventh]).omissions,
+            vec![Omission::Fifth]
+        );
+        assert_eq!(
+            Extensions::from_intervals(&[MinorThird, DiminishedFifth, DiminishedSeventh]).seventh,
+            Some(SeventhKind::Diminished)
+        );
+    }
+
+    //
+    // alter
+    //
+
+    #[test]
+    fn test_alter_swaps_the_natural() {
+        let mut ret =
+            Extensions::from_intervals(&[MajorThird, PerfectFifth, MinorSeventh, MajorNinth]);
+
+        assert!(ret.alter(MinorNinth));
+        assert!(ret.alter(AugmentedNinth));
+        assert!(!ret.alter(MajorThird));
+        assert_eq!(ret.ninths, vec![NinthKind::Flat, NinthKind::Sharp]);
+    }
+
+    //
+    // intervals
+    //
+
+    #[test]
+    fn test_intervals_by_size() {
+        let mut ret = Extensions::default();
+        ret.add(MajorNinth);
+        ret.add(MajorSecond);
+
+        assert_eq!(ret.intervals(), vec![MajorSecond, MajorNinth]);
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+
+use crate::theory::{
+    chord::{Chord, TriadQuality},
+    key::{Key, KeyQuality},
+    pitch_class::PitchClass,
+    roman_numeral::{roman_numeral, Accidental, Origin},
+};
+
+// what job a chord does in a key, home (tonic), leading away (pre-dominant) or pulling back home (dominant)
+// it goes by the degree the root is on, so ii and IV lead to V, and iii and vi stand in for I,
+// with the chromatic pre-dominants told apart, the Neapolitan bII, the augmented 6th and V of V
+// https://en.wikipedia.org/wiki/Function_(music)
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Predominant {
+    // IV or iv
+    Subdominant,
+    // ii, or iiø7 in minor
+    Supertonic,
+    // VI in minor, or bVI borrowed into major
+    Submediant,
+    // bII
+    Neapolitan,
+    // the b6 and #4 spreading out to the 5th, Ab C Eb F# in C
+    AugmentedSixth,
+    // II or #ivo, the dominant of the dominant
+    SecondaryDominant,
+}
+
+impl fmt::Display for Predominant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Predominant::Subdominant => write!(f, "subdominant"),
+            Predominant::Supertonic => write!(f, "supertonic"),
+            Predominant::Submediant => write!(f, "submediant"),
+            Predominant::Neapolitan => write!(f, "Neapolitan"),
+            Predominant::AugmentedSixth => write!(f, "augmented 6th"),
+            Predominant::SecondaryDominant => write!(f, "V of V"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HarmonicFunction {
+    Tonic,
+    Predominant(Predominant),
+    Dominant,
+}
+
+// tonic, pre-dominant (supertonic), dominant
+impl fmt::Display for HarmonicFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HarmonicFunction::Tonic => write!(f, "tonic"),
+            HarmonicFunction::Predominant(kind) => write!(f, "pre-dominant ({})", kind),
+            HarmonicFunction::Dominant => write!(f, "dominant"),
+        }
+    }
+}
+
+// None for chords that don't do any of these in the key, chromatic mediants and the like
+pub fn harmonic_function(chord: &Chord, key: &Key) -> Option<HarmonicFunction> {
+    let tonic = PitchClass::from(key.tonic);
+    let from_tonic = |n| tonic.distance(&PitchClass::from(n));
+    let has = |semitones| chord.notes.iter().any(|n| from_tonic(*n) == semitones);
+
+    let bass = chord.bass.unwrap_or(chord.root);
+    if from_tonic(bass) == 8 && has(6) {
+        return Some(HarmonicFunction::Predominant(Predominant::AugmentedSixth));
+    }
+
+    let numeral = roman_numeral(chord, key);
+    let triad = chord.triad_quality;
+    // iii and vi only stand in for I as they are in the key, E or A7 in C are leading somewhere else
+    let chromatic = numeral.origin == Origin::Chromatic;
+    let function = match (numeral.accidental, numeral.degree) {
+        (None, 2) | (None, 5) if chromatic => return None,
+        (None, 0) | (None, 2) | (Some(Accidental::Flat), 2) => HarmonicFunction::Tonic,
+        (None, 5) => match key.quality {
+            KeyQuality::Major => HarmonicFunction::Tonic,
+            KeyQuality::Minor => HarmonicFunction::Predominant(Predominant::Submediant),
+        },
+        (Some(Accidental::Flat), 5) => HarmonicFunction::Predominant(Predominant::Submediant),
+        // the major II isn't in either key, it's leading to V
+        (None, 1) if triad == TriadQuality::Major => {
+            HarmonicFunction::Predominant(Predominant::SecondaryDominant)
+        }
+        (Some(Accidental::Sharp), 3) if triad == TriadQuality::Diminished => {
+            HarmonicFunction::Predominant(Predominant::SecondaryDominant)
+        }
+        (None, 1) => HarmonicFunction::Predominant(Predominant::Supertonic),
+        (Some(Accidental::Flat), 1) if triad == TriadQuality::Major => {
+            HarmonicFunction::Predominant(Predominant::Neapolitan)
+        }
+        // bVII is iv's family, the backdoor to I
+        (None, 3) | (Some(Accidental::Flat), 6) => {
+            HarmonicFunction::Predominant(Predominant::Subdominant)
+        }
+        (None, 4) | (None, 6) | (Some(Accidental::Sharp), 6) => HarmonicFunction::Dominant,
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::note::Note};
+
+    fn function(name: &str, key: Key) -> Option<HarmonicFunction> {
+        harmonic_function(&identify_from_name(name.to_string()).expect("hmm"), &key)
+    }
+
+    //
+    // harmonic_function
+    //
+
+    #[test]
+    fn test_harmonic_function_major() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(function("C", key), Some(HarmonicFunction::Tonic));
+        assert_eq!(function("Am7", key), Some(HarmonicFunction::Tonic));
+        assert_eq!(
+            function("Dm7", key),
+            Some(HarmonicFunction::Predominant(Predominant::Supertonic))
+        );
+        assert_eq!(
+            function("F", key),
+            Some(HarmonicFunction::Predominant(Predominant::Subdominant))
+        );
+        assert_eq!(function("G7", key), Some(HarmonicFunction::Dominant));
+        assert_eq!(function("Bdim", key), Some(HarmonicFunction::Dominant));
+    }
+
+    #[test]
+    fn test_harmonic_function_minor() {
+        let key = Key::minor(Note::A);
+
+        assert_eq!(function("Am", key), Some(HarmonicFunction::Tonic));
+        assert_eq!(
+            function("F", key),
+            Some(HarmonicFunction::Predominant(Predominant::Submediant))
+        );
+        assert_eq!(
+            function("Bm7b5", key),
+            Some(HarmonicFunction::Predominant(Predominant::Supertonic))
+        );
+        assert_eq!(function("E7", key), Some(HarmonicFunction::Dominant));
+        assert_eq!(function("G#dim7", key), Some(HarmonicFunction::Dominant));
+    }
+
+    #[test]
+    fn test_harmonic_function_chromatic_predominants() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(
+            function("Db", key),
+            Some(HarmonicFunction::Predominant(Predominant::Neapolitan))
+        );
+        assert_eq!(
+            function("D7", key),
+            Some(HarmonicFunction::Predominant(
+                Predominant::SecondaryDominant
+            ))
+        );
+        assert_eq!(
+            function("Ab7", key),
+            Some(HarmonicFunction::Predominant(Predominant::AugmentedSixth))
+        );
+        assert_eq!(function("E", key), None);
+        assert_eq!(function("A7", key), None);
+    }
+
+    #[test]
+    fn test_harmonic_function_display() {
+        assert_eq!(
+            HarmonicFunction::Predominant(Predominant::Supertonic).to_string(),
+            "pre-dominant (supertonic)"
+        );
+    }
+}
@@ -1,7 +1,5 @@
+use crate::theory::{error::ChordParseError, note::Note, pitch_class::PitchClass};
 use core::fmt;
-use itertools::Itertools;
-
-use crate::theory::note::Note;
 
 pub const OCTAVE: [Note; 12] = [
     Note::C,
@@ -18,27 +16,34 @@ pub const OCTAVE: [Note; 12] = [
     Note::B,
 ];
 
-// number of semitone steps
 // https://en.wikipedia.org/wiki/Interval_(music)
 // names refer to chromatic scale positions so we don't need to worry about scales when finding chords intervals
+// some intervals are the same number of semitones under a different name (a major 6th and a diminished 7th),
+// so the semitones live in semitones() rather than being the enum values
+// variants are in order of size so comparisons go smallest to largest
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd)]
 pub enum Interval {
     // we only consider the ones relevant to naming for now
-    MajorSecond = 2,
-    MinorThird = 3,
-    MajorThird = 4,
-    PerfectFourth = 5,
-    DiminishedFifth = 6,
-    PerfectFifth = 7,
-    AugmentedFifth = 8,
-    DiminishedSeventh = 9,
-    MinorSeventh = 10,
-    Seventh = 11,
-    DiminishedNinth = 12,
-    MinorNinth = 13,
-    MajorNinth = 14,
-    PerfectEleventh = 17,
-    Unknown = 100, // TODO: cheese for now
+    MajorSecond,
+    MinorThird,
+    MajorThird,
+    PerfectFourth,
+    DiminishedFifth,
+    PerfectFifth,
+    AugmentedFifth,
+    MajorSixth,
+    DiminishedSeventh,
+    MinorSeventh,
+    MajorSeventh,
+    DiminishedNinth,
+    MinorNinth,
+    MajorNinth,
+    AugmentedNinth,
+    PerfectEleventh,
+    AugmentedEleventh,
+    MinorThirteenth,
+    MajorThirteenth,
+    Unknown, // TODO: cheese for now
 }
 
 impl From<usize> for Interval {
@@ -53,11 +58,15 @@ impl From<usize> for Interval {
             8 => Interval::AugmentedFifth,
             9 => Interval::DiminishedSeventh,
             10 => Interval::MinorSeventh,
-            11 => Interval::Seventh,
+            11 => Interval::MajorSeventh,
             12 => Interval::DiminishedNinth,
             13 => Interval::MinorNinth,
             14 => Interval::MajorNinth,
+            15 => Interval::AugmentedNinth,
             17 => Interval::PerfectEleventh,
+            18 => Interval::AugmentedEleventh,
+            20 => Interval::MinorThirteenth,
+            21 => Interval::MajorThirteenth,
             _ => Interval::Unknown,
         }
     }
@@ -67,39 +76,118 @@ impl fmt::Display for Interval {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Interval::MajorSecond => write!(f, "Major 2nd"),
-            Interval::MinorThird => write!(f, "Minor 2nd"),
+            Interval::MinorThird => write!(f, "Minor 3rd"),
             Interval::MajorThird => write!(f, "Major 3rd"),
-            Interval::PerfectFourth => write!(f, "Perfect 5th"),
+            Interval::PerfectFourth => write!(f, "Perfect 4th"),
             Interval::DiminishedFifth => write!(f, "Diminished 5th"),
             Interval::PerfectFifth => write!(f, "Perfect 5th"),
             Interval::AugmentedFifth => write!(f, "Augmented 5th"),
+            Interval::MajorSixth => write!(f, "Major 6th"),
             Interval::MinorSeventh => write!(f, "Minor 7th"),
-            Interval::Seventh => write!(f, "5th"),
+            Interval::MajorSeventh => write!(f, "Major 7th"),
             Interval::DiminishedSeventh => write!(f, "Diminished 7th"),
             Interval::DiminishedNinth => write!(f, "Diminished 9th"),
             Interval::MinorNinth => write!(f, "Minor 9th"),
-            Interval::MajorNinth => write!(f, "Minor 9th"),
+            Interval::MajorNinth => write!(f, "Major 9th"),
+            Interval::AugmentedNinth => write!(f, "Augmented 9th"),
             Interval::PerfectEleventh => write!(f, "Perfect 11th"),
+            Interval::AugmentedEleventh => write!(f, "Augmented 11th"),
+            Interval::MinorThirteenth => write!(f, "Minor 13th"),
+            Interval::MajorThirteenth => write!(f, "Major 13th"),
             Interval::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+impl Interval {
+    // number of semitone steps
+    pub fn semitones(&self) -> usize {
+        match self {
+            Interval::MajorSecond => 2,
+            Interval::MinorThird => 3,
+            Interval::MajorThird => 4,
+            Interval::PerfectFourth => 5,
+            Interval::DiminishedFifth => 6,
+            Interval::PerfectFifth => 7,
+            Interval::AugmentedFifth => 8,
+            Interval::MajorSixth | Interval::DiminishedSeventh => 9,
+            Interval::MinorSeventh => 10,
+            Interval::MajorSeventh => 11,
+            Interval::DiminishedNinth => 12,
+            Interval::MinorNinth => 13,
+            Interval::MajorNinth => 14,
+            Interval::AugmentedNinth => 15,
+            Interval::PerfectEleventh => 17,
+            Interval::AugmentedEleventh => 18,
+            Interval::MinorThirteenth => 20,
+            Interval::MajorThirteenth => 21,
+            Interval::Unknown => 100,
+        }
+    }
+
+    // how many letters up the interval goes, a 3rd of any size is two (C to E, C to Eb)
+    // and a 9th is a 2nd an octave up so it's one, None for Unknown
+    pub fn letter_steps(&self) -> Option<usize> {
+        match self {
+            Interval::MajorSecond
+            | Interval::DiminishedNinth
+            | Interval::MinorNinth
+            | Interval::MajorNinth
+            | Interval::AugmentedNinth => Some(1),
+            Interval::MinorThird | Interval::MajorThird => Some(2),
+            Interval::PerfectFourth | Interval::PerfectEleventh | Interval::AugmentedEleventh => {
+                Some(3)
+            }
+            Interval::DiminishedFifth | Interval::PerfectFifth | Interval::AugmentedFifth => {
+                Some(4)
+            }
+            Interval::MajorSixth | Interval::MinorThirteenth | Interval::MajorThirteenth => Some(5),
+            Interval::DiminishedSeventh | Interval::MinorSeventh | Interval::MajorSeventh => {
+                Some(6)
+            }
+            Interval::Unknown => None,
+        }
+    }
+
+    // the role a note at this interval plays in a chord, octave doubling aside
+    // e.g. both thirds are the 3rd, both sevenths the 7th
+    pub fn chord_tone_name(&self) -> &'static str {
+        match self {
+            Interval::MajorSecond => "2nd",
+            Interval::MinorThird | Interval::MajorThird => "3rd",
+            Interval::PerfectFourth => "4th",
+            Interval::DiminishedFifth | Interval::PerfectFifth | Interval::AugmentedFifth => "5th",
+            Interval::MajorSixth => "6th",
+            Interval::DiminishedSeventh | Interval::MinorSeventh | Interval::MajorSeventh => "7th",
+            Interval::DiminishedNinth
+            | Interval::MinorNinth
+            | Interval::MajorNinth
+            | Interval::AugmentedNinth => "9th",
+            Interval::PerfectEleventh | Interval::AugmentedEleventh => "11th",
+            Interval::MinorThirteenth | Interval::MajorThirteenth => "13th",
+            Interval::Unknown => "unknown",
+        }
+    }
+}
+
 // get this many semitones above the note
-pub fn get_interval(note: &Note, interval: Interval) -> &Note {
-    // get where the root note is in octave
-    let root_index = match OCTAVE.iter().position(|x| x == note) {
-        Some(res) => res,
-        None => 0, // TODO: fix this
-    };
-
-    // need to loop back around by 12 so
-    let interval_index = (root_index + interval as usize) % 12;
-
-    return match OCTAVE.get(interval_index) {
-        Some(res) => res,
-        None => &Note::A, // TODO: fix this
-    };
+// only the note, a 9th comes back the same as a 2nd, Pitch::add_interval keeps the octave
+// Unknown doesn't have a size, so there's no note to give back for it
+pub fn get_interval(note: &Note, interval: Interval) -> Result<Note, ChordParseError> {
+    if interval == Interval::Unknown {
+        return Err(ChordParseError::InvalidInterval(format!(
+            "no note {} above {}",
+            interval, note
+        )));
+    }
+
+    Ok((PitchClass::from(*note) + interval).note())
+}
+
+// how many semitones up from one note to the next, always within the octave (0 to 11)
+// unlike find_interval this covers every distance, even ones without an Interval like a semitone
+pub fn semitones_between(from: &Note, to: &Note) -> usize {
+    PitchClass::from(*from).distance(&PitchClass::from(*to))
 }
 
 // find what interval a note is from root
@@ -108,28 +196,7 @@ pub fn get_interval(note: &Note, interval: Interval) -> &Note {
 // and we use that to get implied octaves up for 9, 11
 // this seems a little jank but it can wait
 pub fn find_interval(root: &Note, note: &Note) -> Interval {
-    // we could use the integer values of the note enum, but feels more extensible to use the ordering in the octave array in this module
-    // we can find the integer position of the root, integer position of the note
-    let root_pos = OCTAVE
-        .into_iter()
-        .find_position(|e| *e == *root)
-        .expect("NOTE NOT PRESENT IN OCTAVE")
-        .0;
-
-    let mut note_pos = OCTAVE
-        .into_iter()
-        .find_position(|e| *e == *note)
-        .expect("NOTE NOT PRESENT IN OCTAVE")
-        .0;
-
-    // circular array
-    if note_pos < root_pos {
-        note_pos = note_pos + 12;
-    }
-
-    let semitones = note_pos - root_pos;
-
-    return Interval::from(semitones);
+    Interval::from(semitones_between(root, note))
 }
 
 #[cfg(test)]
@@ -146,9 +213,9 @@ mod tests {
         let root = Note::C;
         let interval = Interval::MajorThird;
 
-        let ret = get_interval(&root, interval);
+        let ret = get_interval(&root, interval).expect("hmm");
 
-        assert_eq!(*ret, Note::E);
+        assert_eq!(ret, Note::E);
     }
 
     // test the circular nature of the intervals
@@ -157,9 +224,9 @@ mod tests {
         let root = Note::G;
         let interval = Interval::PerfectFifth;
 
-        let ret = get_interval(&root, interval);
+        let ret = get_interval(&root, interval).expect("hmm");
 
-        assert_eq!(*ret, Note::D);
+        assert_eq!(ret, Note::D);
     }
 
     #[test]
@@ -167,9 +234,29 @@ mod tests {
         let root = Note::G;
         let interval = Interval::MinorSeventh;
 
-        let ret = get_interval(&root, interval);
+        let ret = get_interval(&root, interval).expect("hmm");
+
+        assert_eq!(ret, Note::F);
+    }
+
+    #[test]
+    fn test_get_interval_unknown_is_error() {
+        assert!(get_interval(&Note::C, Interval::Unknown).is_err());
+    }
+
+    //
+    // semitones_between
+    //
+
+    #[test]
+    fn test_semitones_between_semitone() {
+        assert_eq!(semitones_between(&Note::G, &Note::Gs), 1);
+    }
 
-        assert_eq!(*ret, Note::F);
+    #[test]
+    fn test_semitones_between_wraps() {
+        assert_eq!(semitones_between(&Note::A, &Note::C), 3);
+        assert_eq!(semitones_between(&Note::C, &Note::C), 0);
     }
 
     //
@@ -0,0 +1,183 @@
+use crate::theory::{chord::Chord, note::Note, pitch_class::PitchClass, spelling::spell_chord};
+
+// how far the top voice has to move between two notes, either up or down whichever is shorter
+fn top_voice_distance(from: &Note, to: &Note) -> usize {
+    PitchClass::from(*from).nearest_distance(&PitchClass::from(*to))
+}
+
+// the chord tones in close position from each possible bass note
+// only basses that make a proper inversion are kept, so nothing like a 9th in the bass
+pub fn get_inversions(chord: &Chord) -> Vec<Chord> {
+    let chord_tones: Vec<Note> = chord.chord_tones();
+
+    // whatever was written before the slash, C/E => C
+    let symbol = chord.symbol_over_bass();
+    // the bass spelled as the chord tone it is, the Ab in Db/Ab
+    let spelled = spell_chord(chord, None);
+    let written_bass = |bass: Note| {
+        spelled
+            .iter()
+            .find(|n| n.note() == bass)
+            .map_or(bass.to_string(), |n| n.to_string())
+    };
+
+    (0..chord_tones.len())
+        .map(|i| {
+            let mut notes = chord_tones.clone();
+            notes.rotate_left(i);
+
+            let bass = Some(notes[0]).filter(|b| *b != chord.root);
+            let name = match bass {
+                Some(bass) => format!("{}/{}", symbol, written_bass(bass)),
+                None => symbol.to_string(),
+            };
+
+            Chord {
+                name,
+                bass,
+                notes,
+                ..chord.clone()
+            }
+        })
+        .filter(|inversion| inversion.inversion().is_some())
+        .collect()
+}
+
+// pick an inversion for every chord so the top voice moves as little as possible
+// when there's a melody note for a chord the top voice is pulled towards it, landing on it if one of the inversions can
+// this is a shortest path over each chord's inversions, done one chord at a time keeping the cheapest way into each
+pub fn plan_inversions(chords: &[Chord], melody: Option<&[Note]>) -> Vec<Chord> {
+    // how much missing the melody note counts for compared to moving the top voice
+    const MELODY_WEIGHT: usize = 4;
+
+    let options: Vec<Vec<Chord>> = chords.iter().map(get_inversions).collect();
+
+    let melody_cost = |index: usize, option: &Chord| -> usize {
+        let target = melody.and_then(|m| m.get(index));
+        match (target, option.notes.last()) {
+            (Some(target), Some(top)) => top_voice_distance(top, target) * MELODY_WEIGHT,
+            _ => 0,
+        }
+    };
+
+    // (total cost so far, which option of the previous chord it came from)
+    let mut costs: Vec<Vec<(usize, usize)>> = vec![];
+
+    for (index, chord_options) in options.iter().enumerate() {
+        let chord_costs = chord_options
+            .iter()
+            .map(|option| {
+                let own_cost = melody_cost(index, option);
+
+                match (costs.last(), index.checked_sub(1)) {
+                    (Some(previous_costs), Some(previous_index)) => previous_costs
+                        .iter()
+                        .zip(options[previous_index].iter())
+                        .enumerate()
+                        .map(|(from, ((cost, _), previous))| {
+                            let movement = match (previous.notes.last(), option.notes.last()) {
+                                (Some(a), Some(b)) => top_voice_distance(a, b),
+                                _ => 0,
+                            };
+                            (cost + movement + own_cost, from)
+                        })
+                        .min()
+                        .unwrap_or((own_cost, 0)),
+                    _ => (own_cost, 0),
+                }
+            })
+            .collect();
+
+        costs.push(chord_costs);
+    }
+
+    // walk back from the cheapest last chord
+    let mut plan: Vec<Chord> = vec![];
+    let mut choice = costs.last().and_then(|last| {
+        last.iter()
+            .enumerate()
+            .min_by_key(|(_, (cost, _))| *cost)
+            .map(|(i, _)| i)
+    });
+
+    for index in (0..options.len()).rev() {
+        match choice {
+            Some(i) if i < options[index].len() => {
+                plan.push(options[index][i].clone());
+                choice = Some(costs[index][i].1);
+            }
+            _ => break,
+        }
+    }
+
+    plan.reverse();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn parse_all(names: &[&str]) -> Vec<Chord> {
+        names
+            .iter()
+            .map(|n| identify_from_name(n.to_string()).expect("hmm"))
+            .collect()
+    }
+
+    //
+    // get_inversions
+    //
+
+    #[test]
+    fn test_get_inversions_triad() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        let ret: Vec<String> = get_inversions(&chord).into_iter().map(|c| c.name).collect();
+
+        assert_eq!(ret, vec!["C", "C/E", "C/G"]);
+    }
+
+    #[test]
+    fn test_get_inversions_seventh_close_position() {
+        let chord = identify_from_name("G7".to_string()).expect("hmm");
+
+        let ret = get_inversions(&chord);
+
+        assert_eq!(ret.len(), 4);
+        assert_eq!(ret[3].name, "G7/F");
+        assert_eq!(ret[3].notes, vec![Note::F, Note::G, Note::B, Note::D]);
+    }
+
+    //
+    // plan_inversions
+    //
+
+    #[test]
+    fn test_plan_inversions_keeps_top_voice_smooth() {
+        let chords = parse_all(&["C", "F", "G"]);
+
+        let ret = plan_inversions(&chords, None);
+        let tops: Vec<Note> = ret.iter().filter_map(|c| c.notes.last().copied()).collect();
+
+        // every move of the top voice is a step or less
+        assert_eq!(ret.len(), 3);
+        for pair in tops.windows(2) {
+            assert!(top_voice_distance(&pair[0], &pair[1]) <= 2);
+        }
+    }
+
+    #[test]
+    fn test_plan_inversions_follows_melody() {
+        let chords = parse_all(&["C", "Am", "F", "G"]);
+        let melody = vec![Note::E, Note::E, Note::F, Note::D];
+
+        let ret = plan_inversions(&chords, Some(&melody));
+        let tops: Vec<Note> = ret.iter().filter_map(|c| c.notes.last().copied()).collect();
+
+        assert_eq!(tops, melody);
+        assert_eq!(ret[0].name, "C/G");
+        assert_eq!(ret[1].name, "Am");
+    }
+}
@@ -0,0 +1,244 @@
+use std::{fmt, str::FromStr};
+
+use crate::theory::{
+    chord::Chord, error::KeyParseError, note::Note, pitch_class::PitchClass, scale::Scale,
+    spelling::spell_tonic,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum KeyQuality {
+    Major,
+    Minor,
+}
+
+// a tonic and whether it's major or minor, the minor going by its natural minor scale
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Key {
+    pub tonic: Note,
+    pub quality: KeyQuality,
+}
+
+impl Key {
+    pub fn major(tonic: Note) -> Key {
+        Key {
+            tonic,
+            quality: KeyQuality::Major,
+        }
+    }
+
+    pub fn minor(tonic: Note) -> Key {
+        Key {
+            tonic,
+            quality: KeyQuality::Minor,
+        }
+    }
+
+    pub fn scale(&self) -> Scale {
+        match self.quality {
+            KeyQuality::Major => Scale::Major,
+            KeyQuality::Minor => Scale::NaturalMinor,
+        }
+    }
+
+    pub fn notes(&self) -> Vec<Note> {
+        self.scale().notes(&self.tonic)
+    }
+
+    // the key with the same notes the other way up, C major and A minor
+    pub fn relative(&self) -> Key {
+        let tonic = PitchClass::from(self.tonic);
+
+        match self.quality {
+            KeyQuality::Major => Key::minor(tonic.down(3).note()),
+            KeyQuality::Minor => Key::major(tonic.up(3).note()),
+        }
+    }
+
+    // the key on the same tonic the other way, C major and C minor
+    pub fn parallel(&self) -> Key {
+        match self.quality {
+            KeyQuality::Major => Key::minor(self.tonic),
+            KeyQuality::Minor => Key::major(self.tonic),
+        }
+    }
+
+    // the triads then the 7ths on every degree
+    pub fn diatonic_chords(&self) -> Vec<Chord> {
+        [3, 4]
+            .iter()
+            .flat_map(|size| self.scale().diatonic_chords(&self.tonic, *size))
+            .collect()
+    }
+
+    // the parallel key's chords that aren't in this one, for modal interchange,
+    // in C major the iv (Fm), bVI (G#) and bVII (A#) and their 7ths from C minor
+    pub fn borrowed_chords(&self) -> Vec<Chord> {
+        let notes = self.notes();
+
+        self.parallel()
+            .diatonic_chords()
+            .into_iter()
+            .filter(|c| !c.notes.iter().all(|n| notes.contains(n)))
+            .collect()
+    }
+}
+
+// C major, A minor
+// the tonic written the way the signature goes, Bb major and not A# major
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.quality {
+            KeyQuality::Major => write!(f, "{} major", spell_tonic(self)),
+            KeyQuality::Minor => write!(f, "{} minor", spell_tonic(self)),
+        }
+    }
+}
+
+// C, C major, Am or A minor, just the note is the major key
+// in any case the way it's printed or typed quickly, C Major, c major or bb minor
+impl FromStr for Key {
+    type Err = KeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || KeyParseError::InvalidKey(s.to_string());
+
+        let (tonic, quality) = match s.split_once(char::is_whitespace) {
+            Some((tonic, mode)) => match mode.trim().to_lowercase().as_str() {
+                "major" => (tonic, KeyQuality::Major),
+                "minor" => (tonic, KeyQuality::Minor),
+                _ => return Err(invalid()),
+            },
+            None => match s.strip_suffix('m') {
+                Some(tonic) => (tonic, KeyQuality::Minor),
+                None => (s, KeyQuality::Major),
+            },
+        };
+
+        let mut chars = tonic.chars();
+        let tonic = match chars.next() {
+            Some(letter) => format!("{}{}", letter.to_ascii_uppercase(), chars.as_str()),
+            None => return Err(invalid()),
+        };
+        let tonic = Note::parse(&tonic).map_err(|_| invalid())?;
+
+        Ok(Key { tonic, quality })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // fmt
+    //
+
+    #[test]
+    fn test_fmt_spells_the_tonic() {
+        assert_eq!(Key::major(Note::As).to_string(), "Bb major");
+        assert_eq!(Key::minor(Note::Ds).to_string(), "D# minor");
+        assert_eq!(Key::minor(Note::F).to_string(), "F minor");
+        assert_eq!(Key::major(Note::Fs).to_string(), "F# major");
+    }
+
+    //
+    // from_str
+    //
+
+    #[test]
+    fn test_from_str_spellings() {
+        assert_eq!(Key::from_str("C").expect("hmm"), Key::major(Note::C));
+        assert_eq!(
+            Key::from_str("Bb major").expect("hmm"),
+            Key::major(Note::As)
+        );
+        assert_eq!(Key::from_str("F#m").expect("hmm"), Key::minor(Note::Fs));
+        assert_eq!(
+            Key::from_str(" A minor ").expect("hmm"),
+            Key::minor(Note::A)
+        );
+    }
+
+    #[test]
+    fn test_from_str_any_case() {
+        assert_eq!(Key::from_str("C Major").expect("hmm"), Key::major(Note::C));
+        assert_eq!(Key::from_str("c major").expect("hmm"), Key::major(Note::C));
+        assert_eq!(Key::from_str("a minor").expect("hmm"), Key::minor(Note::A));
+        assert_eq!(
+            Key::from_str("bb MINOR").expect("hmm"),
+            Key::minor(Note::As)
+        );
+        assert_eq!(Key::from_str("f#m").expect("hmm"), Key::minor(Note::Fs));
+    }
+
+    #[test]
+    fn test_from_str_roundtrip() {
+        let key = Key::major(Note::As);
+
+        assert_eq!(Key::from_str(&key.to_string()).expect("hmm"), key);
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!(Key::from_str("H").is_err());
+        assert!(Key::from_str("C lydian").is_err());
+        assert!(Key::from_str("").is_err());
+    }
+
+    //
+    // relative
+    //
+
+    //
+    // parallel
+    //
+
+    #[test]
+    fn test_parallel_both_ways() {
+        assert_eq!(Key::major(Note::C).parallel(), Key::minor(Note::C));
+        assert_eq!(Key::minor(Note::Fs).parallel(), Key::major(Note::Fs));
+    }
+
+    //
+    // borrowed_chords
+    //
+
+    #[test]
+    fn test_borrowed_chords_major() {
+        let ret: Vec<String> = Key::major(Note::C)
+            .borrowed_chords()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+
+        for name in ["Cm", "Fm", "G#", "A#", "Fm7", "A#7"] {
+            assert!(ret.contains(&name.to_string()), "{} missing", name);
+        }
+        // G minor's Gm is out of C major, but not the G the two keys share
+        assert!(!ret.contains(&"G".to_string()));
+    }
+
+    #[test]
+    fn test_borrowed_chords_minor() {
+        let ret: Vec<String> = Key::minor(Note::A)
+            .borrowed_chords()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+
+        assert!(ret.contains(&"A".to_string()));
+        assert!(ret.contains(&"D".to_string()));
+        assert!(!ret.contains(&"C".to_string()));
+    }
+
+    #[test]
+    fn test_relative_both_ways() {
+        assert_eq!(Key::major(Note::C).relative(), Key::minor(Note::A));
+        assert_eq!(Key::minor(Note::E).relative(), Key::major(Note::G));
+        assert_eq!(
+            Key::major(Note::Ds).relative().relative(),
+            Key::major(Note::Ds)
+        );
+    }
+}
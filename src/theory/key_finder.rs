@@ -0,0 +1,365 @@
+use std::fmt;
+
+use crate::theory::{
+    chord::{Chord, TriadQuality},
+    interval::OCTAVE,
+    key::{Key, KeyQuality},
+    pitch_class::PitchClass,
+};
+
+// which keys a progression is most likely in, scored out of 100
+// mostly how many of the chords' notes are in the key, the rest is for the things that say where home is,
+// the tonic chord turning up (most of all at the end), and the V chord that leads back to it
+// relative keys have the same notes, so it's those that tell C major from A minor
+
+const FIT_WEIGHT: f64 = 70.0;
+const HAS_TONIC: u8 = 10;
+const STARTS_ON_TONIC: u8 = 5;
+const ENDS_ON_TONIC: u8 = 10;
+const HAS_DOMINANT: u8 = 5;
+
+// for following the key through a progression, what each chord costs in a key and what changing key costs
+// a chord note out of the key costs most, the tonic and a V chord going to it take a little off for saying where home is,
+// and changing has to save more than a chromatic chord or two would cost, so A7 in C stays a V of ii
+const OUT_OF_KEY: i32 = 10;
+const TONIC_CHORD: i32 = -5;
+const DOMINANT_CHORD: i32 = -3;
+const KEY_CHANGE: i32 = 15;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct KeyMatch {
+    pub key: Key,
+    pub confidence: u8,
+}
+
+// C major (92%)
+impl fmt::Display for KeyMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}%)", self.key, self.confidence)
+    }
+}
+
+// the key's notes, a minor key taking the raised 7th as well for its major V chord
+fn key_notes(key: &Key) -> Vec<PitchClass> {
+    let mut notes: Vec<PitchClass> = key.notes().into_iter().map(PitchClass::from).collect();
+    if key.quality == KeyQuality::Minor {
+        notes.push(PitchClass::from(key.tonic).down(1));
+    }
+
+    notes
+}
+
+fn is_tonic(chord: &Chord, key: &Key) -> bool {
+    let quality = match key.quality {
+        KeyQuality::Major => TriadQuality::Major,
+        KeyQuality::Minor => TriadQuality::Minor,
+    };
+
+    chord.root == key.tonic && chord.triad_quality == quality
+}
+
+fn is_dominant(chord: &Chord, key: &Key) -> bool {
+    PitchClass::from(key.tonic).up(7) == PitchClass::from(chord.root)
+        && chord.triad_quality == TriadQuality::Major
+}
+
+pub fn key_confidence(chords: &[Chord], key: &Key) -> u8 {
+    let notes = key_notes(key);
+
+    let tones: Vec<PitchClass> = chords
+        .iter()
+        .flat_map(|c| c.chord_tones())
+        .map(PitchClass::from)
+        .collect();
+    if tones.is_empty() {
+        return 0;
+    }
+    let fit = tones.iter().filter(|t| notes.contains(t)).count() as f64 / tones.len() as f64;
+
+    let mut confidence = (fit * FIT_WEIGHT).round() as u8;
+    if chords.iter().any(|c| is_tonic(c, key)) {
+        confidence += HAS_TONIC;
+    }
+    if chords.first().is_some_and(|c| is_tonic(c, key)) {
+        confidence += STARTS_ON_TONIC;
+    }
+    if chords.last().is_some_and(|c| is_tonic(c, key)) {
+        confidence += ENDS_ON_TONIC;
+    }
+    if chords.iter().any(|c| is_dominant(c, key)) {
+        confidence += HAS_DOMINANT;
+    }
+
+    confidence
+}
+
+// every major and minor key, most likely first
+pub fn find_keys(chords: &[Chord]) -> Vec<KeyMatch> {
+    let mut matches: Vec<KeyMatch> = OCTAVE
+        .iter()
+        .flat_map(|tonic| [Key::major(*tonic), Key::minor(*tonic)])
+        .map(|key| KeyMatch {
+            key,
+            confidence: key_confidence(chords, &key),
+        })
+        .collect();
+
+    // stable, so majors stay ahead of minors they tie with
+    matches.sort_by_key(|m| std::cmp::Reverse(m.confidence));
+
+    matches
+}
+
+// a stretch of the progression in one key, start to end not including end
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct KeySection {
+    pub key: Key,
+    pub start: usize,
+    pub end: usize,
+    // the chord in both this key and the one before that it turned on, None for the first or a direct change
+    pub pivot: Option<usize>,
+}
+
+// the V chord only counts for the key when it goes on to the tonic, so C going to Am isn't a V in F
+fn chord_cost(chord: &Chord, next: Option<&Chord>, key: &Key, notes: &[PitchClass]) -> i32 {
+    let outside = chord
+        .chord_tones()
+        .into_iter()
+        .filter(|n| !notes.contains(&PitchClass::from(*n)))
+        .count() as i32;
+
+    let mut cost = outside * OUT_OF_KEY;
+    if is_tonic(chord, key) {
+        cost += TONIC_CHORD;
+    }
+    if is_dominant(chord, key) && next.is_some_and(|next| is_tonic(next, key)) {
+        cost += DOMINANT_CHORD;
+    }
+
+    cost
+}
+
+fn fits(chord: &Chord, key: &Key) -> bool {
+    let notes = key_notes(key);
+    chord
+        .chord_tones()
+        .iter()
+        .all(|n| notes.contains(&PitchClass::from(*n)))
+}
+
+// the key through the progression rather than one for the whole of it, a section for each key it goes into
+// every chord gets a key, the cheapest way through all of them with a cost for every change (Viterbi),
+// then each section keeps the key the path chose, find_keys only settling keys that cost the same
+pub fn find_modulations(chords: &[Chord]) -> Vec<KeySection> {
+    let keys: Vec<Key> = OCTAVE
+        .iter()
+        .flat_map(|tonic| [Key::major(*tonic), Key::minor(*tonic)])
+        .collect();
+    let notes: Vec<Vec<PitchClass>> = keys.iter().map(key_notes).collect();
+
+    // for every chord and key, the cheapest total getting there and the key the chord before was in
+    let mut best: Vec<Vec<(i32, usize)>> = vec![];
+    for (index, chord) in chords.iter().enumerate() {
+        let here = keys
+            .iter()
+            .enumerate()
+            .map(|(k, key)| {
+                let cost = chord_cost(chord, chords.get(index + 1), key, &notes[k]);
+                match index {
+                    0 => (cost, k),
+                    _ => best[index - 1]
+                        .iter()
+                        .enumerate()
+                        .map(|(from, (total, _))| {
+                            let change = if from == k { 0 } else { KEY_CHANGE };
+                            (total + change + cost, from)
+                        })
+                        // staying put wins a tie
+                        .min_by_key(|(total, from)| (*total, *from != k))
+                        .unwrap_or((cost, k)),
+                }
+            })
+            .collect();
+        best.push(here);
+    }
+
+    let Some(last) = best.last() else {
+        return vec![];
+    };
+    let mut key = last
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (total, _))| *total)
+        .map_or(0, |(k, _)| k);
+    let mut path = vec![0; chords.len()];
+    for index in (0..chords.len()).rev() {
+        path[index] = key;
+        key = best[index][key].1;
+    }
+
+    let mut sections: Vec<KeySection> = vec![];
+    let mut start = 0;
+    while start < chords.len() {
+        let end = path[start..]
+            .iter()
+            .position(|k| *k != path[start])
+            .map_or(chords.len(), |len| start + len);
+        // the path's key, unless another is just as cheap for these chords and find_keys likes it better
+        let section_cost = |k: usize| -> i32 {
+            (start..end)
+                .map(|i| chord_cost(&chords[i], chords.get(i + 1), &keys[k], &notes[k]))
+                .sum()
+        };
+        let cheapest = section_cost(path[start]);
+        let key = find_keys(&chords[start..end])
+            .into_iter()
+            .map(|m| m.key)
+            .find(|key| {
+                keys.iter()
+                    .position(|k| k == key)
+                    .is_some_and(|k| section_cost(k) == cheapest)
+            })
+            .unwrap_or(keys[path[start]]);
+
+        match sections.last_mut() {
+            Some(previous) if previous.key == key => previous.end = end,
+            previous => {
+                // the chord either side of the change that's in both keys, the later one first
+                let pivot = previous.and_then(|previous| {
+                    [start, start - 1]
+                        .into_iter()
+                        .find(|i| fits(&chords[*i], &previous.key) && fits(&chords[*i], &key))
+                });
+                sections.push(KeySection {
+                    key,
+                    start,
+                    end,
+                    pivot,
+                });
+            }
+        }
+
+        start = end;
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::note::Note};
+
+    fn progression(names: &[&str]) -> Vec<Chord> {
+        names
+            .iter()
+            .map(|n| identify_from_name(n.to_string()).expect("hmm"))
+            .collect()
+    }
+
+    //
+    // find_keys
+    //
+
+    #[test]
+    fn test_find_keys_major() {
+        let ret = find_keys(&progression(&["C", "Am", "F", "G7", "C"]));
+
+        assert_eq!(ret[0].key, Key::major(Note::C));
+        assert_eq!(ret[0].confidence, 100);
+    }
+
+    #[test]
+    fn test_find_keys_relative_minor() {
+        let ret = find_keys(&progression(&["Am", "Dm", "E7", "Am"]));
+
+        assert_eq!(ret[0].key, Key::minor(Note::A));
+        assert!(ret[0].confidence > ret[1].confidence);
+    }
+
+    #[test]
+    fn test_find_keys_every_key() {
+        let ret = find_keys(&progression(&["D", "G"]));
+
+        assert_eq!(ret.len(), 24);
+        assert!(ret.windows(2).all(|w| w[0].confidence >= w[1].confidence));
+    }
+
+    //
+    // key_confidence
+    //
+
+    #[test]
+    fn test_key_confidence_out_of_key() {
+        let chords = progression(&["C#", "F#", "G#"]);
+
+        assert!(key_confidence(&chords, &Key::major(Note::C)) < 30);
+    }
+
+    //
+    // find_modulations
+    //
+
+    #[test]
+    fn test_find_modulations_pivot_chord() {
+        let ret = find_modulations(&progression(&[
+            "C", "Dm", "G7", "C", "Am", "D7", "G", "C", "D7", "G",
+        ]));
+
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[0].key, Key::major(Note::C));
+        assert_eq!(ret[1].key, Key::major(Note::G));
+        assert_eq!(ret[0].end, ret[1].start);
+        assert_eq!(ret[1].end, 10);
+        // Am, vi in C and ii in G
+        assert_eq!(ret[1].pivot, Some(4));
+    }
+
+    #[test]
+    fn test_find_modulations_secondary_dominant_stays() {
+        let ret = find_modulations(&progression(&["C", "A7", "Dm", "G7", "C"]));
+
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].key, Key::major(Note::C));
+        assert_eq!((ret[0].start, ret[0].end), (0, 5));
+    }
+
+    #[test]
+    fn test_find_modulations_direct_change() {
+        let ret = find_modulations(&progression(&[
+            "C", "F", "G7", "C", "Db", "Gb", "Ab7", "Db",
+        ]));
+
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[1].key, Key::major(Note::Cs));
+        assert_eq!(ret[1].pivot, None);
+    }
+
+    #[test]
+    fn test_find_modulations_opens_in_c() {
+        let ret = find_modulations(&progression(&[
+            "C", "Am", "F", "G", "C", "D7", "G", "G", "D", "Bm", "Em", "A7", "D",
+        ]));
+
+        assert_eq!(ret[0].key, Key::major(Note::C));
+        assert_eq!((ret[0].start, ret[0].end), (0, 5));
+        assert_eq!(ret.last().expect("hmm").key, Key::major(Note::D));
+    }
+
+    #[test]
+    fn test_find_modulations_c_to_g() {
+        let ret = find_modulations(&progression(&[
+            "C", "Am", "F", "G", "C", "Am", "F", "G", "D7", "G", "C", "G",
+        ]));
+
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[0].key, Key::major(Note::C));
+        assert_eq!(ret[1].key, Key::major(Note::G));
+        assert_eq!(ret[1].end, 12);
+    }
+
+    #[test]
+    fn test_find_modulations_empty() {
+        assert!(find_modulations(&[]).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::theory::{
+    chord::Chord,
+    extensions::Extensions,
+    interval::{semitones_between, Interval},
+    note::Note,
+    spelling::spell_chord,
+};
+
+// a melody note that isn't in the chord sits on top as a tension, so it's named from the octave above
+// e.g. a D over C is the 9th rather than the 2nd
+// returns the tension and how it's written after the chord symbol
+fn get_tension(semitones: usize) -> Option<(Interval, &'static str)> {
+    match semitones {
+        1 => Some((Interval::MinorNinth, "b9")),
+        2 => Some((Interval::MajorNinth, "add9")),
+        3 => Some((Interval::AugmentedNinth, "#9")),
+        5 => Some((Interval::PerfectEleventh, "add11")),
+        6 => Some((Interval::AugmentedEleventh, "#11")),
+        8 => Some((Interval::MinorThirteenth, "b13")),
+        9 => Some((Interval::MajorThirteenth, "add13")),
+        10 => Some((Interval::MinorSeventh, "add7")),
+        _ => None,
+    }
+}
+
+// every way of voicing the chord in close position with the melody note as the highest note
+// if the melody isn't a chord tone it's added as a tension and the name says so, C with a D on top is C(add9)
+// only voicings with a proper inversion in the bass are given, root position first
+pub fn voice_under_melody(chord: &Chord, melody: &Note) -> Vec<Chord> {
+    let semitones = semitones_between(&chord.root, melody);
+
+    let is_chord_tone = semitones == 0
+        || chord
+            .intervals
+            .iter()
+            .any(|i| i.semitones() % 12 == semitones);
+
+    // whatever was written before the slash, C/E => C
+    let symbol = chord.symbol_over_bass();
+    // the bass spelled as the chord tone it is, the Ab in Db/Ab
+    let spelled = spell_chord(chord, None);
+    let written_bass = |bass: Note| {
+        spelled
+            .iter()
+            .find(|n| n.note() == bass)
+            .map_or(bass.to_string(), |n| n.to_string())
+    };
+
+    let (symbol, intervals) = if is_chord_tone {
+        (symbol.to_string(), chord.intervals.clone())
+    } else {
+        match get_tension(semitones) {
+            Some((tension, written)) => {
+                let mut intervals = chord.intervals.clone();
+                intervals.push(tension);
+                (format!("{}({})", symbol, written), intervals)
+            }
+            // something like a major 3rd over a sus4 just clashes
+            None => return vec![],
+        }
+    };
+
+    let below: Vec<Note> = chord
+        .chord_tones()
+        .into_iter()
+        .filter(|n| n != melody)
+        .collect();
+
+    (0..below.len())
+        .map(|i| {
+            let mut notes = below.clone();
+            notes.rotate_left(i);
+            notes.push(*melody);
+
+            let bass = Some(notes[0]).filter(|b| *b != chord.root);
+            let name = match bass {
+                Some(bass) => format!("{}/{}", symbol, written_bass(bass)),
+                None => symbol.clone(),
+            };
+
+            Chord {
+                name,
+                bass,
+                notes,
+                intervals: intervals.clone(),
+                extensions: Extensions::from_intervals(&intervals),
+                ..chord.clone()
+            }
+        })
+        .filter(|voicing| voicing.inversion().is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    //
+    // voice_under_melody
+    //
+
+    #[test]
+    fn test_voice_under_melody_chord_tone() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        let ret = voice_under_melody(&chord, &Note::E);
+
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[0].name, "C");
+        assert_eq!(ret[0].notes, vec![Note::C, Note::G, Note::E]);
+        assert_eq!(ret[1].name, "C/G");
+        assert_eq!(ret[1].notes, vec![Note::G, Note::C, Note::E]);
+    }
+
+    #[test]
+    fn test_voice_under_melody_respells_as_tension() {
+        let chord = identify_from_name("C7".to_string()).expect("hmm");
+
+        let ret = voice_under_melody(&chord, &Note::D);
+
+        assert_eq!(ret[0].name, "C7(add9)");
+        assert_eq!(
+            ret[0].notes,
+            vec![Note::C, Note::E, Note::G, Note::As, Note::D]
+        );
+        assert!(ret[0].intervals.contains(&Interval::MajorNinth));
+        assert!(ret.iter().all(|v| v.notes.last() == Some(&Note::D)));
+    }
+
+    #[test]
+    fn test_voice_under_melody_altered_tension_name_reparses() {
+        let chord = identify_from_name("G7".to_string()).expect("hmm");
+
+        let ret = voice_under_melody(&chord, &Note::Gs);
+        let reparsed = identify_from_name(ret[0].name.clone()).expect("hmm");
+
+        assert_eq!(ret[0].name, "G7(b9)");
+        assert!(reparsed.notes.contains(&Note::Gs));
+    }
+
+    #[test]
+    fn test_voice_under_melody_clash() {
+        let chord = identify_from_name("Csus4".to_string()).expect("hmm");
+
+        let ret = voice_under_melody(&chord, &Note::E);
+
+        assert!(ret.is_empty());
+    }
+}
@@ -1,4 +1,33 @@
+pub mod cadence;
 pub mod chord;
+pub mod chord_scale;
+pub mod circle_of_fifths;
+pub mod compare;
+pub mod degree_name;
 pub mod error;
+pub mod extensions;
+pub mod harmonic_function;
 pub mod interval;
+pub mod inversion_plan;
+pub mod key;
+pub mod key_finder;
+pub mod melody_voicing;
+pub mod morph;
+pub mod naming;
+pub mod nashville;
+pub mod negative_harmony;
 pub mod note;
+pub mod pitch;
+pub mod pitch_class;
+pub mod polychord;
+pub mod progression;
+pub mod progression_fill;
+pub mod roman_numeral;
+pub mod scale;
+pub mod solfege;
+pub mod spelling;
+pub mod template;
+pub mod tension;
+pub mod transpose;
+pub mod voice_leading;
+pub mod voicing;
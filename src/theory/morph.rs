@@ -0,0 +1,212 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{
+    parser::chord_parser::{identify_from_root_and_notes, score_chord, AMBIGUOUS_NAME},
+    theory::{chord::Chord, note::Note, pitch_class::PitchClass},
+};
+
+// a way from one chord to another a note at a time, every step moving one note by a semitone or a tone,
+// adding one of the end chord's notes or dropping one it doesn't have, and every chord on the way
+// having a name, so G7 to Cmaj7 goes by Cmaj7sus4 (the D down to C) then Cmaj7 (the F down to E)
+// it's a shortest path over sets of pitch classes, there are only 4096 of them
+
+// the notes of a chord as bits, C being 1
+type NoteSet = u16;
+
+fn note_set(notes: &[Note]) -> NoteSet {
+    notes
+        .iter()
+        .fold(0, |set, n| set | 1 << PitchClass::from(*n).value())
+}
+
+fn contains(set: NoteSet, pitch_class: u8) -> bool {
+    set & 1 << pitch_class != 0
+}
+
+// the most likely name for the notes in root position, None when there isn't one
+fn name_notes(notes: &[Note]) -> Option<Chord> {
+    (0..notes.len())
+        .map(|i| {
+            let mut rotated = notes.to_vec();
+            rotated.rotate_left(i);
+            identify_from_root_and_notes(&rotated[0], &rotated)
+        })
+        .filter(|c| c.name != AMBIGUOUS_NAME)
+        // max_by_key keeps the last of equals, so go backwards to keep the first
+        .rev()
+        .max_by_key(score_chord)
+}
+
+// every set one step from this one that stays between the two chords' sizes
+fn steps(set: NoteSet, target: NoteSet, sizes: (u32, u32)) -> Vec<NoteSet> {
+    let mut next = vec![];
+    let size = set.count_ones();
+
+    for pitch_class in (0..12).filter(|p| contains(set, *p)) {
+        // up and down a semitone, then up and down a tone
+        for distance in [1, 11, 2, 10] {
+            let moved = (pitch_class + distance) % 12;
+            if !contains(set, moved) {
+                next.push(set & !(1 << pitch_class) | 1 << moved);
+            }
+        }
+
+        if size > sizes.0 && !contains(target, pitch_class) {
+            next.push(set & !(1 << pitch_class));
+        }
+    }
+
+    if size < sizes.1 {
+        next.extend(
+            (0..12)
+                .filter(|p| contains(target, *p) && !contains(set, *p))
+                .map(|p| set | 1 << p),
+        );
+    }
+
+    next
+}
+
+// the chords from the first to the last, None if there's no way between them a named chord at a time
+pub fn morph(from: &Chord, to: &Chord) -> Option<Vec<Chord>> {
+    let start = note_set(&from.notes);
+    let target = note_set(&to.notes);
+    let sizes = (
+        start.count_ones().min(target.count_ones()),
+        start.count_ones().max(target.count_ones()),
+    );
+
+    // breadth first so the first time the target turns up it's by the fewest steps
+    let mut came_from: HashMap<NoteSet, NoteSet> = HashMap::new();
+    let mut named: HashMap<NoteSet, bool> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(set) = queue.pop_front() {
+        if set == target {
+            break;
+        }
+
+        for next in steps(set, target, sizes) {
+            if next == start || came_from.contains_key(&next) {
+                continue;
+            }
+
+            let has_name = next == target
+                || *named.entry(next).or_insert_with(|| {
+                    let notes: Vec<Note> = (0..12)
+                        .filter(|p| contains(next, *p))
+                        .map(|p| PitchClass::new(p as usize).note())
+                        .collect();
+                    name_notes(&notes).is_some()
+                });
+
+            if has_name {
+                came_from.insert(next, set);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if start != target && !came_from.contains_key(&target) {
+        return None;
+    }
+
+    let mut path = vec![target];
+    while let Some(previous) = path.last().and_then(|set| came_from.get(set)) {
+        path.push(*previous);
+    }
+    path.reverse();
+
+    Some(voice_path(from, to, &path))
+}
+
+// each moved note keeps its place in the chord so the names follow the voices,
+// the first and last are the chords as they were given
+fn voice_path(from: &Chord, to: &Chord, path: &[NoteSet]) -> Vec<Chord> {
+    let mut voices: Vec<Note> = from.notes.iter().copied().unique().collect();
+    let mut chords = vec![from.clone()];
+
+    for (set, next) in path.iter().tuple_windows() {
+        let removed = (0..12).find(|p| contains(*set, *p) && !contains(*next, *p));
+        let added = (0..12).find(|p| !contains(*set, *p) && contains(*next, *p));
+        let note = |p: u8| PitchClass::new(p as usize).note();
+
+        match (removed, added) {
+            (Some(removed), Some(added)) => {
+                for voice in voices.iter_mut().filter(|v| **v == note(removed)) {
+                    *voice = note(added);
+                }
+            }
+            (None, Some(added)) => voices.push(note(added)),
+            (Some(removed), None) => voices.retain(|v| *v != note(removed)),
+            (None, None) => (),
+        }
+
+        if *next == path[path.len() - 1] {
+            chords.push(to.clone());
+        } else if let Some(chord) = name_notes(&voices) {
+            chords.push(chord);
+        }
+    }
+
+    chords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn chord(name: &str) -> Chord {
+        identify_from_name(name.to_string()).expect("hmm")
+    }
+
+    //
+    // morph
+    //
+
+    #[test]
+    fn test_morph_one_note_at_a_time() {
+        let ret = morph(&chord("C"), &chord("Fm")).expect("hmm");
+        let names: Vec<&str> = ret.iter().map(|c| c.name.as_str()).collect();
+
+        // C E G to C F G# is two moves, E up to F and G up to G#
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[0], "C");
+        assert_eq!(names[2], "Fm");
+
+        for (a, b) in ret.iter().tuple_windows() {
+            let a = note_set(&a.notes);
+            let b = note_set(&b.notes);
+            assert!((a ^ b).count_ones() <= 2);
+        }
+    }
+
+    #[test]
+    fn test_morph_adds_notes() {
+        let ret = morph(&chord("C"), &chord("Cmaj7")).expect("hmm");
+        let names: Vec<&str> = ret.iter().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(names, vec!["C", "Cmaj7"]);
+    }
+
+    #[test]
+    fn test_morph_same_chord() {
+        let ret = morph(&chord("Am"), &chord("Am")).expect("hmm");
+
+        assert_eq!(ret.len(), 1);
+    }
+
+    //
+    // name_notes
+    //
+
+    #[test]
+    fn test_name_notes_root_position() {
+        let ret = name_notes(&[Note::E, Note::G, Note::C]).expect("hmm");
+
+        assert_eq!(ret.name, "C");
+    }
+}
@@ -0,0 +1,543 @@
+use itertools::Itertools;
+
+use crate::theory::{
+    chord::{get_add_from_interval, ChordStructure},
+    interval::Interval,
+    note::Note,
+};
+
+// how the number on a chord above 7 is read
+// the lead sheet convention is that the highest tension named brings the ones under it,
+// a G13 has the 7th, 9th and 11th as well (the 11th left out over a major 3rd) unless they're marked add
+// read literally it's just the 7th and the tension written, G13 is G B D F E and G13add9 puts the A back
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TensionReading {
+    #[default]
+    Implied,
+    Literal,
+}
+
+// names a chord from the intervals actually there rather than from a ChordQuality,
+// so anything with a 3rd (or a sus) and a 5th or 7th gets a symbol, adds, alterations and omissions included
+// the symbol is built up as root, quality and extension, sus, alterations, adds, then omissions
+// e.g. C E G Bb Db F# => C7(b9,#11), C G Bb => C7(no3)
+// None when there's nothing sensible to call it
+pub fn name_chord(root: &Note, intervals: &[Interval]) -> Option<String> {
+    name_chord_as(root, intervals, TensionReading::Implied)
+}
+
+// the same, with the extension written for how it's going to be read
+pub fn name_chord_as(
+    root: &Note,
+    intervals: &[Interval],
+    reading: TensionReading,
+) -> Option<String> {
+    if intervals.contains(&Interval::Unknown) {
+        return None;
+    }
+
+    // everything gets taken out of here as it's named, whatever's left over is an add or alteration
+    let mut rest: Vec<Interval> = intervals.to_vec();
+
+    let third = find_third(&mut rest);
+
+    let perfect_fifth = take(&mut rest, Interval::PerfectFifth);
+    let augmented_fifth = !perfect_fifth && take(&mut rest, Interval::AugmentedFifth);
+
+    // a b5 would replace the 5th when read back, alongside a perfect 5th it's a #11
+    let diminished_fifth = match perfect_fifth && take(&mut rest, Interval::DiminishedFifth) {
+        true => {
+            rest.push(Interval::AugmentedEleventh);
+            false
+        }
+        false => take(&mut rest, Interval::DiminishedFifth),
+    };
+
+    // 9 semitones up is only a diminished 7th on a diminished triad, anywhere else it's the 6th
+    let diminished = diminished_fifth && !perfect_fifth;
+    let diminished_triad = diminished && third == Third::Minor;
+    if !diminished_triad && take(&mut rest, Interval::DiminishedSeventh) {
+        rest.push(Interval::MajorSixth);
+    }
+
+    let seventh = if diminished_triad && take(&mut rest, Interval::DiminishedSeventh) {
+        Some(Seventh::Diminished)
+    } else if take(&mut rest, Interval::MinorSeventh) {
+        Some(Seventh::Minor)
+    } else if take(&mut rest, Interval::MajorSeventh) {
+        Some(Seventh::Major)
+    } else {
+        None
+    };
+
+    // a symbol only has room for the one 7th
+    if rest.iter().any(|i| {
+        matches!(
+            i,
+            Interval::DiminishedSeventh | Interval::MinorSeventh | Interval::MajorSeventh
+        )
+    }) {
+        return None;
+    }
+
+    // a raised 5th is only an augmented chord over a major 3rd,
+    // E G C would be an Em(#5) otherwise when it's just a C/E
+    if augmented_fifth && third != Third::Major {
+        return None;
+    }
+
+    let has_fifth = perfect_fifth || diminished_fifth || augmented_fifth;
+
+    // a lone 5th is a power chord, otherwise a chord needs its 3rd (or sus) and a 5th, or a 7th
+    if third == Third::None && seventh.is_none() {
+        return match (perfect_fifth, rest.is_empty()) {
+            (true, true) => Some(format!("{}5", root)),
+            _ => None,
+        };
+    }
+
+    if !has_fifth && seventh.is_none() {
+        return None;
+    }
+
+    // a 6th on a plain major or minor triad makes a 6 chord, C E G A is a C6 rather than a Cadd6
+    let sixth = seventh.is_none()
+        && perfect_fifth
+        && matches!(third, Third::Major | Third::Minor)
+        && take(&mut rest, Interval::MajorSixth);
+
+    // above a 7th the 2nd, 4th and 6th are really the 9th, 11th and 13th
+    if seventh.is_some() {
+        for interval in rest.iter_mut() {
+            *interval = match *interval {
+                Interval::MajorSecond => Interval::MajorNinth,
+                Interval::PerfectFourth => Interval::PerfectEleventh,
+                Interval::MajorSixth => Interval::MajorThirteenth,
+                other => other,
+            };
+        }
+        rest = rest.into_iter().unique_by(|i| i.semitones()).collect();
+    }
+
+    // the highest natural extension names the chord, but only when everything it implies is there
+    // as that's what it'll be read back as, an 11 brings the 9th and a 13 the 9th and 11th
+    // except over a major 3rd where the 11th clashes and is left out, C13 is C E G Bb D A
+    // anything short of that is an add instead, C E G Bb A is C7add13
+    // an altered 9th or 11th stands in for the natural one, C13(b9) is a 13 with a b9 instead of a 9,
+    // so with both there the natural can't be part of the extension, it'd be swapped out, C7(b9)add9
+    let altered_ninth =
+        rest.contains(&Interval::MinorNinth) || rest.contains(&Interval::AugmentedNinth);
+    let altered_eleventh = rest.contains(&Interval::AugmentedEleventh);
+    let ninth = altered_ninth || rest.contains(&Interval::MajorNinth);
+    let eleventh = altered_eleventh || rest.contains(&Interval::PerfectEleventh);
+    let thirteenth =
+        rest.contains(&Interval::MajorThirteenth) && !rest.contains(&Interval::MinorThirteenth);
+    let eleventh_implied = matches!(third, Third::Minor | Third::Sus2);
+
+    let extension = match (seventh, reading) {
+        (Some(Seventh::Diminished) | None, _) => "7",
+        // read literally only the highest tension's in the name, anything under it is an add
+        (Some(_), TensionReading::Literal) => {
+            if thirteenth && take(&mut rest, Interval::MajorThirteenth) {
+                "13"
+            } else if third != Third::Sus4
+                && !altered_eleventh
+                && take(&mut rest, Interval::PerfectEleventh)
+            {
+                "11"
+            } else if !altered_ninth && take(&mut rest, Interval::MajorNinth) {
+                "9"
+            } else {
+                "7"
+            }
+        }
+        (Some(_), TensionReading::Implied) => {
+            if ninth && (eleventh || !eleventh_implied) && thirteenth {
+                take(&mut rest, Interval::MajorThirteenth);
+                if !altered_ninth {
+                    take(&mut rest, Interval::MajorNinth);
+                }
+                if eleventh_implied && !altered_eleventh {
+                    take(&mut rest, Interval::PerfectEleventh);
+                }
+                "13"
+            } else if ninth
+                && third != Third::Sus4
+                && !altered_eleventh
+                && take(&mut rest, Interval::PerfectEleventh)
+            {
+                if !altered_ninth {
+                    take(&mut rest, Interval::MajorNinth);
+                }
+                "11"
+            } else if !altered_ninth && take(&mut rest, Interval::MajorNinth) {
+                "9"
+            } else {
+                "7"
+            }
+        }
+    };
+
+    let mut alterations: Vec<&str> = vec![];
+
+    let quality = match (third, seventh) {
+        (Third::Minor, None) if diminished => "dim".to_string(),
+        (Third::Minor, None) if sixth => "m6".to_string(),
+        (Third::Minor, Some(Seventh::Diminished)) => "dim7".to_string(),
+        (Third::Minor, Some(Seventh::Minor)) if diminished => {
+            format!("m{}b5", extension)
+        }
+        (Third::Minor, None) => "m".to_string(),
+        (Third::Minor, Some(Seventh::Minor)) => format!("m{}", extension),
+        (Third::Minor, Some(_)) => format!("m(maj{})", extension),
+        (Third::Major, None) if augmented_fifth => "aug".to_string(),
+        (Third::Major, Some(Seventh::Minor)) if augmented_fifth => format!("aug{}", extension),
+        (_, Some(Seventh::Major)) => format!("maj{}", extension),
+        (_, Some(_)) => extension.to_string(),
+        (_, None) if sixth => "6".to_string(),
+        (_, None) => "".to_string(),
+    };
+
+    // fifths the quality didn't already account for
+    if diminished_fifth && !quality.starts_with("dim") && !quality.ends_with("b5") {
+        alterations.push("b5");
+    }
+    if augmented_fifth && !quality.starts_with("aug") {
+        alterations.push("#5");
+    }
+
+    let sus = match third {
+        Third::Sus2 => "sus2",
+        Third::Sus4 => "sus4",
+        _ => "",
+    };
+
+    // alterations go in brackets after a 7th, anything natural left over is an add
+    let mut adds: Vec<String> = vec![];
+    for interval in rest.iter().sorted_by_key(|i| i.semitones()) {
+        match (interval, seventh) {
+            (Interval::MinorNinth, _) => alterations.push("b9"),
+            (Interval::AugmentedNinth, _) => alterations.push("#9"),
+            (Interval::AugmentedEleventh, Some(_)) => alterations.push("#11"),
+            (Interval::MinorThirteenth, _) => alterations.push("b13"),
+            (interval, _) => match get_add_from_interval(interval) {
+                Some(add) => adds.push(format!("add{}", add)),
+                None => return None,
+            },
+        }
+    }
+
+    let alterations = match alterations.is_empty() {
+        true => "".to_string(),
+        false => format!("({})", alterations.join(",")),
+    };
+
+    // 7ths hold up without a 5th or even a 3rd, they're left out of real voicings all the time
+    let omissions = match (third, has_fifth) {
+        (Third::None, false) => "(no3,no5)",
+        (Third::None, true) => "(no3)",
+        (_, false) => "(no5)",
+        _ => "",
+    };
+
+    Some(format!(
+        "{}{}{}{}{}{}",
+        root,
+        quality,
+        sus,
+        alterations,
+        adds.join(""),
+        omissions
+    ))
+}
+
+// a symbol written with the music symbols instead, Bbmaj7 is B♭Δ7, Cdim7 is C°7 and F#m7b5 is F♯ø7
+// every lowercase b in a symbol is a flat, the note names are capitals and none of the words have one
+pub fn pretty_symbol(name: &str) -> String {
+    name.replace("m7b5", "ø7")
+        .replace("maj", "Δ")
+        .replace("dim", "°")
+        .replace("aug", "+")
+        .replace('b', "♭")
+        .replace('#', "♯")
+}
+
+// stacked chords are named for how they're built, with how many notes high the stack goes past three
+// so D G C is D quartal and D G C F is D quartal 4
+pub fn name_stacked_chord(root: &Note, structure: ChordStructure, count: usize) -> String {
+    match count {
+        3 => format!("{} {}", root, structure),
+        _ => format!("{} {} {}", root, structure, count),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Third {
+    Major,
+    Minor,
+    Sus2,
+    Sus4,
+    None,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Seventh {
+    Minor,
+    Major,
+    Diminished,
+}
+
+// a major and minor 3rd together is really a major 3rd with a #9 on top
+// without a 3rd a 4th (or 11th) makes it sus4, otherwise a 2nd makes it sus2
+fn find_third(rest: &mut Vec<Interval>) -> Third {
+    let major = take(rest, Interval::MajorThird);
+
+    if major {
+        if take(rest, Interval::MinorThird) {
+            rest.push(Interval::AugmentedNinth);
+        }
+        return Third::Major;
+    }
+
+    if take(rest, Interval::MinorThird) {
+        return Third::Minor;
+    }
+
+    if take(rest, Interval::PerfectFourth) || take(rest, Interval::PerfectEleventh) {
+        return Third::Sus4;
+    }
+
+    if take(rest, Interval::MajorSecond) {
+        return Third::Sus2;
+    }
+
+    Third::None
+}
+
+fn take(rest: &mut Vec<Interval>, interval: Interval) -> bool {
+    match rest.iter().position(|i| *i == interval) {
+        Some(index) => {
+            rest.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(intervals: &[Interval]) -> Option<String> {
+        name_chord(&Note::C, intervals)
+    }
+
+    //
+    // pretty_symbol
+    //
+
+    #[test]
+    fn test_pretty_symbol() {
+        assert_eq!(pretty_symbol("Bbmaj7"), "B♭Δ7");
+        assert_eq!(pretty_symbol("Cdim7"), "C°7");
+        assert_eq!(pretty_symbol("F#m7b5"), "F♯ø7");
+        assert_eq!(pretty_symbol("Caug"), "C+");
+        assert_eq!(pretty_symbol("C7(b9,#11)/Bb"), "C7(♭9,♯11)/B♭");
+        assert_eq!(pretty_symbol("Csus4add9"), "Csus4add9");
+    }
+
+    //
+    // name_chord
+    //
+
+    #[test]
+    fn test_name_chord_triads() {
+        use Interval::*;
+
+        assert_eq!(name(&[MajorThird, PerfectFifth]).expect("hmm"), "C");
+        assert_eq!(name(&[MinorThird, PerfectFifth]).expect("hmm"), "Cm");
+        assert_eq!(name(&[MinorThird, DiminishedFifth]).expect("hmm"), "Cdim");
+        assert_eq!(name(&[MajorThird, AugmentedFifth]).expect("hmm"), "Caug");
+        assert_eq!(name(&[MajorSecond, PerfectFifth]).expect("hmm"), "Csus2");
+        assert_eq!(name(&[PerfectFourth, PerfectFifth]).expect("hmm"), "Csus4");
+        assert_eq!(name(&[PerfectFifth]).expect("hmm"), "C5");
+    }
+
+    #[test]
+    fn test_name_chord_sevenths_and_extensions() {
+        use Interval::*;
+
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MajorSeventh]).expect("hmm"),
+            "Cmaj7"
+        );
+        assert_eq!(
+            name(&[MinorThird, DiminishedFifth, MinorSeventh]).expect("hmm"),
+            "Cm7b5"
+        );
+        assert_eq!(
+            name(&[MinorThird, PerfectFifth, MajorSeventh, MajorNinth]).expect("hmm"),
+            "Cm(maj9)"
+        );
+        assert_eq!(
+            name(&[
+                MajorThird,
+                PerfectFifth,
+                MinorSeventh,
+                MajorNinth,
+                MajorThirteenth
+            ])
+            .expect("hmm"),
+            "C13"
+        );
+    }
+
+    #[test]
+    fn test_name_chord_sixths() {
+        use Interval::*;
+
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MajorSixth]).expect("hmm"),
+            "C6"
+        );
+        assert_eq!(
+            name(&[MinorThird, PerfectFifth, DiminishedSeventh]).expect("hmm"),
+            "Cm6"
+        );
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MajorSixth, MajorNinth]).expect("hmm"),
+            "C6add9"
+        );
+        // over a diminished 5th it's still the 7th
+        assert_eq!(
+            name(&[MinorThird, DiminishedFifth, DiminishedSeventh]).expect("hmm"),
+            "Cdim7"
+        );
+    }
+
+    #[test]
+    fn test_name_chord_adds_to_triads() {
+        use Interval::*;
+
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MajorThirteenth]).expect("hmm"),
+            "Cadd13"
+        );
+        assert_eq!(
+            name(&[MajorSecond, MajorThird, PerfectFifth]).expect("hmm"),
+            "Cadd2"
+        );
+    }
+
+    // none of these have a ChordQuality of their own
+    #[test]
+    fn test_name_chord_alterations_and_omissions() {
+        use Interval::*;
+
+        assert_eq!(
+            name(&[
+                MajorThird,
+                PerfectFifth,
+                MinorSeventh,
+                MinorNinth,
+                AugmentedEleventh
+            ])
+            .expect("hmm"),
+            "C7(b9,#11)"
+        );
+        assert_eq!(
+            name(&[MajorThird, MinorThird, PerfectFifth, MinorSeventh]).expect("hmm"),
+            "C7(#9)"
+        );
+        assert_eq!(
+            name(&[MajorThird, DiminishedFifth, MinorSeventh]).expect("hmm"),
+            "C7(b5)"
+        );
+        assert_eq!(
+            name(&[MajorThird, AugmentedFifth, MajorSeventh]).expect("hmm"),
+            "Cmaj7(#5)"
+        );
+        assert_eq!(name(&[PerfectFifth, MinorSeventh]).expect("hmm"), "C7(no3)");
+        assert_eq!(name(&[MajorThird, MinorSeventh]).expect("hmm"), "C7(no5)");
+    }
+
+    // an extension is only used when everything it'd be read back as is there
+    #[test]
+    fn test_name_chord_extensions_must_be_complete() {
+        use Interval::*;
+
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MinorSeventh, MajorThirteenth]).expect("hmm"),
+            "C7add13"
+        );
+        assert_eq!(
+            name(&[
+                MinorThird,
+                PerfectFifth,
+                MinorSeventh,
+                MajorNinth,
+                MajorThirteenth
+            ])
+            .expect("hmm"),
+            "Cm9add13"
+        );
+        assert_eq!(
+            name(&[
+                MajorThird,
+                PerfectFifth,
+                MinorSeventh,
+                MinorNinth,
+                MajorNinth
+            ])
+            .expect("hmm"),
+            "C7(b9)add9"
+        );
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, DiminishedFifth, MinorSeventh]).expect("hmm"),
+            "C7(#11)"
+        );
+    }
+
+    #[test]
+    fn test_name_chord_as_literal() {
+        use Interval::*;
+
+        let literal = |intervals: &[Interval]| {
+            name_chord_as(&Note::C, intervals, TensionReading::Literal).expect("hmm")
+        };
+
+        assert_eq!(
+            literal(&[MajorThird, PerfectFifth, MinorSeventh, MajorThirteenth]),
+            "C13"
+        );
+        assert_eq!(
+            literal(&[
+                MajorThird,
+                PerfectFifth,
+                MinorSeventh,
+                MajorNinth,
+                MajorThirteenth
+            ]),
+            "C13add9"
+        );
+        assert_eq!(
+            literal(&[MinorThird, PerfectFifth, MinorSeventh, MajorNinth]),
+            "Cm9"
+        );
+        assert_eq!(literal(&[MajorThird, PerfectFifth, MajorSeventh]), "Cmaj7");
+    }
+
+    #[test]
+    fn test_name_chord_nothing_to_call_it() {
+        use Interval::*;
+
+        assert_eq!(name(&[MajorThird]), None);
+        assert_eq!(name(&[MajorSecond, PerfectFourth]), None);
+        assert_eq!(name(&[MajorThird, PerfectFifth, Unknown]), None);
+        assert_eq!(name(&[MinorThird, AugmentedFifth]), None);
+        assert_eq!(
+            name(&[MajorThird, PerfectFifth, MinorSeventh, MajorSeventh]),
+            None
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    parser::chord_parser::{read_root, RootCase},
+    theory::{
+        chord::Chord,
+        key::Key,
+        note::Note,
+        roman_numeral::{scale_degree, Accidental},
+    },
+};
+
+// the Nashville number system, a chord's scale degree as a plain number with the rest of its symbol after,
+// so in G Am7 is 2m7, D/F# is 5/7 and an F is b7
+// https://en.wikipedia.org/wiki/Nashville_Number_System
+// numbers count up the key's own scale, the same way the Roman numerals do
+
+fn number(note: &Note, key: &Key) -> String {
+    match scale_degree(note, key) {
+        (Some(Accidental::Flat), degree) => format!("b{}", degree + 1),
+        (Some(Accidental::Sharp), degree) => format!("#{}", degree + 1),
+        (None, degree) => (degree + 1).to_string(),
+    }
+}
+
+pub fn nashville_number(chord: &Chord, key: &Key) -> String {
+    // the root however it was written, Bb as well as A#
+    // the symbol carries the bass as a note name, the number has it as a number too
+    let symbol = chord.symbol_over_bass();
+    let symbol = read_root(symbol, RootCase::Upper).map_or(symbol, |(_, rest)| rest);
+
+    match chord.bass {
+        Some(bass) => format!(
+            "{}{}/{}",
+            number(&chord.root, key),
+            symbol,
+            number(&bass, key)
+        ),
+        None => format!("{}{}", number(&chord.root, key), symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn number_in(name: &str, key: Key) -> String {
+        nashville_number(&identify_from_name(name.to_string()).expect("hmm"), &key)
+    }
+
+    //
+    // nashville_number
+    //
+
+    #[test]
+    fn test_nashville_number() {
+        let key = Key::major(Note::G);
+
+        assert_eq!(number_in("G", key), "1");
+        assert_eq!(number_in("Am7", key), "2m7");
+        assert_eq!(number_in("D/F#", key), "5/7");
+        assert_eq!(number_in("F", key), "b7");
+        assert_eq!(number_in("Cmaj7", key), "4maj7");
+    }
+
+    #[test]
+    fn test_nashville_number_flat_roots() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(number_in("Bb", key), "b7");
+        assert_eq!(number_in("Ebmaj7", key), "b3maj7");
+        assert_eq!(number_in("Abm/Eb", key), "b6m/b3");
+        assert_eq!(number_in("Db7", key), "b27");
+    }
+
+    #[test]
+    fn test_nashville_number_six_nine() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(number_in("F6/9", key), "46/9");
+        assert_eq!(number_in("C6/9/E", key), "16/9/3");
+    }
+}
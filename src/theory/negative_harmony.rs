@@ -0,0 +1,123 @@
+use crate::{
+    parser::chord_parser::identify_from_notes,
+    theory::{
+        chord::Chord, error::ChordParseError, key::Key, note::Note, pitch_class::PitchClass,
+        progression::Progression,
+    },
+};
+
+// every note mirrored around the axis halfway between the key's tonic and its 5th, so in C
+// C and G swap, E and Eb, D and F, and the major chords turn minor, C to Cm, G7 to Dm7b5, F to Gm
+// mirroring turns a chord upside down, so the new one is read from what was its top note
+// https://en.wikipedia.org/wiki/Negative_harmony
+
+pub fn mirror_note(note: &Note, key: &Key) -> Note {
+    // tonic and dominant add up to the same as any note and its mirror
+    let sum = 2 * PitchClass::from(key.tonic).value() as usize + 7;
+    let value = PitchClass::from(*note).value() as usize;
+
+    PitchClass::new(sum + 12 - value).note()
+}
+
+// the mirrored chord with its most likely name, a slash bass mirrored and kept in the bass
+pub fn negative_chord(chord: &Chord, key: &Key) -> Result<Chord, ChordParseError> {
+    let mut notes: Vec<Note> = chord
+        .notes
+        .iter()
+        .rev()
+        .map(|n| mirror_note(n, key))
+        .collect();
+
+    if let Some(bass) = chord.bass.map(|b| mirror_note(&b, key)) {
+        notes.retain(|n| *n != bass);
+        notes.insert(0, bass);
+    }
+
+    identify_from_notes(&notes)
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            ChordParseError::InvalidChordName(format!("no name for the mirror of {}", chord.name))
+        })
+}
+
+// every chord mirrored, the bars and beats as they were
+pub fn negative_progression(
+    progression: &Progression,
+    key: &Key,
+) -> Result<Progression, ChordParseError> {
+    Ok(Progression {
+        chords: progression
+            .chords
+            .iter()
+            .map(|c| negative_chord(c, key))
+            .collect::<Result<Vec<Chord>, ChordParseError>>()?,
+        bars: progression.bars.clone(),
+        beats: progression.beats.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+    use std::str::FromStr;
+
+    fn negative(name: &str, key: Key) -> String {
+        let chord = identify_from_name(name.to_string()).expect("hmm");
+        negative_chord(&chord, &key).expect("hmm").name
+    }
+
+    //
+    // mirror_note
+    //
+
+    #[test]
+    fn test_mirror_note_axis() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(mirror_note(&Note::C, &key), Note::G);
+        assert_eq!(mirror_note(&Note::E, &key), Note::Ds);
+        assert_eq!(mirror_note(&Note::D, &key), Note::F);
+        assert_eq!(mirror_note(&Note::Fs, &key), Note::Cs);
+    }
+
+    //
+    // negative_chord
+    //
+
+    #[test]
+    fn test_negative_chord_in_c() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(negative("C", key), "Cm");
+        assert_eq!(negative("F", key), "Gm");
+        assert_eq!(negative("Am", key), "D#");
+        assert_eq!(negative("G7", key), "Dm7b5");
+    }
+
+    #[test]
+    fn test_negative_chord_other_key() {
+        assert_eq!(negative("D", Key::major(Note::D)), "Dm");
+    }
+
+    #[test]
+    fn test_negative_chord_keeps_slash_bass() {
+        let ret = negative("C/E", Key::major(Note::C));
+
+        assert_eq!(ret, "Cm/D#");
+    }
+
+    //
+    // negative_progression
+    //
+
+    #[test]
+    fn test_negative_progression_keeps_bars() {
+        let progression = Progression::from_str("| C | Am | F | G7 |").expect("hmm");
+
+        let ret = negative_progression(&progression, &Key::major(Note::C)).expect("hmm");
+
+        assert_eq!(ret.to_string(), "| Cm | D# | Gm | Dm7b5 |");
+    }
+}
@@ -1,8 +1,11 @@
 use std::{fmt, str::FromStr};
 
-use crate::theory::error::NoteParseError;
+use crate::theory::{
+    error::{NoteParseError, Span},
+    pitch_class::PitchClass,
+};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub enum Note {
     #[default]
     C,
@@ -19,11 +22,8 @@ pub enum Note {
     B,
 }
 
-// TODO: might be able to do something here about choosing between Db and C#, all depends on the context of the -
-// position of the note in the chord, job for a while later though
-// it might make sense to change the notes above from C, Cs, D etc and change them to just octave positions like -
-// 0, 1, 2, 3 or some kind of pitch class type
-// and have the printout decide the note name based on the chord context
+// always printed with sharps, whether it's a Db or a C# depends on the chord or key around it
+// and that's worked out in theory::spelling
 impl fmt::Display for Note {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -47,26 +47,91 @@ impl FromStr for Note {
     type Err = NoteParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        // the music symbols are the same as # and b, F♯ is F#
+        match s.replace('♯', "#").replace('♭', "b").as_str() {
             "C" => Ok(Note::C),
             "C#" => Ok(Note::Cs),
             "Db" => Ok(Note::Cs), // TODO: worry about flats and sharp matches later
             "D" => Ok(Note::D),
             "D#" => Ok(Note::Ds),
+            "Eb" => Ok(Note::Ds),
             "E" => Ok(Note::E),
             "F" => Ok(Note::F),
             "F#" => Ok(Note::Fs),
+            "Gb" => Ok(Note::Fs),
             "G" => Ok(Note::G),
             "G#" => Ok(Note::Gs),
+            "Ab" => Ok(Note::Gs),
             "A" => Ok(Note::A),
             "A#" => Ok(Note::As),
+            "Bb" => Ok(Note::As),
             "B" => Ok(Note::B),
-            _ => Err(NoteParseError::InvalidNoteStringValue(s.to_string())),
+            _ => Err(NoteParseError::InvalidNoteStringValue(Span::new(
+                s,
+                0,
+                s.len(),
+            ))),
         }
     }
 }
 impl Note {
     pub fn parse(str: &str) -> Result<Note, NoteParseError> {
-        return Note::from_str(str);
+        Note::from_str(str)
+    }
+
+    // where the note sits in the octave counting semitones up from C, 0 to 11, see PitchClass for doing sums with it
+    // matched out rather than looked up in OCTAVE so there's no missing case to fall back on
+    pub fn position(&self) -> usize {
+        match self {
+            Note::C => 0,
+            Note::Cs => 1,
+            Note::D => 2,
+            Note::Ds => 3,
+            Note::E => 4,
+            Note::F => 5,
+            Note::Fs => 6,
+            Note::G => 7,
+            Note::Gs => 8,
+            Note::A => 9,
+            Note::As => 10,
+            Note::B => 11,
+        }
+    }
+
+    // midi numbers count semitones up from a C, 60 being middle C
+    pub fn from_midi(midi_note: u8) -> Note {
+        PitchClass::new(midi_note as usize).note()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::interval::OCTAVE;
+
+    //
+    // position
+    //
+
+    #[test]
+    fn test_position_matches_octave() {
+        for (index, note) in OCTAVE.iter().enumerate() {
+            assert_eq!(note.position(), index);
+        }
+    }
+
+    //
+    // from_midi
+    //
+
+    #[test]
+    fn test_from_midi_middle_c() {
+        assert_eq!(Note::from_midi(60), Note::C);
+    }
+
+    #[test]
+    fn test_from_midi_wraps_octaves() {
+        assert_eq!(Note::from_midi(70), Note::As);
+        assert_eq!(Note::from_midi(21), Note::A);
     }
 }
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use crate::theory::{
+    error::ChordParseError, interval::Interval, note::Note, pitch_class::PitchClass,
+};
+
+// highest midi number there is
+const HIGHEST_MIDI: u8 = 127;
+
+// a note in a particular octave, as a midi number with 60 being middle C (C4)
+// unlike a Note going up an interval from a pitch keeps the octave it ends up in,
+// so a 9th above C4 is D5 rather than folding back down to D4
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Pitch(pub u8);
+
+impl Pitch {
+    // octave 4 being the one starting on middle C
+    pub fn new(note: &Note, octave: u8) -> Pitch {
+        Pitch((octave + 1) * 12 + PitchClass::from(*note).value())
+    }
+
+    pub fn note(&self) -> Note {
+        Note::from_midi(self.0)
+    }
+
+    // midi 0 is a C in octave -1
+    pub fn octave(&self) -> i32 {
+        (self.0 / 12) as i32 - 1
+    }
+
+    // the full size of the interval up, 9ths, 11ths and 13ths land an octave higher than 2nds, 4ths and 6ths
+    pub fn add_interval(&self, interval: Interval) -> Result<Pitch, ChordParseError> {
+        let midi = match interval {
+            Interval::Unknown => None,
+            _ => {
+                Some(self.0 as usize + interval.semitones()).filter(|m| *m <= HIGHEST_MIDI as usize)
+            }
+        };
+
+        match midi {
+            Some(midi) => Ok(Pitch(midi as u8)),
+            None => Err(ChordParseError::InvalidInterval(format!(
+                "no pitch {} above {}",
+                interval, self
+            ))),
+        }
+    }
+}
+
+// scientific pitch notation, C4
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.note(), self.octave())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // new
+    //
+
+    #[test]
+    fn test_new_middle_c() {
+        let ret = Pitch::new(&Note::C, 4);
+
+        assert_eq!(ret, Pitch(60));
+        assert_eq!(ret.to_string(), "C4");
+    }
+
+    //
+    // add_interval
+    //
+
+    #[test]
+    fn test_add_interval_within_octave() {
+        let ret = Pitch::new(&Note::G, 3)
+            .add_interval(Interval::MinorSeventh)
+            .expect("hmm");
+
+        assert_eq!(ret.to_string(), "F4");
+    }
+
+    // a 9th isn't a 2nd, it carries on into the next octave
+    #[test]
+    fn test_add_interval_carries_octave() {
+        let root = Pitch::new(&Note::C, 4);
+
+        assert_eq!(
+            root.add_interval(Interval::MajorNinth)
+                .expect("hmm")
+                .to_string(),
+            "D5"
+        );
+        assert_eq!(
+            root.add_interval(Interval::MajorThirteenth)
+                .expect("hmm")
+                .to_string(),
+            "A5"
+        );
+    }
+
+    #[test]
+    fn test_add_interval_invalid() {
+        assert!(Pitch(60).add_interval(Interval::Unknown).is_err());
+        assert!(Pitch(120).add_interval(Interval::MajorNinth).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use std::ops::{Add, Sub};
+
+use crate::theory::{
+    interval::{Interval, OCTAVE},
+    note::Note,
+};
+
+// a note with the octave taken out, counted in semitones up from C (0 to 11)
+// all the going round the octave lives here so nothing else has to remember to wrap by 12
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct PitchClass(u8);
+
+impl PitchClass {
+    // any number of semitones up from C, wrapped back into the octave
+    pub fn new(semitones: usize) -> PitchClass {
+        PitchClass((semitones % 12) as u8)
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    pub fn note(&self) -> Note {
+        OCTAVE[self.0 as usize]
+    }
+
+    pub fn up(&self, semitones: usize) -> PitchClass {
+        PitchClass::new(self.0 as usize + semitones % 12)
+    }
+
+    pub fn down(&self, semitones: usize) -> PitchClass {
+        PitchClass::new(self.0 as usize + 12 - semitones % 12)
+    }
+
+    // semitones up from this one to the other, always 0 to 11
+    pub fn distance(&self, other: &PitchClass) -> usize {
+        (other.0 as usize + 12 - self.0 as usize) % 12
+    }
+
+    // the shorter way round between the two, up or down, so never more than a tritone (6)
+    pub fn nearest_distance(&self, other: &PitchClass) -> usize {
+        let up = self.distance(other);
+        up.min(12 - up)
+    }
+}
+
+impl From<Note> for PitchClass {
+    fn from(note: Note) -> Self {
+        PitchClass(note.position() as u8)
+    }
+}
+
+impl From<PitchClass> for Note {
+    fn from(pitch_class: PitchClass) -> Self {
+        pitch_class.note()
+    }
+}
+
+// Unknown has no size so it doesn't move anything, get_interval is the place that turns it into an error
+impl Add<Interval> for PitchClass {
+    type Output = PitchClass;
+
+    fn add(self, interval: Interval) -> PitchClass {
+        match interval {
+            Interval::Unknown => self,
+            _ => self.up(interval.semitones()),
+        }
+    }
+}
+
+impl Sub<Interval> for PitchClass {
+    type Output = PitchClass;
+
+    fn sub(self, interval: Interval) -> PitchClass {
+        match interval {
+            Interval::Unknown => self,
+            _ => self.down(interval.semitones()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // new
+    //
+
+    #[test]
+    fn test_new_wraps() {
+        assert_eq!(PitchClass::new(14), PitchClass::new(2));
+        assert_eq!(PitchClass::new(60).note(), Note::C);
+    }
+
+    //
+    // add / sub
+    //
+
+    #[test]
+    fn test_add_interval_wraps() {
+        let g = PitchClass::from(Note::G);
+
+        assert_eq!((g + Interval::PerfectFifth).note(), Note::D);
+        assert_eq!((g + Interval::MajorNinth).note(), Note::A);
+    }
+
+    #[test]
+    fn test_sub_interval_wraps() {
+        let c = PitchClass::from(Note::C);
+
+        assert_eq!((c - Interval::MinorThird).note(), Note::A);
+        assert_eq!((c - Interval::MinorNinth).note(), Note::B);
+    }
+
+    //
+    // distance
+    //
+
+    #[test]
+    fn test_distance_up_and_nearest() {
+        let a = PitchClass::from(Note::A);
+        let c = PitchClass::from(Note::C);
+
+        assert_eq!(a.distance(&c), 3);
+        assert_eq!(c.distance(&a), 9);
+        assert_eq!(c.nearest_distance(&a), 3);
+        assert_eq!(c.distance(&c), 0);
+    }
+}
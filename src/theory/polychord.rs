@@ -0,0 +1,101 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::theory::{chord::Chord, note::Note};
+
+// one chord stacked on top of another, written upper|lower so D|C is a D triad over a C triad
+// not the same thing as a slash chord, D/C is a D with just a C in the bass
+#[derive(Debug, Clone)]
+pub struct Polychord {
+    pub name: String,
+    pub upper: Chord,
+    pub lower: Chord,
+    // the lower chord's notes then whatever the upper one adds, lowest first
+    pub notes: Vec<Note>,
+}
+
+impl Polychord {
+    pub fn new(upper: Chord, lower: Chord) -> Polychord {
+        let notes: Vec<Note> = lower
+            .notes
+            .iter()
+            .chain(upper.notes.iter())
+            .unique()
+            .copied()
+            .collect();
+
+        Polychord {
+            name: format!("{}|{}", upper.name, lower.name),
+            upper,
+            lower,
+            notes,
+        }
+    }
+
+    // notes both halves have, D|G shares the D
+    pub fn shared_notes(&self) -> Vec<Note> {
+        self.upper
+            .notes
+            .iter()
+            .filter(|n| self.lower.notes.contains(n))
+            .copied()
+            .collect()
+    }
+}
+
+impl fmt::Display for Polychord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Polychord: {}", self.name)?;
+        writeln!(
+            f,
+            "Upper: {} ({})",
+            self.upper.name,
+            self.upper.notes.iter().join(" ")
+        )?;
+        writeln!(
+            f,
+            "Lower: {} ({})",
+            self.lower.name,
+            self.lower.notes.iter().join(" ")
+        )?;
+        write!(f, "Notes: {}", self.notes.iter().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn chord(name: &str) -> Chord {
+        identify_from_name(name.to_string()).expect("hmm")
+    }
+
+    //
+    // new
+    //
+
+    #[test]
+    fn test_new_merges_notes_lowest_first() {
+        let ret = Polychord::new(chord("D"), chord("C"));
+
+        assert_eq!(ret.name, "D|C");
+        assert_eq!(
+            ret.notes,
+            vec![Note::C, Note::E, Note::G, Note::D, Note::Fs, Note::A]
+        );
+    }
+
+    //
+    // shared_notes
+    //
+
+    #[test]
+    fn test_shared_notes_only_counted_once() {
+        let ret = Polychord::new(chord("D"), chord("G"));
+
+        assert_eq!(ret.shared_notes(), vec![Note::D]);
+        assert_eq!(ret.notes.len(), 5);
+    }
+}
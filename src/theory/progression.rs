@@ -0,0 +1,169 @@
+use std::{fmt, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    aliases::Aliases,
+    parser::{chord_parser::ParseOptions, progression_parser::identify_progression},
+    theory::{chord::Chord, error::ChordParseError, transpose::transpose_chord},
+};
+
+// chords in the order they're played, and which bar each one's in when it was written with bar lines
+#[derive(Debug, Clone)]
+pub struct Progression {
+    pub chords: Vec<Chord>,
+    // one for every chord counting bars from 0, all 0 when there weren't any bar lines
+    pub bars: Vec<usize>,
+    // one for every chord, the beats it's written for with the dots after it, 1 for a chord on its own
+    pub beats: Vec<usize>,
+}
+
+impl Progression {
+    // chords with no bar lines, one long bar
+    pub fn new(chords: Vec<Chord>) -> Progression {
+        let bars = vec![0; chords.len()];
+        let beats = vec![1; chords.len()];
+        Progression {
+            chords,
+            bars,
+            beats,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.chords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chords.is_empty()
+    }
+
+    pub fn bar_count(&self) -> usize {
+        self.bars.last().map_or(0, |last| last + 1)
+    }
+
+    // the chords in one bar, empty past the end
+    pub fn bar(&self, bar: usize) -> Vec<&Chord> {
+        self.chords
+            .iter()
+            .zip(&self.bars)
+            .filter(|(_, b)| **b == bar)
+            .map(|(chord, _)| chord)
+            .collect()
+    }
+}
+
+impl Progression {
+    // every chord moved by the same semitones, the bars and beats as they were
+    pub fn transpose(&self, semitones: i32) -> Progression {
+        Progression {
+            chords: self
+                .chords
+                .iter()
+                .map(|c| transpose_chord(c, semitones))
+                .collect(),
+            bars: self.bars.clone(),
+            beats: self.beats.clone(),
+        }
+    }
+}
+
+// C Am F G, or with bar lines | Cmaj7 | Am7 | Dm7 G7 | when there's more than the one bar,
+// a chord held for more than a beat with its dots after it, | C . . . |
+impl fmt::Display for Progression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let written: Vec<String> = self
+            .chords
+            .iter()
+            .zip(&self.beats)
+            .map(|(chord, beats)| {
+                std::iter::once(chord.name.as_str())
+                    .chain(std::iter::repeat_n(".", beats.saturating_sub(1)))
+                    .join(" ")
+            })
+            .collect();
+
+        if self.bar_count() <= 1 {
+            return write!(f, "{}", written.join(" "));
+        }
+
+        for bar in 0..self.bar_count() {
+            let chords = written
+                .iter()
+                .zip(&self.bars)
+                .filter(|(_, b)| **b == bar)
+                .map(|(chord, _)| chord)
+                .join(" ");
+            write!(f, "| {} ", chords)?;
+        }
+        write!(f, "|")
+    }
+}
+
+impl FromStr for Progression {
+    type Err = ChordParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        identify_progression(s, &Aliases::default(), ParseOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // from_str
+    //
+
+    #[test]
+    fn test_from_str_plain() {
+        let ret = Progression::from_str("C Am F G").expect("hmm");
+
+        assert_eq!(ret.len(), 4);
+        assert_eq!(ret.bar_count(), 1);
+        assert_eq!(ret.to_string(), "C Am F G");
+    }
+
+    #[test]
+    fn test_from_str_bars() {
+        let ret = Progression::from_str("| Cmaj7 | Am7 | Dm7 G7 |").expect("hmm");
+
+        assert_eq!(ret.bars, vec![0, 1, 2, 2]);
+        assert_eq!(ret.bar(2).len(), 2);
+        assert_eq!(ret.to_string(), "| Cmaj7 | Am7 | Dm7 G7 |");
+    }
+
+    //
+    // bar
+    //
+
+    #[test]
+    fn test_bar_past_the_end() {
+        let ret = Progression::from_str("C G").expect("hmm");
+
+        assert!(ret.bar(3).is_empty());
+    }
+
+    //
+    // transpose
+    //
+
+    #[test]
+    fn test_transpose_keeps_bars() {
+        let ret = Progression::from_str("| C | Am/E | F G7 |")
+            .expect("hmm")
+            .transpose(-5);
+
+        assert_eq!(ret.to_string(), "| G | Em/B | C D7 |");
+    }
+
+    #[test]
+    fn test_transpose_keeps_beats() {
+        let ret = Progression::from_str("| C . . . | . . G7 . |")
+            .expect("hmm")
+            .transpose(2);
+
+        assert_eq!(ret.to_string(), "| D . . . | D . A7 . |");
+    }
+}
@@ -0,0 +1,339 @@
+use itertools::Itertools;
+use std::fmt;
+
+use crate::theory::{
+    chord::Chord,
+    error::{ChordParseError, KeyParseError},
+    key::{Key, KeyQuality},
+    key_finder::find_keys,
+    pitch_class::PitchClass,
+    scale::Scale,
+};
+
+// fills the gaps in a progression like C ? ? G7 C with chords from the key, the triads and 7ths on every degree
+// plus the ones borrowed from the parallel key if a few non-diatonic chords are allowed
+// each way of filling it is scored on how it moves, roots falling a 5th or a 3rd score well and staying put badly,
+// and the best one found by going through the bars keeping the best way to reach every chord (Viterbi)
+
+// how much a change of chord scores for where its root goes, the common moves of functional harmony,
+// with a little on top for smoothness from how many notes the chords share
+const FIFTH_DOWN: i32 = 40;
+const THIRD_DOWN: i32 = 20;
+const STEP_UP: i32 = 10;
+// staying on the same root isn't much of a change, C to Cmaj7
+const SAME_ROOT: i32 = -60;
+const SIMILARITY_WEIGHT: f64 = 0.3;
+const BORROWED_PENALTY: i32 = 10;
+
+#[derive(Debug)]
+pub enum FillError {
+    Chord(ChordParseError),
+    Key(KeyParseError),
+    // nothing to work a key out from
+    NoKey,
+    // the constraints rule out every chord for a gap, or leave no way through
+    Unsolvable(String),
+}
+
+impl fmt::Display for FillError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FillError::Chord(e) => write!(f, "{}", e),
+            FillError::Key(e) => write!(f, "{}", e),
+            FillError::NoKey => write!(f, "there are no chords to work a key out from"),
+            FillError::Unsolvable(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<ChordParseError> for FillError {
+    fn from(e: ChordParseError) -> Self {
+        FillError::Chord(e)
+    }
+}
+
+impl From<KeyParseError> for FillError {
+    fn from(e: KeyParseError) -> Self {
+        FillError::Key(e)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FillConstraints {
+    // worked out from the chords that are there when not given
+    pub key: Option<Key>,
+    // chord symbols without the root (m7, 7, maj7, with maj for a plain major triad), anything when None
+    pub allowed: Option<Vec<String>>,
+    // how many of the filled in chords can come from outside the key
+    pub max_borrowed: usize,
+}
+
+// a chord that could go in a gap
+#[derive(Debug, Clone)]
+struct Candidate {
+    chord: Chord,
+    borrowed: bool,
+}
+
+// the symbol after the root, with maj for a plain major triad
+fn quality_symbol(chord: &Chord) -> &str {
+    match chord.name.strip_prefix(&chord.root.to_string()) {
+        Some("") | None => "maj",
+        Some(symbol) => symbol,
+    }
+}
+
+fn key_chords(key: &Key) -> Vec<Chord> {
+    let mut chords = key.diatonic_chords();
+
+    // a minor key's V and V7 come from the harmonic minor, the raised 7th leading back home
+    if key.quality == KeyQuality::Minor {
+        for size in [3, 4] {
+            if let Some(dominant) = Scale::HarmonicMinor
+                .diatonic_chords(&key.tonic, size)
+                .get(4)
+            {
+                chords.push(dominant.clone());
+            }
+        }
+    }
+
+    chords
+}
+
+fn candidates(key: &Key, constraints: &FillConstraints) -> Vec<Candidate> {
+    let diatonic = key_chords(key);
+    let borrowed: Vec<Chord> = match constraints.max_borrowed {
+        0 => vec![],
+        _ => key
+            .borrowed_chords()
+            .into_iter()
+            .filter(|c| !diatonic.iter().any(|d| d.name == c.name))
+            .collect(),
+    };
+
+    diatonic
+        .into_iter()
+        .map(|chord| Candidate {
+            chord,
+            borrowed: false,
+        })
+        .chain(borrowed.into_iter().map(|chord| Candidate {
+            chord,
+            borrowed: true,
+        }))
+        .unique_by(|c| c.chord.name.clone())
+        .filter(|c| match &constraints.allowed {
+            Some(allowed) => allowed.iter().any(|a| a == quality_symbol(&c.chord)),
+            None => true,
+        })
+        .collect()
+}
+
+fn transition_score(from: &Chord, to: &Chord) -> i32 {
+    let root_motion = match PitchClass::from(from.root).distance(&PitchClass::from(to.root)) {
+        0 => SAME_ROOT,
+        5 => FIFTH_DOWN,
+        8 | 9 => THIRD_DOWN,
+        2 => STEP_UP,
+        _ => 0,
+    };
+
+    root_motion + (from.similarity(to) as f64 * SIMILARITY_WEIGHT).round() as i32
+}
+
+// the key it went with and every bar filled in, None in the bars is a gap
+pub fn fill_progression(
+    bars: &[Option<Chord>],
+    constraints: &FillConstraints,
+) -> Result<(Key, Vec<Chord>), FillError> {
+    let known: Vec<Chord> = bars.iter().flatten().cloned().collect();
+    let key = match constraints.key {
+        Some(key) => key,
+        None if known.is_empty() => return Err(FillError::NoKey),
+        None => find_keys(&known).first().ok_or(FillError::NoKey)?.key,
+    };
+
+    let gap_candidates = candidates(&key, constraints);
+    if gap_candidates.is_empty() && bars.iter().any(|b| b.is_none()) {
+        return Err(FillError::Unsolvable(format!(
+            "no chords in {} fit the allowed qualities",
+            key
+        )));
+    }
+
+    let options: Vec<Vec<Candidate>> = bars
+        .iter()
+        .map(|bar| match bar {
+            Some(chord) => vec![Candidate {
+                chord: chord.clone(),
+                borrowed: false,
+            }],
+            None => gap_candidates.clone(),
+        })
+        .collect();
+
+    // for every bar, chord and number of borrowed chords used so far, the best score getting there
+    // and which chord and count it came from
+    type Best = Option<(i32, usize, usize)>;
+    let borrow_states = constraints.max_borrowed + 1;
+    let mut best: Vec<Vec<Vec<Best>>> = vec![];
+
+    for (bar, bar_options) in options.iter().enumerate() {
+        let mut here = vec![vec![None; borrow_states]; bar_options.len()];
+
+        for (index, candidate) in bar_options.iter().enumerate() {
+            let cost = candidate.borrowed as usize;
+
+            if bar == 0 {
+                if cost < borrow_states {
+                    here[index][cost] = Some((-(cost as i32) * BORROWED_PENALTY, 0, 0));
+                }
+                continue;
+            }
+
+            for (previous_index, previous) in options[bar - 1].iter().enumerate() {
+                for used in 0..borrow_states - cost {
+                    let Some((score, _, _)) = best[bar - 1][previous_index][used] else {
+                        continue;
+                    };
+
+                    let score = score + transition_score(&previous.chord, &candidate.chord)
+                        - cost as i32 * BORROWED_PENALTY;
+                    let slot = &mut here[index][used + cost];
+                    if slot.is_none_or(|(existing, _, _)| score > existing) {
+                        *slot = Some((score, previous_index, used));
+                    }
+                }
+            }
+        }
+
+        best.push(here);
+    }
+
+    let last = match best.last() {
+        Some(last) => last,
+        None => return Ok((key, vec![])),
+    };
+    let (mut index, mut used) = last
+        .iter()
+        .enumerate()
+        .flat_map(|(index, counts)| {
+            counts
+                .iter()
+                .enumerate()
+                .filter_map(move |(used, b)| b.map(|(score, _, _)| (score, index, used)))
+        })
+        // the first of equals, going by the order the candidates are in
+        .rev()
+        .max_by_key(|(score, _, _)| *score)
+        .map(|(_, index, used)| (index, used))
+        .ok_or_else(|| FillError::Unsolvable("no way through the bars".to_string()))?;
+
+    let mut chords = vec![];
+    for bar in (0..options.len()).rev() {
+        chords.push(options[bar][index].chord.clone());
+        if let Some((_, previous_index, previous_used)) = best[bar][index][used] {
+            index = previous_index;
+            used = previous_used;
+        }
+    }
+    chords.reverse();
+
+    Ok((key, chords))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::note::Note};
+
+    fn bars(names: &[&str]) -> Vec<Option<Chord>> {
+        names
+            .iter()
+            .map(|n| match *n {
+                "?" => None,
+                n => Some(identify_from_name(n.to_string()).expect("hmm")),
+            })
+            .collect()
+    }
+
+    fn names(chords: &[Chord]) -> Vec<&str> {
+        chords.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    //
+    // fill_progression
+    //
+
+    #[test]
+    fn test_fill_progression_keeps_known_bars() {
+        let (key, ret) = fill_progression(
+            &bars(&["C", "?", "?", "G7", "C"]),
+            &FillConstraints::default(),
+        )
+        .expect("hmm");
+
+        assert_eq!(key, Key::major(Note::C));
+        assert_eq!(ret.len(), 5);
+        assert_eq!(ret[0].name, "C");
+        assert_eq!(ret[3].name, "G7");
+        assert_eq!(ret[4].name, "C");
+        // the bar before G7 leads in by a 5th, D something
+        assert_eq!(ret[2].root, Note::D);
+    }
+
+    #[test]
+    fn test_fill_progression_allowed_qualities() {
+        let constraints = FillConstraints {
+            allowed: Some(vec!["m7".to_string()]),
+            ..Default::default()
+        };
+
+        let (_, ret) =
+            fill_progression(&bars(&["Cmaj7", "?", "?", "Cmaj7"]), &constraints).expect("hmm");
+
+        assert!(names(&ret[1..3]).iter().all(|n| n.ends_with("m7")));
+    }
+
+    #[test]
+    fn test_fill_progression_borrowed_limit() {
+        let key = Key::major(Note::C);
+        let constraints = FillConstraints {
+            key: Some(key),
+            max_borrowed: 1,
+            ..Default::default()
+        };
+
+        let (_, ret) =
+            fill_progression(&bars(&["C", "?", "?", "?", "C"]), &constraints).expect("hmm");
+        let diatonic = key_chords(&key);
+        let borrowed = ret
+            .iter()
+            .filter(|c| !diatonic.iter().any(|d| d.name == c.name))
+            .count();
+
+        assert!(borrowed <= 1);
+    }
+
+    #[test]
+    fn test_fill_progression_no_key() {
+        assert!(matches!(
+            fill_progression(&bars(&["?", "?"]), &FillConstraints::default()),
+            Err(FillError::NoKey)
+        ));
+    }
+
+    #[test]
+    fn test_fill_progression_nothing_allowed() {
+        let constraints = FillConstraints {
+            allowed: Some(vec!["13".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            fill_progression(&bars(&["C", "?"]), &constraints),
+            Err(FillError::Unsolvable(_))
+        ));
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::theory::{
+    chord::{Chord, ChordQuality, Inversion, SeventhType, SuspendedType, TriadQuality},
+    key::{Key, KeyQuality},
+    note::Note,
+    pitch_class::PitchClass,
+};
+
+// a chord's place in a key, the scale degree its root is on with the case showing the quality
+// (upper for major, lower for minor), e.g. ii, V7 and viio in C major
+// https://en.wikipedia.org/wiki/Roman_numeral_analysis
+
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Accidental {
+    Flat,
+    Sharp,
+}
+
+impl fmt::Display for Accidental {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Accidental::Flat => write!(f, "b"),
+            Accidental::Sharp => write!(f, "#"),
+        }
+    }
+}
+
+// where the chord's notes come from
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Origin {
+    Diatonic,
+    // out of the key but all in the parallel one, like iv or bVII in a major key
+    Borrowed(Key),
+    Chromatic,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RomanNumeral {
+    // for roots off the scale, a bVII in major
+    pub accidental: Option<Accidental>,
+    // 0 for the tonic up to 6
+    pub degree: usize,
+    pub chord_quality: ChordQuality,
+    pub inversion: Option<Inversion>,
+    pub origin: Origin,
+}
+
+// the degree a root off the scale is written as, semitones up from the tonic
+// in major these are the notes borrowed from minor (bIII, bVI, bVII) and the Neapolitan bII, with the tritone as #IV
+// in minor they're raised degrees, like the leading note #vii, except the Neapolitan bII again
+fn chromatic_degree(quality: KeyQuality, from_tonic: usize) -> (Accidental, usize) {
+    match (quality, from_tonic) {
+        (_, 1) => (Accidental::Flat, 1),
+        (KeyQuality::Major, 3) => (Accidental::Flat, 2),
+        (KeyQuality::Major, 6) | (KeyQuality::Minor, 6) => (Accidental::Sharp, 3),
+        (KeyQuality::Major, 8) => (Accidental::Flat, 5),
+        (KeyQuality::Major, _) => (Accidental::Flat, 6),
+        (KeyQuality::Minor, 4) => (Accidental::Sharp, 2),
+        (KeyQuality::Minor, 9) => (Accidental::Sharp, 5),
+        (KeyQuality::Minor, _) => (Accidental::Sharp, 6),
+    }
+}
+
+// which degree of the key a note is, 0 for the tonic, with an accidental if it's off the scale
+pub fn scale_degree(note: &Note, key: &Key) -> (Option<Accidental>, usize) {
+    match key.notes().iter().position(|n| n == note) {
+        Some(degree) => (None, degree),
+        None => {
+            let from_tonic = PitchClass::from(key.tonic).distance(&PitchClass::from(*note));
+            let (accidental, degree) = chromatic_degree(key.quality, from_tonic);
+            (Some(accidental), degree)
+        }
+    }
+}
+
+pub fn roman_numeral(chord: &Chord, key: &Key) -> RomanNumeral {
+    let (accidental, degree) = scale_degree(&chord.root, key);
+    let scale_notes = key.notes();
+
+    let chord_tones = chord.chord_tones();
+    let parallel = key.parallel();
+    let origin = if chord_tones.iter().all(|n| scale_notes.contains(n)) {
+        Origin::Diatonic
+    } else if chord_tones.iter().all(|n| parallel.notes().contains(n)) {
+        Origin::Borrowed(parallel)
+    } else {
+        Origin::Chromatic
+    };
+
+    RomanNumeral {
+        accidental,
+        degree,
+        chord_quality: chord.chord_quality(),
+        inversion: chord.inversion(),
+        origin,
+    }
+}
+
+impl RomanNumeral {
+    // the quality marks after the numeral, with the inversion's figures standing in for the 7
+    fn suffix(&self) -> String {
+        let seventh_figure = match self.inversion {
+            Some(Inversion::First) => "65",
+            Some(Inversion::Second) => "43",
+            Some(Inversion::Third) => "42",
+            _ => "7",
+        };
+        let triad_figure = match self.inversion {
+            Some(Inversion::First) => "6",
+            Some(Inversion::Second) => "64",
+            _ => "",
+        };
+
+        match self.chord_quality {
+            ChordQuality::Major | ChordQuality::Minor => triad_figure.to_string(),
+            ChordQuality::Diminished => format!("o{}", triad_figure),
+            ChordQuality::Augmented => format!("+{}", triad_figure),
+            ChordQuality::Suspended(SuspendedType::Sus2) => "sus2".to_string(),
+            ChordQuality::Suspended(SuspendedType::Sus4) => "sus4".to_string(),
+            ChordQuality::Power => "5".to_string(),
+            ChordQuality::Ambiguous => String::new(),
+            ChordQuality::Seventh(seventh_type) => match seventh_type {
+                SeventhType::Major | SeventhType::MinorMajor => format!("maj{}", seventh_figure),
+                SeventhType::Dominant | SeventhType::Minor => seventh_figure.to_string(),
+                SeventhType::HalfDiminished => format!("ø{}", seventh_figure),
+                SeventhType::Diminished => format!("o{}", seventh_figure),
+                SeventhType::Augmented => format!("+{}", seventh_figure),
+                SeventhType::Suspended(SuspendedType::Sus2) => "7sus2".to_string(),
+                SeventhType::Suspended(SuspendedType::Sus4) => "7sus4".to_string(),
+            },
+        }
+    }
+}
+
+// bVII, ii7, viio, V65
+impl fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(accidental) = self.accidental {
+            write!(f, "{}", accidental)?;
+        }
+
+        let numeral = NUMERALS[self.degree];
+        match TriadQuality::from(self.chord_quality) {
+            TriadQuality::Minor | TriadQuality::Diminished => {
+                write!(f, "{}", numeral.to_lowercase())?
+            }
+            _ => write!(f, "{}", numeral)?,
+        }
+
+        write!(f, "{}", self.suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn numeral(name: &str, key: Key) -> RomanNumeral {
+        roman_numeral(&identify_from_name(name.to_string()).expect("hmm"), &key)
+    }
+
+    //
+    // roman_numeral
+    //
+
+    #[test]
+    fn test_roman_numeral_diatonic() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(numeral("Dm", key).to_string(), "ii");
+        assert_eq!(numeral("G7", key).to_string(), "V7");
+        assert_eq!(numeral("Bdim", key).to_string(), "viio");
+        assert_eq!(numeral("Bm7b5", key).to_string(), "viiø7");
+        assert_eq!(numeral("Fmaj7", key).to_string(), "IVmaj7");
+        assert_eq!(numeral("Am", key).origin, Origin::Diatonic);
+    }
+
+    #[test]
+    fn test_roman_numeral_borrowed() {
+        let key = Key::major(Note::C);
+        let ret = numeral("A#", key);
+
+        assert_eq!(ret.to_string(), "bVII");
+        assert_eq!(ret.origin, Origin::Borrowed(Key::minor(Note::C)));
+        assert_eq!(numeral("Fm", key).to_string(), "iv");
+        assert_eq!(numeral("G#", key).to_string(), "bVI");
+    }
+
+    #[test]
+    fn test_roman_numeral_minor_key() {
+        let key = Key::minor(Note::A);
+
+        assert_eq!(numeral("C", key).to_string(), "III");
+        assert_eq!(numeral("E7", key).to_string(), "V7");
+        assert_eq!(numeral("G#dim", key).to_string(), "#viio");
+        assert_eq!(numeral("A#", key).to_string(), "bII");
+    }
+
+    #[test]
+    fn test_roman_numeral_inversions() {
+        let key = Key::major(Note::C);
+
+        assert_eq!(numeral("C/E", key).to_string(), "I6");
+        assert_eq!(numeral("C/G", key).to_string(), "I64");
+        assert_eq!(numeral("G7/B", key).to_string(), "V65");
+        assert_eq!(numeral("G7/F", key).to_string(), "V42");
+    }
+
+    #[test]
+    fn test_roman_numeral_chromatic() {
+        assert_eq!(numeral("D7", Key::major(Note::C)).origin, Origin::Chromatic);
+    }
+}
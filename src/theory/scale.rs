@@ -0,0 +1,471 @@
+use itertools::Itertools;
+use std::{fmt, str::FromStr};
+
+use crate::{
+    parser::chord_parser::identify_from_root_and_notes,
+    theory::{chord::Chord, error::ScaleParseError, note::Note, pitch_class::PitchClass},
+};
+
+// semitones up from the root to each degree of the major scale, what every formula is written against
+const MAJOR_DEGREES: [usize; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+// https://en.wikipedia.org/wiki/Scale_(music)
+// a scale on its own is just the pattern of steps, it only has notes once it has a root
+// the church modes are the major scale started from each of its degrees in turn,
+// the major and natural minor being the ionian and aeolian modes so they aren't in twice
+// not every scale has seven notes, whole tone has six and the diminished and bebop scales eight
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Scale {
+    Major,
+    NaturalMinor,
+    HarmonicMinor,
+    MelodicMinor,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Locrian,
+    WholeTone,
+    // the one that goes over a 7b9, starting with a half step
+    HalfWholeDiminished,
+    // the one that goes over a dim7, starting with a whole step
+    WholeHalfDiminished,
+    // the 7th mode of melodic minor, every tension over a dominant altered (b9 #9 #11 b13)
+    Altered,
+    // the mixolydian with a passing major 7th so the chord tones land on the beat
+    BebopDominant,
+    BebopMajor,
+    BebopDorian,
+    // the minor pentatonic with the b5 as a passing note
+    Blues,
+}
+
+// in the order they're offered in the menu
+pub const SCALES: [Scale; 17] = [
+    Scale::Major,
+    Scale::NaturalMinor,
+    Scale::HarmonicMinor,
+    Scale::MelodicMinor,
+    Scale::Dorian,
+    Scale::Phrygian,
+    Scale::Lydian,
+    Scale::Mixolydian,
+    Scale::Locrian,
+    Scale::WholeTone,
+    Scale::HalfWholeDiminished,
+    Scale::WholeHalfDiminished,
+    Scale::Altered,
+    Scale::BebopDominant,
+    Scale::BebopMajor,
+    Scale::BebopDorian,
+    Scale::Blues,
+];
+
+// https://en.wikipedia.org/wiki/Mode_(music)#Modern_modes
+// in the order of the major scale degree each one starts on
+pub const MODES: [Scale; 7] = [
+    Scale::Major,
+    Scale::Dorian,
+    Scale::Phrygian,
+    Scale::Lydian,
+    Scale::Mixolydian,
+    Scale::NaturalMinor,
+    Scale::Locrian,
+];
+
+const MAJOR_STEPS: [usize; 7] = [2, 2, 1, 2, 2, 2, 1];
+
+impl fmt::Display for Scale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scale::Major => write!(f, "Major"),
+            Scale::NaturalMinor => write!(f, "Natural Minor"),
+            Scale::HarmonicMinor => write!(f, "Harmonic Minor"),
+            Scale::MelodicMinor => write!(f, "Melodic Minor"),
+            Scale::Dorian => write!(f, "Dorian"),
+            Scale::Phrygian => write!(f, "Phrygian"),
+            Scale::Lydian => write!(f, "Lydian"),
+            Scale::Mixolydian => write!(f, "Mixolydian"),
+            Scale::Locrian => write!(f, "Locrian"),
+            Scale::WholeTone => write!(f, "Whole Tone"),
+            Scale::HalfWholeDiminished => write!(f, "Half-Whole Diminished"),
+            Scale::WholeHalfDiminished => write!(f, "Whole-Half Diminished"),
+            Scale::Altered => write!(f, "Altered"),
+            Scale::BebopDominant => write!(f, "Bebop Dominant"),
+            Scale::BebopMajor => write!(f, "Bebop Major"),
+            Scale::BebopDorian => write!(f, "Bebop Dorian"),
+            Scale::Blues => write!(f, "Blues"),
+        }
+    }
+}
+
+// a scale's name however it's cased or hyphenated, "harmonic minor", "Half-Whole Diminished" or "whole_tone",
+// with the other names some of them go by, aeolian for the natural minor or super locrian for the altered
+impl FromStr for Scale {
+    type Err = ScaleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s
+            .to_lowercase()
+            .replace(['-', '_'], " ")
+            .split_whitespace()
+            .join(" ");
+
+        let alias = match name.as_str() {
+            "ionian" => Some(Scale::Major),
+            "minor" | "aeolian" => Some(Scale::NaturalMinor),
+            "super locrian" => Some(Scale::Altered),
+            "bebop" => Some(Scale::BebopDominant),
+            _ => None,
+        };
+
+        alias
+            .or_else(|| {
+                SCALES
+                    .into_iter()
+                    .find(|scale| scale.to_string().to_lowercase().replace('-', " ") == name)
+            })
+            .ok_or_else(|| ScaleParseError::InvalidScale(s.trim().to_string()))
+    }
+}
+
+impl Scale {
+    // semitones from each note to the next, the last one getting back to the root an octave up
+    // melodic minor is the way up, on the way down it's usually played as the natural minor
+    pub fn steps(&self) -> Vec<usize> {
+        match self {
+            Scale::HarmonicMinor => vec![2, 1, 2, 2, 1, 3, 1],
+            Scale::MelodicMinor => vec![2, 1, 2, 2, 2, 2, 1],
+            Scale::WholeTone => vec![2; 6],
+            Scale::HalfWholeDiminished => [1, 2].repeat(4),
+            Scale::WholeHalfDiminished => [2, 1].repeat(4),
+            Scale::Altered => vec![1, 2, 1, 2, 2, 2, 2],
+            Scale::BebopDominant => vec![2, 2, 1, 2, 2, 1, 1, 1],
+            Scale::BebopMajor => vec![2, 2, 1, 2, 1, 1, 2, 1],
+            Scale::BebopDorian => vec![2, 1, 1, 1, 2, 2, 1, 2],
+            Scale::Blues => vec![3, 2, 1, 1, 3, 2],
+            mode => {
+                let mut steps = MAJOR_STEPS;
+                steps.rotate_left(mode.mode_degree().unwrap_or(0));
+                steps.to_vec()
+            }
+        }
+    }
+
+    // which degree of the major scale a mode starts on counting from 0, so dorian is 1
+    // None for the scales that aren't modes of the major
+    pub fn mode_degree(&self) -> Option<usize> {
+        MODES.iter().position(|m| m == self)
+    }
+
+    // the mode starting on a degree (counting from 0) of a parent major scale, with the note it starts on
+    // the 2nd degree of C major is D, so it's D dorian
+    pub fn mode_of_major(parent: &Note, degree: usize) -> Option<(Note, Scale)> {
+        let mode = MODES.get(degree)?;
+        let root = Scale::Major.notes(parent)[degree];
+
+        Some((root, *mode))
+    }
+
+    // the major scale a mode is a rotation of, D dorian's being C
+    pub fn parent_major(&self, root: &Note) -> Option<Note> {
+        let degree = self.mode_degree()?;
+        let below: usize = MAJOR_STEPS[..degree].iter().sum();
+
+        Some(PitchClass::from(*root).down(below).note())
+    }
+
+    // W for a whole step, H for a half, 3 for the augmented 2nd in the harmonic minor
+    pub fn step_pattern(&self) -> String {
+        self.steps()
+            .iter()
+            .map(|s| match s {
+                1 => "H".to_string(),
+                2 => "W".to_string(),
+                _ => s.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    // each degree against the major scale's, so the natural minor is 1 2 b3 4 5 b6 b7
+    // scales without seven notes skip or double up a degree, so how they're spelled is written out
+    pub fn formula(&self) -> Vec<String> {
+        let spelled: &[&str] = match self {
+            Scale::WholeTone => &["1", "2", "3", "#4", "#5", "b7"],
+            Scale::HalfWholeDiminished => &["1", "b2", "#2", "3", "#4", "5", "6", "b7"],
+            Scale::WholeHalfDiminished => &["1", "2", "b3", "4", "b5", "#5", "6", "7"],
+            Scale::BebopDominant => &["1", "2", "3", "4", "5", "6", "b7", "7"],
+            Scale::BebopMajor => &["1", "2", "3", "4", "5", "#5", "6", "7"],
+            Scale::BebopDorian => &["1", "2", "b3", "3", "4", "5", "6", "b7"],
+            Scale::Blues => &["1", "b3", "4", "b5", "5", "b7"],
+            _ => &[],
+        };
+
+        if !spelled.is_empty() {
+            return spelled.iter().map(|d| d.to_string()).collect();
+        }
+
+        let mut semitones = 0;
+
+        self.steps()
+            .iter()
+            .zip(MAJOR_DEGREES)
+            .enumerate()
+            .map(|(degree, (step, major))| {
+                let accidental = match semitones as i32 - major as i32 {
+                    -1 => "b",
+                    1 => "#",
+                    _ => "",
+                };
+                semitones += step;
+                format!("{}{}", accidental, degree + 1)
+            })
+            .collect()
+    }
+
+    // a chord on every degree from stacking every other note of the scale, 3 notes for triads, 4 for 7ths
+    // every other note is only a 3rd apart in a seven note scale, so the others don't get any
+    pub fn diatonic_chords(&self, root: &Note, size: usize) -> Vec<Chord> {
+        let notes = self.notes(root);
+        if notes.len() != 7 {
+            return vec![];
+        }
+
+        (0..notes.len())
+            .map(|degree| {
+                let chord_notes: Vec<Note> = (0..size)
+                    .map(|i| notes[(degree + i * 2) % notes.len()])
+                    .collect();
+                identify_from_root_and_notes(&notes[degree], &chord_notes)
+            })
+            .collect()
+    }
+
+    // the notes going up from the root, not repeating the root at the top
+    pub fn notes(&self, root: &Note) -> Vec<Note> {
+        let mut pitch_class = PitchClass::from(*root);
+
+        self.steps()
+            .iter()
+            .map(|step| {
+                let note = pitch_class.note();
+                pitch_class = pitch_class.up(*step);
+                note
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // from_str
+    //
+
+    #[test]
+    fn test_from_str_names() {
+        assert_eq!(
+            Scale::from_str("harmonic minor").expect("hmm"),
+            Scale::HarmonicMinor
+        );
+        assert_eq!(Scale::from_str(" Dorian ").expect("hmm"), Scale::Dorian);
+        assert_eq!(
+            Scale::from_str("half whole diminished").expect("hmm"),
+            Scale::HalfWholeDiminished
+        );
+        assert_eq!(
+            Scale::from_str("aeolian").expect("hmm"),
+            Scale::NaturalMinor
+        );
+    }
+
+    #[test]
+    fn test_from_str_every_scale() {
+        for scale in SCALES {
+            assert_eq!(Scale::from_str(&scale.to_string()).expect("hmm"), scale);
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!(Scale::from_str("lydian dominant").is_err());
+        assert!(Scale::from_str("").is_err());
+    }
+
+    //
+    // steps
+    //
+
+    #[test]
+    fn test_steps_cover_an_octave() {
+        for scale in SCALES {
+            assert_eq!(scale.steps().iter().sum::<usize>(), 12);
+        }
+    }
+
+    //
+    // notes
+    //
+
+    #[test]
+    fn test_notes_c_major() {
+        assert_eq!(
+            Scale::Major.notes(&Note::C),
+            vec![
+                Note::C,
+                Note::D,
+                Note::E,
+                Note::F,
+                Note::G,
+                Note::A,
+                Note::B
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notes_a_minors() {
+        assert_eq!(
+            Scale::NaturalMinor.notes(&Note::A),
+            vec![
+                Note::A,
+                Note::B,
+                Note::C,
+                Note::D,
+                Note::E,
+                Note::F,
+                Note::G
+            ]
+        );
+        assert_eq!(Scale::HarmonicMinor.notes(&Note::A)[6], Note::Gs);
+        assert_eq!(Scale::MelodicMinor.notes(&Note::A)[5], Note::Fs);
+    }
+
+    #[test]
+    fn test_notes_modes_are_rotations_of_the_parent() {
+        let c_major = Scale::Major.notes(&Note::C);
+
+        for degree in 0..7 {
+            let (root, mode) = Scale::mode_of_major(&Note::C, degree).expect("hmm");
+            let mut rotated = c_major.clone();
+            rotated.rotate_left(degree);
+
+            assert_eq!(mode.notes(&root), rotated);
+            assert_eq!(mode.parent_major(&root), Some(Note::C));
+        }
+    }
+
+    #[test]
+    fn test_notes_jazz_scales_over_g7() {
+        let notes = |scale: Scale| scale.notes(&Note::G);
+
+        assert_eq!(
+            notes(Scale::Altered),
+            vec![
+                Note::G,
+                Note::Gs,
+                Note::As,
+                Note::B,
+                Note::Cs,
+                Note::Ds,
+                Note::F
+            ]
+        );
+        assert_eq!(notes(Scale::HalfWholeDiminished)[1], Note::Gs);
+        assert_eq!(notes(Scale::WholeTone).len(), 6);
+        assert_eq!(notes(Scale::BebopDominant)[7], Note::Fs);
+    }
+
+    #[test]
+    fn test_notes_diminished_repeats_every_minor_third() {
+        let c = Scale::WholeHalfDiminished.notes(&Note::C);
+        let ds = Scale::WholeHalfDiminished.notes(&Note::Ds);
+        let mut rotated = c.clone();
+        rotated.rotate_left(2);
+
+        assert_eq!(ds, rotated);
+    }
+
+    //
+    // mode_of_major
+    //
+
+    #[test]
+    fn test_mode_of_major() {
+        assert_eq!(
+            Scale::mode_of_major(&Note::C, 1),
+            Some((Note::D, Scale::Dorian))
+        );
+        assert_eq!(
+            Scale::mode_of_major(&Note::G, 4),
+            Some((Note::D, Scale::Mixolydian))
+        );
+        assert_eq!(Scale::mode_of_major(&Note::C, 7), None);
+        assert_eq!(Scale::HarmonicMinor.parent_major(&Note::A), None);
+    }
+
+    //
+    // formula
+    //
+
+    #[test]
+    fn test_formula() {
+        assert_eq!(Scale::Major.formula().join(" "), "1 2 3 4 5 6 7");
+        assert_eq!(Scale::NaturalMinor.formula().join(" "), "1 2 b3 4 5 b6 b7");
+        assert_eq!(Scale::HarmonicMinor.formula().join(" "), "1 2 b3 4 5 b6 7");
+        assert_eq!(Scale::MelodicMinor.formula().join(" "), "1 2 b3 4 5 6 7");
+        assert_eq!(Scale::Lydian.formula().join(" "), "1 2 3 #4 5 6 7");
+        assert_eq!(Scale::Locrian.formula().join(" "), "1 b2 b3 4 b5 b6 b7");
+        assert_eq!(Scale::Altered.formula().join(" "), "1 b2 b3 b4 b5 b6 b7");
+    }
+
+    #[test]
+    fn test_formula_one_degree_per_note() {
+        for scale in SCALES {
+            assert_eq!(scale.formula().len(), scale.steps().len());
+        }
+    }
+
+    //
+    // diatonic_chords
+    //
+
+    #[test]
+    fn test_diatonic_chords_c_major() {
+        let triads: Vec<String> = Scale::Major
+            .diatonic_chords(&Note::C, 3)
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        let sevenths: Vec<String> = Scale::Major
+            .diatonic_chords(&Note::C, 4)
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+
+        assert_eq!(triads, vec!["C", "Dm", "Em", "F", "G", "Am", "Bdim"]);
+        assert_eq!(
+            sevenths,
+            vec!["Cmaj7", "Dm7", "Em7", "Fmaj7", "G7", "Am7", "Bm7b5"]
+        );
+    }
+
+    #[test]
+    fn test_diatonic_chords_only_seven_note_scales() {
+        assert!(Scale::WholeTone.diatonic_chords(&Note::C, 3).is_empty());
+        assert!(Scale::BebopDominant.diatonic_chords(&Note::C, 4).is_empty());
+    }
+
+    //
+    // step_pattern
+    //
+
+    #[test]
+    fn test_step_pattern() {
+        assert_eq!(Scale::Major.step_pattern(), "W W H W W W H");
+        assert_eq!(Scale::HarmonicMinor.step_pattern(), "W H W W H 3 H");
+    }
+}
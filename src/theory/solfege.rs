@@ -0,0 +1,51 @@
+use crate::theory::{interval::semitones_between, note::Note};
+
+// movable do, do is always the tonic of the key
+// chromatic notes use the syllables choirs most often see in practice,
+// the lowered 3rd, 6th and 7th (me, le, te) from borrowing the minor and the raised 4th (fi)
+// https://en.wikipedia.org/wiki/Solf%C3%A8ge#Movable_do_solf%C3%A8ge
+pub const MOVABLE_DO: [&str; 12] = [
+    "do", "ra", "re", "me", "mi", "fa", "fi", "sol", "le", "la", "te", "ti",
+];
+
+pub fn get_syllable(tonic: &Note, note: &Note) -> &'static str {
+    MOVABLE_DO[semitones_between(tonic, note)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // get_syllable
+    //
+
+    #[test]
+    fn test_get_syllable_tonic() {
+        assert_eq!(get_syllable(&Note::G, &Note::G), "do");
+    }
+
+    #[test]
+    fn test_get_syllable_major_scale_in_d() {
+        let scale = [
+            Note::D,
+            Note::E,
+            Note::Fs,
+            Note::G,
+            Note::A,
+            Note::B,
+            Note::Cs,
+        ];
+
+        let ret: Vec<&str> = scale.iter().map(|n| get_syllable(&Note::D, n)).collect();
+
+        assert_eq!(ret, vec!["do", "re", "mi", "fa", "sol", "la", "ti"]);
+    }
+
+    #[test]
+    fn test_get_syllable_chromatic() {
+        assert_eq!(get_syllable(&Note::C, &Note::As), "te");
+        assert_eq!(get_syllable(&Note::C, &Note::Fs), "fi");
+        assert_eq!(get_syllable(&Note::C, &Note::Cs), "ra");
+    }
+}
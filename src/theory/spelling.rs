@@ -0,0 +1,543 @@
+use std::{fmt, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    parser::chord_parser::{identify_from_name, read_root, RootCase},
+    theory::{
+        chord::Chord,
+        error::{NoteParseError, Span},
+        interval::Interval,
+        key::Key,
+        note::Note,
+        pitch::Pitch,
+        pitch_class::PitchClass,
+        voicing::Voicing,
+    },
+};
+
+// a Note is just which key on the piano, how it's written depends on what it's doing
+// the 3rd of an Ebm is a Gb not an F#, every chord tone goes on its own letter up from the root
+// and every note of a key on its own letter up from the tonic, so a key never has two Gs in it
+// https://en.wikipedia.org/wiki/Enharmonic
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+const LETTERS: [Letter; 7] = [
+    Letter::C,
+    Letter::D,
+    Letter::E,
+    Letter::F,
+    Letter::G,
+    Letter::A,
+    Letter::B,
+];
+
+impl Letter {
+    // semitones up from C without any accidental
+    fn position(&self) -> usize {
+        match self {
+            Letter::C => 0,
+            Letter::D => 2,
+            Letter::E => 4,
+            Letter::F => 5,
+            Letter::G => 7,
+            Letter::A => 9,
+            Letter::B => 11,
+        }
+    }
+
+    // this many letters up, going round from B to C
+    pub fn up(&self, steps: usize) -> Letter {
+        let index = LETTERS.iter().position(|l| l == self).unwrap_or(0);
+        LETTERS[(index + steps) % LETTERS.len()]
+    }
+}
+
+impl fmt::Display for Letter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// a letter and how many semitones it's raised (positive, sharps) or lowered (negative, flats)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SpelledNote {
+    pub letter: Letter,
+    pub accidental: i8,
+}
+
+impl SpelledNote {
+    // the note it sounds as
+    pub fn note(&self) -> Note {
+        PitchClass::new((self.letter.position() as i32 + 12 + self.accidental as i32) as usize)
+            .note()
+    }
+
+    // the note written on a letter, whatever accidentals that takes, an E on the letter F is Fb
+    pub fn on_letter(note: Note, letter: Letter) -> SpelledNote {
+        let up = PitchClass::new(letter.position()).distance(&PitchClass::from(note)) as i8;
+
+        SpelledNote {
+            letter,
+            // the nearer way round, 11 semitones up is 1 down
+            accidental: if up > 6 { up - 12 } else { up },
+        }
+    }
+
+    // the black keys as sharps, how Note itself prints them
+    pub fn sharp(note: Note) -> SpelledNote {
+        let letter = LETTERS
+            .into_iter()
+            .rev()
+            .find(|l| l.position() <= note.position())
+            .unwrap_or(Letter::C);
+
+        SpelledNote::on_letter(note, letter)
+    }
+
+    // and as flats
+    pub fn flat(note: Note) -> SpelledNote {
+        let letter = LETTERS
+            .into_iter()
+            .find(|l| l.position() >= note.position())
+            .unwrap_or(Letter::B);
+
+        SpelledNote::on_letter(note, letter)
+    }
+
+    // an interval up, on the letter its number says, a minor 3rd up from Eb is Gb
+    // None for an Unknown interval that has no number
+    pub fn up(&self, interval: Interval) -> Option<SpelledNote> {
+        let letter = self.letter.up(interval.letter_steps()?);
+        Some(SpelledNote::on_letter(
+            (PitchClass::from(self.note()) + interval).note(),
+            letter,
+        ))
+    }
+}
+
+// Eb, F#, Bbb, C##
+impl fmt::Display for SpelledNote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let accidentals = match self.accidental {
+            accidental if accidental < 0 => "b".repeat(accidental.unsigned_abs() as usize),
+            accidental => "#".repeat(accidental as usize),
+        };
+
+        write!(f, "{}{}", self.letter, accidentals)
+    }
+}
+
+impl FromStr for SpelledNote {
+    type Err = NoteParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NoteParseError::InvalidNoteStringValue(Span::new(s, 0, s.len()));
+
+        let mut chars = s.chars();
+        let letter = match chars.next() {
+            Some('C') => Letter::C,
+            Some('D') => Letter::D,
+            Some('E') => Letter::E,
+            Some('F') => Letter::F,
+            Some('G') => Letter::G,
+            Some('A') => Letter::A,
+            Some('B') => Letter::B,
+            _ => return Err(invalid()),
+        };
+
+        let accidentals: String = chars
+            .map(|c| match c {
+                '♯' => '#',
+                '♭' => 'b',
+                c => c,
+            })
+            .collect();
+        let accidental = match accidentals.as_str() {
+            "" => 0,
+            a if a.chars().all(|c| c == '#') && a.len() <= 2 => a.len() as i8,
+            a if a.chars().all(|c| c == 'b') && a.len() <= 2 => -(a.len() as i8),
+            _ => return Err(invalid()),
+        };
+
+        Ok(SpelledNote { letter, accidental })
+    }
+}
+
+// the key's notes a letter each from the tonic, the tonic with flats in a key signature of flats
+// so F major has a Bb and Eb minor is Eb F Gb Ab Bb Cb Db
+pub fn spell_key(key: &Key) -> Vec<SpelledNote> {
+    let tonic = spell_tonic(key);
+
+    key.notes()
+        .into_iter()
+        .enumerate()
+        .map(|(degree, note)| SpelledNote::on_letter(note, tonic.letter.up(degree)))
+        .collect()
+}
+
+// the tonic with a flat in a key signature of flats, Bb major rather than A# major
+pub fn spell_tonic(key: &Key) -> SpelledNote {
+    match key.signature() < 0 {
+        true => SpelledNote::flat(key.tonic),
+        false => SpelledNote::sharp(key.tonic),
+    }
+}
+
+// a note as the key writes it, the notes off the key going the way its signature does
+pub fn spell_in_key(note: Note, key: &Key) -> SpelledNote {
+    match spell_key(key).into_iter().find(|n| n.note() == note) {
+        Some(spelled) => spelled,
+        None if key.signature() < 0 => SpelledNote::flat(note),
+        None => SpelledNote::sharp(note),
+    }
+}
+
+// how the name writes a note, the Eb at the start of Ebm7 or the Bb after the slash in Cm/Bb
+// counted in characters so a B♭ is read whole like a Bb
+fn written(text: &str, note: Note) -> Option<SpelledNote> {
+    let ends: Vec<usize> = text
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take(2)
+        .collect();
+
+    ends.into_iter()
+        .rev()
+        .filter_map(|end| SpelledNote::from_str(&text[..end]).ok())
+        .find(|spelled| spelled.note() == note)
+}
+
+// how many sharps and flats the chord has written on this root, an A#7 needs a C## where a Bb7 has just the Ab
+fn accidentals_on(root: SpelledNote, chord: &Chord) -> u32 {
+    std::iter::once(root)
+        .chain(chord.intervals.iter().filter_map(|i| root.up(*i)))
+        .map(|n| n.accidental.unsigned_abs() as u32)
+        .sum()
+}
+
+// a root someone wrote stays as they wrote it, C# is C# even where Db would be simpler
+// one worked out from notes or a degree is as the key has it if it's one of the key's notes,
+// otherwise whichever of the way the key's signature goes, or sharp or flat, gets the chord written with the fewest accidentals
+fn spell_root(chord: &Chord, key: Option<&Key>) -> SpelledNote {
+    if let Some(root) = written_root(chord) {
+        return root;
+    }
+
+    let in_key = key.and_then(|key| spell_key(key).into_iter().find(|n| n.note() == chord.root));
+    if let Some(root) = in_key {
+        return root;
+    }
+
+    let candidates = [
+        key.map(|key| spell_in_key(chord.root, key)),
+        Some(SpelledNote::sharp(chord.root)),
+        Some(SpelledNote::flat(chord.root)),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        // min_by_key keeps the first of equals
+        .min_by_key(|root| accidentals_on(*root, chord))
+        .unwrap_or(SpelledNote::sharp(chord.root))
+}
+
+fn written_root(chord: &Chord) -> Option<SpelledNote> {
+    chord
+        .written
+        .then(|| written(&chord.name, chord.root))
+        .flatten()
+}
+
+// the bass after the slash as it was written, when it was
+fn written_bass(chord: &Chord) -> Option<SpelledNote> {
+    let bass = chord.bass.filter(|_| chord.written)?;
+    let (_, written_bass) = chord.name.rsplit_once('/')?;
+    written(written_bass.trim(), bass)
+}
+
+// each of the chord's notes in the same order, the chord tones on their letters up from the root
+// and the bass as it was written after the slash, or as the key has it if it's none of them
+pub fn spell_chord(chord: &Chord, key: Option<&Key>) -> Vec<SpelledNote> {
+    let root = spell_root(chord, key);
+    let tones: Vec<SpelledNote> = std::iter::once(root)
+        .chain(chord.intervals.iter().filter_map(|i| root.up(*i)))
+        .collect();
+    let bass = written_bass(chord);
+
+    chord
+        .notes
+        .iter()
+        .map(|note| {
+            bass.filter(|b| b.note() == *note)
+                .or_else(|| tones.iter().find(|t| t.note() == *note).copied())
+                .unwrap_or_else(|| match key {
+                    Some(key) => spell_in_key(*note, key),
+                    None => SpelledNote::sharp(*note),
+                })
+        })
+        .collect()
+}
+
+// the name with its root and bass spelled the same way, A#7 worked out in F is Bb7
+pub fn spell_name(chord: &Chord, key: Option<&Key>) -> String {
+    let spelled = spell_chord(chord, key);
+    let root = spell_root(chord, key);
+
+    let symbol = chord.symbol_over_bass();
+    // a name that doesn't start with the root (Ambiguous) has nothing to respell
+    let quality = match read_root(symbol, RootCase::Upper) {
+        Some(((root, _), quality)) if root == chord.root => quality,
+        _ => return chord.name.clone(),
+    };
+
+    match chord.bass.filter(|_| symbol.len() < chord.name.len()) {
+        Some(bass) => {
+            let bass = spelled
+                .iter()
+                .find(|n| n.note() == bass)
+                .copied()
+                .unwrap_or(SpelledNote::sharp(bass));
+            format!("{}{}/{}", root, quality, bass)
+        }
+        None => format!("{}{}", root, quality),
+    }
+}
+
+// another reading of a chord's notes by its name, spelled the way a name worked out from notes is
+// so the alternatives to Eb6 read Cm7/Eb rather than Cm7/D#
+pub fn spell_reading(name: &str, key: Option<&Key>) -> String {
+    match identify_from_name(name.to_string()) {
+        Ok(chord) => spell_name(
+            &Chord {
+                written: false,
+                ..chord
+            },
+            key,
+        ),
+        Err(_) => name.to_string(),
+    }
+}
+
+// a voicing's pitches with the note names the chord has, the octave going with the letter so a B#3 is a C4's pitch
+pub fn spell_voicing(voicing: &Voicing, spelled: &[SpelledNote]) -> String {
+    voicing
+        .pitches
+        .iter()
+        .map(|pitch| {
+            let note = Note::from_midi(*pitch);
+            match spelled.iter().find(|n| n.note() == note) {
+                Some(n) => {
+                    let natural = *pitch as i32 - n.accidental as i32;
+                    format!("{}{}", n, natural.div_euclid(12) - 1)
+                }
+                None => Pitch(*pitch).to_string(),
+            }
+        })
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the chord as if it had been worked out from its notes, with nothing written to keep to
+    fn worked_out(name: &str) -> Chord {
+        Chord {
+            written: false,
+            ..identify_from_name(name.to_string()).expect("hmm")
+        }
+    }
+
+    fn spelled_chord(chord: &Chord, key: Option<&Key>) -> String {
+        spell_chord(chord, key)
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn spelled(name: &str, key: Option<&Key>) -> String {
+        let chord = identify_from_name(name.to_string()).expect("hmm");
+        spell_chord(&chord, key)
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    //
+    // SpelledNote::from_str
+    //
+
+    #[test]
+    fn test_spelled_note_from_str() {
+        let ret = SpelledNote::from_str("Bbb").expect("hmm");
+
+        assert_eq!(ret.letter, Letter::B);
+        assert_eq!(ret.accidental, -2);
+        assert_eq!(ret.note(), Note::A);
+        assert!(SpelledNote::from_str("H").is_err());
+        assert!(SpelledNote::from_str("C#b").is_err());
+    }
+
+    //
+    // SpelledNote::on_letter
+    //
+
+    #[test]
+    fn test_spelled_note_on_letter() {
+        assert_eq!(SpelledNote::on_letter(Note::E, Letter::F).to_string(), "Fb");
+        assert_eq!(SpelledNote::on_letter(Note::C, Letter::B).to_string(), "B#");
+        assert_eq!(SpelledNote::flat(Note::Ds).to_string(), "Eb");
+        assert_eq!(SpelledNote::sharp(Note::Ds).to_string(), "D#");
+    }
+
+    //
+    // spell_chord
+    //
+
+    #[test]
+    fn test_spell_chord_written_root() {
+        assert_eq!(spelled("Ebm", None), "Eb Gb Bb");
+        assert_eq!(spelled("D#m", None), "D# F# A#");
+    }
+
+    #[test]
+    fn test_spell_chord_double_flats() {
+        assert_eq!(spelled("Ebdim7", None), "Eb Gb Bbb Dbb");
+    }
+
+    #[test]
+    fn test_spell_chord_in_key() {
+        let key = Key::major(Note::F);
+
+        assert_eq!(spelled_chord(&worked_out("A#"), Some(&key)), "Bb D F");
+        assert_eq!(spelled("C7", Some(&key)), "C E G Bb");
+    }
+
+    #[test]
+    fn test_spell_chord_keeps_written_root() {
+        let key = Key::major(Note::F);
+
+        assert_eq!(spelled("C#", None), "C# E# G#");
+        assert_eq!(spelled("F#m7", None), "F# A C# E");
+        assert_eq!(spelled("A#", Some(&key)), "A# C## E#");
+        assert_eq!(spelled("C#/Bb", None), "Bb C# E# G#");
+    }
+
+    #[test]
+    fn test_spell_chord_slash_bass() {
+        assert_eq!(spelled("Cm/Bb", None), "Bb C Eb G");
+    }
+
+    //
+    // spell_key
+    //
+
+    #[test]
+    fn test_spell_key_flats() {
+        let ret: Vec<String> = spell_key(&Key::minor(Note::Ds))
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+
+        assert_eq!(ret, vec!["D#", "E#", "F#", "G#", "A#", "B", "C#"]);
+
+        let ret: Vec<String> = spell_key(&Key::major(Note::Cs))
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+
+        assert_eq!(ret, vec!["Db", "Eb", "F", "Gb", "Ab", "Bb", "C"]);
+    }
+
+    //
+    // spell_name
+    //
+
+    #[test]
+    fn test_spell_name() {
+        let chord = worked_out("A#7/D");
+
+        assert_eq!(spell_name(&chord, Some(&Key::major(Note::F))), "Bb7/D");
+        // no key, but a Bb7 is written with fewer accidentals than an A#7 and its C##
+        assert_eq!(spell_name(&chord, None), "Bb7/D");
+    }
+
+    #[test]
+    fn test_spell_name_keeps_written_root() {
+        let name = |name: &str| {
+            let chord = identify_from_name(name.to_string()).expect("hmm");
+            spell_name(&chord, Some(&Key::major(Note::F)))
+        };
+
+        assert_eq!(name("C#"), "C#");
+        assert_eq!(name("C#/Bb"), "C#/Bb");
+        assert_eq!(name("F#m7"), "F#m7");
+        assert_eq!(name("A#7/D"), "A#7/D");
+        assert_eq!(name("C6/9"), "C6/9");
+    }
+
+    #[test]
+    fn test_spell_name_music_symbols() {
+        let chord = identify_from_name("B♭Δ7".to_string()).expect("hmm");
+
+        assert_eq!(spell_name(&chord, None), "BbΔ7");
+        assert_eq!(spelled("E♭m", None), "Eb Gb Bb");
+    }
+
+    //
+    // spell_reading
+    //
+
+    #[test]
+    fn test_spell_reading() {
+        assert_eq!(spell_reading("D#6/C", None), "Eb6/C");
+        assert_eq!(spell_reading("Ambiguous", None), "Ambiguous");
+    }
+
+    //
+    // spell_voicing
+    //
+
+    #[test]
+    fn test_spell_voicing() {
+        let chord = identify_from_name("Cm7".to_string()).expect("hmm");
+        let ret = spell_voicing(&chord.close_voicing(4), &spell_chord(&chord, None));
+
+        assert_eq!(ret, "C4 Eb4 G4 Bb4");
+
+        let chord = identify_from_name("C#".to_string()).expect("hmm");
+        let voicing = Voicing {
+            pitches: vec![61, 65, 68],
+        };
+
+        assert_eq!(
+            spell_voicing(&voicing, &spell_chord(&chord, None)),
+            "C#4 E#4 G#4"
+        );
+
+        // the B3 key is a Cb4
+        let chord = identify_from_name("Abm".to_string()).expect("hmm");
+        let voicing = Voicing {
+            pitches: vec![56, 59, 63],
+        };
+
+        assert_eq!(
+            spell_voicing(&voicing, &spell_chord(&chord, None)),
+            "Ab3 Cb4 Eb4"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    parser::roman_parser::chord_from_numeral,
+    theory::{chord::Chord, error::ChordParseError, key::Key, progression::Progression},
+};
+
+// progressions everyone plays, written as Roman numerals with bar lines so they can go in any key
+// the numerals are read against the key's own scale, so they're written for a major key
+// except the minor ii-V-i, which is the major key's ii-V resolving to the tonic minor
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Template {
+    pub name: &'static str,
+    pub numerals: &'static str,
+}
+
+pub const TEMPLATES: [Template; 7] = [
+    Template {
+        name: "ii-V-I",
+        numerals: "| ii7 | V7 | Imaj7 |",
+    },
+    Template {
+        name: "minor ii-V-i",
+        numerals: "| iiø7 | V7 | i |",
+    },
+    Template {
+        name: "I-V-vi-IV",
+        numerals: "| I | V | vi | IV |",
+    },
+    Template {
+        name: "I-vi-IV-V",
+        numerals: "| I | vi | IV | V |",
+    },
+    Template {
+        name: "12-bar blues",
+        numerals: "| I7 | IV7 | I7 | I7 | IV7 | IV7 | I7 | I7 | V7 | IV7 | I7 | V7 |",
+    },
+    Template {
+        name: "rhythm changes",
+        // I Got Rhythm's chords, AABA with the bridge going round the dominants back to V
+        // the first A turns round to the second, the others end on I
+        numerals:
+            "| Imaj7 vi7 | ii7 V7 | iii7 vi7 | ii7 V7 | Imaj7 I7 | IV7 iv7 | iii7 vi7 | ii7 V7 \
+             | Imaj7 vi7 | ii7 V7 | iii7 vi7 | ii7 V7 | Imaj7 I7 | IV7 iv7 | ii7 V7 | Imaj7 \
+             | III7 | III7 | VI7 | VI7 | II7 | II7 | V7 | V7 \
+             | Imaj7 vi7 | ii7 V7 | iii7 vi7 | ii7 V7 | Imaj7 I7 | IV7 iv7 | ii7 V7 | Imaj7 |",
+    },
+    Template {
+        name: "turnaround",
+        numerals: "| Imaj7 vi7 | ii7 V7 |",
+    },
+];
+
+impl Template {
+    // the chords in a key, keeping the bars
+    pub fn in_key(&self, key: &Key) -> Result<Progression, ChordParseError> {
+        let mut chords: Vec<Chord> = vec![];
+        let mut bars: Vec<usize> = vec![];
+
+        let written_bars = self
+            .numerals
+            .split('|')
+            .map(|bar| bar.split_whitespace().collect::<Vec<&str>>())
+            .filter(|numerals| !numerals.is_empty());
+
+        for (bar, numerals) in written_bars.enumerate() {
+            for numeral in numerals {
+                chords.push(chord_from_numeral(numeral, key)?);
+                bars.push(bar);
+            }
+        }
+
+        let beats = vec![1; chords.len()];
+        Ok(Progression {
+            chords,
+            bars,
+            beats,
+        })
+    }
+}
+
+// by name, ignoring case and with spaces for dashes, so "ii V I" finds the ii-V-I
+pub fn find_template(name: &str) -> Option<Template> {
+    let simplify = |s: &str| s.trim().to_lowercase().replace(['-', '–'], " ");
+
+    TEMPLATES
+        .into_iter()
+        .find(|t| simplify(t.name) == simplify(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::note::Note;
+
+    fn names(progression: &Progression) -> Vec<&str> {
+        progression.chords.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    //
+    // in_key
+    //
+
+    #[test]
+    fn test_in_key_ii_v_i() {
+        let ret = find_template("ii-V-I")
+            .expect("hmm")
+            .in_key(&Key::major(Note::D))
+            .expect("hmm");
+
+        assert_eq!(names(&ret), vec!["Em7", "A7", "Dmaj7"]);
+        assert_eq!(ret.bar_count(), 3);
+    }
+
+    #[test]
+    fn test_in_key_blues() {
+        let ret = find_template("12-bar blues")
+            .expect("hmm")
+            .in_key(&Key::major(Note::A))
+            .expect("hmm");
+
+        assert_eq!(ret.bar_count(), 12);
+        assert_eq!(ret.chords[1].name, "D7");
+        assert_eq!(ret.chords[8].name, "E7");
+    }
+
+    #[test]
+    fn test_in_key_every_template() {
+        for template in TEMPLATES {
+            assert!(template.in_key(&Key::major(Note::F)).is_ok());
+        }
+
+        let rhythm = find_template("rhythm changes")
+            .expect("hmm")
+            .in_key(&Key::major(Note::C))
+            .expect("hmm");
+        assert_eq!(rhythm.bar_count(), 32);
+    }
+
+    //
+    // find_template
+    //
+
+    #[test]
+    fn test_find_template_loose_name() {
+        assert_eq!(
+            find_template("I V vi IV").map(|t| t.name),
+            Some("I-V-vi-IV")
+        );
+        assert!(find_template("giant steps").is_none());
+    }
+}
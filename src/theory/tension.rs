@@ -0,0 +1,171 @@
+use std::fmt;
+
+use crate::theory::{
+    chord::Chord,
+    extensions::SeventhKind,
+    interval::{get_interval, Interval},
+    note::Note,
+    pitch_class::PitchClass,
+};
+
+// the notes above the 7th worth trying over a chord when improvising, smallest first
+// the altered ones only really belong on a dominant 7th, where they pull towards the next chord
+const TENSIONS: [Interval; 7] = [
+    Interval::MinorNinth,
+    Interval::MajorNinth,
+    Interval::AugmentedNinth,
+    Interval::PerfectEleventh,
+    Interval::AugmentedEleventh,
+    Interval::MinorThirteenth,
+    Interval::MajorThirteenth,
+];
+
+const ALTERED_TENSIONS: [Interval; 3] = [
+    Interval::MinorNinth,
+    Interval::AugmentedNinth,
+    Interval::MinorThirteenth,
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Tension {
+    pub interval: Interval,
+    pub note: Note,
+}
+
+// written the way it'd go in a chord symbol, b9 rather than Minor 9th
+impl fmt::Display for Tension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self.interval {
+            Interval::MinorNinth => "b9",
+            Interval::MajorNinth => "9",
+            Interval::AugmentedNinth => "#9",
+            Interval::PerfectEleventh => "11",
+            Interval::AugmentedEleventh => "#11",
+            Interval::MinorThirteenth => "b13",
+            Interval::MajorThirteenth => "13",
+            _ => "?",
+        };
+
+        write!(f, "{} ({})", label, self.note)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Tensions {
+    pub available: Vec<Tension>,
+    pub avoid: Vec<Tension>,
+}
+
+impl Chord {
+    // a dominant 7th is the one chord that takes the altered tensions
+    fn is_dominant(&self) -> bool {
+        self.intervals.contains(&Interval::MajorThird)
+            && self.extensions.seventh == Some(SeventhKind::Minor)
+    }
+
+    // the tensions that aren't already in the chord, split into ones that sit well over it and ones to avoid
+    // an avoid note is a half step above a chord tone, like the 11 (F) over the 3rd (E) of a C,
+    // it rubs against the chord rather than colouring it
+    // altered tensions are left out unless the chord is a dominant 7th, where they're all fair game
+    // the #11 is only there to stand in for an 11 that has to be avoided, over a minor chord the 11 does the job
+    pub fn tensions(&self) -> Tensions {
+        let chord_tones: Vec<PitchClass> = self
+            .chord_tones()
+            .into_iter()
+            .map(PitchClass::from)
+            .collect();
+        let dominant = self.is_dominant();
+
+        let mut tensions = Tensions::default();
+
+        for interval in TENSIONS {
+            if !dominant && ALTERED_TENSIONS.contains(&interval) {
+                continue;
+            }
+
+            let note = match get_interval(&self.root, interval) {
+                Ok(note) => note,
+                Err(_) => continue,
+            };
+            let pitch_class = PitchClass::from(note);
+
+            if chord_tones.contains(&pitch_class) {
+                continue;
+            }
+
+            if interval == Interval::AugmentedEleventh
+                && !tensions
+                    .avoid
+                    .iter()
+                    .any(|t| t.interval == Interval::PerfectEleventh)
+            {
+                continue;
+            }
+
+            let tension = Tension { interval, note };
+            let rubs = chord_tones.contains(&pitch_class.down(1));
+
+            if rubs && !(dominant && ALTERED_TENSIONS.contains(&interval)) {
+                tensions.avoid.push(tension);
+            } else {
+                tensions.available.push(tension);
+            }
+        }
+
+        tensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    fn labels(tensions: &[Tension]) -> Vec<String> {
+        tensions.iter().map(|t| t.to_string()).collect()
+    }
+
+    fn tensions(name: &str) -> Tensions {
+        identify_from_name(name.to_string())
+            .expect("hmm")
+            .tensions()
+    }
+
+    //
+    // tensions
+    //
+
+    #[test]
+    fn test_tensions_major_seventh_avoids_eleven() {
+        let ret = tensions("Cmaj7");
+
+        assert_eq!(labels(&ret.available), vec!["9 (D)", "#11 (F#)", "13 (A)"]);
+        assert_eq!(labels(&ret.avoid), vec!["11 (F)"]);
+    }
+
+    #[test]
+    fn test_tensions_minor_seventh_takes_eleven() {
+        let ret = tensions("Dm7");
+
+        assert_eq!(labels(&ret.available), vec!["9 (E)", "11 (G)", "13 (B)"]);
+        assert!(ret.avoid.is_empty());
+    }
+
+    #[test]
+    fn test_tensions_dominant_takes_alterations() {
+        let ret = tensions("G7");
+
+        assert_eq!(
+            labels(&ret.available),
+            vec!["b9 (G#)", "9 (A)", "#9 (A#)", "#11 (C#)", "b13 (D#)", "13 (E)"]
+        );
+        assert_eq!(labels(&ret.avoid), vec!["11 (C)"]);
+    }
+
+    #[test]
+    fn test_tensions_leaves_out_chord_tones() {
+        let ret = tensions("C9");
+
+        assert!(!ret.available.iter().any(|t| t.note == Note::D));
+    }
+}
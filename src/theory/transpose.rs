@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use crate::{
+    parser::chord_parser::{identify_from_root_and_notes, read_root, RootCase},
+    theory::{
+        chord::{Alternative, Chord},
+        note::Note,
+        pitch_class::PitchClass,
+    },
+};
+
+// semitones from the tonic for each degree of the major scale
+pub const MAJOR_SCALE_STEPS: [usize; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+// move a note up (or down for negative degrees) by steps of the major scale on the tonic
+// this keeps the note in the key so a 3rd above might be major or minor depending on where it starts
+// notes outside the key keep their distance from the scale note just below them,
+// so in C major a Bb (A + 1) up one degree is a C (B + 1)
+pub fn transpose_note_diatonic(tonic: &Note, note: &Note, degrees: i32) -> Note {
+    let tonic = PitchClass::from(*tonic);
+    let from_tonic = tonic.distance(&PitchClass::from(*note));
+
+    // the nearest scale degree at or below the note, there's always one as the tonic is 0
+    let degree = MAJOR_SCALE_STEPS
+        .iter()
+        .rposition(|step| *step <= from_tonic)
+        .unwrap_or_default();
+    let chromatic_offset = from_tonic - MAJOR_SCALE_STEPS[degree];
+
+    let new_degree = (degree as i32 + degrees).rem_euclid(7) as usize;
+    tonic
+        .up(MAJOR_SCALE_STEPS[new_degree] + chromatic_offset)
+        .note()
+}
+
+pub fn transpose_melody_diatonic(tonic: &Note, melody: &[Note], degrees: i32) -> Vec<Note> {
+    melody
+        .iter()
+        .map(|n| transpose_note_diatonic(tonic, n, degrees))
+        .collect()
+}
+
+// every note of the chord moves within the key so the quality follows the key,
+// C in C major up a degree is a Dm, G7 becomes Am7
+pub fn transpose_chord_diatonic(tonic: &Note, chord: &Chord, degrees: i32) -> Chord {
+    let root = transpose_note_diatonic(tonic, &chord.root, degrees);
+    let notes = transpose_melody_diatonic(tonic, &chord.notes, degrees);
+
+    identify_from_root_and_notes(&root, &notes)
+}
+
+// a note moved by semitones, up for positive and down for negative
+fn shift(note: &Note, semitones: i32) -> Note {
+    PitchClass::from(*note)
+        .up(semitones.rem_euclid(12) as usize)
+        .note()
+}
+
+// the root at the start of a name and any bass after its slash moved, the symbol in between left as written
+// so aliases and however the chord was spelled come through as they were, C^/E up a tone is D^/F#
+// and B♭Δ7 up a tone is CΔ7
+pub fn transpose_name(name: &str, semitones: i32) -> String {
+    let (symbol, bass) = match name.rsplit_once('/') {
+        Some((symbol, bass)) => match Note::from_str(bass.trim()) {
+            Ok(bass) => (symbol, Some(bass)),
+            Err(_) => (name, None),
+        },
+        None => (name, None),
+    };
+
+    // read the way the parser reads it, so a B♭ root is all of the B♭ and the Δ7 after is left alone
+    let moved = match read_root(symbol, RootCase::Upper) {
+        Some(((root, _), rest)) => format!("{}{}", shift(&root, semitones), rest),
+        None => symbol.to_string(),
+    };
+
+    match bass {
+        Some(bass) => format!("{}/{}", moved, shift(&bass, semitones)),
+        None => moved,
+    }
+}
+
+// every note moved by the same number of semitones, a capo or a change of key,
+// the chord's quality, extensions and slash bass all staying what they were
+pub fn transpose_chord(chord: &Chord, semitones: i32) -> Chord {
+    Chord {
+        name: transpose_name(&chord.name, semitones),
+        root: shift(&chord.root, semitones),
+        bass: chord.bass.map(|b| shift(&b, semitones)),
+        notes: chord.notes.iter().map(|n| shift(n, semitones)).collect(),
+        alternatives: chord
+            .alternatives
+            .iter()
+            .map(|a| Alternative {
+                name: transpose_name(&a.name, semitones),
+                score: a.score,
+            })
+            .collect(),
+        // the new root's a sharp whatever the old one was written as, so it's spelled afresh
+        written: false,
+        ..chord.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::chord::ChordQuality};
+
+    //
+    // transpose_note_diatonic
+    //
+
+    #[test]
+    fn test_transpose_note_diatonic_wraps_scale() {
+        let ret = transpose_note_diatonic(&Note::G, &Note::Fs, 1);
+
+        assert_eq!(ret, Note::G);
+    }
+
+    #[test]
+    fn test_transpose_note_diatonic_down() {
+        let ret = transpose_note_diatonic(&Note::C, &Note::C, -2);
+
+        assert_eq!(ret, Note::A);
+    }
+
+    #[test]
+    fn test_transpose_note_diatonic_non_diatonic_note() {
+        let ret = transpose_note_diatonic(&Note::C, &Note::As, 1);
+
+        assert_eq!(ret, Note::C);
+    }
+
+    //
+    // transpose_melody_diatonic
+    //
+
+    #[test]
+    fn test_transpose_melody_diatonic_in_d() {
+        let melody = vec![Note::D, Note::E, Note::Fs, Note::D];
+
+        let ret = transpose_melody_diatonic(&Note::D, &melody, 2);
+
+        assert_eq!(ret, vec![Note::Fs, Note::G, Note::A, Note::Fs]);
+    }
+
+    //
+    // transpose_chord_diatonic
+    //
+
+    #[test]
+    fn test_transpose_chord_diatonic_c_to_dm() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        let ret = transpose_chord_diatonic(&Note::C, &chord, 1);
+
+        assert_eq!(ret.name, "Dm");
+        assert_eq!(ret.chord_quality(), ChordQuality::Minor);
+        assert_eq!(ret.notes, vec![Note::D, Note::F, Note::A]);
+    }
+
+    #[test]
+    fn test_transpose_chord_diatonic_g7_to_am7() {
+        let chord = identify_from_name("G7".to_string()).expect("hmm");
+
+        let ret = transpose_chord_diatonic(&Note::C, &chord, 1);
+
+        assert_eq!(ret.name, "Am7");
+    }
+
+    #[test]
+    fn test_transpose_chord_diatonic_keeps_bass() {
+        let chord = identify_from_name("C/E".to_string()).expect("hmm");
+
+        let ret = transpose_chord_diatonic(&Note::C, &chord, 1);
+
+        assert_eq!(ret.name, "Dm/F");
+        assert_eq!(ret.bass, Some(Note::F));
+    }
+
+    //
+    // transpose_chord
+    //
+
+    #[test]
+    fn test_transpose_chord_keeps_quality() {
+        let chord = identify_from_name("Cm7b5".to_string()).expect("hmm");
+
+        let ret = transpose_chord(&chord, 2);
+
+        assert_eq!(ret.name, "Dm7b5");
+        assert_eq!(ret.chord_quality(), chord.chord_quality());
+        assert_eq!(ret.notes, vec![Note::D, Note::F, Note::Gs, Note::C]);
+    }
+
+    #[test]
+    fn test_transpose_chord_slash_bass_down() {
+        let chord = identify_from_name("Bb7/D".to_string()).expect("hmm");
+
+        let ret = transpose_chord(&chord, -3);
+
+        assert_eq!(ret.name, "G7/B");
+        assert_eq!(ret.bass, Some(Note::B));
+        assert_eq!(ret.notes[0], Note::B);
+    }
+
+    //
+    // transpose_name
+    //
+
+    #[test]
+    fn test_transpose_name_music_symbols() {
+        assert_eq!(transpose_name("B♭Δ7", 2), "CΔ7");
+        assert_eq!(transpose_name("F♯m7♭5", -1), "Fm7♭5");
+        assert_eq!(transpose_name("E♭7♯9/B♭", 2), "F7♯9/C");
+    }
+
+    #[test]
+    fn test_transpose_name_roundtrips_through_the_parser() {
+        let chord = identify_from_name("B♭Δ7".to_string()).expect("hmm");
+
+        let ret = transpose_chord(&chord, 3);
+
+        assert_eq!(ret.name, "C#Δ7");
+        assert_eq!(
+            identify_from_name(ret.name.clone()).expect("hmm").notes,
+            ret.notes
+        );
+    }
+}
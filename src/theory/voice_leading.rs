@@ -0,0 +1,364 @@
+use std::fmt;
+
+use crate::theory::{chord::Chord, inversion_plan::get_inversions, pitch::Pitch, voicing::Voicing};
+
+// how each voice of one voicing moves to the next, matched from the bottom up without any crossing
+// when one voicing has more notes the extra voices come in or drop out wherever that moves the rest least
+// and the textbook faults flagged, two voices a 5th or an octave apart moving together to another 5th or octave
+// https://en.wikipedia.org/wiki/Voice_leading
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VoiceMotion {
+    // None for a voice coming in
+    pub from: Option<u8>,
+    // None for a voice dropping out
+    pub to: Option<u8>,
+}
+
+impl VoiceMotion {
+    // up for positive, None unless the voice is there on both sides
+    pub fn semitones(&self) -> Option<i32> {
+        Some(self.to? as i32 - self.from? as i32)
+    }
+}
+
+// C4 -> B3 (-1), with a dash for a voice that isn't there
+impl fmt::Display for VoiceMotion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |p: Option<u8>| p.map_or("-".to_string(), |p| Pitch(p).to_string());
+
+        write!(f, "{} -> {}", side(self.from), side(self.to))?;
+        match self.semitones() {
+            Some(semitones) => write!(f, " ({:+})", semitones),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Parallel {
+    Fifths,
+    Octaves,
+}
+
+impl fmt::Display for Parallel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parallel::Fifths => write!(f, "parallel 5ths"),
+            Parallel::Octaves => write!(f, "parallel octaves"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParallelMotion {
+    pub kind: Parallel,
+    // indexes into the motions, the lower voice first
+    pub voices: (usize, usize),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VoiceLeading {
+    // from the lowest voice up
+    pub motions: Vec<VoiceMotion>,
+    pub parallels: Vec<ParallelMotion>,
+}
+
+impl VoiceLeading {
+    // how far every voice moves all told, the ones coming in or dropping out not counting
+    pub fn total_movement(&self) -> u32 {
+        self.motions
+            .iter()
+            .filter_map(|m| m.semitones())
+            .map(|s| s.unsigned_abs())
+            .sum()
+    }
+}
+
+// which of the longer list each of the shorter one goes with, in order, moving least in total
+// the usual table of the cheapest way to match the first i of one with the first j of the other
+fn match_voices(shorter: &[u8], longer: &[u8]) -> Vec<usize> {
+    let (n, m) = (shorter.len(), longer.len());
+    let distance = |i: usize, j: usize| (shorter[i] as i32 - longer[j] as i32).unsigned_abs();
+
+    // cost[i][j], the shorter's first i matched within the longer's first j
+    let mut cost = vec![vec![u32::MAX; m + 1]; n + 1];
+    cost[0] = vec![0; m + 1];
+    for i in 1..=n {
+        for j in i..=m {
+            let skip = cost[i][j - 1];
+            let take = cost[i - 1][j - 1].saturating_add(distance(i - 1, j - 1));
+            cost[i][j] = skip.min(take);
+        }
+    }
+
+    let mut matched = vec![0; n];
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        if j > i && cost[i][j] == cost[i][j - 1] {
+            j -= 1;
+        } else {
+            matched[i - 1] = j - 1;
+            i -= 1;
+            j -= 1;
+        }
+    }
+
+    matched
+}
+
+// the interval two voices make folded into an octave, only for 5ths and octaves (or unisons)
+fn perfect_interval(low: u8, high: u8) -> Option<Parallel> {
+    match high.abs_diff(low) % 12 {
+        0 => Some(Parallel::Octaves),
+        7 => Some(Parallel::Fifths),
+        _ => None,
+    }
+}
+
+fn find_parallels(motions: &[VoiceMotion]) -> Vec<ParallelMotion> {
+    let mut parallels = vec![];
+
+    for low in 0..motions.len() {
+        for high in low + 1..motions.len() {
+            let (a, b) = (motions[low], motions[high]);
+            let (Some(a_from), Some(a_to), Some(b_from), Some(b_to)) = (a.from, a.to, b.from, b.to)
+            else {
+                continue;
+            };
+            // held notes aren't parallel motion
+            if a_from == a_to {
+                continue;
+            }
+
+            match (
+                perfect_interval(a_from, b_from),
+                perfect_interval(a_to, b_to),
+            ) {
+                (Some(before), Some(after)) if before == after => parallels.push(ParallelMotion {
+                    kind: after,
+                    voices: (low, high),
+                }),
+                _ => (),
+            }
+        }
+    }
+
+    parallels
+}
+
+pub fn lead_voices(from: &Voicing, to: &Voicing) -> VoiceLeading {
+    let motions: Vec<VoiceMotion> = if from.pitches.len() <= to.pitches.len() {
+        let matched = match_voices(&from.pitches, &to.pitches);
+        to.pitches
+            .iter()
+            .enumerate()
+            .map(|(j, p)| VoiceMotion {
+                from: matched
+                    .iter()
+                    .position(|m| *m == j)
+                    .map(|i| from.pitches[i]),
+                to: Some(*p),
+            })
+            .collect()
+    } else {
+        let matched = match_voices(&to.pitches, &from.pitches);
+        from.pitches
+            .iter()
+            .enumerate()
+            .map(|(i, p)| VoiceMotion {
+                from: Some(*p),
+                to: matched.iter().position(|m| *m == i).map(|j| to.pitches[j]),
+            })
+            .collect()
+    };
+
+    let parallels = find_parallels(&motions);
+
+    VoiceLeading { motions, parallels }
+}
+
+// the voicings a chord could move to, every inversion close from the given octave and the one below,
+// which keeps a long progression from wandering off the keyboard; a slash chord keeps its bass
+fn candidate_voicings(chord: &Chord, octave: u8) -> Vec<Voicing> {
+    let inversions = match chord.bass {
+        Some(_) => vec![chord.clone()],
+        None => get_inversions(chord),
+    };
+
+    [octave, octave.saturating_sub(1)]
+        .into_iter()
+        .flat_map(|o| inversions.iter().map(move |i| i.close_voicing(o)))
+        .collect()
+}
+
+// a voicing for every chord with the voices moving as little as they can all told
+// the first chord is as written in close position from the given octave, after that it's a shortest path
+// over each chord's candidate voicings, keeping the cheapest way into each like plan_inversions does
+pub fn smooth_voicings(chords: &[Chord], octave: u8) -> Vec<Voicing> {
+    let options: Vec<Vec<Voicing>> = chords
+        .iter()
+        .enumerate()
+        .map(|(i, chord)| match i {
+            0 => vec![chord.close_voicing(octave)],
+            _ => candidate_voicings(chord, octave),
+        })
+        .collect();
+
+    // (total movement so far, which option of the previous chord it came from)
+    let mut costs: Vec<Vec<(u32, usize)>> = vec![];
+    for (index, chord_options) in options.iter().enumerate() {
+        let chord_costs = chord_options
+            .iter()
+            .map(|option| match costs.last() {
+                Some(previous_costs) => previous_costs
+                    .iter()
+                    .zip(options[index - 1].iter())
+                    .enumerate()
+                    .map(|(from, ((cost, _), previous))| {
+                        (cost + lead_voices(previous, option).total_movement(), from)
+                    })
+                    .min()
+                    .unwrap_or((0, 0)),
+                None => (0, 0),
+            })
+            .collect();
+
+        costs.push(chord_costs);
+    }
+
+    // walk back from the cheapest last voicing
+    let mut plan: Vec<Voicing> = vec![];
+    let mut choice = costs.last().and_then(|last| {
+        last.iter()
+            .enumerate()
+            .min_by_key(|(_, (cost, _))| *cost)
+            .map(|(i, _)| i)
+    });
+    for index in (0..options.len()).rev() {
+        match choice {
+            Some(i) => {
+                plan.push(options[index][i].clone());
+                choice = Some(costs[index][i].1);
+            }
+            None => break,
+        }
+    }
+
+    plan.reverse();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::chord_parser::identify_from_name, theory::note::Note};
+
+    fn voicing(notes: &[(Note, u8)]) -> Voicing {
+        Voicing {
+            pitches: notes.iter().map(|(n, o)| Pitch::new(n, *o).0).collect(),
+        }
+    }
+
+    //
+    // lead_voices
+    //
+
+    #[test]
+    fn test_lead_voices_motion() {
+        let g7 = voicing(&[(Note::G, 3), (Note::B, 3), (Note::F, 4)]);
+        let c = voicing(&[(Note::G, 3), (Note::C, 4), (Note::E, 4)]);
+
+        let ret = lead_voices(&g7, &c);
+
+        assert_eq!(
+            ret.motions
+                .iter()
+                .map(|m| m.semitones())
+                .collect::<Vec<_>>(),
+            vec![Some(0), Some(1), Some(-1)]
+        );
+        assert_eq!(ret.total_movement(), 2);
+        assert!(ret.parallels.is_empty());
+    }
+
+    #[test]
+    fn test_lead_voices_parallel_fifths() {
+        let c = identify_from_name("C".to_string()).expect("hmm");
+        let d = identify_from_name("D".to_string()).expect("hmm");
+
+        let ret = lead_voices(&c.close_voicing(4), &d.close_voicing(4));
+
+        assert_eq!(
+            ret.parallels,
+            vec![ParallelMotion {
+                kind: Parallel::Fifths,
+                voices: (0, 2)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lead_voices_parallel_octaves() {
+        let from = voicing(&[(Note::C, 3), (Note::C, 4)]);
+        let to = voicing(&[(Note::D, 3), (Note::D, 4)]);
+
+        let ret = lead_voices(&from, &to);
+
+        assert_eq!(ret.parallels[0].kind, Parallel::Octaves);
+    }
+
+    #[test]
+    fn test_lead_voices_voice_comes_in() {
+        let c = voicing(&[(Note::C, 4), (Note::E, 4), (Note::G, 4)]);
+        let f = voicing(&[(Note::C, 4), (Note::F, 4), (Note::A, 4), (Note::C, 5)]);
+
+        let ret = lead_voices(&c, &f);
+
+        assert_eq!(ret.motions.len(), 4);
+        assert_eq!(ret.motions[3].from, None);
+        assert_eq!(ret.motions[3].to_string(), "- -> C5");
+        assert_eq!(ret.total_movement(), 3);
+    }
+
+    //
+    // smooth_voicings
+    //
+
+    #[test]
+    fn test_smooth_voicings_common_tones_held() {
+        let chords: Vec<Chord> = ["C", "F", "G", "C"]
+            .iter()
+            .map(|n| identify_from_name(n.to_string()).expect("hmm"))
+            .collect();
+
+        let ret = smooth_voicings(&chords, 4);
+
+        assert_eq!(ret.len(), 4);
+        assert_eq!(ret[0].to_string(), "C4 E4 G4");
+        // F with the C held, C4 F4 A4
+        assert_eq!(ret[1].to_string(), "C4 F4 A4");
+        let total: u32 = ret
+            .windows(2)
+            .map(|w| lead_voices(&w[0], &w[1]).total_movement())
+            .sum();
+        assert!(total <= 12);
+    }
+
+    #[test]
+    fn test_smooth_voicings_slash_bass_kept() {
+        let chords: Vec<Chord> = ["C", "G/B"]
+            .iter()
+            .map(|n| identify_from_name(n.to_string()).expect("hmm"))
+            .collect();
+
+        let ret = smooth_voicings(&chords, 4);
+
+        assert_eq!(ret[1].notes()[0], Note::B);
+    }
+
+    #[test]
+    fn test_smooth_voicings_empty() {
+        assert!(smooth_voicings(&[], 4).is_empty());
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::theory::{
+    chord::Chord,
+    error::ChordParseError,
+    interval::{get_interval, semitones_between, Interval},
+    note::Note,
+    pitch::Pitch,
+};
+
+// the octave open voicings are built from, C3 is an octave below middle C
+const OPEN_VOICING_OCTAVE: u8 = 3;
+
+// a chord as actually played, every note pinned to an octave
+// pitches are midi numbers from the lowest up, 60 being middle C (C4)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Voicing {
+    pub pitches: Vec<u8>,
+}
+
+impl Voicing {
+    pub fn notes(&self) -> Vec<Note> {
+        self.pitches.iter().map(|p| Note::from_midi(*p)).collect()
+    }
+
+    pub fn lowest(&self) -> Option<u8> {
+        self.pitches.first().copied()
+    }
+
+    pub fn highest(&self) -> Option<u8> {
+        self.pitches.last().copied()
+    }
+}
+
+// scientific pitch notation, C4 E4 G4
+impl fmt::Display for Voicing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.pitches.iter().map(|p| Pitch(*p)).join(" "))
+    }
+}
+
+// what counts as each degree for shell and rootless voicings, a sus chord's 4th or 2nd stands in for the 3rd
+const GUIDE_THIRDS: [Interval; 4] = [
+    Interval::MajorThird,
+    Interval::MinorThird,
+    Interval::PerfectFourth,
+    Interval::MajorSecond,
+];
+const GUIDE_SEVENTHS: [Interval; 3] = [
+    Interval::MinorSeventh,
+    Interval::MajorSeventh,
+    Interval::DiminishedSeventh,
+];
+const FIFTHS: [Interval; 3] = [
+    Interval::PerfectFifth,
+    Interval::DiminishedFifth,
+    Interval::AugmentedFifth,
+];
+const NINTHS: [Interval; 3] = [
+    Interval::MajorNinth,
+    Interval::MinorNinth,
+    Interval::AugmentedNinth,
+];
+
+// each note the next one up from the note before, never a unison, starting from the first in the given octave
+fn stack_up(notes: &[Note], octave: u8) -> Vec<u8> {
+    let mut pitches: Vec<u8> = vec![];
+
+    for note in notes.iter() {
+        let pitch = match pitches.last() {
+            Some(below) => {
+                let gap = match semitones_between(&Note::from_midi(*below), note) {
+                    0 => 12,
+                    gap => gap,
+                };
+                below + gap as u8
+            }
+            None => Pitch::new(note, octave).0,
+        };
+        pitches.push(pitch);
+    }
+
+    pitches
+}
+
+impl Chord {
+    // every note as close as it'll go, stacked up from the lowest chord note in the given octave
+    // the lowest note is the bass for slash chords, otherwise the root
+    pub fn close_voicing(&self, octave: u8) -> Voicing {
+        let mut notes: Vec<Note> = self.notes.iter().copied().unique().collect();
+
+        // above a slash bass the chord carries on from the next chord tone up, C/E is E G C not E C G
+        if let Some(bass) = self.bass.filter(|_| !notes.is_empty()) {
+            let upper = &mut notes[1..];
+            if let Some(next) = upper
+                .iter()
+                .position_min_by_key(|n| semitones_between(&bass, n))
+            {
+                upper.rotate_left(next);
+            }
+        }
+
+        Voicing {
+            pitches: stack_up(&notes, octave),
+        }
+    }
+
+    // the jazz shell, just the root with the 3rd and 7th over it, the notes that say what kind of 7th it is
+    // None for anything without both, there's no shell to a triad
+    pub fn shell_voicing(&self, octave: u8) -> Option<Voicing> {
+        let third = self.find_tone(&GUIDE_THIRDS)?;
+        let seventh = self.find_tone(&GUIDE_SEVENTHS)?;
+
+        Some(Voicing {
+            pitches: stack_up(&[self.root, third, seventh], octave),
+        })
+    }
+
+    // the left hand leaves the root to the bass player, 3rd, 5th, 7th and 9th stacked up from the 3rd
+    // a 13th takes the 5th's place, and without a 9th it's just the 3rd, 5th and 7th
+    // so C9 is E G Bb D and C13 is E A Bb D
+    pub fn rootless_voicing(&self, octave: u8) -> Option<Voicing> {
+        let third = self.find_tone(&GUIDE_THIRDS)?;
+        let seventh = self.find_tone(&GUIDE_SEVENTHS)?;
+
+        let fifth = self
+            .find_tone(&[Interval::MajorThirteenth, Interval::MinorThirteenth])
+            .or_else(|| self.find_tone(&FIFTHS));
+        let ninth = self.find_tone(&NINTHS);
+
+        let notes: Vec<Note> = [Some(third), fifth, Some(seventh), ninth]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Some(Voicing {
+            pitches: stack_up(&notes, octave),
+        })
+    }
+
+    // the note for whichever of these intervals the chord has first
+    fn find_tone(&self, intervals: &[Interval]) -> Option<Note> {
+        intervals
+            .iter()
+            .find(|i| self.intervals.contains(i))
+            .and_then(|i| get_interval(&self.root, *i).ok())
+    }
+
+    // spread over more than an octave, every other note above the bass goes up an octave
+    // so C E G becomes C3 G3 E4 and Cmaj7 becomes C3 G3 E4 B4
+    pub fn open_voicing(&self) -> Voicing {
+        let close = self.close_voicing(OPEN_VOICING_OCTAVE);
+
+        let mut pitches: Vec<u8> = close
+            .pitches
+            .iter()
+            .enumerate()
+            .map(|(i, p)| match i % 2 {
+                1 => p + 12,
+                _ => *p,
+            })
+            .collect();
+        pitches.sort();
+
+        Voicing { pitches }
+    }
+
+    // a four note close voicing with the second note from the top dropped an octave, the guitar and
+    // big band spread for 7th chords, so Cmaj7 from C4 is G3 C4 E4 B4
+    // None for anything that isn't four notes
+    pub fn drop2_voicing(&self, octave: u8) -> Option<Voicing> {
+        let mut pitches = self.close_voicing(octave).pitches;
+        if pitches.len() != 4 {
+            return None;
+        }
+
+        pitches[2] -= 12;
+        pitches.sort();
+
+        Some(Voicing { pitches })
+    }
+
+    // every interval at its full size up from the root in the given octave, as the chord is written
+    // so the 9th, 11th and 13th sit above the octave, Gm11 from G3 is G3 A#3 D4 F4 A4 C5
+    // a slash bass goes underneath, as close below the root as it can be
+    pub fn realized_voicing(&self, octave: u8) -> Result<Voicing, ChordParseError> {
+        let root = Pitch::new(&self.root, octave);
+
+        let mut pitches: Vec<u8> = std::iter::once(Ok(root))
+            .chain(self.intervals.iter().map(|i| root.add_interval(*i)))
+            .map(|p| p.map(|p| p.0))
+            .collect::<Result<Vec<u8>, ChordParseError>>()?;
+
+        if let Some(bass) = self.bass {
+            let below = match semitones_between(&bass, &self.root) {
+                0 => 12,
+                gap => gap,
+            };
+            match root.0.checked_sub(below as u8) {
+                Some(bass) => pitches.push(bass),
+                None => {
+                    return Err(ChordParseError::InvalidInterval(format!(
+                        "no room for {} below {}",
+                        bass, root
+                    )))
+                }
+            }
+        }
+
+        pitches.sort();
+        pitches.dedup();
+
+        Ok(Voicing { pitches })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chord_parser::identify_from_name;
+
+    //
+    // close_voicing
+    //
+
+    #[test]
+    fn test_close_voicing_c_major() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        let ret = chord.close_voicing(4);
+
+        assert_eq!(ret.pitches, vec![60, 64, 67]);
+        assert_eq!(ret.to_string(), "C4 E4 G4");
+    }
+
+    // the 9th ends up above the 7th rather than squeezed in as a 2nd
+    #[test]
+    fn test_close_voicing_extensions_stack_up() {
+        let chord = identify_from_name("C9".to_string()).expect("hmm");
+
+        let ret = chord.close_voicing(3);
+
+        assert_eq!(ret.to_string(), "C3 E3 G3 A#3 D4");
+    }
+
+    #[test]
+    fn test_close_voicing_slash_chord_starts_on_bass() {
+        let chord = identify_from_name("C/E".to_string()).expect("hmm");
+
+        let ret = chord.close_voicing(3);
+
+        assert_eq!(ret.to_string(), "E3 G3 C4");
+        assert_eq!(ret.notes(), vec![Note::E, Note::G, Note::C]);
+    }
+
+    #[test]
+    fn test_close_voicing_slash_bass_outside_chord() {
+        let chord = identify_from_name("Am/G".to_string()).expect("hmm");
+
+        assert_eq!(chord.close_voicing(3).to_string(), "G3 A3 C4 E4");
+    }
+
+    //
+    // realized_voicing
+    //
+
+    // the 11th of a Gm11 is well over an octave up, not tucked in as a 4th
+    #[test]
+    fn test_realized_voicing_gm11_spans_two_octaves() {
+        let chord = identify_from_name("Gm11".to_string()).expect("hmm");
+
+        let ret = chord.realized_voicing(3).expect("hmm");
+
+        assert_eq!(ret.to_string(), "G3 A#3 D4 F4 A4 C5");
+        assert_eq!(ret.highest().expect("hmm") - ret.lowest().expect("hmm"), 17);
+        assert_eq!(Pitch(ret.highest().expect("hmm")).octave(), 5);
+    }
+
+    #[test]
+    fn test_realized_voicing_slash_bass_below() {
+        let chord = identify_from_name("C9/E".to_string()).expect("hmm");
+
+        let ret = chord.realized_voicing(4).expect("hmm");
+
+        assert_eq!(ret.to_string(), "E3 C4 E4 G4 A#4 D5");
+    }
+
+    //
+    // shell_voicing
+    //
+
+    #[test]
+    fn test_shell_voicing_dominant() {
+        let chord = identify_from_name("G7".to_string()).expect("hmm");
+
+        let ret = chord.shell_voicing(2).expect("hmm");
+
+        assert_eq!(ret.to_string(), "G2 B2 F3");
+    }
+
+    #[test]
+    fn test_shell_voicing_needs_a_seventh() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        assert_eq!(chord.shell_voicing(3), None);
+    }
+
+    //
+    // rootless_voicing
+    //
+
+    #[test]
+    fn test_rootless_voicing_ninth() {
+        let chord = identify_from_name("Dm9".to_string()).expect("hmm");
+
+        let ret = chord.rootless_voicing(3).expect("hmm");
+
+        assert_eq!(ret.to_string(), "F3 A3 C4 E4");
+        assert!(!ret.notes().contains(&Note::D));
+    }
+
+    #[test]
+    fn test_rootless_voicing_thirteenth_replaces_fifth() {
+        let chord = identify_from_name("C13".to_string()).expect("hmm");
+
+        let ret = chord.rootless_voicing(3).expect("hmm");
+
+        assert_eq!(ret.notes(), vec![Note::E, Note::A, Note::As, Note::D]);
+    }
+
+    #[test]
+    fn test_rootless_voicing_plain_seventh() {
+        let chord = identify_from_name("Cmaj7".to_string()).expect("hmm");
+
+        assert_eq!(
+            chord.rootless_voicing(3).expect("hmm").to_string(),
+            "E3 G3 B3"
+        );
+        assert_eq!(
+            identify_from_name("Cm".to_string())
+                .expect("hmm")
+                .rootless_voicing(3),
+            None
+        );
+    }
+
+    //
+    // open_voicing
+    //
+
+    #[test]
+    fn test_open_voicing_triad() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        assert_eq!(chord.open_voicing().to_string(), "C3 G3 E4");
+    }
+
+    #[test]
+    fn test_open_voicing_seventh() {
+        let chord = identify_from_name("Cmaj7".to_string()).expect("hmm");
+
+        let ret = chord.open_voicing();
+
+        assert_eq!(ret.to_string(), "C3 G3 E4 B4");
+        assert_eq!(ret.lowest(), Some(48));
+        assert_eq!(ret.highest(), Some(71));
+    }
+
+    //
+    // drop2_voicing
+    //
+
+    #[test]
+    fn test_drop2_voicing_seventh() {
+        let chord = identify_from_name("Cmaj7".to_string()).expect("hmm");
+
+        let ret = chord.drop2_voicing(4).expect("hmm");
+
+        assert_eq!(ret.to_string(), "G3 C4 E4 B4");
+    }
+
+    #[test]
+    fn test_drop2_voicing_needs_four_notes() {
+        let chord = identify_from_name("C".to_string()).expect("hmm");
+
+        assert_eq!(chord.drop2_voicing(4), None);
+    }
+}
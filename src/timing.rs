@@ -0,0 +1,81 @@
+use std::fmt;
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+// the parts of a command --timing reports on
+// parse is reading what was typed in or loaded, derivation is working out notes, voicings or fingerings from it,
+// analysis is identifying and ranking chords
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phase {
+    Parse,
+    Derivation,
+    Analysis,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Derivation => write!(f, "derivation"),
+            Phase::Analysis => write!(f, "analysis"),
+        }
+    }
+}
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+pub fn set_enabled(enabled: bool) {
+    // only ever set once at startup, a second go is ignored
+    let _ = ENABLED.set(enabled);
+}
+
+// run a phase of a command, reporting how long it took when --timing is on
+// reports go to stderr so they never get mixed in with the output itself
+pub fn timed<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !*ENABLED.get().unwrap_or(&false) {
+        return f();
+    }
+
+    let start = Instant::now();
+    let res = f();
+    eprintln!("{}", format_report(phase, start.elapsed()));
+
+    res
+}
+
+// timing: analysis 1.250ms
+fn format_report(phase: Phase, elapsed: Duration) -> String {
+    format!("timing: {} {:.3}ms", phase, elapsed.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // format_report
+    //
+
+    #[test]
+    fn test_format_report_milliseconds() {
+        assert_eq!(
+            format_report(Phase::Analysis, Duration::from_micros(1250)),
+            "timing: analysis 1.250ms"
+        );
+        assert_eq!(
+            format_report(Phase::Parse, Duration::from_secs(2)),
+            "timing: parse 2000.000ms"
+        );
+    }
+
+    //
+    // timed
+    //
+
+    #[test]
+    fn test_timed_passes_result_through() {
+        assert_eq!(timed(Phase::Derivation, || 1 + 1), 2);
+    }
+}
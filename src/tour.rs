@@ -0,0 +1,148 @@
+use std::io::{self, IsTerminal};
+
+use dialoguer::Input;
+use itertools::Itertools;
+
+use struck::{
+    instrument::{
+        fingering::{find_fingerings, FingeringStrategy},
+        fretboard::Tuning,
+    },
+    parser::chord_parser::{identify_from_name, identify_from_notes},
+    theory::{chord::Chord, error::ChordParseError, inversion_plan::plan_inversions, note::Note},
+};
+
+// struck tour, a few of the main things struck does run for real on examples picked to show them off
+// every step goes through the same engine the menu does, so if the tour runs everything's wired up
+
+pub struct TourStep {
+    pub title: &'static str,
+    // what to pick in the menu to do it yourself
+    pub try_it: &'static str,
+    pub output: String,
+}
+
+pub fn tour_steps() -> Result<Vec<TourStep>, ChordParseError> {
+    Ok(vec![
+        identify_a_chord()?,
+        name_some_notes(),
+        plan_a_progression()?,
+        finger_a_chord()?,
+    ])
+}
+
+// prints each step, waiting for enter between them when someone's there to press it
+pub fn run() -> Result<(), ChordParseError> {
+    let steps = tour_steps()?;
+    let interactive = io::stdin().is_terminal();
+
+    for (index, step) in steps.iter().enumerate() {
+        println!("{}/{} {}", index + 1, steps.len(), step.title);
+        println!("{}", step.output);
+        println!("try it: {}", step.try_it);
+        println!();
+
+        if interactive && index + 1 < steps.len() {
+            // nothing typed is needed, a failed read just ends the pause
+            let _: Result<String, _> = Input::new()
+                .with_prompt("press enter for the next one ")
+                .allow_empty(true)
+                .interact_text();
+        }
+    }
+
+    println!("that's the tour, run struck with no arguments for the menu");
+
+    Ok(())
+}
+
+fn identify_a_chord() -> Result<TourStep, ChordParseError> {
+    let chord = identify_from_name("Gm7b5".to_string())?;
+
+    Ok(TourStep {
+        title: "a chord from its name",
+        try_it: "Information on a known chord",
+        output: chord.to_string(),
+    })
+}
+
+fn name_some_notes() -> TourStep {
+    let notes = [Note::A, Note::C, Note::E, Note::G];
+    let readings = identify_from_notes(&notes);
+
+    TourStep {
+        title: "naming some notes",
+        try_it: "Create chord from notes",
+        output: format!(
+            "{} could be {}",
+            notes.iter().join(" "),
+            readings.iter().map(|c| &c.name).join(" or ")
+        ),
+    }
+}
+
+fn plan_a_progression() -> Result<TourStep, ChordParseError> {
+    let chords = ["C", "Am", "F", "G7"]
+        .iter()
+        .map(|name| identify_from_name(name.to_string()))
+        .collect::<Result<Vec<Chord>, ChordParseError>>()?;
+
+    let plan = plan_inversions(&chords, None);
+
+    Ok(TourStep {
+        title: "smooth inversions through a progression",
+        try_it: "Piano inversion plan for a progression",
+        output: plan
+            .iter()
+            .map(|c| format!("{}: {}", c.name, c.notes.iter().join(" ")))
+            .join("\n"),
+    })
+}
+
+fn finger_a_chord() -> Result<TourStep, ChordParseError> {
+    let chord = identify_from_name("D".to_string())?;
+    let tuning = Tuning::standard_guitar();
+    let fingerings = find_fingerings(&chord, &tuning, FingeringStrategy::OpenStrings);
+
+    let output = match fingerings.first() {
+        Some(fingering) => format!(
+            "{} on guitar: {} ({})",
+            chord.name,
+            fingering,
+            fingering.sounding_notes(&tuning).iter().join(" ")
+        ),
+        None => {
+            return Err(ChordParseError::InvalidChordName(format!(
+                "no fingering found for {}",
+                chord.name
+            )))
+        }
+    };
+
+    Ok(TourStep {
+        title: "a chord on the guitar",
+        try_it: "Guitar fingerings for a chord",
+        output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // tour_steps
+    //
+
+    // the tour is a run through the engine end to end, so it's worth knowing it still works
+    #[test]
+    fn test_tour_steps_all_run() {
+        let steps = tour_steps().expect("hmm");
+
+        assert_eq!(steps.len(), 4);
+        assert!(steps[0].output.contains("Gm7b5"));
+        assert!(steps[1].output.contains("Am7"));
+        assert_eq!(steps[2].output.lines().count(), 4);
+        assert!(steps[3].output.starts_with("D on guitar: "));
+    }
+}